@@ -80,6 +80,7 @@ async fn worker_task(
     let mut rng_worker = RngWorker {
         key_store: &key_store,
         rng: &rng,
+        reseed_client: None,
         requests: rng_req_rx,
         responses: rng_resp_tx,
     };
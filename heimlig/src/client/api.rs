@@ -1,4 +1,6 @@
-use crate::common::jobs::{ClientId, Request, RequestId, Response};
+use crate::common::jobs::{ClientId, Request, RequestId, Response, SessionId};
+use crate::crypto::{ecies, hpke, secoc};
+use crate::hsm::counter_store::CounterId;
 use crate::hsm::keystore::KeyId;
 use futures::{Sink, SinkExt, Stream, StreamExt};
 
@@ -43,10 +45,41 @@ impl<
 
     /// Request random bytes and write to provided buffer.
     pub async fn get_random(&mut self, output: &'data mut [u8]) -> Result<RequestId, Error> {
+        self.get_random_with_input(output, false, &[]).await
+    }
+
+    /// Request random bytes with additional input and write to provided buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `output`: Buffer the random bytes are written to.
+    /// * `prediction_resistance`: Whether the random number generator is reseeded with fresh
+    ///   entropy before the random bytes are generated.
+    /// * `additional_input`: Optional (possibly empty) personalization string or additional input
+    ///   that is mixed into the random number generator.
+    pub async fn get_random_with_input(
+        &mut self,
+        output: &'data mut [u8],
+        prediction_resistance: bool,
+        additional_input: &'data [u8],
+    ) -> Result<RequestId, Error> {
         let request = Request::GetRandom {
             client_id: ClientId::default(),
             request_id: RequestId::default(),
             output,
+            prediction_resistance,
+            additional_input,
+        };
+        self.send_request(request).await
+    }
+
+    /// Reseed the random number generator with fresh entropy, e.g. after resuming from sleep.
+    /// Only the client configured in the random number generator worker is allowed to send this
+    /// request.
+    pub async fn reseed_rng(&mut self) -> Result<RequestId, Error> {
+        let request = Request::ReseedRng {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
         };
         self.send_request(request).await
     }
@@ -163,7 +196,8 @@ impl<
         self.send_request(request).await
     }
 
-    /// Check whether a key for the given `KeyId` is stored in the HSM
+    /// Check whether a key for the given `KeyId` is stored in the HSM. Keys the client has no
+    /// access to are reported as not available, like unconfigured keys.
     pub async fn is_key_available(&mut self, key_id: KeyId) -> Result<RequestId, Error> {
         let request = Request::IsKeyAvailable {
             client_id: ClientId::default(),
@@ -173,6 +207,54 @@ impl<
         self.send_request(request).await
     }
 
+    /// Get the type and permissions of the key slot for the given `KeyId` and whether a key is
+    /// stored in it. Keys the client has no access to are reported with `InvalidKeyId`, like
+    /// unconfigured keys.
+    pub async fn get_key_info(&mut self, key_id: KeyId) -> Result<RequestId, Error> {
+        let request = Request::GetKeyInfo {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            key_id,
+        };
+        self.send_request(request).await
+    }
+
+    /// List the `KeyId`s of the key slots configured in the HSM, ordered by `KeyId`. Only key slots
+    /// the client has any access to are listed and counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset`: Index of the first key slot to list
+    /// * `key_ids`: Buffer for the listed `KeyId`s. The response contains the filled part of the
+    ///   buffer and the total number of key slots, so that the next page can be requested with
+    ///   `offset` increased by the number of listed `KeyId`s.
+    pub async fn list_keys(
+        &mut self,
+        offset: usize,
+        key_ids: &'data mut [KeyId],
+    ) -> Result<RequestId, Error> {
+        let request = Request::ListKeys {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            offset,
+            key_ids,
+        };
+        self.send_request(request).await
+    }
+
+    /// Delete the key for the given `KeyId` from the HSM.
+    ///
+    /// The key must have the `delete` permission. Deleting a key that is not stored results in a
+    /// `KeyNotFound` error.
+    pub async fn delete_key(&mut self, key_id: KeyId) -> Result<RequestId, Error> {
+        let request = Request::DeleteKey {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            key_id,
+        };
+        self.send_request(request).await
+    }
+
     /// Symmetrically encrypt a buffer in-place using a key stored in the HSM.
     ///
     /// # Arguments
@@ -457,6 +539,801 @@ impl<
         self.send_request(request).await
     }
 
+    /// Create a compact JWS from a JSON header and a payload using a key stored in the HSM
+    pub async fn sign_jws(
+        &mut self,
+        key_id: KeyId,
+        header: &'data [u8],
+        payload: &'data [u8],
+        token: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::SignJws {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            key_id,
+            header,
+            payload,
+            token,
+        };
+        self.send_request(request).await
+    }
+
+    /// Verify a compact JWS using a key stored in the HSM
+    pub async fn verify_jws(
+        &mut self,
+        key_id: KeyId,
+        token: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::VerifyJws {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            key_id,
+            token,
+        };
+        self.send_request(request).await
+    }
+
+    /// Verify a compact JWS using a caller-provided public key
+    pub async fn verify_jws_external_key(
+        &mut self,
+        public_key: &'data [u8],
+        token: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::VerifyJwsExternalKey {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            public_key,
+            token,
+        };
+        self.send_request(request).await
+    }
+
+    /// Encrypt a buffer in-place to a recipient public key using HPKE (RFC 9180). The KEM is
+    /// determined by the size of `recipient_public_key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender_key_id`: The key to authenticate the sender with (auth mode). Base mode is used
+    ///   if no key is given.
+    /// * `aead`: The AEAD to be used
+    /// * `recipient_public_key`: The public key of the recipient
+    /// * `info`: Application-supplied information bound to the key schedule
+    /// * `aad`: 'Additional authenticated data' to be used for tag computation
+    /// * `buffer`: The buffer containing the plaintext
+    /// * `encapsulated_key`: Buffer for the encapsulated key. Must be at least as large as the
+    ///   encapsulated key of the used KEM.
+    /// * `tag`: Buffer for the generated tag
+    #[allow(clippy::too_many_arguments)]
+    pub async fn hpke_seal(
+        &mut self,
+        sender_key_id: Option<KeyId>,
+        aead: hpke::Aead,
+        recipient_public_key: &'data [u8],
+        info: &'data [u8],
+        aad: &'data [u8],
+        buffer: &'data mut [u8],
+        encapsulated_key: &'data mut [u8],
+        tag: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::HpkeSeal {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            sender_key_id,
+            aead,
+            recipient_public_key,
+            info,
+            aad,
+            buffer,
+            encapsulated_key,
+            tag,
+        };
+        self.send_request(request).await
+    }
+
+    /// Decrypt an HPKE (RFC 9180) encrypted buffer in-place using a recipient key stored in the
+    /// HSM.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id`: The recipient key identifier to use
+    /// * `sender_public_key`: The public key of the sender if the message was sent in auth mode
+    /// * `aead`: The AEAD to be used
+    /// * `encapsulated_key`: The encapsulated key received from the sender
+    /// * `info`: Application-supplied information bound to the key schedule
+    /// * `aad`: 'Additional authenticated data' to be used for tag computation
+    /// * `buffer`: The buffer containing the ciphertext
+    /// * `tag`: The authentication tag used to authenticate the data
+    #[allow(clippy::too_many_arguments)]
+    pub async fn hpke_open(
+        &mut self,
+        key_id: KeyId,
+        sender_public_key: Option<&'data [u8]>,
+        aead: hpke::Aead,
+        encapsulated_key: &'data [u8],
+        info: &'data [u8],
+        aad: &'data [u8],
+        buffer: &'data mut [u8],
+        tag: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::HpkeOpen {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            key_id,
+            sender_public_key,
+            aead,
+            encapsulated_key,
+            info,
+            aad,
+            buffer,
+            tag,
+        };
+        self.send_request(request).await
+    }
+
+    /// Encrypt a buffer in-place to a caller-provided recipient public key using ECIES. The curve
+    /// is determined by the size of `recipient_public_key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `kdf`: The key derivation function to be used
+    /// * `cipher`: The authenticated cipher to be used
+    /// * `recipient_public_key`: The public key of the recipient
+    /// * `shared_info`: Information shared by both parties that is input to the KDF
+    /// * `aad`: 'Additional authenticated data' to be used for tag computation
+    /// * `buffer`: The buffer containing the plaintext
+    /// * `ephemeral_public_key`: Buffer for the ephemeral public key. Must be at least as large as
+    ///   an uncompressed point of the used curve.
+    /// * `tag`: Buffer for the generated tag
+    #[allow(clippy::too_many_arguments)]
+    pub async fn ecies_encrypt(
+        &mut self,
+        kdf: ecies::Kdf,
+        cipher: ecies::Cipher,
+        recipient_public_key: &'data [u8],
+        shared_info: &'data [u8],
+        aad: &'data [u8],
+        buffer: &'data mut [u8],
+        ephemeral_public_key: &'data mut [u8],
+        tag: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::EciesEncrypt {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            kdf,
+            cipher,
+            recipient_public_key,
+            shared_info,
+            aad,
+            buffer,
+            ephemeral_public_key,
+            tag,
+        };
+        self.send_request(request).await
+    }
+
+    /// Decrypt an ECIES encrypted buffer in-place using a key stored in the HSM.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id`: The key identifier to use
+    /// * `kdf`: The key derivation function to be used
+    /// * `cipher`: The authenticated cipher to be used
+    /// * `ephemeral_public_key`: The ephemeral public key received from the sender
+    /// * `shared_info`: Information shared by both parties that is input to the KDF
+    /// * `aad`: 'Additional authenticated data' to be used for tag computation
+    /// * `buffer`: The buffer containing the ciphertext
+    /// * `tag`: The authentication tag used to authenticate the data
+    #[allow(clippy::too_many_arguments)]
+    pub async fn ecies_decrypt(
+        &mut self,
+        key_id: KeyId,
+        kdf: ecies::Kdf,
+        cipher: ecies::Cipher,
+        ephemeral_public_key: &'data [u8],
+        shared_info: &'data [u8],
+        aad: &'data [u8],
+        buffer: &'data mut [u8],
+        tag: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::EciesDecrypt {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            key_id,
+            kdf,
+            cipher,
+            ephemeral_public_key,
+            shared_info,
+            aad,
+            buffer,
+            tag,
+        };
+        self.send_request(request).await
+    }
+
+    /// Perform a TLS 1.3 HKDF-Extract with keystore-resident inputs and store the resulting secret
+    /// in the HSM.
+    ///
+    /// # Arguments
+    ///
+    /// * `salt_key_id`: The secret used as salt. `None` uses a string of zeros.
+    /// * `ikm_key_id`: The secret used as input keying material. `None` uses a string of zeros.
+    ///   If `peer_key_exchange` is given, this is the local (EC)DHE key pair instead.
+    /// * `peer_key_exchange`: The key exchange value of the peer's key share
+    /// * `output_key_id`: The key identifier where the extracted secret is stored
+    /// * `overwrite`: Whether or not an existing secret in the output slot may be overwritten
+    pub async fn tls_hkdf_extract(
+        &mut self,
+        salt_key_id: Option<KeyId>,
+        ikm_key_id: Option<KeyId>,
+        peer_key_exchange: Option<&'data [u8]>,
+        output_key_id: KeyId,
+        overwrite: bool,
+    ) -> Result<RequestId, Error> {
+        let request = Request::TlsHkdfExtract {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            salt_key_id,
+            ikm_key_id,
+            peer_key_exchange,
+            output_key_id,
+            overwrite,
+        };
+        self.send_request(request).await
+    }
+
+    /// Perform a TLS 1.3 HKDF-Expand-Label on a secret stored in the HSM. The output length is
+    /// the key size of the output slot.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key_id`: The secret to expand
+    /// * `label`: The label without the "tls13 " prefix
+    /// * `context`: The context
+    /// * `output_key_id`: The key identifier where the output is stored
+    /// * `overwrite`: Whether or not an existing key in the output slot may be overwritten
+    pub async fn tls_hkdf_expand_label(
+        &mut self,
+        secret_key_id: KeyId,
+        label: &'data [u8],
+        context: &'data [u8],
+        output_key_id: KeyId,
+        overwrite: bool,
+    ) -> Result<RequestId, Error> {
+        let request = Request::TlsHkdfExpandLabel {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            secret_key_id,
+            label,
+            context,
+            output_key_id,
+            overwrite,
+        };
+        self.send_request(request).await
+    }
+
+    /// Perform a TLS 1.3 Derive-Secret on a secret stored in the HSM.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key_id`: The secret to derive from
+    /// * `label`: The label without the "tls13 " prefix
+    /// * `transcript_hash`: The hash of the handshake messages
+    /// * `output_key_id`: The key identifier where the derived secret is stored
+    /// * `overwrite`: Whether or not an existing secret in the output slot may be overwritten
+    pub async fn tls_derive_secret(
+        &mut self,
+        secret_key_id: KeyId,
+        label: &'data [u8],
+        transcript_hash: &'data [u8],
+        output_key_id: KeyId,
+        overwrite: bool,
+    ) -> Result<RequestId, Error> {
+        let request = Request::TlsDeriveSecret {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            secret_key_id,
+            label,
+            transcript_hash,
+            output_key_id,
+            overwrite,
+        };
+        self.send_request(request).await
+    }
+
+    /// Derive the record protection IV from a traffic secret stored in the HSM.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key_id`: The traffic secret
+    /// * `iv`: The buffer the IV is written to
+    pub async fn tls_derive_iv(
+        &mut self,
+        secret_key_id: KeyId,
+        iv: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::TlsDeriveIv {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            secret_key_id,
+            iv,
+        };
+        self.send_request(request).await
+    }
+
+    /// Compute the verify data of a TLS 1.3 Finished message.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_key_id`: The handshake traffic secret of the sender
+    /// * `transcript_hash`: The hash of the handshake messages
+    /// * `verify_data`: The buffer the verify data is written to
+    pub async fn tls_finished(
+        &mut self,
+        base_key_id: KeyId,
+        transcript_hash: &'data [u8],
+        verify_data: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::TlsFinished {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            base_key_id,
+            transcript_hash,
+            verify_data,
+        };
+        self.send_request(request).await
+    }
+
+    /// Sign a TLS 1.3 CertificateVerify message with an ECDSA key stored in the HSM. The
+    /// signature is DER encoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id`: The key identifier of the signing key
+    /// * `server`: Whether the signature is created by the server or by the client
+    /// * `transcript_hash`: The hash of the handshake messages
+    /// * `signature`: The buffer the signature is written to
+    pub async fn tls_sign_certificate_verify(
+        &mut self,
+        key_id: KeyId,
+        server: bool,
+        transcript_hash: &'data [u8],
+        signature: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::TlsSignCertificateVerify {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            key_id,
+            server,
+            transcript_hash,
+            signature,
+        };
+        self.send_request(request).await
+    }
+
+    /// Start a Noise_XX_25519_ChaChaPoly_SHA256 handshake with a static X25519 key stored in the
+    /// HSM. The response contains the handle of the new session.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id`: The key identifier of the local static key
+    /// * `initiator`: Whether the local party sends the first handshake message
+    /// * `prologue`: Data both parties must agree on
+    pub async fn noise_start_handshake(
+        &mut self,
+        key_id: KeyId,
+        initiator: bool,
+        prologue: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::NoiseStartHandshake {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            key_id,
+            initiator,
+            prologue,
+        };
+        self.send_request(request).await
+    }
+
+    /// Write the next handshake message of a Noise session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session`: The session handle
+    /// * `payload`: The payload to send along with the handshake message
+    /// * `message`: The buffer the handshake message is written to
+    pub async fn noise_write_message(
+        &mut self,
+        session: SessionId,
+        payload: &'data [u8],
+        message: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::NoiseWriteMessage {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            session,
+            payload,
+            message,
+        };
+        self.send_request(request).await
+    }
+
+    /// Read the next handshake message of a Noise session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session`: The session handle
+    /// * `message`: The received handshake message
+    /// * `payload`: The buffer the received payload is written to
+    pub async fn noise_read_message(
+        &mut self,
+        session: SessionId,
+        message: &'data [u8],
+        payload: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::NoiseReadMessage {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            session,
+            message,
+            payload,
+        };
+        self.send_request(request).await
+    }
+
+    /// Get the authenticated static public key of the remote party and the handshake hash of a
+    /// Noise session whose handshake is finished.
+    ///
+    /// # Arguments
+    ///
+    /// * `session`: The session handle
+    /// * `remote_static_public_key`: The buffer the remote static public key is written to
+    /// * `handshake_hash`: The buffer the handshake hash is written to
+    pub async fn noise_session_info(
+        &mut self,
+        session: SessionId,
+        remote_static_public_key: &'data mut [u8],
+        handshake_hash: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::NoiseSessionInfo {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            session,
+            remote_static_public_key,
+            handshake_hash,
+        };
+        self.send_request(request).await
+    }
+
+    /// Encrypt a transport message of a Noise session in-place.
+    ///
+    /// # Arguments
+    ///
+    /// * `session`: The session handle
+    /// * `buffer`: The buffer containing the plaintext
+    /// * `tag`: The buffer the authentication tag is written to
+    pub async fn noise_encrypt(
+        &mut self,
+        session: SessionId,
+        buffer: &'data mut [u8],
+        tag: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::NoiseEncrypt {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            session,
+            buffer,
+            tag,
+        };
+        self.send_request(request).await
+    }
+
+    /// Decrypt a transport message of a Noise session in-place.
+    ///
+    /// # Arguments
+    ///
+    /// * `session`: The session handle
+    /// * `buffer`: The buffer containing the ciphertext
+    /// * `tag`: The authentication tag used to authenticate the data
+    pub async fn noise_decrypt(
+        &mut self,
+        session: SessionId,
+        buffer: &'data mut [u8],
+        tag: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::NoiseDecrypt {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            session,
+            buffer,
+            tag,
+        };
+        self.send_request(request).await
+    }
+
+    /// Close a Noise session and erase its state.
+    ///
+    /// # Arguments
+    ///
+    /// * `session`: The session handle
+    pub async fn noise_close_session(&mut self, session: SessionId) -> Result<RequestId, Error> {
+        let request = Request::NoiseCloseSession {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            session,
+        };
+        self.send_request(request).await
+    }
+
+    /// Build a SecOC secured PDU with the next freshness value of a data ID.
+    ///
+    /// The key and the truncation sizes are taken from the HSM configuration of the data ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_id`: The data ID of the PDU
+    /// * `payload`: The authentic payload of the PDU
+    /// * `secured_pdu`: The buffer the secured PDU is written to. It has to hold the payload plus
+    ///   the truncated freshness value and MAC.
+    pub async fn secoc_authenticate(
+        &mut self,
+        data_id: secoc::DataId,
+        payload: &'data [u8],
+        secured_pdu: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::SecOcAuthenticate {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            data_id,
+            payload,
+            secured_pdu,
+        };
+        self.send_request(request).await
+    }
+
+    /// Verify a received SecOC secured PDU of a data ID.
+    ///
+    /// PDUs with a freshness value that is not newer than the latest accepted one are rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_id`: The data ID of the PDU
+    /// * `secured_pdu`: The received secured PDU
+    pub async fn secoc_verify(
+        &mut self,
+        data_id: secoc::DataId,
+        secured_pdu: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::SecOcVerify {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            data_id,
+            secured_pdu,
+        };
+        self.send_request(request).await
+    }
+
+    /// Create a monotonic counter that starts at zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter_id`: The ID of the new counter
+    /// * `max_value`: The value the counter can never exceed
+    pub async fn create_counter(
+        &mut self,
+        counter_id: CounterId,
+        max_value: u64,
+    ) -> Result<RequestId, Error> {
+        let request = Request::CreateCounter {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            counter_id,
+            max_value,
+        };
+        self.send_request(request).await
+    }
+
+    /// Read the current and the maximum value of a monotonic counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter_id`: The ID of the counter
+    pub async fn read_counter(&mut self, counter_id: CounterId) -> Result<RequestId, Error> {
+        let request = Request::ReadCounter {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            counter_id,
+        };
+        self.send_request(request).await
+    }
+
+    /// Increase the value of a monotonic counter.
+    ///
+    /// The counter is left unchanged if the new value would exceed its maximum value.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter_id`: The ID of the counter
+    /// * `amount`: The value added to the counter
+    pub async fn increment_counter(
+        &mut self,
+        counter_id: CounterId,
+        amount: u64,
+    ) -> Result<RequestId, Error> {
+        let request = Request::IncrementCounter {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            counter_id,
+            amount,
+        };
+        self.send_request(request).await
+    }
+
+    /// Start the verification of a firmware image against a signed manifest.
+    ///
+    /// The manifest signature is verified with the given key and its security version is checked
+    /// against the minimum security version of the HSM before a session is started.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id`: The ID of the root or intermediate key the manifest is signed with. It has to be
+    ///   one of the trusted keys configured in the boot worker.
+    /// * `manifest`: The signed manifest
+    pub async fn verify_image_start(
+        &mut self,
+        key_id: KeyId,
+        manifest: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::VerifyImageStart {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            key_id,
+            manifest,
+        };
+        self.send_request(request).await
+    }
+
+    /// Hash the next chunk of the firmware image of an image verification session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session`: The session handle
+    /// * `chunk`: The next chunk of the image
+    pub async fn verify_image_update(
+        &mut self,
+        session: SessionId,
+        chunk: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::VerifyImageUpdate {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            session,
+            chunk,
+        };
+        self.send_request(request).await
+    }
+
+    /// Finish an image verification session and get the verdict.
+    ///
+    /// The image is verified if its size and hash match the manifest. The session is closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `session`: The session handle
+    pub async fn verify_image_finish(&mut self, session: SessionId) -> Result<RequestId, Error> {
+        let request = Request::VerifyImageFinish {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            session,
+        };
+        self.send_request(request).await
+    }
+
+    /// Extend a measurement register with a digest.
+    ///
+    /// The new register value is the SHA-256 digest of the old register value and `digest`.
+    ///
+    /// # Arguments
+    ///
+    /// * `register`: The index of the measurement register
+    /// * `digest`: The SHA-256 digest of the measured data
+    pub async fn extend_measurement(
+        &mut self,
+        register: u32,
+        digest: &'data [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::ExtendMeasurement {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            register,
+            digest,
+        };
+        self.send_request(request).await
+    }
+
+    /// Read the value of a measurement register.
+    ///
+    /// # Arguments
+    ///
+    /// * `register`: The index of the measurement register
+    /// * `value`: The buffer the register value is written to
+    pub async fn read_measurement(
+        &mut self,
+        register: u32,
+        value: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::ReadMeasurement {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            register,
+            value,
+        };
+        self.send_request(request).await
+    }
+
+    /// Sign the selected measurement registers and a nonce with the attestation key.
+    ///
+    /// # Arguments
+    ///
+    /// * `selection`: Bit mask of the registers to quote. Bit `i` selects register `i`.
+    /// * `nonce`: The nonce provided by the verifier
+    /// * `quote`: The buffer the signed quote is written to
+    pub async fn quote_measurements(
+        &mut self,
+        selection: u32,
+        nonce: &'data [u8],
+        quote: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::QuoteMeasurements {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            selection,
+            nonce,
+            quote,
+        };
+        self.send_request(request).await
+    }
+
+    /// Derive the alias key of the next boot layer and issue its certificate. Only the boot client
+    /// configured in the DICE worker may report measurements; other clients get `AccessDenied`.
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement`: SHA-256 digest of the next layer
+    /// * `certificate`: The buffer the alias certificate is written to
+    pub async fn derive_dice_alias(
+        &mut self,
+        measurement: &'data [u8],
+        certificate: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::DeriveDiceAlias {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            measurement,
+            certificate,
+        };
+        self.send_request(request).await
+    }
+
+    /// Process an SPDM request message with the SPDM responder.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`: The SPDM request message
+    /// * `response`: The buffer the SPDM response message is written to
+    pub async fn process_spdm_message(
+        &mut self,
+        message: &'data [u8],
+        response: &'data mut [u8],
+    ) -> Result<RequestId, Error> {
+        let request = Request::ProcessSpdmMessage {
+            client_id: ClientId::default(),
+            request_id: RequestId::default(),
+            message,
+            response,
+        };
+        self.send_request(request).await
+    }
+
     async fn send_request(
         &mut self,
         mut request_without_id: Request<'data>,
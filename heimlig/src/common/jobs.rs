@@ -1,5 +1,8 @@
+use crate::crypto::{ecies, hpke, rng, secoc};
+use crate::hsm::counter_store;
+use crate::hsm::counter_store::CounterId;
 use crate::hsm::keystore;
-use crate::hsm::keystore::KeyId;
+use crate::hsm::keystore::{KeyId, KeyInfo};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Error {
@@ -19,6 +22,33 @@ pub enum Error {
     Crypto(crate::crypto::Error),
     /// A key store error occurred.
     KeyStore(keystore::Error),
+    /// The session does not exist or belongs to another client.
+    InvalidSession,
+    /// The maximum number of concurrent sessions has been reached.
+    TooManySessions,
+    /// The data ID is not configured or is configured more than once.
+    InvalidDataId,
+    /// A counter store error occurred.
+    CounterStore(counter_store::Error),
+    /// The security version of the image is lower than the minimum security version.
+    SecurityVersionTooLow,
+    /// The measurement register does not exist.
+    InvalidRegister,
+    /// The health tests of the entropy source failed.
+    HealthTestFailure,
+    /// The entropy source could not provide a seed.
+    EntropySourceFailure,
+    /// The client is not allowed to send this request.
+    AccessDenied,
+}
+
+impl From<rng::Error> for Error {
+    fn from(value: rng::Error) -> Self {
+        match value {
+            rng::Error::HealthTestFailure => Error::HealthTestFailure,
+            rng::Error::SourceFailure => Error::EntropySourceFailure,
+        }
+    }
 }
 
 /// Used to distinguish multiple clients
@@ -71,6 +101,22 @@ impl RequestId {
     }
 }
 
+/// Handle of a session kept by a worker across requests
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SessionId(pub u32);
+
+impl From<u32> for SessionId {
+    fn from(value: u32) -> Self {
+        SessionId(value)
+    }
+}
+
+impl From<SessionId> for u32 {
+    fn from(value: SessionId) -> Self {
+        value.0
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum RequestType {
     GetRandom,
@@ -98,6 +144,43 @@ pub enum RequestType {
     SignExternalKey,
     Verify,
     VerifyExternalKey,
+    SignJws,
+    VerifyJws,
+    VerifyJwsExternalKey,
+    HpkeSeal,
+    HpkeOpen,
+    EciesEncrypt,
+    EciesDecrypt,
+    TlsHkdfExtract,
+    TlsHkdfExpandLabel,
+    TlsDeriveSecret,
+    TlsDeriveIv,
+    TlsFinished,
+    TlsSignCertificateVerify,
+    NoiseStartHandshake,
+    NoiseWriteMessage,
+    NoiseReadMessage,
+    NoiseSessionInfo,
+    NoiseEncrypt,
+    NoiseDecrypt,
+    NoiseCloseSession,
+    SecOcAuthenticate,
+    SecOcVerify,
+    CreateCounter,
+    ReadCounter,
+    IncrementCounter,
+    VerifyImageStart,
+    VerifyImageUpdate,
+    VerifyImageFinish,
+    ExtendMeasurement,
+    ReadMeasurement,
+    QuoteMeasurements,
+    DeriveDiceAlias,
+    ProcessSpdmMessage,
+    ReseedRng,
+    DeleteKey,
+    GetKeyInfo,
+    ListKeys,
 }
 
 /// A request for the HSM to perform a cryptographic task.
@@ -107,6 +190,8 @@ pub enum Request<'data> {
         client_id: ClientId,
         request_id: RequestId,
         output: &'data mut [u8],
+        prediction_resistance: bool,
+        additional_input: &'data [u8],
     },
     GenerateSymmetricKey {
         client_id: ClientId,
@@ -292,6 +377,267 @@ pub enum Request<'data> {
         prehashed: bool,
         signature: &'data [u8],
     },
+    SignJws {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        header: &'data [u8],
+        payload: &'data [u8],
+        token: &'data mut [u8],
+    },
+    VerifyJws {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        token: &'data [u8],
+    },
+    VerifyJwsExternalKey {
+        client_id: ClientId,
+        request_id: RequestId,
+        public_key: &'data [u8],
+        token: &'data [u8],
+    },
+    HpkeSeal {
+        client_id: ClientId,
+        request_id: RequestId,
+        sender_key_id: Option<KeyId>,
+        aead: hpke::Aead,
+        recipient_public_key: &'data [u8],
+        info: &'data [u8],
+        aad: &'data [u8],
+        buffer: &'data mut [u8],
+        encapsulated_key: &'data mut [u8],
+        tag: &'data mut [u8],
+    },
+    HpkeOpen {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        sender_public_key: Option<&'data [u8]>,
+        aead: hpke::Aead,
+        encapsulated_key: &'data [u8],
+        info: &'data [u8],
+        aad: &'data [u8],
+        buffer: &'data mut [u8],
+        tag: &'data [u8],
+    },
+    EciesEncrypt {
+        client_id: ClientId,
+        request_id: RequestId,
+        kdf: ecies::Kdf,
+        cipher: ecies::Cipher,
+        recipient_public_key: &'data [u8],
+        shared_info: &'data [u8],
+        aad: &'data [u8],
+        buffer: &'data mut [u8],
+        ephemeral_public_key: &'data mut [u8],
+        tag: &'data mut [u8],
+    },
+    EciesDecrypt {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        kdf: ecies::Kdf,
+        cipher: ecies::Cipher,
+        ephemeral_public_key: &'data [u8],
+        shared_info: &'data [u8],
+        aad: &'data [u8],
+        buffer: &'data mut [u8],
+        tag: &'data [u8],
+    },
+    TlsHkdfExtract {
+        client_id: ClientId,
+        request_id: RequestId,
+        salt_key_id: Option<KeyId>,
+        ikm_key_id: Option<KeyId>,
+        peer_key_exchange: Option<&'data [u8]>,
+        output_key_id: KeyId,
+        overwrite: bool,
+    },
+    TlsHkdfExpandLabel {
+        client_id: ClientId,
+        request_id: RequestId,
+        secret_key_id: KeyId,
+        label: &'data [u8],
+        context: &'data [u8],
+        output_key_id: KeyId,
+        overwrite: bool,
+    },
+    TlsDeriveSecret {
+        client_id: ClientId,
+        request_id: RequestId,
+        secret_key_id: KeyId,
+        label: &'data [u8],
+        transcript_hash: &'data [u8],
+        output_key_id: KeyId,
+        overwrite: bool,
+    },
+    TlsDeriveIv {
+        client_id: ClientId,
+        request_id: RequestId,
+        secret_key_id: KeyId,
+        iv: &'data mut [u8],
+    },
+    TlsFinished {
+        client_id: ClientId,
+        request_id: RequestId,
+        base_key_id: KeyId,
+        transcript_hash: &'data [u8],
+        verify_data: &'data mut [u8],
+    },
+    TlsSignCertificateVerify {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        server: bool,
+        transcript_hash: &'data [u8],
+        signature: &'data mut [u8],
+    },
+    NoiseStartHandshake {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        initiator: bool,
+        prologue: &'data [u8],
+    },
+    NoiseWriteMessage {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        payload: &'data [u8],
+        message: &'data mut [u8],
+    },
+    NoiseReadMessage {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        message: &'data [u8],
+        payload: &'data mut [u8],
+    },
+    NoiseSessionInfo {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        remote_static_public_key: &'data mut [u8],
+        handshake_hash: &'data mut [u8],
+    },
+    NoiseEncrypt {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        buffer: &'data mut [u8],
+        tag: &'data mut [u8],
+    },
+    NoiseDecrypt {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        buffer: &'data mut [u8],
+        tag: &'data [u8],
+    },
+    NoiseCloseSession {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+    },
+    SecOcAuthenticate {
+        client_id: ClientId,
+        request_id: RequestId,
+        data_id: secoc::DataId,
+        payload: &'data [u8],
+        secured_pdu: &'data mut [u8],
+    },
+    SecOcVerify {
+        client_id: ClientId,
+        request_id: RequestId,
+        data_id: secoc::DataId,
+        secured_pdu: &'data [u8],
+    },
+    CreateCounter {
+        client_id: ClientId,
+        request_id: RequestId,
+        counter_id: CounterId,
+        max_value: u64,
+    },
+    ReadCounter {
+        client_id: ClientId,
+        request_id: RequestId,
+        counter_id: CounterId,
+    },
+    IncrementCounter {
+        client_id: ClientId,
+        request_id: RequestId,
+        counter_id: CounterId,
+        amount: u64,
+    },
+    VerifyImageStart {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        manifest: &'data [u8],
+    },
+    VerifyImageUpdate {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        chunk: &'data [u8],
+    },
+    VerifyImageFinish {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+    },
+    ExtendMeasurement {
+        client_id: ClientId,
+        request_id: RequestId,
+        register: u32,
+        digest: &'data [u8],
+    },
+    ReadMeasurement {
+        client_id: ClientId,
+        request_id: RequestId,
+        register: u32,
+        value: &'data mut [u8],
+    },
+    QuoteMeasurements {
+        client_id: ClientId,
+        request_id: RequestId,
+        selection: u32,
+        nonce: &'data [u8],
+        quote: &'data mut [u8],
+    },
+    DeriveDiceAlias {
+        client_id: ClientId,
+        request_id: RequestId,
+        measurement: &'data [u8],
+        certificate: &'data mut [u8],
+    },
+    ProcessSpdmMessage {
+        client_id: ClientId,
+        request_id: RequestId,
+        message: &'data [u8],
+        response: &'data mut [u8],
+    },
+    ReseedRng {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    DeleteKey {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+    },
+    GetKeyInfo {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+    },
+    ListKeys {
+        client_id: ClientId,
+        request_id: RequestId,
+        offset: usize,
+        key_ids: &'data mut [KeyId],
+    },
 }
 
 impl RequestType {
@@ -306,6 +652,9 @@ impl RequestType {
                 | RequestType::ExportPublicKey
                 | RequestType::ExportPrivateKey
                 | RequestType::IsKeyAvailable
+                | RequestType::DeleteKey
+                | RequestType::GetKeyInfo
+                | RequestType::ListKeys
         )
     }
 
@@ -408,6 +757,188 @@ pub enum Response<'data> {
         request_id: RequestId,
         verified: bool,
     },
+    SignJws {
+        client_id: ClientId,
+        request_id: RequestId,
+        token: &'data mut [u8], // Subslice of original buffer containing the compact JWS
+    },
+    VerifyJws {
+        client_id: ClientId,
+        request_id: RequestId,
+        verified: bool,
+    },
+    HpkeSeal {
+        client_id: ClientId,
+        request_id: RequestId,
+        encapsulated_key: &'data mut [u8],
+        buffer: &'data mut [u8],
+        tag: &'data mut [u8],
+    },
+    HpkeOpen {
+        client_id: ClientId,
+        request_id: RequestId,
+        plaintext: &'data mut [u8],
+    },
+    EciesEncrypt {
+        client_id: ClientId,
+        request_id: RequestId,
+        ephemeral_public_key: &'data mut [u8],
+        buffer: &'data mut [u8],
+        tag: &'data mut [u8],
+    },
+    EciesDecrypt {
+        client_id: ClientId,
+        request_id: RequestId,
+        plaintext: &'data mut [u8],
+    },
+    TlsHkdfExtract {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    TlsHkdfExpandLabel {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    TlsDeriveSecret {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    TlsDeriveIv {
+        client_id: ClientId,
+        request_id: RequestId,
+        iv: &'data mut [u8],
+    },
+    TlsFinished {
+        client_id: ClientId,
+        request_id: RequestId,
+        verify_data: &'data mut [u8],
+    },
+    TlsSignCertificateVerify {
+        client_id: ClientId,
+        request_id: RequestId,
+        signature: &'data mut [u8],
+    },
+    NoiseStartHandshake {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+    },
+    NoiseWriteMessage {
+        client_id: ClientId,
+        request_id: RequestId,
+        message: &'data mut [u8],
+        handshake_finished: bool,
+    },
+    NoiseReadMessage {
+        client_id: ClientId,
+        request_id: RequestId,
+        payload: &'data mut [u8],
+        handshake_finished: bool,
+    },
+    NoiseSessionInfo {
+        client_id: ClientId,
+        request_id: RequestId,
+        remote_static_public_key: &'data mut [u8],
+        handshake_hash: &'data mut [u8],
+    },
+    NoiseEncrypt {
+        client_id: ClientId,
+        request_id: RequestId,
+        buffer: &'data mut [u8],
+        tag: &'data mut [u8],
+    },
+    NoiseDecrypt {
+        client_id: ClientId,
+        request_id: RequestId,
+        plaintext: &'data mut [u8],
+    },
+    NoiseCloseSession {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    SecOcAuthenticate {
+        client_id: ClientId,
+        request_id: RequestId,
+        secured_pdu: &'data mut [u8],
+    },
+    SecOcVerify {
+        client_id: ClientId,
+        request_id: RequestId,
+        verified: bool,
+    },
+    CreateCounter {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    ReadCounter {
+        client_id: ClientId,
+        request_id: RequestId,
+        value: u64,
+        max_value: u64,
+    },
+    IncrementCounter {
+        client_id: ClientId,
+        request_id: RequestId,
+        value: u64,
+    },
+    VerifyImageStart {
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+    },
+    VerifyImageUpdate {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    VerifyImageFinish {
+        client_id: ClientId,
+        request_id: RequestId,
+        verified: bool,
+    },
+    ExtendMeasurement {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    ReadMeasurement {
+        client_id: ClientId,
+        request_id: RequestId,
+        value: &'data mut [u8],
+    },
+    QuoteMeasurements {
+        client_id: ClientId,
+        request_id: RequestId,
+        quote: &'data mut [u8],
+    },
+    DeriveDiceAlias {
+        client_id: ClientId,
+        request_id: RequestId,
+        certificate: &'data mut [u8],
+    },
+    ProcessSpdmMessage {
+        client_id: ClientId,
+        request_id: RequestId,
+        response: &'data mut [u8],
+    },
+    ReseedRng {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    DeleteKey {
+        client_id: ClientId,
+        request_id: RequestId,
+    },
+    GetKeyInfo {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_info: KeyInfo,
+        is_available: bool,
+    },
+    ListKeys {
+        client_id: ClientId,
+        request_id: RequestId,
+        key_ids: &'data mut [KeyId],
+        num_keys: usize,
+    },
 }
 
 impl<'data> Request<'data> {
@@ -442,6 +973,43 @@ impl<'data> Request<'data> {
             Request::SignExternalKey { .. } => RequestType::SignExternalKey,
             Request::Verify { .. } => RequestType::Verify,
             Request::VerifyExternalKey { .. } => RequestType::VerifyExternalKey,
+            Request::SignJws { .. } => RequestType::SignJws,
+            Request::VerifyJws { .. } => RequestType::VerifyJws,
+            Request::VerifyJwsExternalKey { .. } => RequestType::VerifyJwsExternalKey,
+            Request::HpkeSeal { .. } => RequestType::HpkeSeal,
+            Request::HpkeOpen { .. } => RequestType::HpkeOpen,
+            Request::EciesEncrypt { .. } => RequestType::EciesEncrypt,
+            Request::EciesDecrypt { .. } => RequestType::EciesDecrypt,
+            Request::TlsHkdfExtract { .. } => RequestType::TlsHkdfExtract,
+            Request::TlsHkdfExpandLabel { .. } => RequestType::TlsHkdfExpandLabel,
+            Request::TlsDeriveSecret { .. } => RequestType::TlsDeriveSecret,
+            Request::TlsDeriveIv { .. } => RequestType::TlsDeriveIv,
+            Request::TlsFinished { .. } => RequestType::TlsFinished,
+            Request::TlsSignCertificateVerify { .. } => RequestType::TlsSignCertificateVerify,
+            Request::NoiseStartHandshake { .. } => RequestType::NoiseStartHandshake,
+            Request::NoiseWriteMessage { .. } => RequestType::NoiseWriteMessage,
+            Request::NoiseReadMessage { .. } => RequestType::NoiseReadMessage,
+            Request::NoiseSessionInfo { .. } => RequestType::NoiseSessionInfo,
+            Request::NoiseEncrypt { .. } => RequestType::NoiseEncrypt,
+            Request::NoiseDecrypt { .. } => RequestType::NoiseDecrypt,
+            Request::NoiseCloseSession { .. } => RequestType::NoiseCloseSession,
+            Request::SecOcAuthenticate { .. } => RequestType::SecOcAuthenticate,
+            Request::SecOcVerify { .. } => RequestType::SecOcVerify,
+            Request::CreateCounter { .. } => RequestType::CreateCounter,
+            Request::ReadCounter { .. } => RequestType::ReadCounter,
+            Request::IncrementCounter { .. } => RequestType::IncrementCounter,
+            Request::VerifyImageStart { .. } => RequestType::VerifyImageStart,
+            Request::VerifyImageUpdate { .. } => RequestType::VerifyImageUpdate,
+            Request::VerifyImageFinish { .. } => RequestType::VerifyImageFinish,
+            Request::ExtendMeasurement { .. } => RequestType::ExtendMeasurement,
+            Request::ReadMeasurement { .. } => RequestType::ReadMeasurement,
+            Request::QuoteMeasurements { .. } => RequestType::QuoteMeasurements,
+            Request::DeriveDiceAlias { .. } => RequestType::DeriveDiceAlias,
+            Request::ProcessSpdmMessage { .. } => RequestType::ProcessSpdmMessage,
+            Request::ReseedRng { .. } => RequestType::ReseedRng,
+            Request::DeleteKey { .. } => RequestType::DeleteKey,
+            Request::GetKeyInfo { .. } => RequestType::GetKeyInfo,
+            Request::ListKeys { .. } => RequestType::ListKeys,
         }
     }
 
@@ -472,6 +1040,43 @@ impl<'data> Request<'data> {
             Request::SignExternalKey { client_id, .. } => *client_id = new_client_id,
             Request::Verify { client_id, .. } => *client_id = new_client_id,
             Request::VerifyExternalKey { client_id, .. } => *client_id = new_client_id,
+            Request::SignJws { client_id, .. } => *client_id = new_client_id,
+            Request::VerifyJws { client_id, .. } => *client_id = new_client_id,
+            Request::VerifyJwsExternalKey { client_id, .. } => *client_id = new_client_id,
+            Request::HpkeSeal { client_id, .. } => *client_id = new_client_id,
+            Request::HpkeOpen { client_id, .. } => *client_id = new_client_id,
+            Request::EciesEncrypt { client_id, .. } => *client_id = new_client_id,
+            Request::EciesDecrypt { client_id, .. } => *client_id = new_client_id,
+            Request::TlsHkdfExtract { client_id, .. } => *client_id = new_client_id,
+            Request::TlsHkdfExpandLabel { client_id, .. } => *client_id = new_client_id,
+            Request::TlsDeriveSecret { client_id, .. } => *client_id = new_client_id,
+            Request::TlsDeriveIv { client_id, .. } => *client_id = new_client_id,
+            Request::TlsFinished { client_id, .. } => *client_id = new_client_id,
+            Request::TlsSignCertificateVerify { client_id, .. } => *client_id = new_client_id,
+            Request::NoiseStartHandshake { client_id, .. } => *client_id = new_client_id,
+            Request::NoiseWriteMessage { client_id, .. } => *client_id = new_client_id,
+            Request::NoiseReadMessage { client_id, .. } => *client_id = new_client_id,
+            Request::NoiseSessionInfo { client_id, .. } => *client_id = new_client_id,
+            Request::NoiseEncrypt { client_id, .. } => *client_id = new_client_id,
+            Request::NoiseDecrypt { client_id, .. } => *client_id = new_client_id,
+            Request::NoiseCloseSession { client_id, .. } => *client_id = new_client_id,
+            Request::SecOcAuthenticate { client_id, .. } => *client_id = new_client_id,
+            Request::SecOcVerify { client_id, .. } => *client_id = new_client_id,
+            Request::CreateCounter { client_id, .. } => *client_id = new_client_id,
+            Request::ReadCounter { client_id, .. } => *client_id = new_client_id,
+            Request::IncrementCounter { client_id, .. } => *client_id = new_client_id,
+            Request::VerifyImageStart { client_id, .. } => *client_id = new_client_id,
+            Request::VerifyImageUpdate { client_id, .. } => *client_id = new_client_id,
+            Request::VerifyImageFinish { client_id, .. } => *client_id = new_client_id,
+            Request::ExtendMeasurement { client_id, .. } => *client_id = new_client_id,
+            Request::ReadMeasurement { client_id, .. } => *client_id = new_client_id,
+            Request::QuoteMeasurements { client_id, .. } => *client_id = new_client_id,
+            Request::DeriveDiceAlias { client_id, .. } => *client_id = new_client_id,
+            Request::ProcessSpdmMessage { client_id, .. } => *client_id = new_client_id,
+            Request::ReseedRng { client_id, .. } => *client_id = new_client_id,
+            Request::DeleteKey { client_id, .. } => *client_id = new_client_id,
+            Request::GetKeyInfo { client_id, .. } => *client_id = new_client_id,
+            Request::ListKeys { client_id, .. } => *client_id = new_client_id,
         }
     }
 
@@ -506,6 +1111,43 @@ impl<'data> Request<'data> {
             Request::SignExternalKey { request_id, .. } => *request_id = new_request_id,
             Request::Verify { request_id, .. } => *request_id = new_request_id,
             Request::VerifyExternalKey { request_id, .. } => *request_id = new_request_id,
+            Request::SignJws { request_id, .. } => *request_id = new_request_id,
+            Request::VerifyJws { request_id, .. } => *request_id = new_request_id,
+            Request::VerifyJwsExternalKey { request_id, .. } => *request_id = new_request_id,
+            Request::HpkeSeal { request_id, .. } => *request_id = new_request_id,
+            Request::HpkeOpen { request_id, .. } => *request_id = new_request_id,
+            Request::EciesEncrypt { request_id, .. } => *request_id = new_request_id,
+            Request::EciesDecrypt { request_id, .. } => *request_id = new_request_id,
+            Request::TlsHkdfExtract { request_id, .. } => *request_id = new_request_id,
+            Request::TlsHkdfExpandLabel { request_id, .. } => *request_id = new_request_id,
+            Request::TlsDeriveSecret { request_id, .. } => *request_id = new_request_id,
+            Request::TlsDeriveIv { request_id, .. } => *request_id = new_request_id,
+            Request::TlsFinished { request_id, .. } => *request_id = new_request_id,
+            Request::TlsSignCertificateVerify { request_id, .. } => *request_id = new_request_id,
+            Request::NoiseStartHandshake { request_id, .. } => *request_id = new_request_id,
+            Request::NoiseWriteMessage { request_id, .. } => *request_id = new_request_id,
+            Request::NoiseReadMessage { request_id, .. } => *request_id = new_request_id,
+            Request::NoiseSessionInfo { request_id, .. } => *request_id = new_request_id,
+            Request::NoiseEncrypt { request_id, .. } => *request_id = new_request_id,
+            Request::NoiseDecrypt { request_id, .. } => *request_id = new_request_id,
+            Request::NoiseCloseSession { request_id, .. } => *request_id = new_request_id,
+            Request::SecOcAuthenticate { request_id, .. } => *request_id = new_request_id,
+            Request::SecOcVerify { request_id, .. } => *request_id = new_request_id,
+            Request::CreateCounter { request_id, .. } => *request_id = new_request_id,
+            Request::ReadCounter { request_id, .. } => *request_id = new_request_id,
+            Request::IncrementCounter { request_id, .. } => *request_id = new_request_id,
+            Request::VerifyImageStart { request_id, .. } => *request_id = new_request_id,
+            Request::VerifyImageUpdate { request_id, .. } => *request_id = new_request_id,
+            Request::VerifyImageFinish { request_id, .. } => *request_id = new_request_id,
+            Request::ExtendMeasurement { request_id, .. } => *request_id = new_request_id,
+            Request::ReadMeasurement { request_id, .. } => *request_id = new_request_id,
+            Request::QuoteMeasurements { request_id, .. } => *request_id = new_request_id,
+            Request::DeriveDiceAlias { request_id, .. } => *request_id = new_request_id,
+            Request::ProcessSpdmMessage { request_id, .. } => *request_id = new_request_id,
+            Request::ReseedRng { request_id, .. } => *request_id = new_request_id,
+            Request::DeleteKey { request_id, .. } => *request_id = new_request_id,
+            Request::GetKeyInfo { request_id, .. } => *request_id = new_request_id,
+            Request::ListKeys { request_id, .. } => *request_id = new_request_id,
         }
     }
 }
@@ -531,6 +1173,42 @@ impl<'data> Response<'data> {
             Response::DecryptAesCbc { client_id, .. } => client_id,
             Response::Sign { client_id, .. } => client_id,
             Response::Verify { client_id, .. } => client_id,
+            Response::SignJws { client_id, .. } => client_id,
+            Response::VerifyJws { client_id, .. } => client_id,
+            Response::HpkeSeal { client_id, .. } => client_id,
+            Response::HpkeOpen { client_id, .. } => client_id,
+            Response::EciesEncrypt { client_id, .. } => client_id,
+            Response::EciesDecrypt { client_id, .. } => client_id,
+            Response::TlsHkdfExtract { client_id, .. } => client_id,
+            Response::TlsHkdfExpandLabel { client_id, .. } => client_id,
+            Response::TlsDeriveSecret { client_id, .. } => client_id,
+            Response::TlsDeriveIv { client_id, .. } => client_id,
+            Response::TlsFinished { client_id, .. } => client_id,
+            Response::TlsSignCertificateVerify { client_id, .. } => client_id,
+            Response::NoiseStartHandshake { client_id, .. } => client_id,
+            Response::NoiseWriteMessage { client_id, .. } => client_id,
+            Response::NoiseReadMessage { client_id, .. } => client_id,
+            Response::NoiseSessionInfo { client_id, .. } => client_id,
+            Response::NoiseEncrypt { client_id, .. } => client_id,
+            Response::NoiseDecrypt { client_id, .. } => client_id,
+            Response::NoiseCloseSession { client_id, .. } => client_id,
+            Response::SecOcAuthenticate { client_id, .. } => client_id,
+            Response::SecOcVerify { client_id, .. } => client_id,
+            Response::CreateCounter { client_id, .. } => client_id,
+            Response::ReadCounter { client_id, .. } => client_id,
+            Response::IncrementCounter { client_id, .. } => client_id,
+            Response::VerifyImageStart { client_id, .. } => client_id,
+            Response::VerifyImageUpdate { client_id, .. } => client_id,
+            Response::VerifyImageFinish { client_id, .. } => client_id,
+            Response::ExtendMeasurement { client_id, .. } => client_id,
+            Response::ReadMeasurement { client_id, .. } => client_id,
+            Response::QuoteMeasurements { client_id, .. } => client_id,
+            Response::DeriveDiceAlias { client_id, .. } => client_id,
+            Response::ProcessSpdmMessage { client_id, .. } => client_id,
+            Response::ReseedRng { client_id, .. } => client_id,
+            Response::DeleteKey { client_id, .. } => client_id,
+            Response::GetKeyInfo { client_id, .. } => client_id,
+            Response::ListKeys { client_id, .. } => client_id,
         }
     }
 }
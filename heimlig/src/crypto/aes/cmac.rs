@@ -0,0 +1,219 @@
+use crate::crypto::Error;
+use aes::{cipher::KeyInit, Aes128, Aes192, Aes256};
+use cmac::{Cmac, Mac};
+
+/// Computes the CMAC of the concatenation of `message` parts.
+fn compute<C>(key: &[u8], message: &[&[u8]]) -> Result<C, Error>
+where
+    C: Mac + KeyInit,
+{
+    let mut mac =
+        <C as KeyInit>::new_from_slice(key).map_err(|_| Error::InvalidSymmetricKeySize)?;
+    for part in message {
+        Mac::update(&mut mac, part);
+    }
+    Ok(mac)
+}
+
+/// AES-CMAC generation: generic over an underlying CMAC implementation.
+///
+/// The tag is truncated to the size of the `tag` buffer by keeping its leftmost bytes.
+fn generate<C>(key: &[u8], message: &[&[u8]], tag: &mut [u8]) -> Result<(), Error>
+where
+    C: Mac + KeyInit,
+{
+    if tag.is_empty() || tag.len() > super::CMAC_TAG_SIZE {
+        return Err(Error::InvalidTagSize);
+    }
+    let computed_tag = compute::<C>(key, message)?.finalize().into_bytes();
+    tag.copy_from_slice(&computed_tag[..tag.len()]);
+    Ok(())
+}
+
+/// AES-CMAC verification: generic over an underlying CMAC implementation.
+///
+/// The `tag` is compared in constant time against the leftmost bytes of the computed tag.
+fn verify<C>(key: &[u8], message: &[&[u8]], tag: &[u8]) -> Result<(), Error>
+where
+    C: Mac + KeyInit,
+{
+    if tag.is_empty() || tag.len() > super::CMAC_TAG_SIZE {
+        return Err(Error::InvalidTagSize);
+    }
+    compute::<C>(key, message)?
+        .verify_truncated_left(tag)
+        .map_err(|_| Error::InvalidSignature)
+}
+
+macro_rules! define_aes_cmac_impl {
+    (
+        $generator:ident,
+        $verifier:ident,
+        $core:ty
+    ) => {
+        /// Generates a (possibly truncated) CMAC tag.
+        ///
+        /// The `message` is given as a list of parts that are authenticated as if they were
+        /// concatenated.
+        ///
+        /// # Errors
+        ///
+        /// * `InvalidSymmetricKeySize`: The size of the `key` does not match the cipher.
+        /// * `InvalidTagSize`: The size of the `tag` is zero or exceeds [CMAC_TAG_SIZE].
+        ///
+        /// [CMAC_TAG_SIZE]: super::CMAC_TAG_SIZE
+        pub fn $generator(key: &[u8], message: &[&[u8]], tag: &mut [u8]) -> Result<(), Error> {
+            generate::<$core>(key, message, tag)
+        }
+
+        /// Verifies a (possibly truncated) CMAC tag.
+        ///
+        /// # Errors
+        ///
+        /// * `InvalidSymmetricKeySize`: The size of the `key` does not match the cipher.
+        /// * `InvalidTagSize`: The size of the `tag` is zero or exceeds [CMAC_TAG_SIZE].
+        /// * `InvalidSignature`: The `tag` does not match the `message`.
+        ///
+        /// [CMAC_TAG_SIZE]: super::CMAC_TAG_SIZE
+        pub fn $verifier(key: &[u8], message: &[&[u8]], tag: &[u8]) -> Result<(), Error> {
+            verify::<$core>(key, message, tag)
+        }
+    };
+}
+
+define_aes_cmac_impl!(aes128cmac_generate, aes128cmac_verify, Cmac<Aes128>);
+define_aes_cmac_impl!(aes192cmac_generate, aes192cmac_verify, Cmac<Aes192>);
+define_aes_cmac_impl!(aes256cmac_generate, aes256cmac_verify, Cmac<Aes256>);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::aes::CMAC_TAG_SIZE;
+
+    // Test vectors from RFC 4493 and NIST SP 800-38B
+    const KEY128: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const KEY256: &str = "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+    const MESSAGE: &str = concat!(
+        "6bc1bee22e409f96e93d7e117393172a",
+        "ae2d8a571e03ac9c9eb76fac45af8e51",
+        "30c81c46a35ce411e5fbc1191a0a52ef",
+        "f69f2445df4f9b17ad2b417be66c3710"
+    );
+
+    macro_rules! define_aes_cmac_test {
+        (
+            $test_name:ident,
+            $generator:ident,
+            $verifier:ident,
+            $key:expr,
+            $message_size:expr,
+            $tag:expr
+        ) => {
+            #[test]
+            fn $test_name() {
+                let key = hex::decode($key).unwrap();
+                let message = hex::decode(MESSAGE).unwrap();
+                let message = &message[..$message_size];
+                let expected_tag = hex::decode($tag).unwrap();
+
+                let mut tag = [0u8; CMAC_TAG_SIZE];
+                $generator(&key, &[message], &mut tag).expect("failed to generate tag");
+                assert_eq!(tag.as_slice(), expected_tag);
+                $verifier(&key, &[message], &tag).expect("failed to verify tag");
+
+                // Split message and truncated tag
+                let (first, second) = message.split_at($message_size / 2);
+                let mut truncated_tag = [0u8; 4];
+                $generator(&key, &[first, second], &mut truncated_tag)
+                    .expect("failed to generate truncated tag");
+                assert_eq!(truncated_tag.as_slice(), &expected_tag[..4]);
+                $verifier(&key, &[first, &[], second], &truncated_tag)
+                    .expect("failed to verify truncated tag");
+            }
+        };
+    }
+
+    define_aes_cmac_test!(
+        test_aes128cmac_empty,
+        aes128cmac_generate,
+        aes128cmac_verify,
+        KEY128,
+        0,
+        "bb1d6929e95937287fa37d129b756746"
+    );
+    define_aes_cmac_test!(
+        test_aes128cmac_one_block,
+        aes128cmac_generate,
+        aes128cmac_verify,
+        KEY128,
+        16,
+        "070a16b46b4d4144f79bdd9dd04a287c"
+    );
+    define_aes_cmac_test!(
+        test_aes128cmac_partial_block,
+        aes128cmac_generate,
+        aes128cmac_verify,
+        KEY128,
+        40,
+        "dfa66747de9ae63030ca32611497c827"
+    );
+    define_aes_cmac_test!(
+        test_aes128cmac_four_blocks,
+        aes128cmac_generate,
+        aes128cmac_verify,
+        KEY128,
+        64,
+        "51f0bebf7e3b9d92fc49741779363cfe"
+    );
+    define_aes_cmac_test!(
+        test_aes256cmac_empty,
+        aes256cmac_generate,
+        aes256cmac_verify,
+        KEY256,
+        0,
+        "028962f61b7bf89efc6b551f4667d983"
+    );
+    define_aes_cmac_test!(
+        test_aes256cmac_one_block,
+        aes256cmac_generate,
+        aes256cmac_verify,
+        KEY256,
+        16,
+        "28a7023f452e8f82bd4bf28d8c37c35c"
+    );
+
+    #[test]
+    fn test_aes_cmac_errors() {
+        let key = hex::decode(KEY128).unwrap();
+        let message = hex::decode(MESSAGE).unwrap();
+        let mut tag = [0u8; CMAC_TAG_SIZE];
+
+        assert_eq!(
+            aes256cmac_generate(&key, &[&message], &mut tag),
+            Err(Error::InvalidSymmetricKeySize)
+        );
+        assert_eq!(
+            aes128cmac_generate(&key, &[&message], &mut []),
+            Err(Error::InvalidTagSize)
+        );
+        assert_eq!(
+            aes128cmac_generate(&key, &[&message], &mut [0u8; CMAC_TAG_SIZE + 1]),
+            Err(Error::InvalidTagSize)
+        );
+
+        aes128cmac_generate(&key, &[&message], &mut tag).expect("failed to generate tag");
+        assert_eq!(
+            aes128cmac_verify(&key, &[&message[1..]], &tag),
+            Err(Error::InvalidSignature)
+        );
+        tag[0] ^= 1;
+        assert_eq!(
+            aes128cmac_verify(&key, &[&message], &tag[..8]),
+            Err(Error::InvalidSignature)
+        );
+        assert_eq!(
+            aes128cmac_verify(&key, &[&message], &[]),
+            Err(Error::InvalidTagSize)
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use crate::crypto::ecdsa::{nist_p256_sign, nist_p256_verify, nist_p384_sign, nist_p384_verify};
+use crate::crypto::ed25519::{ed25519_sign, ed25519_verify};
+use crate::crypto::hash::SHA256_SIZE;
+use crate::crypto::jws::{Algorithm, MAX_SIGNATURE_SIZE};
+use crate::crypto::Error;
+use sha2::{Digest, Sha256};
+
+/// Size of a measurement register and of the digests it is extended with in bytes.
+pub const REGISTER_SIZE: usize = SHA256_SIZE;
+/// Maximum number of registers that can be selected for a quote.
+pub const MAX_REGISTERS: usize = u32::BITS as usize;
+/// Maximum size of the caller nonce of a quote in bytes.
+pub const MAX_NONCE_SIZE: usize = 64;
+/// Magic value at the start of every quote.
+pub const MAGIC: [u8; 4] = *b"HQT1";
+/// Maximum size of a signed quote in bytes.
+pub const MAX_QUOTE_SIZE: usize =
+    MAGIC.len() + 4 + SHA256_SIZE + 1 + MAX_NONCE_SIZE + MAX_SIGNATURE_SIZE;
+
+/// Size of the signed quote for the given signature algorithm and nonce size.
+///
+/// A quote is encoded as `magic || selection || composite digest || nonce size || nonce ||
+/// signature`. The selection is a big-endian `u32` bit mask where bit `i` selects register `i`.
+/// The composite digest is the SHA-256 digest of the selected register values in ascending order.
+/// The nonce size is a single byte and the signature covers all preceding bytes. ECDSA signatures
+/// are encoded as `r||s`.
+pub const fn quote_size(algorithm: Algorithm, nonce_size: usize) -> usize {
+    MAGIC.len() + 4 + SHA256_SIZE + 1 + nonce_size + algorithm.signature_size()
+}
+
+/// Extends a measurement register: `register = SHA-256(register || digest)`.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidDigestSize`: The `digest` is not a SHA-256 digest.
+pub fn extend(register: &mut [u8; REGISTER_SIZE], digest: &[u8]) -> Result<(), Error> {
+    if digest.len() != REGISTER_SIZE {
+        return Err(Error::InvalidDigestSize);
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(register.as_slice());
+    hasher.update(digest);
+    register.copy_from_slice(&hasher.finalize());
+    Ok(())
+}
+
+/// Computes the composite digest of the registers selected by `selection`.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidBufferSize`: The `selection` references registers that do not exist.
+pub fn composite_digest(
+    selection: u32,
+    registers: &[[u8; REGISTER_SIZE]],
+) -> Result<[u8; SHA256_SIZE], Error> {
+    let mut hasher = Sha256::new();
+    for index in 0..MAX_REGISTERS {
+        if selection & (1 << index) == 0 {
+            continue;
+        }
+        let register = registers.get(index).ok_or(Error::InvalidBufferSize)?;
+        hasher.update(register);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Signs a quote over the registers selected by `selection` and a caller `nonce`.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidBufferSize`: The `selection` references registers that do not exist, the `nonce` is
+///   larger than [MAX_NONCE_SIZE] or the `quote` buffer is too small.
+/// * `InvalidPrivateKey`: The `private_key` is not valid for `algorithm`.
+pub fn sign_quote<'a>(
+    algorithm: Algorithm,
+    private_key: &[u8],
+    selection: u32,
+    registers: &[[u8; REGISTER_SIZE]],
+    nonce: &[u8],
+    quote: &'a mut [u8],
+) -> Result<&'a mut [u8], Error> {
+    if nonce.len() > MAX_NONCE_SIZE {
+        return Err(Error::InvalidBufferSize);
+    }
+    let size = quote_size(algorithm, nonce.len());
+    if quote.len() < size {
+        return Err(Error::InvalidBufferSize);
+    }
+    let digest = composite_digest(selection, registers)?;
+    let (info, signature) = quote[..size].split_at_mut(size - algorithm.signature_size());
+    info[..4].copy_from_slice(&MAGIC);
+    info[4..8].copy_from_slice(&selection.to_be_bytes());
+    info[8..8 + SHA256_SIZE].copy_from_slice(&digest);
+    info[8 + SHA256_SIZE] = nonce.len() as u8;
+    info[9 + SHA256_SIZE..].copy_from_slice(nonce);
+    match algorithm {
+        Algorithm::Es256 => nist_p256_sign(private_key, info, signature)?,
+        Algorithm::Es384 => nist_p384_sign(private_key, info, signature)?,
+        Algorithm::EdDsa => ed25519_sign(private_key, info, signature)?,
+    }
+    Ok(&mut quote[..size])
+}
+
+/// Verifies the signature of a quote.
+///
+/// returns: The selection, the composite digest and the nonce of the quote.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidEncoding`: The `quote` is malformed.
+/// * `InvalidPublicKey`: The `public_key` is not valid for `algorithm`.
+/// * `InvalidSignature`: The quote was not signed by `algorithm` with the given key.
+pub fn verify_quote<'a>(
+    algorithm: Algorithm,
+    public_key: &[u8],
+    quote: &'a [u8],
+) -> Result<(u32, &'a [u8], &'a [u8]), Error> {
+    let nonce_size = *quote.get(8 + SHA256_SIZE).ok_or(Error::InvalidEncoding)? as usize;
+    if nonce_size > MAX_NONCE_SIZE
+        || quote.len() != quote_size(algorithm, nonce_size)
+        || quote[..4] != MAGIC
+    {
+        return Err(Error::InvalidEncoding);
+    }
+    let (info, signature) = quote.split_at(quote.len() - algorithm.signature_size());
+    match algorithm {
+        Algorithm::Es256 => nist_p256_verify(public_key, info, signature)?,
+        Algorithm::Es384 => nist_p384_verify(public_key, info, signature)?,
+        Algorithm::EdDsa => ed25519_verify(public_key, info, signature)?,
+    }
+    let selection = u32::from_be_bytes([info[4], info[5], info[6], info[7]]);
+    Ok((
+        selection,
+        &info[8..8 + SHA256_SIZE],
+        &info[9 + SHA256_SIZE..],
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::ecdsa::nist_p256_generate_key_pair;
+    use crate::crypto::hash::sha256;
+    use crate::crypto::rng::{test::TestEntropySource, Rng};
+
+    const NONCE: &[u8] = b"backend nonce";
+
+    #[test]
+    fn extend_register() {
+        let mut register = [0u8; REGISTER_SIZE];
+        let digest = sha256(b"bootloader");
+        extend(&mut register, &digest).expect("failed to extend register");
+        let mut expected = [0u8; 2 * REGISTER_SIZE];
+        expected[REGISTER_SIZE..].copy_from_slice(&digest);
+        assert_eq!(register, sha256(expected));
+        assert_eq!(
+            extend(&mut register, &digest[1..]),
+            Err(Error::InvalidDigestSize)
+        );
+    }
+
+    #[test]
+    fn sign_verify_quote() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, public_key) = nist_p256_generate_key_pair(&mut rng);
+        let mut registers = [[0u8; REGISTER_SIZE]; 3];
+        extend(&mut registers[0], &sha256(b"bootloader")).unwrap();
+        extend(&mut registers[2], &sha256(b"application")).unwrap();
+        let mut quote = [0u8; MAX_QUOTE_SIZE];
+
+        let quote = sign_quote(
+            Algorithm::Es256,
+            &private_key,
+            0b101,
+            &registers,
+            NONCE,
+            &mut quote,
+        )
+        .expect("failed to sign quote");
+        assert_eq!(quote.len(), quote_size(Algorithm::Es256, NONCE.len()));
+        let (selection, digest, nonce) =
+            verify_quote(Algorithm::Es256, &public_key, quote).expect("failed to verify quote");
+        assert_eq!(selection, 0b101);
+        let mut expected = [0u8; 2 * REGISTER_SIZE];
+        expected[..REGISTER_SIZE].copy_from_slice(&registers[0]);
+        expected[REGISTER_SIZE..].copy_from_slice(&registers[2]);
+        assert_eq!(digest, sha256(expected));
+        assert_eq!(nonce, NONCE);
+
+        quote[5] ^= 1;
+        assert_eq!(
+            verify_quote(Algorithm::Es256, &public_key, quote),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn quote_errors() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, public_key) = nist_p256_generate_key_pair(&mut rng);
+        let registers = [[0u8; REGISTER_SIZE]; 2];
+        let mut quote = [0u8; MAX_QUOTE_SIZE];
+
+        assert_eq!(
+            sign_quote(
+                Algorithm::Es256,
+                &private_key,
+                0b100,
+                &registers,
+                NONCE,
+                &mut quote
+            ),
+            Err(Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            sign_quote(
+                Algorithm::Es256,
+                &private_key,
+                0b1,
+                &registers,
+                &[0u8; MAX_NONCE_SIZE + 1],
+                &mut quote
+            ),
+            Err(Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            sign_quote(
+                Algorithm::Es256,
+                &private_key,
+                0b1,
+                &registers,
+                NONCE,
+                &mut quote[..40]
+            ),
+            Err(Error::InvalidBufferSize)
+        );
+        let size = sign_quote(
+            Algorithm::Es256,
+            &private_key,
+            0b1,
+            &registers,
+            NONCE,
+            &mut quote,
+        )
+        .expect("failed to sign quote")
+        .len();
+        assert_eq!(
+            verify_quote(Algorithm::Es256, &public_key, &quote[..size - 1]),
+            Err(Error::InvalidEncoding)
+        );
+        assert_eq!(
+            verify_quote(Algorithm::Es256, &public_key, &quote[..20]),
+            Err(Error::InvalidEncoding)
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use crate::crypto::ecdsa::{
+    nist_p256_sign, nist_p256_verify, NIST_P256_PRIVATE_KEY_SIZE, NIST_P256_PUBLIC_KEY_SIZE,
+    NIST_P256_SIGNATURE_SIZE,
+};
+use crate::crypto::hash::SHA256_SIZE;
+use crate::crypto::hkdf::{hkdf_sha256_expand, hkdf_sha256_extract, SHA256_PRK_SIZE};
+use crate::crypto::Error;
+use elliptic_curve::sec1::ToEncodedPoint;
+use p256::SecretKey;
+use zeroize::Zeroizing;
+
+/// Size of a Compound Device Identifier (CDI) in bytes.
+pub const CDI_SIZE: usize = SHA256_PRK_SIZE;
+/// Size of a firmware measurement in bytes.
+pub const MEASUREMENT_SIZE: usize = SHA256_SIZE;
+/// Magic value at the start of every alias certificate.
+pub const MAGIC: [u8; 4] = *b"HDA1";
+/// Size of the signed part of an alias certificate in bytes.
+pub const TBS_SIZE: usize = MAGIC.len() + MEASUREMENT_SIZE + NIST_P256_PUBLIC_KEY_SIZE;
+/// Size of an alias certificate in bytes.
+pub const ALIAS_CERTIFICATE_SIZE: usize = TBS_SIZE + NIST_P256_SIGNATURE_SIZE;
+
+const CDI_LABEL: &[u8] = b"CDI_Attest";
+const DEVICE_ID_LABEL: &[u8] = b"DICE DeviceID";
+const ALIAS_LABEL: &[u8] = b"DICE Alias";
+/// Number of candidates tried before key derivation gives up. Each candidate is rejected with a
+/// probability of about 2^-32.
+const MAX_KEY_CANDIDATES: u8 = 8;
+
+/// NIST P-256 key pair derived by DICE.
+pub struct DiceKeyPair {
+    pub private_key: Zeroizing<[u8; NIST_P256_PRIVATE_KEY_SIZE]>,
+    pub public_key: [u8; NIST_P256_PUBLIC_KEY_SIZE],
+}
+
+/// Derives the Compound Device Identifier of the next layer from the Unique Device Secret (UDS)
+/// and the measurement of the next layer: `CDI = HKDF-SHA256(salt = measurement, ikm = UDS)`.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidDigestSize`: The `measurement` is not a SHA-256 digest.
+pub fn derive_cdi(uds: &[u8], measurement: &[u8]) -> Result<Zeroizing<[u8; CDI_SIZE]>, Error> {
+    if measurement.len() != MEASUREMENT_SIZE {
+        return Err(Error::InvalidDigestSize);
+    }
+    let mut prk = Zeroizing::new([0u8; SHA256_PRK_SIZE]);
+    hkdf_sha256_extract(measurement, &[uds], prk.as_mut_slice())?;
+    let mut cdi = Zeroizing::new([0u8; CDI_SIZE]);
+    hkdf_sha256_expand(prk.as_slice(), &[CDI_LABEL], cdi.as_mut_slice())?;
+    Ok(cdi)
+}
+
+/// Deterministically derives a NIST P-256 key pair from `secret` and `label`.
+///
+/// Candidate scalars are expanded with HKDF-SHA256 until one is a valid private key.
+fn derive_key_pair(secret: &[u8], label: &[u8]) -> Result<DiceKeyPair, Error> {
+    let mut prk = Zeroizing::new([0u8; SHA256_PRK_SIZE]);
+    hkdf_sha256_extract(&[], &[secret], prk.as_mut_slice())?;
+    let mut candidate = Zeroizing::new([0u8; NIST_P256_PRIVATE_KEY_SIZE]);
+    for counter in 0..MAX_KEY_CANDIDATES {
+        hkdf_sha256_expand(
+            prk.as_slice(),
+            &[label, &[counter]],
+            candidate.as_mut_slice(),
+        )?;
+        let Ok(secret_key) = SecretKey::from_slice(candidate.as_slice()) else {
+            continue;
+        };
+        let mut public_key = [0u8; NIST_P256_PUBLIC_KEY_SIZE];
+        // Skip the tag of the uncompressed SEC 1 encoding
+        public_key
+            .copy_from_slice(&secret_key.public_key().to_encoded_point(false).as_bytes()[1..]);
+        return Ok(DiceKeyPair {
+            private_key: candidate,
+            public_key,
+        });
+    }
+    Err(Error::InvalidPrivateKey)
+}
+
+/// Derives the DeviceID key pair from the Unique Device Secret.
+///
+/// The DeviceID key pair is the long-term identity of the device and does not depend on any
+/// firmware measurement.
+pub fn derive_device_id(uds: &[u8]) -> Result<DiceKeyPair, Error> {
+    derive_key_pair(uds, DEVICE_ID_LABEL)
+}
+
+/// Derives the alias key pair of the next layer from its Compound Device Identifier.
+pub fn derive_alias(cdi: &[u8; CDI_SIZE]) -> Result<DiceKeyPair, Error> {
+    derive_key_pair(cdi, ALIAS_LABEL)
+}
+
+/// Issues an alias certificate for the next layer.
+///
+/// The certificate is encoded as `magic || measurement || alias public key || signature`. The
+/// signature is an ES256 signature (`r||s`) of the DeviceID key over all preceding bytes.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidDigestSize`: The `measurement` is not a SHA-256 digest.
+/// * `InvalidPublicKey`: The `alias_public_key` has the wrong size.
+/// * `InvalidBufferSize`: The `certificate` buffer is too small.
+/// * `InvalidPrivateKey`: The `device_id_private_key` is not a NIST P-256 private key.
+pub fn issue_alias_certificate<'a>(
+    device_id_private_key: &[u8],
+    measurement: &[u8],
+    alias_public_key: &[u8],
+    certificate: &'a mut [u8],
+) -> Result<&'a mut [u8], Error> {
+    if measurement.len() != MEASUREMENT_SIZE {
+        return Err(Error::InvalidDigestSize);
+    }
+    if alias_public_key.len() != NIST_P256_PUBLIC_KEY_SIZE {
+        return Err(Error::InvalidPublicKey);
+    }
+    if certificate.len() < ALIAS_CERTIFICATE_SIZE {
+        return Err(Error::InvalidBufferSize);
+    }
+    let (tbs, signature) = certificate[..ALIAS_CERTIFICATE_SIZE].split_at_mut(TBS_SIZE);
+    tbs[..4].copy_from_slice(&MAGIC);
+    tbs[4..4 + MEASUREMENT_SIZE].copy_from_slice(measurement);
+    tbs[4 + MEASUREMENT_SIZE..].copy_from_slice(alias_public_key);
+    nist_p256_sign(device_id_private_key, tbs, signature)?;
+    Ok(&mut certificate[..ALIAS_CERTIFICATE_SIZE])
+}
+
+/// Verifies an alias certificate against the DeviceID public key.
+///
+/// returns: The measurement and the alias public key of the certificate.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidEncoding`: The `certificate` has the wrong size or magic value.
+/// * `InvalidPublicKey`: The `device_id_public_key` is not a NIST P-256 public key.
+/// * `InvalidSignature`: The certificate was not issued by the given DeviceID key.
+pub fn verify_alias_certificate<'a>(
+    device_id_public_key: &[u8],
+    certificate: &'a [u8],
+) -> Result<(&'a [u8], &'a [u8]), Error> {
+    if certificate.len() != ALIAS_CERTIFICATE_SIZE || certificate[..4] != MAGIC {
+        return Err(Error::InvalidEncoding);
+    }
+    let (tbs, signature) = certificate.split_at(TBS_SIZE);
+    nist_p256_verify(device_id_public_key, tbs, signature)?;
+    Ok((&tbs[4..4 + MEASUREMENT_SIZE], &tbs[4 + MEASUREMENT_SIZE..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::ecdsa::nist_p256_generate_key_pair;
+    use crate::crypto::hash::sha256;
+    use crate::crypto::rng::{test::TestEntropySource, EntropySource, Rng};
+
+    #[test]
+    fn derivation_is_deterministic() {
+        let uds = TestEntropySource::default().random_seed();
+        let measurement = sha256(b"layer 1");
+        let cdi = derive_cdi(&uds, &measurement).expect("failed to derive CDI");
+        assert_eq!(
+            cdi,
+            derive_cdi(&uds, &measurement).expect("failed to derive CDI")
+        );
+        assert_ne!(
+            cdi,
+            derive_cdi(&uds, &sha256(b"layer 1'")).expect("failed to derive CDI")
+        );
+        assert_eq!(
+            derive_cdi(&uds, &measurement[1..]),
+            Err(Error::InvalidDigestSize)
+        );
+
+        let device_id = derive_device_id(&uds).expect("failed to derive DeviceID");
+        let alias = derive_alias(&cdi).expect("failed to derive alias key");
+        assert_eq!(
+            device_id.public_key,
+            derive_device_id(&uds)
+                .expect("failed to derive DeviceID")
+                .public_key
+        );
+        assert_eq!(
+            alias.public_key,
+            derive_alias(&cdi)
+                .expect("failed to derive alias key")
+                .public_key
+        );
+        assert_ne!(device_id.public_key, alias.public_key);
+
+        // Derived keys are usable for signing
+        let mut signature = [0u8; NIST_P256_SIGNATURE_SIZE];
+        nist_p256_sign(alias.private_key.as_slice(), b"message", &mut signature)
+            .expect("failed to sign");
+        nist_p256_verify(&alias.public_key, b"message", &signature).expect("failed to verify");
+    }
+
+    #[test]
+    fn issue_verify_alias_certificate() {
+        let uds = TestEntropySource::default().random_seed();
+        let measurement = sha256(b"layer 1");
+        let device_id = derive_device_id(&uds).expect("failed to derive DeviceID");
+        let cdi = derive_cdi(&uds, &measurement).expect("failed to derive CDI");
+        let alias = derive_alias(&cdi).expect("failed to derive alias key");
+        let mut certificate = [0u8; ALIAS_CERTIFICATE_SIZE];
+
+        let certificate = issue_alias_certificate(
+            device_id.private_key.as_slice(),
+            &measurement,
+            &alias.public_key,
+            &mut certificate,
+        )
+        .expect("failed to issue certificate");
+        let (certified_measurement, certified_key) =
+            verify_alias_certificate(&device_id.public_key, certificate)
+                .expect("failed to verify certificate");
+        assert_eq!(certified_measurement, measurement);
+        assert_eq!(certified_key, alias.public_key);
+
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (_, other_public_key) = nist_p256_generate_key_pair(&mut rng);
+        assert_eq!(
+            verify_alias_certificate(&other_public_key, certificate),
+            Err(Error::InvalidSignature)
+        );
+        assert_eq!(
+            verify_alias_certificate(&device_id.public_key, &certificate[1..]),
+            Err(Error::InvalidEncoding)
+        );
+        certificate[10] ^= 1;
+        assert_eq!(
+            verify_alias_certificate(&device_id.public_key, certificate),
+            Err(Error::InvalidSignature)
+        );
+        assert_eq!(
+            issue_alias_certificate(
+                device_id.private_key.as_slice(),
+                &measurement,
+                &alias.public_key,
+                &mut [0u8; ALIAS_CERTIFICATE_SIZE - 1],
+            ),
+            Err(Error::InvalidBufferSize)
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::crypto::drbg::{Drbg, Error};
+use rand::Rng as RandRng;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+/// Generator based on the ChaCha20 stream cipher.
+///
+/// The entropy input is used as the ChaCha20 key directly. Nonces, personalization strings and
+/// additional input are mixed into the key by hashing them together with the entropy input or the
+/// next 32 bytes of output, respectively.
+pub struct ChaCha20Drbg {
+    rng: ChaCha20Rng,
+    reseed_counter: u64,
+}
+
+fn derive_seed(parts: &[&[u8]]) -> Zeroizing<[u8; 32]> {
+    let mut seed = Zeroizing::new([0u8; 32]);
+    let mut non_empty_parts = parts.iter().filter(|part| !part.is_empty());
+    if let (Some(key), None) = (non_empty_parts.next(), non_empty_parts.next()) {
+        if key.len() == seed.len() {
+            seed.copy_from_slice(key);
+            return seed;
+        }
+    }
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    seed.copy_from_slice(&hasher.finalize());
+    seed
+}
+
+impl Drbg for ChaCha20Drbg {
+    const ENTROPY_INPUT_SIZE: usize = 32;
+    const NONCE_SIZE: usize = 0;
+    const MAX_REQUEST_SIZE: usize = usize::MAX;
+    const RESEED_INTERVAL: u64 = u64::MAX;
+
+    fn instantiate(entropy_input: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        let seed = derive_seed(&[entropy_input, nonce, personalization]);
+        ChaCha20Drbg {
+            rng: ChaCha20Rng::from_seed(*seed),
+            reseed_counter: 1,
+        }
+    }
+
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) {
+        let seed = derive_seed(&[entropy_input, additional_input]);
+        self.rng = ChaCha20Rng::from_seed(*seed);
+        self.reseed_counter = 1;
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        if !additional_input.is_empty() {
+            let mut key = Zeroizing::new([0u8; 32]);
+            self.rng.fill(key.as_mut_slice());
+            let seed = derive_seed(&[key.as_slice(), additional_input]);
+            self.rng = ChaCha20Rng::from_seed(*seed);
+        }
+        self.rng.fill(output);
+        self.reseed_counter = self.reseed_counter.saturating_add(1);
+        Ok(())
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn entropy_input_is_key() {
+        let entropy_input = [7u8; 32];
+        let mut drbg = ChaCha20Drbg::instantiate(&entropy_input, &[], &[]);
+        let mut expected = [0u8; 100];
+        let mut output = [0u8; 100];
+        ChaCha20Rng::from_seed(entropy_input).fill(expected.as_mut_slice());
+        drbg.generate(&mut output, &[]).expect("failed to generate");
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn additional_input() {
+        let mut drbg = ChaCha20Drbg::instantiate(&[7u8; 32], &[], &[]);
+        let mut drbg_with_input = ChaCha20Drbg::instantiate(&[7u8; 32], &[], &[]);
+        let mut output = [0u8; 32];
+        let mut output_with_input = [0u8; 32];
+        drbg.generate(&mut output, &[]).expect("failed to generate");
+        drbg_with_input
+            .generate(&mut output_with_input, b"additional input")
+            .expect("failed to generate");
+        assert_ne!(output, output_with_input);
+        assert_eq!(drbg_with_input.reseed_counter(), 2);
+        drbg_with_input.reseed(&[7u8; 32], &[]);
+        drbg_with_input
+            .generate(&mut output_with_input, &[])
+            .expect("failed to generate");
+        assert_eq!(output, output_with_input);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::crypto::drbg::{Drbg, Error};
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::{Aes256, Block};
+use zeroize::{Zeroize, Zeroizing};
+
+const KEY_SIZE: usize = 32;
+const BLOCK_SIZE: usize = 16;
+const SEED_SIZE: usize = KEY_SIZE + BLOCK_SIZE;
+
+/// CTR_DRBG with AES-256 and derivation function as specified in NIST SP 800-90A, section 10.2.
+pub struct CtrDrbg {
+    key: [u8; KEY_SIZE],
+    v: [u8; BLOCK_SIZE],
+    reseed_counter: u64,
+}
+
+fn encrypt(cipher: &Aes256, block: &mut [u8; BLOCK_SIZE]) {
+    cipher.encrypt_block(Block::from_mut_slice(block));
+}
+
+/// BCC function (section 10.3.3) over data that is fed in arbitrarily sized pieces.
+struct Bcc<'a> {
+    cipher: &'a Aes256,
+    chaining_value: [u8; BLOCK_SIZE],
+    position: usize,
+}
+
+impl<'a> Bcc<'a> {
+    fn new(cipher: &'a Aes256) -> Self {
+        Bcc {
+            cipher,
+            chaining_value: [0u8; BLOCK_SIZE],
+            position: 0,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.chaining_value[self.position] ^= byte;
+            self.position += 1;
+            if self.position == BLOCK_SIZE {
+                encrypt(self.cipher, &mut self.chaining_value);
+                self.position = 0;
+            }
+        }
+    }
+
+    /// Pad the data with zeros to a multiple of the block size and return the output block.
+    fn finalize(mut self) -> [u8; BLOCK_SIZE] {
+        if self.position != 0 {
+            encrypt(self.cipher, &mut self.chaining_value);
+        }
+        self.chaining_value
+    }
+}
+
+/// Block_Cipher_df (section 10.3.2) of the concatenation of `input` with an output of
+/// [SEED_SIZE] bytes.
+fn derivation_function(input: &[&[u8]]) -> Zeroizing<[u8; SEED_SIZE]> {
+    let input_size: usize = input.iter().map(|part| part.len()).sum();
+    let df_key: [u8; KEY_SIZE] = core::array::from_fn(|i| i as u8);
+    let cipher = Aes256::new(&df_key.into());
+    let mut temp = Zeroizing::new([0u8; SEED_SIZE]);
+    for (i, block) in temp.chunks_mut(BLOCK_SIZE).enumerate() {
+        let mut bcc = Bcc::new(&cipher);
+        bcc.update(&(i as u32).to_be_bytes());
+        bcc.update(&[0u8; BLOCK_SIZE - 4]);
+        bcc.update(&(input_size as u32).to_be_bytes());
+        bcc.update(&(SEED_SIZE as u32).to_be_bytes());
+        for part in input {
+            bcc.update(part);
+        }
+        bcc.update(&[0x80]);
+        block.copy_from_slice(&bcc.finalize());
+    }
+
+    let cipher = Aes256::new_from_slice(&temp[..KEY_SIZE]).expect("invalid AES-256 key size");
+    let mut x = Zeroizing::new([0u8; BLOCK_SIZE]);
+    x.copy_from_slice(&temp[KEY_SIZE..]);
+    let mut seed = Zeroizing::new([0u8; SEED_SIZE]);
+    for block in seed.chunks_mut(BLOCK_SIZE) {
+        encrypt(&cipher, &mut x);
+        block.copy_from_slice(x.as_slice());
+    }
+    seed
+}
+
+impl CtrDrbg {
+    fn increment_v(&mut self) {
+        self.v = u128::from_be_bytes(self.v).wrapping_add(1).to_be_bytes();
+    }
+
+    /// CTR_DRBG_Update (section 10.2.1.2).
+    fn update(&mut self, provided_data: &[u8; SEED_SIZE]) {
+        let cipher = Aes256::new(&self.key.into());
+        let mut temp = Zeroizing::new([0u8; SEED_SIZE]);
+        for block in temp.chunks_mut(BLOCK_SIZE) {
+            self.increment_v();
+            let mut output = self.v;
+            encrypt(&cipher, &mut output);
+            block.copy_from_slice(&output);
+        }
+        for (temp, provided) in temp.iter_mut().zip(provided_data) {
+            *temp ^= provided;
+        }
+        self.key.copy_from_slice(&temp[..KEY_SIZE]);
+        self.v.copy_from_slice(&temp[KEY_SIZE..]);
+    }
+}
+
+impl Drbg for CtrDrbg {
+    const ENTROPY_INPUT_SIZE: usize = 32;
+    const NONCE_SIZE: usize = 16;
+    const MAX_REQUEST_SIZE: usize = 1 << 16;
+    const RESEED_INTERVAL: u64 = 1 << 48;
+
+    fn instantiate(entropy_input: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        let seed_material = derivation_function(&[entropy_input, nonce, personalization]);
+        let mut drbg = CtrDrbg {
+            key: [0u8; KEY_SIZE],
+            v: [0u8; BLOCK_SIZE],
+            reseed_counter: 1,
+        };
+        drbg.update(&seed_material);
+        drbg
+    }
+
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) {
+        let seed_material = derivation_function(&[entropy_input, additional_input]);
+        self.update(&seed_material);
+        self.reseed_counter = 1;
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        if self.reseed_counter > Self::RESEED_INTERVAL {
+            return Err(Error::ReseedRequired);
+        }
+        if output.len() > Self::MAX_REQUEST_SIZE {
+            return Err(Error::RequestTooLarge);
+        }
+        let additional_input = if additional_input.is_empty() {
+            Zeroizing::new([0u8; SEED_SIZE])
+        } else {
+            let additional_input = derivation_function(&[additional_input]);
+            self.update(&additional_input);
+            additional_input
+        };
+        let cipher = Aes256::new(&self.key.into());
+        for chunk in output.chunks_mut(BLOCK_SIZE) {
+            self.increment_v();
+            let mut block = Zeroizing::new(self.v);
+            encrypt(&cipher, &mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.update(&additional_input);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+}
+
+impl Drop for CtrDrbg {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.v.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex::FromHex;
+
+    const ENTROPY_INPUT: &str = "5a194d5e2b31581454def675fb7958fec7db873e5689fc9d03217c68d8033820";
+    const NONCE: &str = "1b54b8ff0642bff521f15c1c0b665f3f";
+    const RETURNED_BITS: &str = "d098a9e86b47db1a09637ac2f18911bf938c2c0897bc0d01991092aaf9e50c2e\
+                                 ddd2801450b6c48fa1939a05b76a81ae96cdf203a13c8df251b1014b24f63841";
+
+    #[test]
+    fn generate() {
+        let entropy_input = <[u8; 32]>::from_hex(ENTROPY_INPUT).unwrap();
+        let nonce = <[u8; 16]>::from_hex(NONCE).unwrap();
+        let mut drbg = CtrDrbg::instantiate(&entropy_input, &nonce, &[]);
+        let mut output = [0u8; 64];
+        drbg.generate(&mut output, &[]).expect("failed to generate");
+        drbg.generate(&mut output, &[]).expect("failed to generate");
+        assert_eq!(output, <[u8; 64]>::from_hex(RETURNED_BITS).unwrap());
+        assert_eq!(drbg.reseed_counter(), 3);
+    }
+
+    #[test]
+    fn additional_input_and_reseed() {
+        let entropy_input: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let nonce: [u8; 16] = core::array::from_fn(|i| 32 + i as u8);
+        let reseed_entropy_input: [u8; 32] = core::array::from_fn(|i| 48 + i as u8);
+        let mut drbg = CtrDrbg::instantiate(&entropy_input, &nonce, b"heimlig personalization");
+        let mut output = [0u8; 32];
+        drbg.generate(&mut output, b"additional input")
+            .expect("failed to generate");
+        assert_eq!(
+            output,
+            <[u8; 32]>::from_hex(
+                "8cb9fede8f5557d01b0729a122b65f7211f104f5620c8425d3d5703f2cecd1ef"
+            )
+            .unwrap()
+        );
+        drbg.reseed(&reseed_entropy_input, b"additional input");
+        assert_eq!(drbg.reseed_counter(), 1);
+        drbg.generate(&mut output, &[]).expect("failed to generate");
+        assert_eq!(
+            output,
+            <[u8; 32]>::from_hex(
+                "ce7b089f772b79e06088cb019a0bfa4029b110552c7e3eb4e3f061f5facb0270"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let mut drbg = CtrDrbg::instantiate(&[0u8; 32], &[0u8; 16], &[]);
+        let mut output = [0u8; CtrDrbg::MAX_REQUEST_SIZE + 1];
+        assert_eq!(drbg.generate(&mut output, &[]), Err(Error::RequestTooLarge));
+        drbg.reseed_counter = CtrDrbg::RESEED_INTERVAL + 1;
+        assert_eq!(
+            drbg.generate(&mut output[..1], &[]),
+            Err(Error::ReseedRequired)
+        );
+        drbg.reseed(&[1u8; 32], &[]);
+        assert_eq!(drbg.generate(&mut output[..1], &[]), Ok(()));
+    }
+}
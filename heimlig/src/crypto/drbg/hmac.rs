@@ -0,0 +1,162 @@
+use crate::crypto::drbg::{Drbg, Error};
+use crate::crypto::hash::SHA256_SIZE;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+/// HMAC_DRBG with SHA-256 as specified in NIST SP 800-90A, section 10.1.2.
+pub struct HmacDrbg {
+    key: [u8; SHA256_SIZE],
+    v: [u8; SHA256_SIZE],
+    reseed_counter: u64,
+}
+
+impl HmacDrbg {
+    fn hmac(&self, parts: &[&[u8]]) -> [u8; SHA256_SIZE] {
+        let mut mac =
+            <Hmac<Sha256> as Mac>::new_from_slice(&self.key).expect("HMAC accepts any key size");
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().into()
+    }
+
+    /// HMAC_DRBG_Update with the concatenation of `provided_data` (section 10.1.2.2).
+    fn update(&mut self, provided_data: &[&[u8]]) {
+        let provided = provided_data.iter().any(|part| !part.is_empty());
+        for separator in [0x00u8, 0x01] {
+            if separator == 0x01 && !provided {
+                break;
+            }
+            let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.key)
+                .expect("HMAC accepts any key size");
+            mac.update(&self.v);
+            mac.update(&[separator]);
+            for part in provided_data {
+                mac.update(part);
+            }
+            self.key = mac.finalize().into_bytes().into();
+            self.v = self.hmac(&[&self.v]);
+        }
+    }
+}
+
+impl Drbg for HmacDrbg {
+    const ENTROPY_INPUT_SIZE: usize = 32;
+    const NONCE_SIZE: usize = 16;
+    const MAX_REQUEST_SIZE: usize = 1 << 16;
+    const RESEED_INTERVAL: u64 = 1 << 48;
+
+    fn instantiate(entropy_input: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        let mut drbg = HmacDrbg {
+            key: [0x00; SHA256_SIZE],
+            v: [0x01; SHA256_SIZE],
+            reseed_counter: 1,
+        };
+        drbg.update(&[entropy_input, nonce, personalization]);
+        drbg
+    }
+
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) {
+        self.update(&[entropy_input, additional_input]);
+        self.reseed_counter = 1;
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        if self.reseed_counter > Self::RESEED_INTERVAL {
+            return Err(Error::ReseedRequired);
+        }
+        if output.len() > Self::MAX_REQUEST_SIZE {
+            return Err(Error::RequestTooLarge);
+        }
+        if !additional_input.is_empty() {
+            self.update(&[additional_input]);
+        }
+        for chunk in output.chunks_mut(SHA256_SIZE) {
+            self.v = self.hmac(&[&self.v]);
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+        self.update(&[additional_input]);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+}
+
+impl Drop for HmacDrbg {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.v.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex::FromHex;
+
+    // NIST CAVP HMAC_DRBG, SHA-256, no prediction resistance, no reseed, COUNT = 0
+    const ENTROPY_INPUT: &str = "ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488";
+    const NONCE: &str = "659ba96c601dc69fc902940805ec0ca8";
+    const RETURNED_BITS: &str = "e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89\
+                                 d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc1\
+                                 07694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668\
+                                 961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8";
+
+    #[test]
+    fn nist_vector() {
+        let entropy_input = <[u8; 32]>::from_hex(ENTROPY_INPUT).unwrap();
+        let nonce = <[u8; 16]>::from_hex(NONCE).unwrap();
+        let mut drbg = HmacDrbg::instantiate(&entropy_input, &nonce, &[]);
+        let mut output = [0u8; 128];
+        drbg.generate(&mut output, &[]).expect("failed to generate");
+        drbg.generate(&mut output, &[]).expect("failed to generate");
+        assert_eq!(output, <[u8; 128]>::from_hex(RETURNED_BITS).unwrap());
+        assert_eq!(drbg.reseed_counter(), 3);
+    }
+
+    #[test]
+    fn additional_input_and_reseed() {
+        let entropy_input: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let nonce: [u8; 16] = core::array::from_fn(|i| 32 + i as u8);
+        let reseed_entropy_input: [u8; 32] = core::array::from_fn(|i| 48 + i as u8);
+        let mut drbg = HmacDrbg::instantiate(&entropy_input, &nonce, b"heimlig personalization");
+        let mut output = [0u8; 32];
+        drbg.generate(&mut output, b"additional input")
+            .expect("failed to generate");
+        assert_eq!(
+            output,
+            <[u8; 32]>::from_hex(
+                "0f1960c97dde5e1b31cb8e96b9c23b4de54dcc8940b46d056c746f6cf1bb8dad"
+            )
+            .unwrap()
+        );
+        drbg.reseed(&reseed_entropy_input, b"additional input");
+        assert_eq!(drbg.reseed_counter(), 1);
+        drbg.generate(&mut output, &[]).expect("failed to generate");
+        assert_eq!(
+            output,
+            <[u8; 32]>::from_hex(
+                "a344648fca287fac77ab7ab3839fbdc583da44bb0c4e2dd77c02853f569b4486"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let mut drbg = HmacDrbg::instantiate(&[0u8; 32], &[0u8; 16], &[]);
+        let mut output = [0u8; HmacDrbg::MAX_REQUEST_SIZE + 1];
+        assert_eq!(drbg.generate(&mut output, &[]), Err(Error::RequestTooLarge));
+        drbg.reseed_counter = HmacDrbg::RESEED_INTERVAL + 1;
+        assert_eq!(
+            drbg.generate(&mut output[..1], &[]),
+            Err(Error::ReseedRequired)
+        );
+        drbg.reseed(&[1u8; 32], &[]);
+        assert_eq!(drbg.generate(&mut output[..1], &[]), Ok(()));
+    }
+}
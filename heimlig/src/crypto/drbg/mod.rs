@@ -0,0 +1,53 @@
+pub mod chacha20;
+pub mod ctr;
+pub mod hmac;
+
+/// Maximum number of entropy input and nonce bytes any [Drbg] needs to be instantiated.
+pub const MAX_SEED_MATERIAL_SIZE: usize = 64;
+
+/// Errors of deterministic random bit generators.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The generator has to be reseeded before it can generate more output.
+    ReseedRequired,
+    /// More output was requested than the generator produces in a single request.
+    RequestTooLarge,
+}
+
+/// Deterministic random bit generator (DRBG) with the interface of NIST SP 800-90A.
+pub trait Drbg {
+    /// Number of entropy input bytes used to instantiate and reseed the generator.
+    const ENTROPY_INPUT_SIZE: usize;
+    /// Number of nonce bytes used to instantiate the generator.
+    const NONCE_SIZE: usize;
+    /// Maximum number of bytes produced by a single generate request.
+    const MAX_REQUEST_SIZE: usize;
+    /// Maximum number of generate requests between two reseeds.
+    const RESEED_INTERVAL: u64;
+
+    /// Instantiate the generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy_input`: [Self::ENTROPY_INPUT_SIZE] bytes from an entropy source.
+    /// * `nonce`: [Self::NONCE_SIZE] bytes that are not expected to repeat.
+    /// * `personalization`: Optional (possibly empty) personalization string.
+    fn instantiate(entropy_input: &[u8], nonce: &[u8], personalization: &[u8]) -> Self;
+
+    /// Reseed the generator with fresh entropy and optional additional input.
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]);
+
+    /// Fill `output` with pseudorandom bytes. The optional `additional_input` is mixed into the
+    /// state before and after the output is generated.
+    ///
+    /// # Errors
+    ///
+    /// The function returns an error if:
+    /// * `ReseedRequired`: The generator reached its reseed interval.
+    /// * `RequestTooLarge`: `output` is larger than [Self::MAX_REQUEST_SIZE].
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), Error>;
+
+    /// Number of generate requests since the generator was instantiated or last reseeded, plus
+    /// one.
+    fn reseed_counter(&self) -> u64;
+}
@@ -0,0 +1,585 @@
+use crate::crypto::aes::gcm::{
+    aes128gcm_decrypt_in_place_detached, aes128gcm_encrypt_in_place_detached,
+    aes256gcm_decrypt_in_place_detached, aes256gcm_encrypt_in_place_detached,
+};
+use crate::crypto::ecdh::derive_shared_secret;
+use crate::crypto::hkdf::{hkdf_sha256_expand, hkdf_sha256_extract, SHA256_PRK_SIZE};
+use crate::crypto::{chacha20poly1305, Error};
+use elliptic_curve::sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint};
+use elliptic_curve::{AffinePoint, CurveArithmetic, FieldBytesSize, PublicKey, SecretKey};
+use p256::NistP256;
+use p384::NistP384;
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+/// Size of the largest supported private key (P-384) in bytes.
+pub const MAX_PRIVATE_KEY_SIZE: usize = 48;
+/// Size of the largest ephemeral public key (an uncompressed P-384 point) in bytes.
+pub const MAX_EPHEMERAL_PUBLIC_KEY_SIZE: usize = 97;
+/// Size of the nonce derived for the cipher in bytes. Identical for all supported ciphers.
+pub const NONCE_SIZE: usize = 12;
+/// Size of the authentication tag in bytes. Identical for all supported ciphers.
+pub const TAG_SIZE: usize = 16;
+
+const MAX_CIPHER_KEY_SIZE: usize = 32;
+const MAX_SHARED_SECRET_SIZE: usize = 48;
+
+/// Elliptic curves supported for ECIES.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Curve {
+    NistP256,
+    NistP384,
+}
+
+/// Key derivation functions used to derive the cipher key and nonce from the shared secret.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Kdf {
+    /// ANSI X9.63 KDF with SHA-256
+    AnsiX963Sha256 = 0,
+    /// HKDF with SHA-256 (RFC 5869) and an empty salt
+    HkdfSha256 = 1,
+}
+
+/// Authenticated ciphers used to encrypt the payload.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Cipher {
+    Aes128Gcm = 0,
+    Aes256Gcm = 1,
+    ChaCha20Poly1305 = 2,
+}
+
+impl Curve {
+    /// Size of a private key in bytes.
+    pub const fn private_key_size(&self) -> usize {
+        match self {
+            Curve::NistP256 => 32,
+            Curve::NistP384 => MAX_PRIVATE_KEY_SIZE,
+        }
+    }
+
+    /// Size of a public key as it is stored in the key store (untagged X and Y coordinates).
+    pub const fn public_key_size(&self) -> usize {
+        2 * self.private_key_size()
+    }
+
+    /// Size of the ephemeral public key in bytes. Ephemeral public keys are transmitted as
+    /// uncompressed SEC 1 points, i.e. with a leading 0x04 tag byte.
+    pub const fn ephemeral_public_key_size(&self) -> usize {
+        1 + self.public_key_size()
+    }
+
+    /// Determine the curve from the size of a public key as it is stored in the key store.
+    pub fn from_public_key_size(size: usize) -> Option<Self> {
+        [Curve::NistP256, Curve::NistP384]
+            .into_iter()
+            .find(|curve| curve.public_key_size() == size)
+    }
+}
+
+impl Cipher {
+    /// Size of the cipher key in bytes.
+    pub const fn key_size(&self) -> usize {
+        match self {
+            Cipher::Aes128Gcm => 16,
+            Cipher::Aes256Gcm | Cipher::ChaCha20Poly1305 => 32,
+        }
+    }
+}
+
+/// Generate an ephemeral private key for the given curve.
+///
+/// returns: A buffer whose first [Curve::private_key_size] bytes hold the private key.
+pub fn generate_ephemeral_private_key<R: CryptoRng + RngCore>(
+    curve: Curve,
+    rng: &mut R,
+) -> Zeroizing<[u8; MAX_PRIVATE_KEY_SIZE]> {
+    let mut private_key = Zeroizing::new([0u8; MAX_PRIVATE_KEY_SIZE]);
+    match curve {
+        Curve::NistP256 => private_key[..curve.private_key_size()]
+            .copy_from_slice(&SecretKey::<NistP256>::random(rng).to_bytes()),
+        Curve::NistP384 => private_key[..curve.private_key_size()]
+            .copy_from_slice(&SecretKey::<NistP384>::random(rng).to_bytes()),
+    }
+    private_key
+}
+
+/// Encrypt a buffer in-place to a recipient public key with ECIES.
+///
+/// The cipher key and nonce are derived from `R || Z` and `shared_info`, where `R` is the
+/// ephemeral public key and `Z` the X coordinate of the shared point (ISO 18033-2 style).
+///
+/// # Arguments
+///
+/// * `curve`: The curve of the recipient key.
+/// * `kdf`: The key derivation function.
+/// * `cipher`: The authenticated cipher.
+/// * `ephemeral_private_key`: Freshly generated private key
+///   (see [generate_ephemeral_private_key]).
+/// * `recipient_public_key`: Public key of the recipient. Must be [Curve::public_key_size] bytes
+///   long.
+/// * `shared_info`: Optional information shared by both parties that is input to the KDF.
+/// * `aad`: Additional authenticated data.
+/// * `buffer`: Buffer holding the plaintext. Holds the ciphertext after successful execution.
+/// * `ephemeral_public_key`: Output buffer for the ephemeral public key. Must be
+///   [Curve::ephemeral_public_key_size] bytes long.
+/// * `tag`: Output buffer for the authentication tag. Must be [TAG_SIZE] bytes long.
+#[allow(clippy::too_many_arguments)]
+pub fn encrypt(
+    curve: Curve,
+    kdf: Kdf,
+    cipher: Cipher,
+    ephemeral_private_key: &[u8],
+    recipient_public_key: &[u8],
+    shared_info: &[u8],
+    aad: &[u8],
+    buffer: &mut [u8],
+    ephemeral_public_key: &mut [u8],
+    tag: &mut [u8],
+) -> Result<(), Error> {
+    if ephemeral_public_key.len() != curve.ephemeral_public_key_size() {
+        return Err(Error::InvalidBufferSize);
+    }
+    if recipient_public_key.len() != curve.public_key_size() {
+        return Err(Error::InvalidPublicKey);
+    }
+    let mut recipient_point = [0u8; MAX_EPHEMERAL_PUBLIC_KEY_SIZE];
+    let recipient_point = &mut recipient_point[..curve.ephemeral_public_key_size()];
+    recipient_point[0] = 0x04;
+    recipient_point[1..].copy_from_slice(recipient_public_key);
+
+    let mut shared_secret = Zeroizing::new([0u8; MAX_SHARED_SECRET_SIZE]);
+    let shared_secret = &mut shared_secret[..curve.private_key_size()];
+    match curve {
+        Curve::NistP256 => {
+            compute_public_key::<NistP256>(ephemeral_private_key, ephemeral_public_key)?;
+            compute_shared_secret::<NistP256>(
+                ephemeral_private_key,
+                recipient_point,
+                shared_secret,
+            )?;
+        }
+        Curve::NistP384 => {
+            compute_public_key::<NistP384>(ephemeral_private_key, ephemeral_public_key)?;
+            compute_shared_secret::<NistP384>(
+                ephemeral_private_key,
+                recipient_point,
+                shared_secret,
+            )?;
+        }
+    }
+
+    let mut key = Zeroizing::new([0u8; MAX_CIPHER_KEY_SIZE + NONCE_SIZE]);
+    let (key, nonce) = key[..cipher.key_size() + NONCE_SIZE].split_at_mut(cipher.key_size());
+    derive_key(
+        kdf,
+        ephemeral_public_key,
+        shared_secret,
+        shared_info,
+        key,
+        nonce,
+    )?;
+
+    match cipher {
+        Cipher::Aes128Gcm => aes128gcm_encrypt_in_place_detached(key, nonce, aad, buffer, tag),
+        Cipher::Aes256Gcm => aes256gcm_encrypt_in_place_detached(key, nonce, aad, buffer, tag),
+        Cipher::ChaCha20Poly1305 => {
+            chacha20poly1305::encrypt_in_place_detached(key, nonce, aad, buffer, tag)
+        }
+    }
+}
+
+/// Decrypt a buffer in-place that was encrypted with [encrypt].
+///
+/// # Arguments
+///
+/// * `curve`: The curve of the recipient key.
+/// * `kdf`: The key derivation function.
+/// * `cipher`: The authenticated cipher.
+/// * `private_key`: Private key of the recipient.
+/// * `ephemeral_public_key`: Ephemeral public key of the sender as uncompressed SEC 1 point.
+/// * `shared_info`: Optional information shared by both parties that is input to the KDF.
+/// * `aad`: Additional authenticated data.
+/// * `buffer`: Buffer holding the ciphertext. Holds the plaintext after successful execution.
+/// * `tag`: The authentication tag produced by [encrypt].
+#[allow(clippy::too_many_arguments)]
+pub fn decrypt(
+    curve: Curve,
+    kdf: Kdf,
+    cipher: Cipher,
+    private_key: &[u8],
+    ephemeral_public_key: &[u8],
+    shared_info: &[u8],
+    aad: &[u8],
+    buffer: &mut [u8],
+    tag: &[u8],
+) -> Result<(), Error> {
+    // Only accept uncompressed points as they are part of the KDF input
+    if ephemeral_public_key.len() != curve.ephemeral_public_key_size() {
+        return Err(Error::InvalidPublicKey);
+    }
+    let mut shared_secret = Zeroizing::new([0u8; MAX_SHARED_SECRET_SIZE]);
+    let shared_secret = &mut shared_secret[..curve.private_key_size()];
+    match curve {
+        Curve::NistP256 => {
+            compute_shared_secret::<NistP256>(private_key, ephemeral_public_key, shared_secret)?
+        }
+        Curve::NistP384 => {
+            compute_shared_secret::<NistP384>(private_key, ephemeral_public_key, shared_secret)?
+        }
+    }
+
+    let mut key = Zeroizing::new([0u8; MAX_CIPHER_KEY_SIZE + NONCE_SIZE]);
+    let (key, nonce) = key[..cipher.key_size() + NONCE_SIZE].split_at_mut(cipher.key_size());
+    derive_key(
+        kdf,
+        ephemeral_public_key,
+        shared_secret,
+        shared_info,
+        key,
+        nonce,
+    )?;
+
+    match cipher {
+        Cipher::Aes128Gcm => aes128gcm_decrypt_in_place_detached(key, nonce, aad, buffer, tag),
+        Cipher::Aes256Gcm => aes256gcm_decrypt_in_place_detached(key, nonce, aad, buffer, tag),
+        Cipher::ChaCha20Poly1305 => {
+            chacha20poly1305::decrypt_in_place_detached(key, nonce, aad, buffer, tag)
+        }
+    }
+}
+
+fn compute_public_key<C>(private_key: &[u8], public_key: &mut [u8]) -> Result<(), Error>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let private_key =
+        SecretKey::<C>::from_slice(private_key).map_err(|_| Error::InvalidPrivateKey)?;
+    public_key.copy_from_slice(private_key.public_key().to_encoded_point(false).as_bytes());
+    Ok(())
+}
+
+fn compute_shared_secret<C>(
+    private_key: &[u8],
+    public_key: &[u8],
+    shared_secret: &mut [u8],
+) -> Result<(), Error>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let private_key =
+        SecretKey::<C>::from_slice(private_key).map_err(|_| Error::InvalidPrivateKey)?;
+    let public_key =
+        PublicKey::<C>::from_sec1_bytes(public_key).map_err(|_| Error::InvalidPublicKey)?;
+    shared_secret
+        .copy_from_slice(derive_shared_secret(&private_key, &public_key).raw_secret_bytes());
+    Ok(())
+}
+
+/// Derive the cipher key and nonce from `ephemeral_public_key || shared_secret`.
+fn derive_key(
+    kdf: Kdf,
+    ephemeral_public_key: &[u8],
+    shared_secret: &[u8],
+    shared_info: &[u8],
+    key: &mut [u8],
+    nonce: &mut [u8],
+) -> Result<(), Error> {
+    let mut okm = Zeroizing::new([0u8; MAX_CIPHER_KEY_SIZE + NONCE_SIZE]);
+    let okm = &mut okm[..key.len() + nonce.len()];
+    match kdf {
+        Kdf::AnsiX963Sha256 => {
+            for (counter, chunk) in (1u32..).zip(okm.chunks_mut(<Sha256 as Digest>::output_size()))
+            {
+                let digest = Sha256::new()
+                    .chain_update(ephemeral_public_key)
+                    .chain_update(shared_secret)
+                    .chain_update(counter.to_be_bytes())
+                    .chain_update(shared_info)
+                    .finalize();
+                chunk.copy_from_slice(&digest[..chunk.len()]);
+            }
+        }
+        Kdf::HkdfSha256 => {
+            let mut prk = Zeroizing::new([0u8; SHA256_PRK_SIZE]);
+            hkdf_sha256_extract(
+                &[],
+                &[ephemeral_public_key, shared_secret],
+                prk.as_mut_slice(),
+            )?;
+            hkdf_sha256_expand(prk.as_slice(), &[shared_info], okm)?;
+        }
+    }
+    let (okm_key, okm_nonce) = okm.split_at(key.len());
+    key.copy_from_slice(okm_key);
+    nonce.copy_from_slice(okm_nonce);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::rng::{test::TestEntropySource, Rng};
+
+    const PLAINTEXT: &[u8] = b"Hello, World! This is a secret.";
+
+    struct TestVector {
+        curve: Curve,
+        kdf: Kdf,
+        cipher: Cipher,
+        ephemeral_private_key: &'static str,
+        recipient_private_key: &'static str,
+        recipient_public_key: &'static str,
+        shared_info: &'static [u8],
+        aad: &'static [u8],
+        ephemeral_public_key: &'static str,
+        ciphertext: &'static str,
+        tag: &'static str,
+    }
+
+    // Generated with the X963KDF/HKDF, ECDH and AEAD primitives of the Python cryptography package
+    const TEST_VECTORS: [TestVector; 2] = [
+        TestVector {
+            curve: Curve::NistP256,
+            kdf: Kdf::AnsiX963Sha256,
+            cipher: Cipher::Aes128Gcm,
+            ephemeral_private_key: "c88f01f510d9ac3f70a292daa2316de544e9aab8afe84049c62a9c57862d1433",
+            recipient_private_key: "7d7dc5f71eb29ddaf80d6214632eeae03d9058af1fb6d22ed80badb62bc1a534",
+            recipient_public_key: "ead218590119e8876b29146ff89ca61770c4edbbf97d38ce385ed281d8a6b23028af61281fd35e2fa7002523acc85a429cb06ee6648325389f59edfce1405141",
+            shared_info: b"partner-id",
+            aad: b"",
+            ephemeral_public_key: "04dad0b65394221cf9b051e1feca5787d098dfe637fc90b9ef945d0c37725811805271a0461cdb8252d61f1c456fa3e59ab1f45b33accf5f58389e0577b8990bb3",
+            ciphertext: "4e1a19cdde386dac812e0d6006f6b1c61aba7be4e388a4145a2f2522974a74",
+            tag: "3a2dcacf8cf1c75b7489543cfbd8f3bc",
+        },
+        TestVector {
+            curve: Curve::NistP384,
+            kdf: Kdf::HkdfSha256,
+            cipher: Cipher::ChaCha20Poly1305,
+            ephemeral_private_key: "3cc3122a68f0d95027ad38c067916ba0eb8c38894d22e1b15618b6818a661774ad463b205da88cf699ab4d43c9cf98a1",
+            recipient_private_key: "c602bc74a34592c311a6569661e0832c84f7207274676cc42a89f058162630184b52f0d99b855a7783c987476d7f9e6b",
+            recipient_public_key: "0400193b21f07cd059826e9453d3e96dd145041c97d49ff6b7047f86bb0b0439e909274cb9c282bfab88674c0765bc75f70d89c52acbc70468d2c5ae75c76d7f69b76af62dcf95e99eba5dd11adf8f42ec9a425b0c5ec98e2f234a926b82a147",
+            shared_info: b"",
+            aad: b"aad",
+            ephemeral_public_key: "049803807f2f6d2fd966cdd0290bd410c0190352fbec7ff6247de1302df86f25d34fe4a97bef60cff548355c015dbb3e5fba26ca69ec2f5b5d9dad20cc9da711383a9dbe34ea3fa5a2af75b46502629ad54dd8b7d73a8abb06a3a3be47d650cc99",
+            ciphertext: "3bfd778118275360eacf4db6bdc0acd2aec0fb17ea08117f7b46bb11ab90d0",
+            tag: "d732a2964aff4a3b478601c16df2696d",
+        },
+    ];
+
+    #[test]
+    fn test_vectors_encrypt() {
+        for vector in TEST_VECTORS.iter() {
+            let mut buffer = [0u8; PLAINTEXT.len()];
+            buffer.copy_from_slice(PLAINTEXT);
+            let mut ephemeral_public_key = [0u8; MAX_EPHEMERAL_PUBLIC_KEY_SIZE];
+            let ephemeral_public_key =
+                &mut ephemeral_public_key[..vector.curve.ephemeral_public_key_size()];
+            let mut tag = [0u8; TAG_SIZE];
+            encrypt(
+                vector.curve,
+                vector.kdf,
+                vector.cipher,
+                &hex::decode(vector.ephemeral_private_key).unwrap(),
+                &hex::decode(vector.recipient_public_key).unwrap(),
+                vector.shared_info,
+                vector.aad,
+                &mut buffer,
+                ephemeral_public_key,
+                &mut tag,
+            )
+            .expect("failed to encrypt");
+            assert_eq!(
+                ephemeral_public_key,
+                hex::decode(vector.ephemeral_public_key).unwrap()
+            );
+            assert_eq!(buffer.as_slice(), hex::decode(vector.ciphertext).unwrap());
+            assert_eq!(tag.as_slice(), hex::decode(vector.tag).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_vectors_decrypt() {
+        for vector in TEST_VECTORS.iter() {
+            let mut buffer = hex::decode(vector.ciphertext).unwrap();
+            decrypt(
+                vector.curve,
+                vector.kdf,
+                vector.cipher,
+                &hex::decode(vector.recipient_private_key).unwrap(),
+                &hex::decode(vector.ephemeral_public_key).unwrap(),
+                vector.shared_info,
+                vector.aad,
+                &mut buffer,
+                &hex::decode(vector.tag).unwrap(),
+            )
+            .expect("failed to decrypt");
+            assert_eq!(buffer, PLAINTEXT);
+        }
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_all_parameters() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        for curve in [Curve::NistP256, Curve::NistP384] {
+            let recipient_private_key = generate_ephemeral_private_key(curve, &mut rng);
+            let recipient_private_key = &recipient_private_key[..curve.private_key_size()];
+            let mut recipient_public_key = [0u8; MAX_EPHEMERAL_PUBLIC_KEY_SIZE];
+            let recipient_public_key =
+                &mut recipient_public_key[..curve.ephemeral_public_key_size()];
+            match curve {
+                Curve::NistP256 => {
+                    compute_public_key::<NistP256>(recipient_private_key, recipient_public_key)
+                }
+                Curve::NistP384 => {
+                    compute_public_key::<NistP384>(recipient_private_key, recipient_public_key)
+                }
+            }
+            .unwrap();
+            for kdf in [Kdf::AnsiX963Sha256, Kdf::HkdfSha256] {
+                for cipher in [
+                    Cipher::Aes128Gcm,
+                    Cipher::Aes256Gcm,
+                    Cipher::ChaCha20Poly1305,
+                ] {
+                    let ephemeral_private_key = generate_ephemeral_private_key(curve, &mut rng);
+                    let mut buffer = [0u8; PLAINTEXT.len()];
+                    buffer.copy_from_slice(PLAINTEXT);
+                    let mut ephemeral_public_key = [0u8; MAX_EPHEMERAL_PUBLIC_KEY_SIZE];
+                    let ephemeral_public_key =
+                        &mut ephemeral_public_key[..curve.ephemeral_public_key_size()];
+                    let mut tag = [0u8; TAG_SIZE];
+                    encrypt(
+                        curve,
+                        kdf,
+                        cipher,
+                        &ephemeral_private_key[..curve.private_key_size()],
+                        &recipient_public_key[1..],
+                        b"info",
+                        b"aad",
+                        &mut buffer,
+                        ephemeral_public_key,
+                        &mut tag,
+                    )
+                    .expect("failed to encrypt");
+                    assert_ne!(buffer, PLAINTEXT);
+
+                    // Decrypting with other parameters must fail
+                    let other_kdf = match kdf {
+                        Kdf::AnsiX963Sha256 => Kdf::HkdfSha256,
+                        Kdf::HkdfSha256 => Kdf::AnsiX963Sha256,
+                    };
+                    let mut copy = buffer;
+                    assert_eq!(
+                        decrypt(
+                            curve,
+                            other_kdf,
+                            cipher,
+                            recipient_private_key,
+                            ephemeral_public_key,
+                            b"info",
+                            b"aad",
+                            &mut copy,
+                            &tag,
+                        ),
+                        Err(Error::Decrypt)
+                    );
+
+                    decrypt(
+                        curve,
+                        kdf,
+                        cipher,
+                        recipient_private_key,
+                        ephemeral_public_key,
+                        b"info",
+                        b"aad",
+                        &mut buffer,
+                        &tag,
+                    )
+                    .expect("failed to decrypt");
+                    assert_eq!(buffer, PLAINTEXT);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let vector = &TEST_VECTORS[0];
+        let private_key = hex::decode(vector.recipient_private_key).unwrap();
+        let public_key = hex::decode(vector.recipient_public_key).unwrap();
+        let ephemeral_public_key = hex::decode(vector.ephemeral_public_key).unwrap();
+        let mut buffer = hex::decode(vector.ciphertext).unwrap();
+        let mut tag = hex::decode(vector.tag).unwrap();
+        let mut output = [0u8; MAX_EPHEMERAL_PUBLIC_KEY_SIZE];
+
+        // Compressed ephemeral public key
+        assert_eq!(
+            decrypt(
+                vector.curve,
+                vector.kdf,
+                vector.cipher,
+                &private_key,
+                &ephemeral_public_key[..33],
+                vector.shared_info,
+                vector.aad,
+                &mut buffer,
+                &tag,
+            ),
+            Err(Error::InvalidPublicKey)
+        );
+        // Point not on the curve
+        let mut invalid_point = ephemeral_public_key.clone();
+        invalid_point[64] ^= 1;
+        assert_eq!(
+            decrypt(
+                vector.curve,
+                vector.kdf,
+                vector.cipher,
+                &private_key,
+                &invalid_point,
+                vector.shared_info,
+                vector.aad,
+                &mut buffer,
+                &tag,
+            ),
+            Err(Error::InvalidPublicKey)
+        );
+        // Recipient key of the wrong curve
+        assert_eq!(
+            encrypt(
+                Curve::NistP384,
+                vector.kdf,
+                vector.cipher,
+                &private_key,
+                &public_key,
+                vector.shared_info,
+                vector.aad,
+                &mut buffer,
+                &mut output[..Curve::NistP384.ephemeral_public_key_size()],
+                &mut tag,
+            ),
+            Err(Error::InvalidPublicKey)
+        );
+        // Ephemeral public key buffer too small
+        assert_eq!(
+            encrypt(
+                vector.curve,
+                vector.kdf,
+                vector.cipher,
+                &private_key,
+                &public_key,
+                vector.shared_info,
+                vector.aad,
+                &mut buffer,
+                &mut output[..64],
+                &mut tag,
+            ),
+            Err(Error::InvalidBufferSize)
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use crate::crypto::Error;
 use ed25519_dalek::{SecretKey, Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroize;
 
 /// Ed25519 signature size in bytes.
 pub const SIGNATURE_SIZE: usize = ed25519_dalek::SIGNATURE_LENGTH;
@@ -94,6 +96,27 @@ pub fn ed25519_calculate_public_key(
     Ok(())
 }
 
+/// Generates an Ed25519 key pair.
+///
+/// # Arguments
+///
+/// * `rng`: Random number generator to use for key generation.
+///
+/// returns: The private key and the public key, both `PRIVATE_KEY_SIZE` and `PUBLIC_KEY_SIZE`
+/// bytes long.
+pub fn ed25519_generate_key_pair<R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> ([u8; PRIVATE_KEY_SIZE], [u8; PUBLIC_KEY_SIZE]) {
+    let mut secret_key: SecretKey = [0u8; PRIVATE_KEY_SIZE];
+    rng.fill_bytes(&mut secret_key);
+    let signing_key = SigningKey::from_bytes(&secret_key);
+    secret_key.zeroize();
+    (
+        signing_key.to_bytes(),
+        signing_key.verifying_key().to_bytes(),
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -119,6 +142,22 @@ mod test {
         ed25519_verify(&public_key, MESSAGE, &signature).expect("verifying error");
     }
 
+    #[test]
+    fn test_ed25519_generate_key_pair() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+
+        let (private_key, public_key) = ed25519_generate_key_pair(&mut rng);
+
+        let mut calculated_public_key = [0u8; PUBLIC_KEY_SIZE];
+        ed25519_calculate_public_key(&private_key, &mut calculated_public_key)
+            .expect("public key calculation error");
+        assert_eq!(public_key, calculated_public_key);
+
+        let mut signature = [0u8; SIGNATURE_SIZE];
+        ed25519_sign(&private_key, MESSAGE, &mut signature).expect("signing error");
+        ed25519_verify(&public_key, MESSAGE, &signature).expect("verifying error");
+    }
+
     #[test]
     fn test_ed25519_size_errors() {
         const BUFF_SIZE: usize = 128;
@@ -0,0 +1,258 @@
+use crate::crypto::hash::{blake3, sha256};
+use crate::crypto::rng::{Error, TryEntropySource};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Size of the pool state and of the emitted seeds.
+pub const POOL_SIZE: usize = 32;
+
+/// Size of a raw sample taken from a source.
+pub const SAMPLE_SIZE: usize = 32;
+
+/// Bits of min-entropy that must be credited before a seed is emitted. Full-entropy output of a
+/// vetted conditioning component requires 64 bits more than its output size (NIST SP 800-90B,
+/// section 3.1.5.1.2).
+pub const SEED_ENTROPY: u32 = 8 * POOL_SIZE as u32 + 64;
+
+/// Domain separation of the conditioning inputs.
+const MIX_TAG: u8 = 0x00;
+const OUTPUT_TAG: u8 = 0x01;
+const UPDATE_TAG: u8 = 0x02;
+
+/// Hash function used to condition the raw samples.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Conditioner {
+    /// SHA-256 as specified in FIPS 180-4.
+    Sha256,
+    /// BLAKE3 with 256-bit output.
+    Blake3,
+}
+
+impl Conditioner {
+    fn hash(&self, input: &[u8]) -> [u8; POOL_SIZE] {
+        match self {
+            Conditioner::Sha256 => sha256(input),
+            Conditioner::Blake3 => blake3(input),
+        }
+    }
+}
+
+/// Raw entropy source of a pool together with its min-entropy estimate.
+pub struct PoolSource<'a> {
+    pub source: &'a mut (dyn TryEntropySource + Send),
+    /// Estimated bits of min-entropy per sample of [SAMPLE_SIZE] bytes. Samples of sources with
+    /// an estimate of 0 are mixed into the pool without being credited. Estimates above
+    /// `8 * SAMPLE_SIZE` are capped.
+    pub min_entropy: u32,
+}
+
+/// Entropy source that accumulates raw samples from several sources in a pool conditioned by a
+/// hash function. A seed is only emitted once at least [SEED_ENTROPY] bits of min-entropy have
+/// been credited to the pool since the last seed.
+///
+/// Sources are sampled in a round-robin fashion. A failing source is skipped. Seeding fails if a
+/// full round over all sources does not credit any entropy.
+pub struct EntropyPool<'a, const NUM_SOURCES: usize> {
+    sources: [PoolSource<'a>; NUM_SOURCES],
+    conditioner: Conditioner,
+    state: [u8; POOL_SIZE],
+    credited: u32,
+}
+
+impl<'a, const NUM_SOURCES: usize> EntropyPool<'a, NUM_SOURCES> {
+    /// Create an empty pool. No entropy is credited to the pool before the first seed is
+    /// requested.
+    pub fn new(sources: [PoolSource<'a>; NUM_SOURCES], conditioner: Conditioner) -> Self {
+        EntropyPool {
+            sources,
+            conditioner,
+            state: [0u8; POOL_SIZE],
+            credited: 0,
+        }
+    }
+
+    /// Bits of min-entropy credited to the pool since the last seed was emitted.
+    pub fn credited(&self) -> u32 {
+        self.credited
+    }
+
+    /// Take one sample from every source and mix it into the pool.
+    fn collect(&mut self) -> Result<(), Error> {
+        let mut input = Zeroizing::new([0u8; 1 + POOL_SIZE + 1 + SAMPLE_SIZE]);
+        let mut credited = 0;
+        let mut error = None;
+        for (index, source) in self.sources.iter_mut().enumerate() {
+            match source.source.try_random_seed() {
+                Ok(sample) => {
+                    let sample = Zeroizing::new(sample);
+                    input[0] = MIX_TAG;
+                    input[1..][..POOL_SIZE].copy_from_slice(&self.state);
+                    input[1 + POOL_SIZE] = index as u8;
+                    input[2 + POOL_SIZE..].copy_from_slice(sample.as_slice());
+                    self.state = self.conditioner.hash(input.as_slice());
+                    credited += source.min_entropy.min(8 * SAMPLE_SIZE as u32);
+                }
+                Err(e) => error = Some(e),
+            }
+        }
+        if credited == 0 {
+            return Err(error.unwrap_or(Error::SourceFailure));
+        }
+        self.credited = self.credited.saturating_add(credited);
+        Ok(())
+    }
+}
+
+impl<const NUM_SOURCES: usize> TryEntropySource for EntropyPool<'_, NUM_SOURCES> {
+    fn try_random_seed(&mut self) -> Result<[u8; 32], Error> {
+        while self.credited < SEED_ENTROPY {
+            self.collect()?;
+        }
+        let mut input = Zeroizing::new([0u8; 1 + POOL_SIZE]);
+        input[1..].copy_from_slice(&self.state);
+        input[0] = OUTPUT_TAG;
+        let seed = self.conditioner.hash(input.as_slice());
+        input[0] = UPDATE_TAG;
+        self.state = self.conditioner.hash(input.as_slice());
+        self.credited = 0;
+        Ok(seed)
+    }
+}
+
+impl<const NUM_SOURCES: usize> Drop for EntropyPool<'_, NUM_SOURCES> {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::rng::test::TestEntropySource;
+    use crate::crypto::rng::{EntropySource, Rng};
+    use rand::RngCore;
+
+    /// Source counting the samples taken from it.
+    #[derive(Default)]
+    struct CountingSource {
+        source: TestEntropySource,
+        samples: usize,
+    }
+
+    impl EntropySource for CountingSource {
+        fn random_seed(&mut self) -> [u8; 32] {
+            self.samples += 1;
+            self.source.random_seed()
+        }
+    }
+
+    struct FailingSource;
+
+    impl TryEntropySource for FailingSource {
+        fn try_random_seed(&mut self) -> Result<[u8; 32], Error> {
+            Err(Error::SourceFailure)
+        }
+    }
+
+    #[test]
+    fn credit_entropy() {
+        let mut trng = CountingSource::default();
+        let mut jitter = CountingSource::default();
+        let mut pool = EntropyPool::new(
+            [
+                PoolSource {
+                    source: &mut trng,
+                    min_entropy: 64,
+                },
+                PoolSource {
+                    source: &mut jitter,
+                    min_entropy: 16,
+                },
+            ],
+            Conditioner::Sha256,
+        );
+        let seed = pool.try_random_seed().expect("failed to seed");
+        assert_eq!(pool.credited(), 0);
+        let next_seed = pool.try_random_seed().expect("failed to seed");
+        assert_ne!(seed, next_seed);
+        drop(pool);
+        assert_eq!(trng.samples, 2 * 4);
+        assert_eq!(jitter.samples, 2 * 4);
+    }
+
+    #[test]
+    fn conditioners() {
+        let mut seeds = [[0u8; 32]; 3];
+        for (seed, conditioner) in seeds.iter_mut().zip([
+            Conditioner::Sha256,
+            Conditioner::Blake3,
+            Conditioner::Sha256,
+        ]) {
+            let mut source = TestEntropySource::default();
+            let mut pool = EntropyPool::new(
+                [PoolSource {
+                    source: &mut source,
+                    min_entropy: 1000,
+                }],
+                conditioner,
+            );
+            *seed = pool.try_random_seed().expect("failed to seed");
+        }
+        assert_ne!(seeds[0], seeds[1]);
+        assert_eq!(seeds[0], seeds[2]);
+    }
+
+    #[test]
+    fn failing_sources() {
+        let mut trng = FailingSource;
+        let mut jitter = CountingSource::default();
+        let mut pool = EntropyPool::new(
+            [
+                PoolSource {
+                    source: &mut trng,
+                    min_entropy: 128,
+                },
+                PoolSource {
+                    source: &mut jitter,
+                    min_entropy: 128,
+                },
+            ],
+            Conditioner::Blake3,
+        );
+        assert!(pool.try_random_seed().is_ok());
+        drop(pool);
+        assert_eq!(jitter.samples, 3);
+
+        let mut trng = FailingSource;
+        let mut jitter = CountingSource::default();
+        let mut pool = EntropyPool::new(
+            [
+                PoolSource {
+                    source: &mut trng,
+                    min_entropy: 128,
+                },
+                PoolSource {
+                    source: &mut jitter,
+                    min_entropy: 0,
+                },
+            ],
+            Conditioner::Blake3,
+        );
+        assert_eq!(pool.try_random_seed(), Err(Error::SourceFailure));
+    }
+
+    #[test]
+    fn seed_rng() {
+        let mut source = TestEntropySource::default();
+        let pool = EntropyPool::new(
+            [PoolSource {
+                source: &mut source,
+                min_entropy: 32,
+            }],
+            Conditioner::Sha256,
+        );
+        let mut rng = Rng::try_new(pool, None).expect("failed to seed");
+        let mut data = [0u8; 32];
+        rng.fill_bytes(&mut data);
+        assert_ne!(data, [0u8; 32]);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::crypto::rng::{Error, TryEntropySource};
+
+/// Number of samples in the window of the adaptive proportion test.
+pub const ADAPTIVE_PROPORTION_WINDOW_SIZE: u32 = 512;
+
+/// Number of samples that are tested (and discarded) by the start-up test.
+pub const START_UP_SAMPLES: usize = 1024;
+
+/// Cutoff values of the adaptive proportion test for a window of 512 samples, a false positive
+/// probability of 2^-20 and a min-entropy of 1 to 8 bits per sample (NIST SP 800-90B, section
+/// 4.4.2).
+const ADAPTIVE_PROPORTION_CUTOFFS: [u32; 8] = [311, 177, 103, 62, 39, 25, 18, 13];
+
+/// Cutoff values of the continuous health tests.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HealthTestConfig {
+    /// Number of identical consecutive samples that make the repetition count test fail.
+    pub repetition_count_cutoff: u32,
+    /// Number of occurrences of the first sample of a window that make the adaptive proportion
+    /// test fail.
+    pub adaptive_proportion_cutoff: u32,
+}
+
+impl HealthTestConfig {
+    /// Cutoff values for a false positive probability of 2^-20 and the given min-entropy
+    /// estimate of the noise source in bits per byte. Returns `None` if the estimate is not in
+    /// the range from 1 to 8.
+    pub const fn from_min_entropy(bits_per_sample: u32) -> Option<Self> {
+        if bits_per_sample < 1 || bits_per_sample > 8 {
+            return None;
+        }
+        Some(HealthTestConfig {
+            repetition_count_cutoff: 1 + 20u32.div_ceil(bits_per_sample),
+            adaptive_proportion_cutoff: ADAPTIVE_PROPORTION_CUTOFFS[bits_per_sample as usize - 1],
+        })
+    }
+}
+
+/// Entropy source wrapper that runs the health tests of NIST SP 800-90B on every byte produced
+/// by the wrapped source.
+///
+/// The repetition count and adaptive proportion tests are run on [START_UP_SAMPLES] samples
+/// before the first seed is provided and continuously afterwards. Once a test fails, the wrapper
+/// stays unhealthy and only returns errors.
+pub struct HealthTestedSource<E: TryEntropySource> {
+    source: E,
+    config: HealthTestConfig,
+    started: bool,
+    last_sample: Option<u8>,
+    repetition_count: u32,
+    reference_sample: u8,
+    reference_count: u32,
+    window_position: u32,
+    failed: bool,
+}
+
+impl<E: TryEntropySource> HealthTestedSource<E> {
+    /// Wrap `source`. The start-up test is run when the first seed is requested.
+    pub fn new(source: E, config: HealthTestConfig) -> Self {
+        HealthTestedSource {
+            source,
+            config,
+            started: false,
+            last_sample: None,
+            repetition_count: 0,
+            reference_sample: 0,
+            reference_count: 0,
+            window_position: 0,
+            failed: false,
+        }
+    }
+
+    /// Whether all health tests passed so far.
+    pub fn is_healthy(&self) -> bool {
+        !self.failed
+    }
+
+    fn start_up(&mut self) -> Result<(), Error> {
+        for _ in 0..START_UP_SAMPLES.div_ceil(32) {
+            let samples = self.source.try_random_seed()?;
+            self.test(&samples);
+        }
+        self.started = true;
+        Ok(())
+    }
+
+    fn test(&mut self, samples: &[u8]) {
+        for &sample in samples {
+            // Repetition count test (section 4.4.1)
+            if self.last_sample == Some(sample) {
+                self.repetition_count += 1;
+                if self.repetition_count >= self.config.repetition_count_cutoff {
+                    self.failed = true;
+                }
+            } else {
+                self.last_sample = Some(sample);
+                self.repetition_count = 1;
+            }
+
+            // Adaptive proportion test (section 4.4.2)
+            if self.window_position == 0 {
+                self.reference_sample = sample;
+                self.reference_count = 1;
+            } else if sample == self.reference_sample {
+                self.reference_count += 1;
+                if self.reference_count >= self.config.adaptive_proportion_cutoff {
+                    self.failed = true;
+                }
+            }
+            self.window_position = (self.window_position + 1) % ADAPTIVE_PROPORTION_WINDOW_SIZE;
+        }
+    }
+}
+
+impl<E: TryEntropySource> TryEntropySource for HealthTestedSource<E> {
+    fn try_random_seed(&mut self) -> Result<[u8; 32], Error> {
+        if !self.started && !self.failed {
+            self.start_up()?;
+        }
+        if self.failed {
+            return Err(Error::HealthTestFailure);
+        }
+        let seed = self.source.try_random_seed()?;
+        self.test(&seed);
+        if self.failed {
+            return Err(Error::HealthTestFailure);
+        }
+        Ok(seed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::rng::test::TestEntropySource;
+    use crate::crypto::rng::{EntropySource, Rng};
+    use rand::RngCore;
+
+    const CONFIG: HealthTestConfig = match HealthTestConfig::from_min_entropy(8) {
+        Some(config) => config,
+        None => panic!("invalid min-entropy"),
+    };
+
+    /// Source producing distinct bytes for `healthy_seeds` seeds and zeros afterwards.
+    struct StuckSource {
+        source: TestEntropySource,
+        healthy_seeds: u32,
+    }
+
+    impl StuckSource {
+        fn new(healthy_seeds: u32) -> Self {
+            StuckSource {
+                source: TestEntropySource::default(),
+                healthy_seeds,
+            }
+        }
+    }
+
+    impl EntropySource for StuckSource {
+        fn random_seed(&mut self) -> [u8; 32] {
+            if self.healthy_seeds == 0 {
+                return [0u8; 32];
+            }
+            self.healthy_seeds -= 1;
+            self.source.random_seed()
+        }
+    }
+
+    #[test]
+    fn config() {
+        assert_eq!(HealthTestConfig::from_min_entropy(0), None);
+        assert_eq!(HealthTestConfig::from_min_entropy(9), None);
+        assert_eq!(
+            HealthTestConfig::from_min_entropy(1),
+            Some(HealthTestConfig {
+                repetition_count_cutoff: 21,
+                adaptive_proportion_cutoff: 311
+            })
+        );
+        assert_eq!(
+            CONFIG,
+            HealthTestConfig {
+                repetition_count_cutoff: 4,
+                adaptive_proportion_cutoff: 13
+            }
+        );
+    }
+
+    #[test]
+    fn healthy_source() {
+        let mut source = HealthTestedSource::new(TestEntropySource::default(), CONFIG);
+        for _ in 0..100 {
+            assert!(source.try_random_seed().is_ok());
+        }
+        assert!(source.is_healthy());
+    }
+
+    #[test]
+    fn start_up_test_failure() {
+        let mut source = HealthTestedSource::new(StuckSource::new(0), CONFIG);
+        assert!(source.is_healthy());
+        assert_eq!(source.try_random_seed(), Err(Error::HealthTestFailure));
+        assert!(!source.is_healthy());
+    }
+
+    #[test]
+    fn repetition_count_test_failure() {
+        let start_up_seeds = START_UP_SAMPLES.div_ceil(32) as u32;
+        let mut source = HealthTestedSource::new(StuckSource::new(start_up_seeds + 1), CONFIG);
+        assert!(source.try_random_seed().is_ok());
+        assert_eq!(source.try_random_seed(), Err(Error::HealthTestFailure));
+        assert!(!source.is_healthy());
+        source.source.healthy_seeds = 1;
+        assert_eq!(source.try_random_seed(), Err(Error::HealthTestFailure));
+    }
+
+    #[test]
+    fn adaptive_proportion_test_failure() {
+        /// Source alternating between a fixed and an increasing byte.
+        struct BiasedSource(u8);
+
+        impl EntropySource for BiasedSource {
+            fn random_seed(&mut self) -> [u8; 32] {
+                let mut seed = [0u8; 32];
+                for pair in seed.chunks_mut(2) {
+                    self.0 = self.0.wrapping_add(1);
+                    pair.copy_from_slice(&[0x55, self.0]);
+                }
+                seed
+            }
+        }
+
+        let mut source = HealthTestedSource::new(BiasedSource(0), CONFIG);
+        assert_eq!(source.try_random_seed(), Err(Error::HealthTestFailure));
+    }
+
+    #[test]
+    fn rng_error_state() {
+        // Start-up test, instantiation and first reseed
+        let healthy_seeds = START_UP_SAMPLES.div_ceil(32) as u32 + 2;
+        let source = HealthTestedSource::new(StuckSource::new(healthy_seeds), CONFIG);
+        let mut rng = Rng::new(source, Some(32));
+        let mut data = [0u8; 32];
+        assert!(rng.try_fill_bytes(&mut data).is_ok());
+        assert_eq!(rng.error(), None);
+        rng.fill_bytes(&mut data);
+        assert_eq!(rng.error(), Some(Error::HealthTestFailure));
+        assert!(rng.try_fill_bytes(&mut data).is_err());
+        assert_eq!(rng.try_fill(&mut data), Err(Error::HealthTestFailure));
+        assert_eq!(data, [0u8; 32]);
+    }
+}
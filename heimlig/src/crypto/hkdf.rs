@@ -0,0 +1,99 @@
+use crate::crypto::Error;
+use hkdf::{Hkdf, HkdfExtract};
+use sha2::Sha256;
+
+/// Size of a pseudorandom key (PRK) produced by HKDF-SHA256 in bytes.
+pub const SHA256_PRK_SIZE: usize = 32;
+/// Maximum number of output bytes HKDF-SHA256 can expand a single PRK into.
+pub const SHA256_MAX_OUTPUT_SIZE: usize = 255 * SHA256_PRK_SIZE;
+
+/// HKDF-SHA256 extract step (RFC 5869, section 2.2).
+///
+/// # Arguments
+///
+/// * `salt`: The (optional, possibly empty) salt value.
+/// * `ikm`: The input keying material. The parts are concatenated before extraction.
+/// * `prk`: Output buffer for the pseudorandom key. Must be exactly [SHA256_PRK_SIZE] bytes long.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidBufferSize`: The length of `prk` is not [SHA256_PRK_SIZE] bytes.
+pub fn hkdf_sha256_extract(salt: &[u8], ikm: &[&[u8]], prk: &mut [u8]) -> Result<(), Error> {
+    if prk.len() != SHA256_PRK_SIZE {
+        return Err(Error::InvalidBufferSize);
+    }
+    let mut extract = HkdfExtract::<Sha256>::new(Some(salt));
+    for part in ikm {
+        extract.input_ikm(part);
+    }
+    let (computed_prk, _) = extract.finalize();
+    prk.copy_from_slice(&computed_prk);
+    Ok(())
+}
+
+/// HKDF-SHA256 expand step (RFC 5869, section 2.3).
+///
+/// # Arguments
+///
+/// * `prk`: The pseudorandom key. Must be at least [SHA256_PRK_SIZE] bytes long.
+/// * `info`: Context and application specific information. The parts are concatenated.
+/// * `okm`: Output buffer for the output keying material. Its length determines the amount of
+///   derived bytes and must not exceed [SHA256_MAX_OUTPUT_SIZE].
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidSymmetricKeySize`: The `prk` is shorter than [SHA256_PRK_SIZE] bytes.
+/// * `InvalidBufferSize`: The length of `okm` exceeds [SHA256_MAX_OUTPUT_SIZE].
+pub fn hkdf_sha256_expand(prk: &[u8], info: &[&[u8]], okm: &mut [u8]) -> Result<(), Error> {
+    Hkdf::<Sha256>::from_prk(prk)
+        .map_err(|_| Error::InvalidSymmetricKeySize)?
+        .expand_multi_info(info, okm)
+        .map_err(|_| Error::InvalidBufferSize)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // RFC 5869, test case 1
+    const IKM: [u8; 22] = [0x0b; 22];
+    const SALT: [u8; 13] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+    ];
+    const INFO: [u8; 10] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+    const PRK: &str = "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5";
+    const OKM: &str =
+        "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865";
+
+    #[test]
+    fn test_extract_expand() {
+        let mut prk = [0u8; SHA256_PRK_SIZE];
+        hkdf_sha256_extract(&SALT, &[&IKM[..10], &IKM[10..]], &mut prk).expect("extract failed");
+        assert_eq!(prk.as_slice(), hex::decode(PRK).unwrap());
+
+        let mut okm = [0u8; 42];
+        hkdf_sha256_expand(&prk, &[&INFO[..3], &INFO[3..]], &mut okm).expect("expand failed");
+        assert_eq!(okm.as_slice(), hex::decode(OKM).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut prk = [0u8; SHA256_PRK_SIZE + 1];
+        assert_eq!(
+            hkdf_sha256_extract(&SALT, &[&IKM], &mut prk),
+            Err(Error::InvalidBufferSize)
+        );
+        let mut okm = [0u8; 16];
+        assert_eq!(
+            hkdf_sha256_expand(&prk[..SHA256_PRK_SIZE - 1], &[&INFO], &mut okm),
+            Err(Error::InvalidSymmetricKeySize)
+        );
+        let mut okm = [0u8; SHA256_MAX_OUTPUT_SIZE + 1];
+        assert_eq!(
+            hkdf_sha256_expand(&prk[..SHA256_PRK_SIZE], &[&INFO], &mut okm),
+            Err(Error::InvalidBufferSize)
+        );
+    }
+}
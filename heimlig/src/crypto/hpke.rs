@@ -0,0 +1,754 @@
+use crate::crypto::aes::gcm::{
+    aes128gcm_decrypt_in_place_detached, aes128gcm_encrypt_in_place_detached,
+    aes256gcm_decrypt_in_place_detached, aes256gcm_encrypt_in_place_detached,
+};
+use crate::crypto::ecdh::derive_shared_secret;
+use crate::crypto::hkdf::{hkdf_sha256_expand, hkdf_sha256_extract, SHA256_PRK_SIZE};
+use crate::crypto::x25519::{x25519_calculate_public_key, x25519_calculate_shared_secret};
+use crate::crypto::{chacha20poly1305, Error};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+/// Size of a KEM private key in bytes. Identical for all supported KEMs.
+pub const PRIVATE_KEY_SIZE: usize = 32;
+/// Size of the largest encapsulated key (an uncompressed P-256 point) in bytes.
+pub const MAX_ENCAPSULATED_KEY_SIZE: usize = 65;
+/// Size of the AEAD nonce in bytes. Identical for all supported AEADs.
+pub const NONCE_SIZE: usize = 12;
+/// Size of the AEAD authentication tag in bytes. Identical for all supported AEADs.
+pub const TAG_SIZE: usize = 16;
+
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+const KDF_HKDF_SHA256: u16 = 0x0001;
+const MODE_BASE: u8 = 0x00;
+const MODE_AUTH: u8 = 0x02;
+/// `Ndh` and `Nsecret` of both supported DHKEMs.
+const DH_SIZE: usize = 32;
+const MAX_AEAD_KEY_SIZE: usize = 32;
+/// `enc || pkRm || pkSm` in auth mode.
+const MAX_KEM_CONTEXT_SIZE: usize = 3 * MAX_ENCAPSULATED_KEY_SIZE;
+/// `mode || psk_id_hash || info_hash`
+const KEY_SCHEDULE_CONTEXT_SIZE: usize = 1 + 2 * SHA256_PRK_SIZE;
+
+/// Key encapsulation mechanisms (RFC 9180, section 7.1).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Kem {
+    /// DHKEM(X25519, HKDF-SHA256)
+    X25519HkdfSha256,
+    /// DHKEM(P-256, HKDF-SHA256)
+    P256HkdfSha256,
+}
+
+/// Authenticated encryption algorithms (RFC 9180, section 7.3).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Aead {
+    Aes128Gcm,
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+impl Kem {
+    /// IANA registry value of the KEM.
+    pub const fn id(&self) -> u16 {
+        match self {
+            Kem::X25519HkdfSha256 => 0x0020,
+            Kem::P256HkdfSha256 => 0x0010,
+        }
+    }
+
+    /// Size of the serialized encapsulated key (`Nenc`) in bytes.
+    pub const fn encapsulated_key_size(&self) -> usize {
+        match self {
+            Kem::X25519HkdfSha256 => 32,
+            Kem::P256HkdfSha256 => MAX_ENCAPSULATED_KEY_SIZE,
+        }
+    }
+
+    /// Size of a public key as it is stored in the key store. Other than the serialized form used
+    /// by HPKE, P-256 public keys omit the leading 0x04 tag byte.
+    pub const fn public_key_size(&self) -> usize {
+        match self {
+            Kem::X25519HkdfSha256 => 32,
+            Kem::P256HkdfSha256 => 64,
+        }
+    }
+
+    /// Determine the KEM from the size of a public key as it is stored in the key store.
+    pub fn from_public_key_size(size: usize) -> Option<Self> {
+        [Kem::X25519HkdfSha256, Kem::P256HkdfSha256]
+            .into_iter()
+            .find(|kem| kem.public_key_size() == size)
+    }
+}
+
+impl Aead {
+    /// IANA registry value of the AEAD.
+    pub const fn id(&self) -> u16 {
+        match self {
+            Aead::Aes128Gcm => 0x0001,
+            Aead::Aes256Gcm => 0x0002,
+            Aead::ChaCha20Poly1305 => 0x0003,
+        }
+    }
+
+    /// Determine the AEAD from its IANA registry value.
+    pub fn from_id(id: u16) -> Option<Self> {
+        [Aead::Aes128Gcm, Aead::Aes256Gcm, Aead::ChaCha20Poly1305]
+            .into_iter()
+            .find(|aead| aead.id() == id)
+    }
+
+    /// Size of the AEAD key (`Nk`) in bytes.
+    pub const fn key_size(&self) -> usize {
+        match self {
+            Aead::Aes128Gcm => 16,
+            Aead::Aes256Gcm | Aead::ChaCha20Poly1305 => 32,
+        }
+    }
+}
+
+/// Generate a private key for the given KEM. Used to create ephemeral keys during encapsulation.
+pub fn generate_private_key<R: CryptoRng + RngCore>(
+    kem: Kem,
+    rng: &mut R,
+) -> Zeroizing<[u8; PRIVATE_KEY_SIZE]> {
+    let mut private_key = Zeroizing::new([0u8; PRIVATE_KEY_SIZE]);
+    match kem {
+        // Every 32-byte string is a valid X25519 private key
+        Kem::X25519HkdfSha256 => rng.fill_bytes(private_key.as_mut_slice()),
+        Kem::P256HkdfSha256 => {
+            private_key.copy_from_slice(&p256::SecretKey::random(rng).to_bytes());
+        }
+    }
+    private_key
+}
+
+/// Encrypt a buffer in-place to a recipient public key in HPKE base or auth mode (RFC 9180). Only
+/// a single message is encrypted per encapsulation, i.e. the sequence number is always zero.
+///
+/// # Arguments
+///
+/// * `kem`: The KEM matching the recipient (and sender) keys.
+/// * `aead`: The AEAD used to encrypt `buffer`.
+/// * `ephemeral_private_key`: Freshly generated private key (see [generate_private_key]).
+/// * `recipient_public_key`: Public key of the recipient. Must be [Kem::public_key_size] bytes
+///   long.
+/// * `sender_private_key`: Private key of the sender. If present, auth mode is used. Otherwise,
+///   base mode is used.
+/// * `info`: Application-supplied information bound to the key schedule.
+/// * `aad`: Additional authenticated data.
+/// * `buffer`: Buffer holding the plaintext. Holds the ciphertext after successful execution.
+/// * `encapsulated_key`: Output buffer for the encapsulated key. Must be
+///   [Kem::encapsulated_key_size] bytes long.
+/// * `tag`: Output buffer for the authentication tag. Must be [TAG_SIZE] bytes long.
+#[allow(clippy::too_many_arguments)]
+pub fn seal(
+    kem: Kem,
+    aead: Aead,
+    ephemeral_private_key: &[u8],
+    recipient_public_key: &[u8],
+    sender_private_key: Option<&[u8]>,
+    info: &[u8],
+    aad: &[u8],
+    buffer: &mut [u8],
+    encapsulated_key: &mut [u8],
+    tag: &mut [u8],
+) -> Result<(), Error> {
+    if encapsulated_key.len() != kem.encapsulated_key_size() {
+        return Err(Error::InvalidBufferSize);
+    }
+    let mut recipient_public_key_buffer = [0u8; MAX_ENCAPSULATED_KEY_SIZE];
+    let recipient_public_key =
+        serialize_public_key(kem, recipient_public_key, &mut recipient_public_key_buffer)?;
+    let mut enc_buffer = [0u8; MAX_ENCAPSULATED_KEY_SIZE];
+    let enc = derive_public_key(kem, ephemeral_private_key, &mut enc_buffer)?;
+
+    let mut dh_buffer = Zeroizing::new([0u8; 2 * DH_SIZE]);
+    let mut kem_context_buffer = [0u8; MAX_KEM_CONTEXT_SIZE];
+    dh(
+        kem,
+        ephemeral_private_key,
+        recipient_public_key,
+        &mut dh_buffer[..DH_SIZE],
+    )?;
+    let (mode, dh_output, kem_context) = match sender_private_key {
+        None => (
+            MODE_BASE,
+            &dh_buffer[..DH_SIZE],
+            concat(&[enc, recipient_public_key], &mut kem_context_buffer),
+        ),
+        Some(sender_private_key) => {
+            dh(
+                kem,
+                sender_private_key,
+                recipient_public_key,
+                &mut dh_buffer[DH_SIZE..],
+            )?;
+            let mut sender_public_key_buffer = [0u8; MAX_ENCAPSULATED_KEY_SIZE];
+            let sender_public_key =
+                derive_public_key(kem, sender_private_key, &mut sender_public_key_buffer)?;
+            (
+                MODE_AUTH,
+                &dh_buffer[..],
+                concat(
+                    &[enc, recipient_public_key, sender_public_key],
+                    &mut kem_context_buffer,
+                ),
+            )
+        }
+    };
+
+    let mut key = Zeroizing::new([0u8; MAX_AEAD_KEY_SIZE]);
+    let key = &mut key[..aead.key_size()];
+    let mut nonce = Zeroizing::new([0u8; NONCE_SIZE]);
+    let shared_secret = extract_and_expand(kem, dh_output, kem_context)?;
+    key_schedule(
+        kem,
+        aead,
+        mode,
+        shared_secret.as_slice(),
+        info,
+        key,
+        nonce.as_mut_slice(),
+    )?;
+
+    match aead {
+        Aead::Aes128Gcm => aes128gcm_encrypt_in_place_detached(key, &nonce[..], aad, buffer, tag),
+        Aead::Aes256Gcm => aes256gcm_encrypt_in_place_detached(key, &nonce[..], aad, buffer, tag),
+        Aead::ChaCha20Poly1305 => {
+            chacha20poly1305::encrypt_in_place_detached(key, &nonce[..], aad, buffer, tag)
+        }
+    }?;
+    encapsulated_key.copy_from_slice(enc);
+    Ok(())
+}
+
+/// Decrypt a buffer in-place that was encrypted with [seal] in HPKE base or auth mode.
+///
+/// # Arguments
+///
+/// * `kem`: The KEM matching the recipient (and sender) keys.
+/// * `aead`: The AEAD used to decrypt `buffer`.
+/// * `recipient_private_key`: Private key of the recipient.
+/// * `sender_public_key`: Public key of the sender. Must be present if and only if the message was
+///   sealed in auth mode. Must be [Kem::public_key_size] bytes long.
+/// * `encapsulated_key`: The encapsulated key produced by [seal].
+/// * `info`: Application-supplied information bound to the key schedule.
+/// * `aad`: Additional authenticated data.
+/// * `buffer`: Buffer holding the ciphertext. Holds the plaintext after successful execution.
+/// * `tag`: The authentication tag produced by [seal].
+#[allow(clippy::too_many_arguments)]
+pub fn open(
+    kem: Kem,
+    aead: Aead,
+    recipient_private_key: &[u8],
+    sender_public_key: Option<&[u8]>,
+    encapsulated_key: &[u8],
+    info: &[u8],
+    aad: &[u8],
+    buffer: &mut [u8],
+    tag: &[u8],
+) -> Result<(), Error> {
+    if encapsulated_key.len() != kem.encapsulated_key_size() {
+        return Err(Error::InvalidPublicKey);
+    }
+    let mut recipient_public_key_buffer = [0u8; MAX_ENCAPSULATED_KEY_SIZE];
+    let recipient_public_key =
+        derive_public_key(kem, recipient_private_key, &mut recipient_public_key_buffer)?;
+
+    let mut dh_buffer = Zeroizing::new([0u8; 2 * DH_SIZE]);
+    let mut kem_context_buffer = [0u8; MAX_KEM_CONTEXT_SIZE];
+    dh(
+        kem,
+        recipient_private_key,
+        encapsulated_key,
+        &mut dh_buffer[..DH_SIZE],
+    )?;
+    let (mode, dh_output, kem_context) = match sender_public_key {
+        None => (
+            MODE_BASE,
+            &dh_buffer[..DH_SIZE],
+            concat(
+                &[encapsulated_key, recipient_public_key],
+                &mut kem_context_buffer,
+            ),
+        ),
+        Some(sender_public_key) => {
+            let mut sender_public_key_buffer = [0u8; MAX_ENCAPSULATED_KEY_SIZE];
+            let sender_public_key =
+                serialize_public_key(kem, sender_public_key, &mut sender_public_key_buffer)?;
+            dh(
+                kem,
+                recipient_private_key,
+                sender_public_key,
+                &mut dh_buffer[DH_SIZE..],
+            )?;
+            (
+                MODE_AUTH,
+                &dh_buffer[..],
+                concat(
+                    &[encapsulated_key, recipient_public_key, sender_public_key],
+                    &mut kem_context_buffer,
+                ),
+            )
+        }
+    };
+
+    let mut key = Zeroizing::new([0u8; MAX_AEAD_KEY_SIZE]);
+    let key = &mut key[..aead.key_size()];
+    let mut nonce = Zeroizing::new([0u8; NONCE_SIZE]);
+    let shared_secret = extract_and_expand(kem, dh_output, kem_context)?;
+    key_schedule(
+        kem,
+        aead,
+        mode,
+        shared_secret.as_slice(),
+        info,
+        key,
+        nonce.as_mut_slice(),
+    )?;
+
+    match aead {
+        Aead::Aes128Gcm => aes128gcm_decrypt_in_place_detached(key, &nonce[..], aad, buffer, tag),
+        Aead::Aes256Gcm => aes256gcm_decrypt_in_place_detached(key, &nonce[..], aad, buffer, tag),
+        Aead::ChaCha20Poly1305 => {
+            chacha20poly1305::decrypt_in_place_detached(key, &nonce[..], aad, buffer, tag)
+        }
+    }
+}
+
+/// Convert a public key from its key store representation into its HPKE serialization.
+fn serialize_public_key<'a>(
+    kem: Kem,
+    public_key: &[u8],
+    output: &'a mut [u8; MAX_ENCAPSULATED_KEY_SIZE],
+) -> Result<&'a [u8], Error> {
+    if public_key.len() != kem.public_key_size() {
+        return Err(Error::InvalidPublicKey);
+    }
+    match kem {
+        Kem::X25519HkdfSha256 => Ok(concat(&[public_key], output)),
+        // Uncompressed SEC 1 encoding
+        Kem::P256HkdfSha256 => Ok(concat(&[&[0x04], public_key], output)),
+    }
+}
+
+/// Compute the serialized public key belonging to a private key.
+fn derive_public_key<'a>(
+    kem: Kem,
+    private_key: &[u8],
+    output: &'a mut [u8; MAX_ENCAPSULATED_KEY_SIZE],
+) -> Result<&'a [u8], Error> {
+    match kem {
+        Kem::X25519HkdfSha256 => {
+            let output = &mut output[..kem.encapsulated_key_size()];
+            x25519_calculate_public_key(private_key, output)?;
+            Ok(output)
+        }
+        Kem::P256HkdfSha256 => {
+            let private_key =
+                p256::SecretKey::from_slice(private_key).map_err(|_| Error::InvalidPrivateKey)?;
+            let public_key = private_key.public_key().to_encoded_point(false);
+            Ok(concat(&[public_key.as_bytes()], output))
+        }
+    }
+}
+
+/// Diffie-Hellman key exchange with a serialized public key.
+fn dh(kem: Kem, private_key: &[u8], public_key: &[u8], output: &mut [u8]) -> Result<(), Error> {
+    match kem {
+        Kem::X25519HkdfSha256 => {
+            x25519_calculate_shared_secret(private_key, public_key, output)?;
+            // Reject low-order points (RFC 9180, section 7.1.4)
+            if output.iter().all(|byte| *byte == 0) {
+                return Err(Error::InvalidPublicKey);
+            }
+        }
+        Kem::P256HkdfSha256 => {
+            let private_key =
+                p256::SecretKey::from_slice(private_key).map_err(|_| Error::InvalidPrivateKey)?;
+            let public_key = p256::PublicKey::from_sec1_bytes(public_key)
+                .map_err(|_| Error::InvalidPublicKey)?;
+            if output.len() != DH_SIZE {
+                return Err(Error::InvalidBufferSize);
+            }
+            output.copy_from_slice(
+                derive_shared_secret(&private_key, &public_key).raw_secret_bytes(),
+            );
+        }
+    }
+    Ok(())
+}
+
+fn extract_and_expand(
+    kem: Kem,
+    dh: &[u8],
+    kem_context: &[u8],
+) -> Result<Zeroizing<[u8; DH_SIZE]>, Error> {
+    let mut suite_id = [0u8; 5];
+    let suite_id = concat(&[b"KEM", &kem.id().to_be_bytes()], &mut suite_id);
+    let mut eae_prk = Zeroizing::new([0u8; SHA256_PRK_SIZE]);
+    labeled_extract(suite_id, &[], b"eae_prk", dh, eae_prk.as_mut_slice())?;
+    let mut shared_secret = Zeroizing::new([0u8; DH_SIZE]);
+    labeled_expand(
+        suite_id,
+        eae_prk.as_slice(),
+        b"shared_secret",
+        kem_context,
+        shared_secret.as_mut_slice(),
+    )?;
+    Ok(shared_secret)
+}
+
+/// Key schedule without pre-shared key (RFC 9180, section 5.1).
+fn key_schedule(
+    kem: Kem,
+    aead: Aead,
+    mode: u8,
+    shared_secret: &[u8],
+    info: &[u8],
+    key: &mut [u8],
+    base_nonce: &mut [u8],
+) -> Result<(), Error> {
+    let mut suite_id = [0u8; 10];
+    let suite_id = concat(
+        &[
+            b"HPKE",
+            &kem.id().to_be_bytes(),
+            &KDF_HKDF_SHA256.to_be_bytes(),
+            &aead.id().to_be_bytes(),
+        ],
+        &mut suite_id,
+    );
+    let mut psk_id_hash = [0u8; SHA256_PRK_SIZE];
+    labeled_extract(suite_id, &[], b"psk_id_hash", &[], &mut psk_id_hash)?;
+    let mut info_hash = [0u8; SHA256_PRK_SIZE];
+    labeled_extract(suite_id, &[], b"info_hash", info, &mut info_hash)?;
+    let mut context = [0u8; KEY_SCHEDULE_CONTEXT_SIZE];
+    let context = concat(&[&[mode], &psk_id_hash, &info_hash], &mut context);
+
+    let mut secret = Zeroizing::new([0u8; SHA256_PRK_SIZE]);
+    labeled_extract(
+        suite_id,
+        shared_secret,
+        b"secret",
+        &[],
+        secret.as_mut_slice(),
+    )?;
+    labeled_expand(suite_id, secret.as_slice(), b"key", context, key)?;
+    labeled_expand(
+        suite_id,
+        secret.as_slice(),
+        b"base_nonce",
+        context,
+        base_nonce,
+    )
+}
+
+fn labeled_extract(
+    suite_id: &[u8],
+    salt: &[u8],
+    label: &[u8],
+    ikm: &[u8],
+    prk: &mut [u8],
+) -> Result<(), Error> {
+    hkdf_sha256_extract(salt, &[VERSION_LABEL, suite_id, label, ikm], prk)
+}
+
+fn labeled_expand(
+    suite_id: &[u8],
+    prk: &[u8],
+    label: &[u8],
+    info: &[u8],
+    okm: &mut [u8],
+) -> Result<(), Error> {
+    let length = u16::try_from(okm.len())
+        .map_err(|_| Error::InvalidBufferSize)?
+        .to_be_bytes();
+    hkdf_sha256_expand(prk, &[&length, VERSION_LABEL, suite_id, label, info], okm)
+}
+
+/// Concatenate `parts` into the beginning of `output`. All callers use statically sized buffers
+/// that are large enough for their inputs.
+fn concat<'a>(parts: &[&[u8]], output: &'a mut [u8]) -> &'a [u8] {
+    let mut offset = 0;
+    for part in parts {
+        output[offset..offset + part.len()].copy_from_slice(part);
+        offset += part.len();
+    }
+    &output[..offset]
+}
+
+#[cfg(test)]
+mod test {
+    extern crate alloc;
+    use super::*;
+    use crate::crypto::rng::{test::TestEntropySource, Rng};
+    use alloc::vec::Vec;
+
+    // Test vectors from RFC 9180, appendix A. All of them use the same inputs for the first
+    // message (sequence number 0).
+    const INFO: &str = "4f6465206f6e2061204772656369616e2055726e";
+    const AAD: &str = "436f756e742d30";
+    const PLAINTEXT: &str = "4265617574792069732074727574682c20747275746820626561757479";
+
+    struct TestVector {
+        kem: Kem,
+        aead: Aead,
+        ephemeral_private_key: &'static str,
+        encapsulated_key: &'static str,
+        recipient_private_key: &'static str,
+        // Serialized as in the RFC, i.e. with leading 0x04 tag for P-256
+        recipient_public_key: &'static str,
+        sender_private_key: Option<&'static str>,
+        sender_public_key: Option<&'static str>,
+        // Ciphertext followed by the tag
+        ciphertext: &'static str,
+    }
+
+    const TEST_VECTORS: [TestVector; 5] = [
+        // A.1.1. DHKEM(X25519, HKDF-SHA256), HKDF-SHA256, AES-128-GCM, Base
+        TestVector {
+            kem: Kem::X25519HkdfSha256,
+            aead: Aead::Aes128Gcm,
+            ephemeral_private_key: "52c4a758a802cd8b936eceea314432798d5baf2d7e9235dc084ab1b9cfa2f736",
+            encapsulated_key: "37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431",
+            recipient_private_key: "4612c550263fc8ad58375df3f557aac531d26850903e55a9f23f21d8534e8ac8",
+            recipient_public_key: "3948cfe0ad1ddb695d780e59077195da6c56506b027329794ab02bca80815c4d",
+            sender_private_key: None,
+            sender_public_key: None,
+            ciphertext: "f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a",
+        },
+        // A.1.3. DHKEM(X25519, HKDF-SHA256), HKDF-SHA256, AES-128-GCM, Auth
+        TestVector {
+            kem: Kem::X25519HkdfSha256,
+            aead: Aead::Aes128Gcm,
+            ephemeral_private_key: "ff4442ef24fbc3c1ff86375b0be1e77e88a0de1e79b30896d73411c5ff4c3518",
+            encapsulated_key: "23fb952571a14a25e3d678140cd0e5eb47a0961bb18afcf85896e5453c312e76",
+            recipient_private_key: "fdea67cf831f1ca98d8e27b1f6abeb5b7745e9d35348b80fa407ff6958f9137e",
+            recipient_public_key: "1632d5c2f71c2b38d0a8fcc359355200caa8b1ffdf28618080466c909cb69b2e",
+            sender_private_key: Some("dc4a146313cce60a278a5323d321f051c5707e9c45ba21a3479fecdf76fc69dd"),
+            sender_public_key: Some("8b0c70873dc5aecb7f9ee4e62406a397b350e57012be45cf53b7105ae731790b"),
+            ciphertext: "5fd92cc9d46dbf8943e72a07e42f363ed5f721212cd90bcfd072bfd9f44e06b80fd17824947496e21b680c141b",
+        },
+        // A.2.1. DHKEM(X25519, HKDF-SHA256), HKDF-SHA256, ChaCha20Poly1305, Base
+        TestVector {
+            kem: Kem::X25519HkdfSha256,
+            aead: Aead::ChaCha20Poly1305,
+            ephemeral_private_key: "f4ec9b33b792c372c1d2c2063507b684ef925b8c75a42dbcbf57d63ccd381600",
+            encapsulated_key: "1afa08d3dec047a643885163f1180476fa7ddb54c6a8029ea33f95796bf2ac4a",
+            recipient_private_key: "8057991eef8f1f1af18f4a9491d16a1ce333f695d4db8e38da75975c4478e0fb",
+            recipient_public_key: "4310ee97d88cc1f088a5576c77ab0cf5c3ac797f3d95139c6c84b5429c59662a",
+            sender_private_key: None,
+            sender_public_key: None,
+            ciphertext: "1c5250d8034ec2b784ba2cfd69dbdb8af406cfe3ff938e131f0def8c8b60b4db21993c62ce81883d2dd1b51a28",
+        },
+        // A.3.1. DHKEM(P-256, HKDF-SHA256), HKDF-SHA256, AES-128-GCM, Base
+        TestVector {
+            kem: Kem::P256HkdfSha256,
+            aead: Aead::Aes128Gcm,
+            ephemeral_private_key: "4995788ef4b9d6132b249ce59a77281493eb39af373d236a1fe415cb0c2d7beb",
+            encapsulated_key: "04a92719c6195d5085104f469a8b9814d5838ff72b60501e2c4466e5e67b325ac98536d7b61a1af4b78e5b7f951c0900be863c403ce65c9bfcb9382657222d18c4",
+            recipient_private_key: "f3ce7fdae57e1a310d87f1ebbde6f328be0a99cdbcadf4d6589cf29de4b8ffd2",
+            recipient_public_key: "04fe8c19ce0905191ebc298a9245792531f26f0cece2460639e8bc39cb7f706a826a779b4cf969b8a0e539c7f62fb3d30ad6aa8f80e30f1d128aafd68a2ce72ea0",
+            sender_private_key: None,
+            sender_public_key: None,
+            ciphertext: "5ad590bb8baa577f8619db35a36311226a896e7342a6d836d8b7bcd2f20b6c7f9076ac232e3ab2523f39513434",
+        },
+        // A.3.3. DHKEM(P-256, HKDF-SHA256), HKDF-SHA256, AES-128-GCM, Auth
+        TestVector {
+            kem: Kem::P256HkdfSha256,
+            aead: Aead::Aes128Gcm,
+            ephemeral_private_key: "6b8de0873aed0c1b2d09b8c7ed54cbf24fdf1dfc7a47fa501f918810642d7b91",
+            encapsulated_key: "042224f3ea800f7ec55c03f29fc9865f6ee27004f818fcbdc6dc68932c1e52e15b79e264a98f2c535ef06745f3d308624414153b22c7332bc1e691cb4af4d53454",
+            recipient_private_key: "d929ab4be2e59f6954d6bedd93e638f02d4046cef21115b00cdda2acb2a4440e",
+            recipient_public_key: "04423e363e1cd54ce7b7573110ac121399acbc9ed815fae03b72ffbd4c18b01836835c5a09513f28fc971b7266cfde2e96afe84bb0f266920e82c4f53b36e1a78d",
+            sender_private_key: Some("1120ac99fb1fccc1e8230502d245719d1b217fe20505c7648795139d177f0de9"),
+            sender_public_key: Some("04a817a0902bf28e036d66add5d544cc3a0457eab150f104285df1e293b5c10eef8651213e43d9cd9086c80b309df22cf37609f58c1127f7607e85f210b2804f73"),
+            ciphertext: "82ffc8c44760db691a07c5627e5fc2c08e7a86979ee79b494a17cc3405446ac2bdb8f265db4a099ed3289ffe19",
+        },
+    ];
+
+    /// Convert an RFC public key serialization into the key store representation.
+    fn stored_public_key(kem: Kem, serialized: &str) -> Vec<u8> {
+        let serialized = hex::decode(serialized).unwrap();
+        match kem {
+            Kem::X25519HkdfSha256 => serialized,
+            Kem::P256HkdfSha256 => serialized[1..].to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_vectors_seal() {
+        for vector in TEST_VECTORS.iter() {
+            let sender_private_key = vector.sender_private_key.map(|k| hex::decode(k).unwrap());
+            let expected = hex::decode(vector.ciphertext).unwrap();
+            let mut buffer = hex::decode(PLAINTEXT).unwrap();
+            let mut encapsulated_key = [0u8; MAX_ENCAPSULATED_KEY_SIZE];
+            let encapsulated_key = &mut encapsulated_key[..vector.kem.encapsulated_key_size()];
+            let mut tag = [0u8; TAG_SIZE];
+            seal(
+                vector.kem,
+                vector.aead,
+                &hex::decode(vector.ephemeral_private_key).unwrap(),
+                &stored_public_key(vector.kem, vector.recipient_public_key),
+                sender_private_key.as_deref(),
+                &hex::decode(INFO).unwrap(),
+                &hex::decode(AAD).unwrap(),
+                &mut buffer,
+                encapsulated_key,
+                &mut tag,
+            )
+            .expect("failed to seal");
+            assert_eq!(
+                encapsulated_key,
+                hex::decode(vector.encapsulated_key).unwrap()
+            );
+            assert_eq!(buffer, expected[..buffer.len()]);
+            assert_eq!(tag, expected[buffer.len()..]);
+        }
+    }
+
+    #[test]
+    fn test_vectors_open() {
+        for vector in TEST_VECTORS.iter() {
+            let sender_public_key = vector
+                .sender_public_key
+                .map(|k| stored_public_key(vector.kem, k));
+            let ciphertext = hex::decode(vector.ciphertext).unwrap();
+            let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_SIZE);
+            let mut buffer = ciphertext.to_vec();
+            open(
+                vector.kem,
+                vector.aead,
+                &hex::decode(vector.recipient_private_key).unwrap(),
+                sender_public_key.as_deref(),
+                &hex::decode(vector.encapsulated_key).unwrap(),
+                &hex::decode(INFO).unwrap(),
+                &hex::decode(AAD).unwrap(),
+                &mut buffer,
+                tag,
+            )
+            .expect("failed to open");
+            assert_eq!(buffer, hex::decode(PLAINTEXT).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_open_errors() {
+        // A.1.3. Auth mode
+        let vector = &TEST_VECTORS[1];
+        let recipient_private_key = hex::decode(vector.recipient_private_key).unwrap();
+        let sender_public_key = hex::decode(vector.sender_public_key.unwrap()).unwrap();
+        let encapsulated_key = hex::decode(vector.encapsulated_key).unwrap();
+        let ciphertext = hex::decode(vector.ciphertext).unwrap();
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_SIZE);
+        let open_with = |sender_public_key: Option<&[u8]>, encapsulated_key: &[u8], info: &[u8]| {
+            let mut buffer = ciphertext.to_vec();
+            open(
+                vector.kem,
+                vector.aead,
+                &recipient_private_key,
+                sender_public_key,
+                encapsulated_key,
+                info,
+                &hex::decode(AAD).unwrap(),
+                &mut buffer,
+                tag,
+            )
+        };
+        let info = hex::decode(INFO).unwrap();
+
+        assert_eq!(
+            open_with(Some(&sender_public_key), &encapsulated_key, &info),
+            Ok(())
+        );
+        // Missing sender authentication
+        assert_eq!(
+            open_with(None, &encapsulated_key, &info),
+            Err(Error::Decrypt)
+        );
+        // Wrong sender
+        assert_eq!(
+            open_with(Some(&encapsulated_key), &encapsulated_key, &info),
+            Err(Error::Decrypt)
+        );
+        // Wrong info
+        assert_eq!(
+            open_with(Some(&sender_public_key), &encapsulated_key, b"info"),
+            Err(Error::Decrypt)
+        );
+        // Invalid encapsulated key size
+        assert_eq!(
+            open_with(Some(&sender_public_key), &encapsulated_key[1..], &info),
+            Err(Error::InvalidPublicKey)
+        );
+        // Low-order point
+        assert_eq!(
+            open_with(Some(&sender_public_key), &[0u8; 32], &info),
+            Err(Error::InvalidPublicKey)
+        );
+    }
+
+    #[test]
+    fn test_seal_open_generated_keys() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        for kem in [Kem::X25519HkdfSha256, Kem::P256HkdfSha256] {
+            // RFC 9180 has no vectors for AES-256-GCM with the supported KEMs
+            for aead in [Aead::Aes128Gcm, Aead::Aes256Gcm, Aead::ChaCha20Poly1305] {
+                let recipient_private_key = generate_private_key(kem, &mut rng);
+                let mut recipient_public_key = [0u8; MAX_ENCAPSULATED_KEY_SIZE];
+                let recipient_public_key = derive_public_key(
+                    kem,
+                    recipient_private_key.as_slice(),
+                    &mut recipient_public_key,
+                )
+                .unwrap();
+                let recipient_public_key =
+                    &recipient_public_key[recipient_public_key.len() - kem.public_key_size()..];
+                let ephemeral_private_key = generate_private_key(kem, &mut rng);
+
+                let mut buffer = *b"Hello, World!";
+                let mut encapsulated_key = [0u8; MAX_ENCAPSULATED_KEY_SIZE];
+                let encapsulated_key = &mut encapsulated_key[..kem.encapsulated_key_size()];
+                let mut tag = [0u8; TAG_SIZE];
+                seal(
+                    kem,
+                    aead,
+                    ephemeral_private_key.as_slice(),
+                    recipient_public_key,
+                    None,
+                    b"info",
+                    b"aad",
+                    &mut buffer,
+                    encapsulated_key,
+                    &mut tag,
+                )
+                .expect("failed to seal");
+                assert_ne!(&buffer, b"Hello, World!");
+                open(
+                    kem,
+                    aead,
+                    recipient_private_key.as_slice(),
+                    None,
+                    encapsulated_key,
+                    b"info",
+                    b"aad",
+                    &mut buffer,
+                    &tag,
+                )
+                .expect("failed to open");
+                assert_eq!(&buffer, b"Hello, World!");
+            }
+        }
+    }
+
+    #[test]
+    fn test_ids() {
+        assert_eq!(Aead::from_id(0x0002), Some(Aead::Aes256Gcm));
+        assert_eq!(Aead::from_id(0xffff), None);
+        assert_eq!(Kem::from_public_key_size(64), Some(Kem::P256HkdfSha256));
+        assert_eq!(Kem::from_public_key_size(65), None);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::crypto::ecdsa::{nist_p256_sign, nist_p256_verify, nist_p384_sign, nist_p384_verify};
+use crate::crypto::ed25519::{ed25519_sign, ed25519_verify};
+use crate::crypto::hash::SHA256_SIZE;
+use crate::crypto::jws::{Algorithm, MAX_SIGNATURE_SIZE};
+use crate::crypto::Error;
+
+/// Magic value at the start of every firmware image manifest.
+pub const MAGIC: [u8; 4] = *b"HBM1";
+/// Size of the signed part of a manifest in bytes.
+pub const BODY_SIZE: usize = MAGIC.len() + 4 + 4 + SHA256_SIZE;
+/// Maximum size of a signed manifest in bytes.
+pub const MAX_MANIFEST_SIZE: usize = BODY_SIZE + MAX_SIGNATURE_SIZE;
+
+/// Firmware image manifest.
+///
+/// A signed manifest is encoded as `magic || security version || image size || image hash ||
+/// signature`. Integers are big-endian `u32` values, the image hash is a SHA-256 digest and the
+/// signature covers all preceding bytes. ECDSA signatures are encoded as `r||s`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Manifest {
+    /// Security version of the image used for anti-rollback protection.
+    pub security_version: u32,
+    /// Size of the image in bytes.
+    pub image_size: u32,
+    /// SHA-256 digest of the image.
+    pub image_hash: [u8; SHA256_SIZE],
+}
+
+impl Manifest {
+    /// Size of the signed manifest for the given signature algorithm.
+    pub const fn size(algorithm: Algorithm) -> usize {
+        BODY_SIZE + algorithm.signature_size()
+    }
+
+    fn body(&self) -> [u8; BODY_SIZE] {
+        let mut body = [0u8; BODY_SIZE];
+        body[..4].copy_from_slice(&MAGIC);
+        body[4..8].copy_from_slice(&self.security_version.to_be_bytes());
+        body[8..12].copy_from_slice(&self.image_size.to_be_bytes());
+        body[12..].copy_from_slice(&self.image_hash);
+        body
+    }
+
+    fn from_body(body: &[u8; BODY_SIZE]) -> Result<Self, Error> {
+        if body[..4] != MAGIC {
+            return Err(Error::InvalidEncoding);
+        }
+        let mut image_hash = [0u8; SHA256_SIZE];
+        image_hash.copy_from_slice(&body[12..]);
+        Ok(Manifest {
+            security_version: u32::from_be_bytes([body[4], body[5], body[6], body[7]]),
+            image_size: u32::from_be_bytes([body[8], body[9], body[10], body[11]]),
+            image_hash,
+        })
+    }
+}
+
+/// Signs a manifest.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidBufferSize`: The `output` is too small.
+/// * `InvalidPrivateKey`: The `private_key` is not valid for `algorithm`.
+pub fn sign<'a>(
+    algorithm: Algorithm,
+    private_key: &[u8],
+    manifest: &Manifest,
+    output: &'a mut [u8],
+) -> Result<&'a mut [u8], Error> {
+    let size = Manifest::size(algorithm);
+    if output.len() < size {
+        return Err(Error::InvalidBufferSize);
+    }
+    let (body, signature) = output[..size].split_at_mut(BODY_SIZE);
+    body.copy_from_slice(&manifest.body());
+    match algorithm {
+        Algorithm::Es256 => nist_p256_sign(private_key, body, signature)?,
+        Algorithm::Es384 => nist_p384_sign(private_key, body, signature)?,
+        Algorithm::EdDsa => ed25519_sign(private_key, body, signature)?,
+    }
+    Ok(&mut output[..size])
+}
+
+/// Verifies the signature of a manifest and decodes it.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidEncoding`: The `manifest` has the wrong size or magic value.
+/// * `InvalidPublicKey`: The `public_key` is not valid for `algorithm`.
+/// * `InvalidSignature`: The manifest was not signed by `algorithm` with the given key.
+pub fn verify(algorithm: Algorithm, public_key: &[u8], manifest: &[u8]) -> Result<Manifest, Error> {
+    if manifest.len() != Manifest::size(algorithm) {
+        return Err(Error::InvalidEncoding);
+    }
+    let (body, signature) = manifest.split_at(BODY_SIZE);
+    let body: &[u8; BODY_SIZE] = body.try_into().map_err(|_| Error::InvalidEncoding)?;
+    let decoded = Manifest::from_body(body)?;
+    match algorithm {
+        Algorithm::Es256 => nist_p256_verify(public_key, body, signature)?,
+        Algorithm::Es384 => nist_p384_verify(public_key, body, signature)?,
+        Algorithm::EdDsa => ed25519_verify(public_key, body, signature)?,
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::ecdsa::nist_p384_generate_key_pair;
+    use crate::crypto::ed25519::ed25519_generate_key_pair;
+    use crate::crypto::hash::sha256;
+    use crate::crypto::rng::{test::TestEntropySource, Rng};
+
+    const MANIFEST: Manifest = Manifest {
+        security_version: 3,
+        image_size: 0x0102_0304,
+        image_hash: [0xab; SHA256_SIZE],
+    };
+
+    #[test]
+    fn sign_verify() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let mut output = [0u8; MAX_MANIFEST_SIZE];
+
+        let (private_key, public_key) = nist_p384_generate_key_pair(&mut rng);
+        let signed = sign(Algorithm::Es384, &private_key, &MANIFEST, &mut output)
+            .expect("failed to sign manifest");
+        assert_eq!(signed.len(), MAX_MANIFEST_SIZE);
+        assert_eq!(&signed[..12], b"HBM1\x00\x00\x00\x03\x01\x02\x03\x04");
+        assert_eq!(verify(Algorithm::Es384, &public_key, signed), Ok(MANIFEST));
+
+        let (private_key, public_key) = ed25519_generate_key_pair(&mut rng);
+        let signed = sign(Algorithm::EdDsa, &private_key, &MANIFEST, &mut output)
+            .expect("failed to sign manifest");
+        assert_eq!(verify(Algorithm::EdDsa, &public_key, signed), Ok(MANIFEST));
+    }
+
+    #[test]
+    fn reject_invalid_manifests() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let mut output = [0u8; MAX_MANIFEST_SIZE];
+        let (private_key, public_key) = ed25519_generate_key_pair(&mut rng);
+        let manifest = Manifest {
+            image_hash: sha256(b"firmware"),
+            ..MANIFEST
+        };
+        let size = sign(Algorithm::EdDsa, &private_key, &manifest, &mut output)
+            .expect("failed to sign manifest")
+            .len();
+
+        assert_eq!(
+            verify(Algorithm::EdDsa, &public_key, &output[..size - 1]),
+            Err(Error::InvalidEncoding)
+        );
+        assert_eq!(
+            verify(Algorithm::Es384, &public_key, &output[..size]),
+            Err(Error::InvalidEncoding)
+        );
+        assert_eq!(
+            verify(Algorithm::Es256, &public_key, &output[..size]),
+            Err(Error::InvalidPublicKey)
+        );
+        output[7] ^= 1;
+        assert_eq!(
+            verify(Algorithm::EdDsa, &public_key, &output[..size]),
+            Err(Error::InvalidSignature)
+        );
+        output[7] ^= 1;
+        output[0] = b'X';
+        assert_eq!(
+            verify(Algorithm::EdDsa, &public_key, &output[..size]),
+            Err(Error::InvalidEncoding)
+        );
+        assert_eq!(
+            sign(
+                Algorithm::EdDsa,
+                &private_key,
+                &manifest,
+                &mut output[..size - 1]
+            ),
+            Err(Error::InvalidBufferSize)
+        );
+    }
+}
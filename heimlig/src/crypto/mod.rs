@@ -1,11 +1,25 @@
 pub mod aes;
+pub mod attestation;
 pub mod chacha20poly1305;
+pub mod dice;
+pub mod drbg;
 pub mod ecc;
 pub mod ecdh;
 pub mod ecdsa;
+pub mod ecies;
 pub mod ed25519;
+pub mod entropy_pool;
 pub mod hash;
+pub mod health;
+pub mod hkdf;
+pub mod hpke;
+pub mod jws;
+pub mod manifest;
+pub mod noise;
 pub mod rng;
+pub mod secoc;
+pub mod spdm;
+pub mod tls;
 pub mod x25519;
 
 /// Common errors.
@@ -35,6 +49,14 @@ pub enum Error {
     InvalidSignature,
     /// Invalid size of the digest.
     InvalidDigestSize,
+    /// Provided data is not correctly encoded.
+    InvalidEncoding,
+    /// The requested algorithm is not supported or does not match the key.
+    InvalidAlgorithm,
+    /// The operation is not allowed in the current state of the protocol.
+    InvalidState,
+    /// The freshness value is not newer than the latest accepted one or is exhausted.
+    InvalidFreshness,
 }
 
 /// Validation of key and initialization vector/nonce sizes.
@@ -0,0 +1,663 @@
+use crate::crypto::chacha20poly1305::{decrypt_in_place_detached, encrypt_in_place_detached};
+use crate::crypto::hkdf::{hkdf_sha256_expand, hkdf_sha256_extract};
+use crate::crypto::x25519::{x25519_calculate_public_key, x25519_calculate_shared_secret};
+use crate::crypto::Error;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+/// Name of the only supported protocol.
+pub const PROTOCOL_NAME: &[u8] = b"Noise_XX_25519_ChaChaPoly_SHA256";
+/// Size of X25519 keys and shared secrets in bytes.
+pub const DH_SIZE: usize = 32;
+/// Size of the SHA-256 hash in bytes.
+pub const HASH_SIZE: usize = 32;
+/// Size of the ChaCha20Poly1305 authentication tag in bytes.
+pub const TAG_SIZE: usize = 16;
+/// Maximum size of a Noise message in bytes.
+pub const MAX_MESSAGE_SIZE: usize = 65535;
+/// Number of handshake messages of the XX pattern.
+pub const HANDSHAKE_MESSAGES: usize = 3;
+
+const KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Token {
+    E,
+    S,
+    Ee,
+    Es,
+    Se,
+}
+
+/// Message patterns of the XX handshake:
+/// ```text
+/// -> e
+/// <- e, ee, s, es
+/// -> s, se
+/// ```
+const XX: [&[Token]; HANDSHAKE_MESSAGES] = [
+    &[Token::E],
+    &[Token::E, Token::Ee, Token::S, Token::Es],
+    &[Token::S, Token::Se],
+];
+
+/// Number of bytes a handshake message adds to its payload.
+///
+/// # Arguments
+///
+/// * `index`: The index of the handshake message, starting at zero.
+pub const fn handshake_overhead(index: usize) -> usize {
+    match index {
+        0 => DH_SIZE,
+        1 => DH_SIZE + DH_SIZE + TAG_SIZE + TAG_SIZE,
+        2 => DH_SIZE + TAG_SIZE + TAG_SIZE,
+        _ => 0,
+    }
+}
+
+/// Cipher state of the Noise protocol framework (section 5.1).
+struct CipherState {
+    key: Option<Zeroizing<[u8; KEY_SIZE]>>,
+    nonce: u64,
+}
+
+impl CipherState {
+    fn new(key: Option<&[u8]>) -> Self {
+        CipherState {
+            key: key.map(|key| {
+                let mut k = Zeroizing::new([0u8; KEY_SIZE]);
+                k.copy_from_slice(key);
+                k
+            }),
+            nonce: 0,
+        }
+    }
+
+    fn nonce(&self) -> Result<[u8; NONCE_SIZE], Error> {
+        // The maximum nonce value is reserved
+        if self.nonce == u64::MAX {
+            return Err(Error::InvalidIvSize);
+        }
+        let mut nonce = [0u8; NONCE_SIZE];
+        nonce[4..].copy_from_slice(&self.nonce.to_le_bytes());
+        Ok(nonce)
+    }
+
+    fn encrypt_with_ad(
+        &mut self,
+        ad: &[u8],
+        buffer: &mut [u8],
+        tag: &mut [u8],
+    ) -> Result<(), Error> {
+        let key = self.key.as_ref().ok_or(Error::InvalidSymmetricKeySize)?;
+        encrypt_in_place_detached(key.as_slice(), &self.nonce()?, ad, buffer, tag)?;
+        self.nonce += 1;
+        Ok(())
+    }
+
+    fn decrypt_with_ad(&mut self, ad: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<(), Error> {
+        let key = self.key.as_ref().ok_or(Error::InvalidSymmetricKeySize)?;
+        decrypt_in_place_detached(key.as_slice(), &self.nonce()?, ad, buffer, tag)?;
+        self.nonce += 1;
+        Ok(())
+    }
+}
+
+/// Symmetric state of the Noise protocol framework (section 5.2).
+struct SymmetricState {
+    ck: Zeroizing<[u8; HASH_SIZE]>,
+    h: [u8; HASH_SIZE],
+    cipher: CipherState,
+}
+
+impl SymmetricState {
+    fn new(prologue: &[u8]) -> Self {
+        // The protocol name has exactly HASH_SIZE bytes and is used without hashing
+        let mut h = [0u8; HASH_SIZE];
+        h.copy_from_slice(PROTOCOL_NAME);
+        let mut state = SymmetricState {
+            ck: Zeroizing::new(h),
+            h,
+            cipher: CipherState::new(None),
+        };
+        state.mix_hash(prologue);
+        state
+    }
+
+    fn mix_hash(&mut self, data: &[u8]) {
+        self.h = Sha256::new()
+            .chain_update(self.h)
+            .chain_update(data)
+            .finalize()
+            .into();
+    }
+
+    fn mix_key(&mut self, input_key_material: &[u8]) -> Result<(), Error> {
+        let output = hkdf(self.ck.as_slice(), input_key_material)?;
+        self.ck.copy_from_slice(&output[..HASH_SIZE]);
+        self.cipher = CipherState::new(Some(&output[HASH_SIZE..HASH_SIZE + KEY_SIZE]));
+        Ok(())
+    }
+
+    /// Encrypt the first `size` bytes of `buffer` in-place and append the tag if a key is set.
+    ///
+    /// returns: The size of the ciphertext including the tag.
+    fn encrypt_and_hash(&mut self, buffer: &mut [u8], size: usize) -> Result<usize, Error> {
+        let size = if self.cipher.key.is_some() {
+            if buffer.len() < size + TAG_SIZE {
+                return Err(Error::InvalidBufferSize);
+            }
+            let (plaintext, tag) = buffer[..size + TAG_SIZE].split_at_mut(size);
+            self.cipher.encrypt_with_ad(&self.h, plaintext, tag)?;
+            size + TAG_SIZE
+        } else {
+            size
+        };
+        self.mix_hash(&buffer[..size]);
+        Ok(size)
+    }
+
+    /// Decrypt `ciphertext` into `output` if a key is set or copy it otherwise.
+    ///
+    /// returns: The size of the plaintext.
+    fn decrypt_and_hash(&mut self, ciphertext: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let size = if self.cipher.key.is_some() {
+            if ciphertext.len() < TAG_SIZE {
+                return Err(Error::InvalidBufferSize);
+            }
+            let size = ciphertext.len() - TAG_SIZE;
+            let output = output.get_mut(..size).ok_or(Error::InvalidBufferSize)?;
+            output.copy_from_slice(&ciphertext[..size]);
+            self.cipher
+                .decrypt_with_ad(&self.h, output, &ciphertext[size..])?;
+            size
+        } else {
+            let output = output
+                .get_mut(..ciphertext.len())
+                .ok_or(Error::InvalidBufferSize)?;
+            output.copy_from_slice(ciphertext);
+            ciphertext.len()
+        };
+        self.mix_hash(ciphertext);
+        Ok(size)
+    }
+
+    fn split(&self) -> Result<(CipherState, CipherState), Error> {
+        let output = hkdf(self.ck.as_slice(), &[])?;
+        Ok((
+            CipherState::new(Some(&output[..KEY_SIZE])),
+            CipherState::new(Some(&output[HASH_SIZE..HASH_SIZE + KEY_SIZE])),
+        ))
+    }
+}
+
+/// HKDF of the Noise protocol framework with two outputs (section 4.3).
+fn hkdf(
+    chaining_key: &[u8],
+    input_key_material: &[u8],
+) -> Result<Zeroizing<[u8; 2 * HASH_SIZE]>, Error> {
+    let mut prk = Zeroizing::new([0u8; HASH_SIZE]);
+    hkdf_sha256_extract(chaining_key, &[input_key_material], prk.as_mut_slice())?;
+    let mut output = Zeroizing::new([0u8; 2 * HASH_SIZE]);
+    hkdf_sha256_expand(prk.as_slice(), &[], output.as_mut_slice())?;
+    Ok(output)
+}
+
+struct KeyPair {
+    private_key: Zeroizing<[u8; DH_SIZE]>,
+    public_key: [u8; DH_SIZE],
+}
+
+impl KeyPair {
+    fn new(private_key: &[u8]) -> Result<Self, Error> {
+        let mut key_pair = KeyPair {
+            private_key: Zeroizing::new([0u8; DH_SIZE]),
+            public_key: [0u8; DH_SIZE],
+        };
+        x25519_calculate_public_key(private_key, &mut key_pair.public_key)?;
+        key_pair.private_key.copy_from_slice(private_key);
+        Ok(key_pair)
+    }
+
+    fn dh(&self, public_key: &[u8]) -> Result<Zeroizing<[u8; DH_SIZE]>, Error> {
+        let mut shared_secret = Zeroizing::new([0u8; DH_SIZE]);
+        x25519_calculate_shared_secret(
+            self.private_key.as_slice(),
+            public_key,
+            shared_secret.as_mut_slice(),
+        )?;
+        Ok(shared_secret)
+    }
+}
+
+/// Handshake state of a Noise_XX_25519_ChaChaPoly_SHA256 handshake (section 5.3).
+pub struct HandshakeState {
+    symmetric: SymmetricState,
+    initiator: bool,
+    s: KeyPair,
+    e: KeyPair,
+    rs: Option<[u8; DH_SIZE]>,
+    re: Option<[u8; DH_SIZE]>,
+    message_index: usize,
+}
+
+impl HandshakeState {
+    /// Create a new handshake state.
+    ///
+    /// # Arguments
+    ///
+    /// * `initiator`: Whether the local party sends the first handshake message.
+    /// * `prologue`: Data both parties must agree on. It is authenticated by the handshake.
+    /// * `static_private_key`: The local static X25519 private key.
+    /// * `ephemeral_private_key`: A freshly generated X25519 private key used for this handshake.
+    ///
+    /// # Errors
+    ///
+    /// The function returns an error if:
+    /// * `InvalidPrivateKey`: One of the private keys is not [DH_SIZE] bytes long.
+    pub fn new(
+        initiator: bool,
+        prologue: &[u8],
+        static_private_key: &[u8],
+        ephemeral_private_key: &[u8],
+    ) -> Result<Self, Error> {
+        Ok(HandshakeState {
+            symmetric: SymmetricState::new(prologue),
+            initiator,
+            s: KeyPair::new(static_private_key)?,
+            e: KeyPair::new(ephemeral_private_key)?,
+            rs: None,
+            re: None,
+            message_index: 0,
+        })
+    }
+
+    /// Whether the local party has to write the next handshake message.
+    pub fn is_write_turn(&self) -> bool {
+        !self.is_finished() && self.message_index.is_multiple_of(2) == self.initiator
+    }
+
+    /// Whether all handshake messages have been exchanged.
+    pub fn is_finished(&self) -> bool {
+        self.message_index == HANDSHAKE_MESSAGES
+    }
+
+    /// The static public key of the remote party once it has been received.
+    pub fn remote_static_public_key(&self) -> Option<&[u8; DH_SIZE]> {
+        self.rs.as_ref()
+    }
+
+    /// The handshake hash that can be used for channel binding.
+    pub fn handshake_hash(&self) -> &[u8; HASH_SIZE] {
+        &self.symmetric.h
+    }
+
+    /// Write the next handshake message.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload`: The payload sent along with the handshake message. It is encrypted from the
+    ///   second message on.
+    /// * `message`: Output buffer for the message. It must be able to hold the payload and the
+    ///   [handshake_overhead] of the message.
+    ///
+    /// returns: The size of the message.
+    ///
+    /// # Errors
+    ///
+    /// The function returns an error if:
+    /// * `InvalidState`: It is not the local party's turn to write a handshake message.
+    /// * `InvalidBufferSize`: The `message` buffer is too small or exceeds [MAX_MESSAGE_SIZE].
+    pub fn write_message(&mut self, payload: &[u8], message: &mut [u8]) -> Result<usize, Error> {
+        if !self.is_write_turn() {
+            return Err(Error::InvalidState);
+        }
+        let size = handshake_overhead(self.message_index) + payload.len();
+        if size > MAX_MESSAGE_SIZE || message.len() < size {
+            return Err(Error::InvalidBufferSize);
+        }
+        let mut offset = 0;
+        for token in XX[self.message_index] {
+            match token {
+                Token::E => {
+                    message[offset..offset + DH_SIZE].copy_from_slice(&self.e.public_key);
+                    self.symmetric.mix_hash(&self.e.public_key);
+                    offset += DH_SIZE;
+                }
+                Token::S => {
+                    message[offset..offset + DH_SIZE].copy_from_slice(&self.s.public_key);
+                    offset += self
+                        .symmetric
+                        .encrypt_and_hash(&mut message[offset..], DH_SIZE)?;
+                }
+                _ => self.mix_dh(*token)?,
+            }
+        }
+        message[offset..offset + payload.len()].copy_from_slice(payload);
+        offset += self
+            .symmetric
+            .encrypt_and_hash(&mut message[offset..], payload.len())?;
+        self.message_index += 1;
+        Ok(offset)
+    }
+
+    /// Read the next handshake message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`: The received handshake message.
+    /// * `payload`: Output buffer for the payload transported in the message.
+    ///
+    /// returns: The size of the payload.
+    ///
+    /// # Errors
+    ///
+    /// The function returns an error if:
+    /// * `InvalidState`: It is not the remote party's turn to write a handshake message.
+    /// * `InvalidBufferSize`: The `message` is too short or too long or `payload` is too small.
+    /// * `Decrypt`: The message could not be authenticated.
+    pub fn read_message(&mut self, message: &[u8], payload: &mut [u8]) -> Result<usize, Error> {
+        if self.is_finished() || self.is_write_turn() {
+            return Err(Error::InvalidState);
+        }
+        if message.len() < handshake_overhead(self.message_index)
+            || message.len() > MAX_MESSAGE_SIZE
+        {
+            return Err(Error::InvalidBufferSize);
+        }
+        let mut offset = 0;
+        for token in XX[self.message_index] {
+            match token {
+                Token::E => {
+                    let mut re = [0u8; DH_SIZE];
+                    re.copy_from_slice(&message[offset..offset + DH_SIZE]);
+                    self.symmetric.mix_hash(&re);
+                    self.re = Some(re);
+                    offset += DH_SIZE;
+                }
+                Token::S => {
+                    // Encrypted because a key has been established by a previous token
+                    let size = DH_SIZE + TAG_SIZE;
+                    let mut rs = [0u8; DH_SIZE];
+                    self.symmetric
+                        .decrypt_and_hash(&message[offset..offset + size], &mut rs)?;
+                    self.rs = Some(rs);
+                    offset += size;
+                }
+                _ => self.mix_dh(*token)?,
+            }
+        }
+        let size = self
+            .symmetric
+            .decrypt_and_hash(&message[offset..], payload)?;
+        self.message_index += 1;
+        Ok(size)
+    }
+
+    /// Derive the transport cipher states of a finished handshake.
+    ///
+    /// # Errors
+    ///
+    /// The function returns an error if:
+    /// * `InvalidState`: The handshake is not finished yet.
+    pub fn split(&self) -> Result<TransportState, Error> {
+        if !self.is_finished() {
+            return Err(Error::InvalidState);
+        }
+        let (c1, c2) = self.symmetric.split()?;
+        let (send, receive) = if self.initiator { (c1, c2) } else { (c2, c1) };
+        Ok(TransportState {
+            send,
+            receive,
+            handshake_hash: self.symmetric.h,
+            remote_static_public_key: self.rs.ok_or(Error::InvalidPublicKey)?,
+        })
+    }
+
+    fn mix_dh(&mut self, token: Token) -> Result<(), Error> {
+        let re = self.re.as_ref().ok_or(Error::InvalidPublicKey);
+        let rs = self.rs.as_ref().ok_or(Error::InvalidPublicKey);
+        let shared_secret = match (token, self.initiator) {
+            (Token::Ee, _) => self.e.dh(re?)?,
+            (Token::Es, true) | (Token::Se, false) => self.e.dh(rs?)?,
+            (Token::Es, false) | (Token::Se, true) => self.s.dh(re?)?,
+            _ => return Err(Error::InvalidState),
+        };
+        self.symmetric.mix_key(shared_secret.as_slice())
+    }
+}
+
+/// Transport cipher states after a completed handshake.
+pub struct TransportState {
+    send: CipherState,
+    receive: CipherState,
+    handshake_hash: [u8; HASH_SIZE],
+    remote_static_public_key: [u8; DH_SIZE],
+}
+
+impl TransportState {
+    /// The handshake hash that can be used for channel binding.
+    pub fn handshake_hash(&self) -> &[u8; HASH_SIZE] {
+        &self.handshake_hash
+    }
+
+    /// The authenticated static public key of the remote party.
+    pub fn remote_static_public_key(&self) -> &[u8; DH_SIZE] {
+        &self.remote_static_public_key
+    }
+
+    /// Encrypt a transport message in-place.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer`: The buffer holding the plaintext. After successful execution, it holds the
+    ///   ciphertext.
+    /// * `tag`: Output buffer for the authentication tag. Must be exactly [TAG_SIZE] bytes long.
+    ///
+    /// # Errors
+    ///
+    /// The function returns an error if:
+    /// * `InvalidBufferSize`: The message exceeds [MAX_MESSAGE_SIZE].
+    /// * `InvalidIvSize`: The nonces of the sending cipher state are exhausted.
+    /// * `InvalidTagSize`: The `tag` is not [TAG_SIZE] bytes long.
+    pub fn encrypt(&mut self, buffer: &mut [u8], tag: &mut [u8]) -> Result<(), Error> {
+        if buffer.len() + TAG_SIZE > MAX_MESSAGE_SIZE {
+            return Err(Error::InvalidBufferSize);
+        }
+        self.send.encrypt_with_ad(&[], buffer, tag)
+    }
+
+    /// Decrypt a transport message in-place.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer`: The buffer holding the ciphertext. After successful execution, it holds the
+    ///   plaintext.
+    /// * `tag`: The authentication tag. Must be exactly [TAG_SIZE] bytes long.
+    ///
+    /// # Errors
+    ///
+    /// The function returns an error if:
+    /// * `InvalidBufferSize`: The message exceeds [MAX_MESSAGE_SIZE].
+    /// * `InvalidIvSize`: The nonces of the receiving cipher state are exhausted.
+    /// * `InvalidTagSize`: The `tag` is not [TAG_SIZE] bytes long.
+    /// * `Decrypt`: The message could not be authenticated.
+    pub fn decrypt(&mut self, buffer: &mut [u8], tag: &[u8]) -> Result<(), Error> {
+        if buffer.len() + TAG_SIZE > MAX_MESSAGE_SIZE {
+            return Err(Error::InvalidBufferSize);
+        }
+        self.receive.decrypt_with_ad(&[], buffer, tag)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use heapless::Vec;
+
+    // Noise_XX_25519_ChaChaPoly_SHA256 vector from the cacophony test suite
+    const PROLOGUE: &str = "4a6f686e2047616c74";
+    const INITIATOR_STATIC: &str =
+        "e61ef9919cde45dd5f82166404bd08e38bceb5dfdfded0a34c8df7ed542214d1";
+    const INITIATOR_EPHEMERAL: &str =
+        "893e28b9dc6ca8d611ab664754b8ceb7bac5117349a4439a6b0569da977c464a";
+    const RESPONDER_STATIC: &str =
+        "4a3acbfdb163dec651dfa3194dece676d437029c62a408b4c5ea9114246e4893";
+    const RESPONDER_EPHEMERAL: &str =
+        "bbdb4cdbd309f1a1f2e1456967fe288cadd6f712d65dc7b7793d5e63da6b375b";
+    const HANDSHAKE_HASH: &str = "c8e5f64e846193be2a834104c2a009868d6c9f3bd3c186299888b488b2f1f58e";
+    const MESSAGES: [(&str, &str); 6] = [
+        (
+            "4c756477696720766f6e204d69736573",
+            "ca35def5ae56cec33dc2036731ab14896bc4c75dbb07a61f879f8e3afa4c79444c756477696720766f6e204d69736573",
+        ),
+        (
+            "4d757272617920526f746862617264",
+            "95ebc60d2b1fa672c1f46a8aa265ef51bfe38e7ccb39ec5be34069f14480884381cbad1f276e038c48378ffce2b65285e08d6b68aaa3629a5a8639392490e5b9bd5269c2f1e4f488ed8831161f19b7815528f8982ffe09be9b5c412f8a0db50f8814c7194e83f23dbd8d162c9326ad",
+        ),
+        (
+            "462e20412e20486179656b",
+            "c7195ffacac1307ff99046f219750fc47693e23c3cb08b89c2af808b444850a80ae475b9df0f169ae80a89be0865b57f58c9fea0d4ec82a286427402f113e4b6ae769a1d95941d49b25030",
+        ),
+        (
+            "4361726c204d656e676572",
+            "96763ed773f8e47bb3712f0e29b3060ffc956ffc146cee53d5e1df",
+        ),
+        (
+            "4a65616e2d426170746973746520536179",
+            "3e40f15f6f3a46ae446b253bf8b1d9ffb6ed9b174d272328ff91a7e2e5c79c07f5",
+        ),
+        (
+            "457567656e2042f6686d20766f6e2042617765726b",
+            "eb3f3515110702e047a6c9da4478b6ead94873c11c0f2d710ddb3f09fce024b3a58502ae3f",
+        ),
+    ];
+
+    fn handshake_states() -> (HandshakeState, HandshakeState) {
+        let prologue = hex::decode(PROLOGUE).unwrap();
+        let initiator = HandshakeState::new(
+            true,
+            &prologue,
+            &hex::decode(INITIATOR_STATIC).unwrap(),
+            &hex::decode(INITIATOR_EPHEMERAL).unwrap(),
+        )
+        .expect("failed to create initiator");
+        let responder = HandshakeState::new(
+            false,
+            &prologue,
+            &hex::decode(RESPONDER_STATIC).unwrap(),
+            &hex::decode(RESPONDER_EPHEMERAL).unwrap(),
+        )
+        .expect("failed to create responder");
+        (initiator, responder)
+    }
+
+    #[test]
+    fn test_vector() {
+        let (mut initiator, mut responder) = handshake_states();
+        for (index, (payload, expected)) in MESSAGES[..HANDSHAKE_MESSAGES].iter().enumerate() {
+            let payload = hex::decode(payload).unwrap();
+            let expected = hex::decode(expected).unwrap();
+            let (writer, reader) = if index % 2 == 0 {
+                (&mut initiator, &mut responder)
+            } else {
+                (&mut responder, &mut initiator)
+            };
+            assert!(writer.is_write_turn());
+            assert!(!reader.is_write_turn());
+            let mut message = [0u8; 256];
+            let size = writer
+                .write_message(&payload, &mut message)
+                .expect("failed to write message");
+            assert_eq!(&message[..size], expected.as_slice());
+            assert_eq!(size, handshake_overhead(index) + payload.len());
+            let mut received = [0u8; 256];
+            let size = reader
+                .read_message(&message[..size], &mut received)
+                .expect("failed to read message");
+            assert_eq!(&received[..size], payload.as_slice());
+        }
+        assert!(initiator.is_finished() && responder.is_finished());
+        assert_eq!(
+            initiator.handshake_hash().as_slice(),
+            hex::decode(HANDSHAKE_HASH).unwrap()
+        );
+        let mut responder_static = [0u8; DH_SIZE];
+        x25519_calculate_public_key(
+            &hex::decode(RESPONDER_STATIC).unwrap(),
+            &mut responder_static,
+        )
+        .unwrap();
+        assert_eq!(
+            initiator.remote_static_public_key(),
+            Some(&responder_static)
+        );
+
+        let mut initiator = initiator.split().expect("failed to split");
+        let mut responder = responder.split().expect("failed to split");
+        assert_eq!(
+            responder.handshake_hash().as_slice(),
+            hex::decode(HANDSHAKE_HASH).unwrap()
+        );
+        for (index, (payload, expected)) in MESSAGES[HANDSHAKE_MESSAGES..].iter().enumerate() {
+            let payload = hex::decode(payload).unwrap();
+            let expected = hex::decode(expected).unwrap();
+            // The responder sends first after the handshake since the initiator sent last
+            let (writer, reader) = if index % 2 == 0 {
+                (&mut responder, &mut initiator)
+            } else {
+                (&mut initiator, &mut responder)
+            };
+            let mut buffer: Vec<u8, 64> = Vec::from_slice(&payload).unwrap();
+            let mut tag = [0u8; TAG_SIZE];
+            writer
+                .encrypt(&mut buffer, &mut tag)
+                .expect("failed to encrypt");
+            assert_eq!(buffer.as_slice(), &expected[..payload.len()]);
+            assert_eq!(tag.as_slice(), &expected[payload.len()..]);
+            reader
+                .decrypt(&mut buffer, &tag)
+                .expect("failed to decrypt");
+            assert_eq!(buffer.as_slice(), payload.as_slice());
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let (mut initiator, mut responder) = handshake_states();
+        let mut message = [0u8; 256];
+        let mut payload = [0u8; 256];
+        assert_eq!(
+            responder.write_message(&[], &mut message),
+            Err(Error::InvalidState)
+        );
+        assert_eq!(
+            initiator.read_message(&message[..DH_SIZE], &mut payload),
+            Err(Error::InvalidState)
+        );
+        assert_eq!(
+            initiator.write_message(&[], &mut message[..DH_SIZE - 1]),
+            Err(Error::InvalidBufferSize)
+        );
+        let size = initiator
+            .write_message(&[], &mut message)
+            .expect("failed to write message");
+        assert_eq!(
+            responder.read_message(&message[..size - 1], &mut payload),
+            Err(Error::InvalidBufferSize)
+        );
+        responder
+            .read_message(&message[..size], &mut payload)
+            .expect("failed to read message");
+        let size = responder
+            .write_message(&[], &mut message)
+            .expect("failed to write message");
+        message[size - 1] ^= 1;
+        assert_eq!(
+            initiator.read_message(&message[..size], &mut payload),
+            Err(Error::Decrypt)
+        );
+        assert!(matches!(responder.split(), Err(Error::InvalidState)));
+    }
+}
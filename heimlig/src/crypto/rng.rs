@@ -1,7 +1,9 @@
+use crate::crypto::drbg::chacha20::ChaCha20Drbg;
+use crate::crypto::drbg::{Drbg, MAX_SEED_MATERIAL_SIZE};
 use core::cmp::min;
-use rand::Rng as RandRng;
-use rand_chacha::rand_core::{impls, CryptoRng, SeedableRng};
-use rand_chacha::ChaCha20Rng;
+use core::num::NonZeroU32;
+use rand_chacha::rand_core::{impls, CryptoRng};
+use zeroize::{Zeroize, Zeroizing};
 
 /// Entropy source from which a random number generator can be seeded.
 pub trait EntropySource {
@@ -9,81 +11,330 @@ pub trait EntropySource {
     fn random_seed(&mut self) -> [u8; 32];
 }
 
-/// Random number generator based on the ChaCha20 stream cipher.
-pub struct Rng<E>
+/// Entropy source whose seeding can fail, e.g. because a hardware TRNG timed out or reported a
+/// clock error. Every [EntropySource] is a [TryEntropySource] that never fails.
+pub trait TryEntropySource {
+    /// Extract a fixed-size random seed from the source.
+    ///
+    /// # Errors
+    ///
+    /// The function returns an error if:
+    /// * `SourceFailure`: The source could not provide a seed.
+    /// * `HealthTestFailure`: The health tests of the source failed.
+    fn try_random_seed(&mut self) -> Result<[u8; 32], Error>;
+}
+
+impl<E: EntropySource> TryEntropySource for E {
+    fn try_random_seed(&mut self) -> Result<[u8; 32], Error> {
+        Ok(self.random_seed())
+    }
+}
+
+/// Random number generator errors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The health tests of the entropy source failed.
+    HealthTestFailure,
+    /// The entropy source could not provide a seed.
+    SourceFailure,
+}
+
+/// Size of the seed that is persisted across resets.
+pub const SEED_FILE_SIZE: usize = 32;
+
+/// Errors of seed persistence.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SeedError {
+    /// The random number generator is in its error state.
+    Rng(Error),
+    /// The seed could not be read from or written to the seed store.
+    Storage,
+}
+
+/// Storage of a seed that is persisted across resets.
+pub trait SeedStore {
+    /// Read the stored seed. Returns `None` if no seed has been stored yet.
+    fn read_seed(&mut self) -> Result<Option<[u8; SEED_FILE_SIZE]>, SeedError>;
+
+    /// Replace the stored seed.
+    fn write_seed(&mut self, seed: &[u8; SEED_FILE_SIZE]) -> Result<(), SeedError>;
+}
+
+/// Random number generator based on a deterministic random bit generator (DRBG) that is seeded
+/// from an entropy source. By default, the ChaCha20 stream cipher is used as DRBG.
+pub struct Rng<E, D = ChaCha20Drbg>
 where
-    E: EntropySource,
+    E: TryEntropySource,
+    D: Drbg,
 {
-    rng: ChaCha20Rng,
+    drbg: D,
     entropy: E,
     reseed_threshold: u128,
+    bytes_since_reseed: u128,
+    error: Option<Error>,
 }
 
-impl<E: EntropySource> Rng<E> {
-    /// Number of bytes after which this random number generator cycles and must be reseeded.
-    const CYCLE_LENGTH: u128 = 1 << 70; // 1 ZiB (zebibyte)
-
-    /// Create a new random number generator instance.
+impl<E: TryEntropySource> Rng<E> {
+    /// Create a new random number generator instance based on ChaCha20.
     ///
     /// # Arguments
     ///
     /// * `entropy`: The entropy source from which the generator is seeded and reseeded if
-    /// `reseed_threshold` is set.
+    ///   `reseed_threshold` is set.
+    /// * `reseed_threshold`: Optional number of bytes after which the generator will reseed itself.
+    ///   Reseeding the generator is an additional defense in depth measure in case an attacker gets
+    ///   access to the internal state of the generator.
+    ///
+    /// If the entropy source fails, the generator starts in its error state.
+    pub fn new(entropy: E, reseed_threshold: Option<u128>) -> Self {
+        Self::instantiate(entropy, &[], reseed_threshold)
+    }
+
+    /// Create a new random number generator instance based on ChaCha20. In contrast to
+    /// [Rng::new], an error is returned if the entropy source fails.
+    pub fn try_new(entropy: E, reseed_threshold: Option<u128>) -> Result<Self, Error> {
+        Self::try_instantiate(entropy, &[], reseed_threshold)
+    }
+}
+
+impl<E: TryEntropySource, D: Drbg> Rng<E, D> {
+    /// Default number of bytes after which the generator reseeds itself.
+    const DEFAULT_RESEED_THRESHOLD: u128 = 1 << 70; // 1 ZiB (zebibyte)
+
+    /// Create a new random number generator instance based on the DRBG `D`.
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy`: The entropy source from which the entropy input and nonce of the DRBG are
+    ///   taken.
+    /// * `personalization`: Optional (possibly empty) personalization string of the DRBG.
     /// * `reseed_threshold`: Optional number of bytes after which the generator will reseed itself.
-    /// Reseeding the generator is an additional defense in depth measure in case an attacker gets
-    /// access to the internal state of the generator.
-    pub fn new(mut entropy: E, reseed_threshold: Option<u128>) -> Self {
+    ///   Independent of this threshold, the generator reseeds itself whenever the DRBG reaches its
+    ///   reseed interval.
+    ///
+    /// If the entropy source fails, the generator starts in its error state.
+    pub fn instantiate(
+        mut entropy: E,
+        personalization: &[u8],
+        reseed_threshold: Option<u128>,
+    ) -> Self {
+        let mut seed_material = Zeroizing::new([0u8; MAX_SEED_MATERIAL_SIZE]);
+        let seed_material = &mut seed_material[..D::ENTROPY_INPUT_SIZE + D::NONCE_SIZE];
+        let result = Self::gather_entropy(&mut entropy, seed_material);
+        let (entropy_input, nonce) = seed_material.split_at(D::ENTROPY_INPUT_SIZE);
         Rng {
-            rng: ChaCha20Rng::from_seed(entropy.random_seed()),
+            drbg: D::instantiate(entropy_input, nonce, personalization),
             entropy,
-            reseed_threshold: reseed_threshold.unwrap_or(Rng::<E>::CYCLE_LENGTH),
+            reseed_threshold: reseed_threshold.unwrap_or(Self::DEFAULT_RESEED_THRESHOLD),
+            bytes_since_reseed: 0,
+            error: result.err(),
         }
     }
 
-    /// Reseeds the random number generator. Without reseeding, the generator cycles after
-    /// generating 1 ZiB (zebibyte) of pseudorandom data.
-    pub fn reseed(&mut self) {
-        self.rng = ChaCha20Rng::from_seed(self.entropy.random_seed());
+    /// Create a new random number generator instance based on the DRBG `D`. In contrast to
+    /// [Rng::instantiate], an error is returned if the entropy source fails.
+    pub fn try_instantiate(
+        entropy: E,
+        personalization: &[u8],
+        reseed_threshold: Option<u128>,
+    ) -> Result<Self, Error> {
+        let rng = Self::instantiate(entropy, personalization, reseed_threshold);
+        match rng.error {
+            None => Ok(rng),
+            Some(e) => Err(e),
+        }
     }
-}
 
-impl<E: EntropySource> CryptoRng for Rng<E> {}
+    /// Reseeds the random number generator with fresh entropy. If the entropy source fails, the
+    /// generator keeps its state and enters its error state.
+    pub fn reseed(&mut self) -> Result<(), Error> {
+        self.reseed_with_input(&[])
+    }
 
-impl<E: EntropySource> rand::RngCore for Rng<E> {
-    fn next_u32(&mut self) -> u32 {
-        impls::next_u32_via_fill(self)
+    /// Reseeds the random number generator with fresh entropy and the optional (possibly empty)
+    /// `additional_input`. If the entropy source fails, the generator keeps its state and enters
+    /// its error state.
+    pub fn reseed_with_input(&mut self, additional_input: &[u8]) -> Result<(), Error> {
+        self.bytes_since_reseed = 0;
+        // Health test failures are permanent
+        if self.error == Some(Error::HealthTestFailure) {
+            return Err(Error::HealthTestFailure);
+        }
+        let mut entropy_input = Zeroizing::new([0u8; MAX_SEED_MATERIAL_SIZE]);
+        let entropy_input = &mut entropy_input[..D::ENTROPY_INPUT_SIZE];
+        match Self::gather_entropy(&mut self.entropy, entropy_input) {
+            Ok(()) => {
+                self.drbg.reseed(entropy_input, additional_input);
+                self.error = None;
+                Ok(())
+            }
+            Err(e) => {
+                self.error = Some(e);
+                Err(e)
+            }
+        }
     }
 
-    fn next_u64(&mut self) -> u64 {
-        impls::next_u64_via_fill(self)
+    /// Error state of the generator. Output produced in the error state must not be used. A
+    /// successful reseed leaves the error state unless the health tests of the entropy source
+    /// failed.
+    pub fn error(&self) -> Option<Error> {
+        self.error
     }
 
-    fn fill_bytes(&mut self, dest: &mut [u8]) {
+    /// Fill `dest` with random bytes. In contrast to [rand::RngCore::fill_bytes], an error is
+    /// returned and `dest` is zeroized if the generator is in its error state afterwards.
+    pub fn try_fill(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.try_fill_with_input(dest, false, &[])
+    }
+
+    /// Fill `dest` with random bytes like [Rng::try_fill].
+    ///
+    /// # Arguments
+    ///
+    /// * `dest`: Buffer the random bytes are written to.
+    /// * `prediction_resistance`: Whether the generator is reseeded with fresh entropy before the
+    ///   output is generated. If the reseed fails, no output is generated.
+    /// * `additional_input`: Optional (possibly empty) additional input that is mixed into the
+    ///   state of the DRBG. With prediction resistance, it is used as additional input of the
+    ///   reseed.
+    pub fn try_fill_with_input(
+        &mut self,
+        dest: &mut [u8],
+        prediction_resistance: bool,
+        additional_input: &[u8],
+    ) -> Result<(), Error> {
+        if prediction_resistance {
+            if let Err(e) = self.reseed_with_input(additional_input) {
+                dest.zeroize();
+                return Err(e);
+            }
+            self.generate(dest, &[]);
+        } else {
+            self.generate(dest, additional_input);
+        }
+        match self.error {
+            None => Ok(()),
+            Some(e) => {
+                dest.zeroize();
+                Err(e)
+            }
+        }
+    }
+
+    fn generate(&mut self, dest: &mut [u8], additional_input: &[u8]) {
+        // Failed reseeds are recorded in the error state. The output buffer is filled from the
+        // current state anyway so that callers retrying on unsuitable output terminate.
+        if self.error.is_some() {
+            let _ = self.reseed();
+        }
         // Reseed as many times as needed to fill the output buffer
-        let mut rem_dest = dest;
+        let mut rem_dest = &mut *dest;
         while !rem_dest.is_empty() {
+            if self.drbg.reseed_counter() > D::RESEED_INTERVAL {
+                let _ = self.reseed();
+            }
             let bytes_until_reseed = self
                 .reseed_threshold
-                .saturating_sub(4 * self.rng.get_word_pos());
-            let bytes_to_write: usize = min(bytes_until_reseed, rem_dest.len() as u128) as usize;
+                .saturating_sub(self.bytes_since_reseed);
+            let bytes_to_write: usize = min(
+                min(bytes_until_reseed, rem_dest.len() as u128),
+                D::MAX_REQUEST_SIZE as u128,
+            ) as usize;
             let (fill_now, fill_later) = rem_dest.split_at_mut(bytes_to_write);
-            self.rng.fill(fill_now);
+            if self.drbg.generate(fill_now, additional_input).is_err() {
+                // The DRBG is past its reseed interval and the reseed failed. No more random output
+                // may be generated until a reseed succeeds. The output buffer is filled with a
+                // non-constant placeholder instead of zeros, so that callers retrying on unsuitable
+                // output (e.g. scalar generation) terminate and can check the error state.
+                self.error.get_or_insert(Error::SourceFailure);
+                for (index, byte) in dest.iter_mut().enumerate() {
+                    *byte = index as u8;
+                }
+                return;
+            }
+            self.bytes_since_reseed += bytes_to_write as u128;
             if bytes_to_write as u128 >= bytes_until_reseed {
-                self.reseed();
+                let _ = self.reseed();
             }
             rem_dest = fill_later;
         }
     }
 
-    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
-        self.fill_bytes(dest);
+    /// Mix the seed persisted at the previous shutdown into the state of the generator and
+    /// immediately overwrite it so that it is never reused. This is supposed to be called once
+    /// after the generator was instantiated from fresh entropy.
+    pub fn load_seed(&mut self, store: &mut dyn SeedStore) -> Result<(), SeedError> {
+        let mut new_seed = Zeroizing::new([0u8; SEED_FILE_SIZE]);
+        match store.read_seed()? {
+            Some(seed) => {
+                let seed = Zeroizing::new(seed);
+                self.try_fill_with_input(new_seed.as_mut_slice(), false, seed.as_slice())
+            }
+            None => self.try_fill(new_seed.as_mut_slice()),
+        }
+        .map_err(SeedError::Rng)?;
+        store.write_seed(&new_seed)
+    }
+
+    /// Overwrite the persisted seed with fresh output of the generator. This is supposed to be
+    /// called periodically and before shutdown.
+    pub fn refresh_seed(&mut self, store: &mut dyn SeedStore) -> Result<(), SeedError> {
+        let mut new_seed = Zeroizing::new([0u8; SEED_FILE_SIZE]);
+        self.try_fill(new_seed.as_mut_slice())
+            .map_err(SeedError::Rng)?;
+        store.write_seed(&new_seed)
+    }
+
+    fn gather_entropy(entropy: &mut E, dest: &mut [u8]) -> Result<(), Error> {
+        for chunk in dest.chunks_mut(32) {
+            match entropy.try_random_seed() {
+                Ok(seed) => {
+                    let seed = Zeroizing::new(seed);
+                    chunk.copy_from_slice(&seed[..chunk.len()]);
+                }
+                Err(e) => {
+                    dest.zeroize();
+                    return Err(e);
+                }
+            }
+        }
         Ok(())
     }
 }
 
+impl<E: TryEntropySource, D: Drbg> CryptoRng for Rng<E, D> {}
+
+impl<E: TryEntropySource, D: Drbg> rand::RngCore for Rng<E, D> {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.generate(dest, &[]);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.try_fill(dest).map_err(|e| {
+            NonZeroU32::new(rand_core::Error::CUSTOM_START + e as u32)
+                .expect("invalid error code")
+                .into()
+        })
+    }
+}
+
 #[cfg(test)]
 pub mod test {
-    use crate::crypto::rng::{EntropySource, Rng};
+    use crate::crypto::drbg::ctr::CtrDrbg;
+    use crate::crypto::drbg::hmac::HmacDrbg;
+    use crate::crypto::drbg::Drbg;
+    use crate::crypto::ecdsa::nist_p256_generate_key_pair;
+    use crate::crypto::rng::{EntropySource, Error, Rng, SeedError, SeedStore, TryEntropySource};
+    use crate::integration::memory_seed_store::MemorySeedStore;
     use rand::RngCore;
 
     #[derive(Default)]
@@ -136,4 +387,243 @@ pub mod test {
         rng.fill_bytes(&mut data);
         assert_eq!(rng.entropy.counter, 32 + 4 * 32);
     }
+
+    #[test]
+    fn nist_drbg_seeding() {
+        let mut data = [0u8; 4 * 256];
+        let mut rng = Rng::<_, HmacDrbg>::instantiate(TestEntropySource::default(), &[], Some(256));
+        assert_eq!(rng.entropy.counter, 64);
+        rng.fill_bytes(&mut data);
+        assert_eq!(rng.entropy.counter, 64 + 4 * 32);
+
+        let mut rng = Rng::<_, CtrDrbg>::instantiate(TestEntropySource::default(), &[], Some(256));
+        assert_eq!(rng.entropy.counter, 64);
+        rng.fill_bytes(&mut data[..255]);
+        assert_eq!(rng.entropy.counter, 64);
+    }
+
+    #[test]
+    fn personalization() {
+        let mut data = [0u8; 32];
+        let mut personalized_data = [0u8; 32];
+        Rng::<_, HmacDrbg>::instantiate(TestEntropySource::default(), &[], None)
+            .fill_bytes(&mut data);
+        Rng::<_, HmacDrbg>::instantiate(TestEntropySource::default(), b"heimlig", None)
+            .fill_bytes(&mut personalized_data);
+        assert_ne!(data, personalized_data);
+    }
+
+    /// Source failing for the seeds with the indices in `failures`.
+    struct FailingEntropySource {
+        source: TestEntropySource,
+        index: usize,
+        failures: core::ops::Range<usize>,
+    }
+
+    impl TryEntropySource for FailingEntropySource {
+        fn try_random_seed(&mut self) -> Result<[u8; 32], Error> {
+            let index = self.index;
+            self.index += 1;
+            if self.failures.contains(&index) {
+                return Err(Error::SourceFailure);
+            }
+            Ok(self.source.random_seed())
+        }
+    }
+
+    #[test]
+    fn instantiation_failure() {
+        let entropy = FailingEntropySource {
+            source: TestEntropySource::default(),
+            index: 0,
+            failures: 0..1,
+        };
+        assert!(matches!(
+            Rng::try_new(entropy, None),
+            Err(Error::SourceFailure)
+        ));
+    }
+
+    #[test]
+    fn reseed_failure() {
+        let entropy = FailingEntropySource {
+            source: TestEntropySource::default(),
+            index: 0,
+            failures: 1..2,
+        };
+        let mut data = [0xffu8; 32];
+        let mut rng = Rng::try_new(entropy, Some(32)).expect("failed to instantiate");
+        assert_eq!(rng.error(), None);
+        assert_eq!(rng.reseed(), Err(Error::SourceFailure));
+        assert_eq!(rng.error(), Some(Error::SourceFailure));
+        assert!(rng.try_fill_bytes(&mut data).is_ok());
+        assert_eq!(rng.error(), None);
+        assert_eq!(rng.entropy.source.counter, 3 * 32);
+    }
+
+    #[test]
+    fn try_fill_failure() {
+        let entropy = FailingEntropySource {
+            source: TestEntropySource::default(),
+            index: 0,
+            failures: 1..3,
+        };
+        let mut data = [0xffu8; 32];
+        let mut rng = Rng::try_new(entropy, Some(32)).expect("failed to instantiate");
+        assert_eq!(rng.try_fill(&mut data), Err(Error::SourceFailure));
+        assert_eq!(data, [0u8; 32]);
+        assert_eq!(rng.try_fill(&mut data[..16]), Err(Error::SourceFailure));
+        assert_eq!(rng.try_fill(&mut data[..16]), Ok(()));
+        assert_ne!(data[..16], [0u8; 16]);
+    }
+
+    /// HMAC_DRBG that has to be reseeded after every generate request.
+    struct ShortIntervalDrbg {
+        drbg: HmacDrbg,
+        reseed_counter: u64,
+    }
+
+    impl Drbg for ShortIntervalDrbg {
+        const ENTROPY_INPUT_SIZE: usize = HmacDrbg::ENTROPY_INPUT_SIZE;
+        const NONCE_SIZE: usize = HmacDrbg::NONCE_SIZE;
+        const MAX_REQUEST_SIZE: usize = HmacDrbg::MAX_REQUEST_SIZE;
+        const RESEED_INTERVAL: u64 = 1;
+
+        fn instantiate(entropy_input: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+            ShortIntervalDrbg {
+                drbg: HmacDrbg::instantiate(entropy_input, nonce, personalization),
+                reseed_counter: 1,
+            }
+        }
+
+        fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) {
+            self.drbg.reseed(entropy_input, additional_input);
+            self.reseed_counter = 1;
+        }
+
+        fn generate(
+            &mut self,
+            output: &mut [u8],
+            additional_input: &[u8],
+        ) -> Result<(), crate::crypto::drbg::Error> {
+            if self.reseed_counter > Self::RESEED_INTERVAL {
+                return Err(crate::crypto::drbg::Error::ReseedRequired);
+            }
+            self.drbg.generate(output, additional_input)?;
+            self.reseed_counter += 1;
+            Ok(())
+        }
+
+        fn reseed_counter(&self) -> u64 {
+            self.reseed_counter
+        }
+    }
+
+    #[test]
+    fn reseed_interval_failure() {
+        let entropy = FailingEntropySource {
+            source: TestEntropySource::default(),
+            index: 0,
+            failures: 2..usize::MAX,
+        };
+        let mut data = [0xffu8; 32];
+        let mut rng = Rng::<_, ShortIntervalDrbg>::instantiate(entropy, &[], None);
+        assert_eq!(rng.try_fill(&mut data), Ok(()));
+        assert_ne!(data, [0u8; 32]);
+        // The DRBG must not generate output past its reseed interval without a successful reseed
+        assert_eq!(rng.try_fill(&mut data), Err(Error::SourceFailure));
+        assert_eq!(data, [0u8; 32]);
+        rng.fill_bytes(&mut data);
+        assert_ne!(data, [data[0]; 32]);
+        assert_eq!(rng.error(), Some(Error::SourceFailure));
+
+        // Key generation retrying on unsuitable output must terminate in the error state
+        let _ = nist_p256_generate_key_pair(&mut rng);
+        assert_eq!(rng.error(), Some(Error::SourceFailure));
+    }
+
+    #[test]
+    fn prediction_resistance() {
+        let mut data = [0u8; 32];
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        rng.try_fill_with_input(&mut data, true, &[])
+            .expect("failed to generate");
+        assert_eq!(rng.entropy.counter, 2 * 32);
+        rng.try_fill_with_input(&mut data, false, b"additional input")
+            .expect("failed to generate");
+        assert_eq!(rng.entropy.counter, 2 * 32);
+
+        let entropy = FailingEntropySource {
+            source: TestEntropySource::default(),
+            index: 0,
+            failures: 1..2,
+        };
+        let mut data = [0xffu8; 32];
+        let mut rng = Rng::try_new(entropy, None).expect("failed to instantiate");
+        assert_eq!(
+            rng.try_fill_with_input(&mut data, true, &[]),
+            Err(Error::SourceFailure)
+        );
+        assert_eq!(data, [0u8; 32]);
+    }
+
+    #[test]
+    fn additional_input() {
+        let mut data = [0u8; 32];
+        let mut data_with_input = [0u8; 32];
+        Rng::<_, HmacDrbg>::instantiate(TestEntropySource::default(), &[], None)
+            .try_fill(&mut data)
+            .expect("failed to generate");
+        Rng::<_, HmacDrbg>::instantiate(TestEntropySource::default(), &[], None)
+            .try_fill_with_input(&mut data_with_input, false, b"additional input")
+            .expect("failed to generate");
+        assert_ne!(data, data_with_input);
+    }
+
+    #[test]
+    fn load_seed() {
+        let mut data = [0u8; 32];
+        let mut data_with_seed = [0u8; 32];
+        let mut store = MemorySeedStore::new();
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        rng.load_seed(&mut store).expect("failed to load seed");
+        let stored_seed = store.read_seed().expect("failed to read seed");
+        assert!(stored_seed.is_some());
+        rng.fill_bytes(&mut data);
+
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        rng.load_seed(&mut store).expect("failed to load seed");
+        rng.fill_bytes(&mut data_with_seed);
+        assert_ne!(data, data_with_seed);
+        assert_ne!(store.read_seed().expect("failed to read seed"), stored_seed);
+    }
+
+    #[test]
+    fn refresh_seed() {
+        let mut store = MemorySeedStore::new();
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        rng.refresh_seed(&mut store)
+            .expect("failed to refresh seed");
+        let stored_seed = store.read_seed().expect("failed to read seed");
+        rng.refresh_seed(&mut store)
+            .expect("failed to refresh seed");
+        assert_ne!(store.read_seed().expect("failed to read seed"), stored_seed);
+    }
+
+    #[test]
+    fn seed_in_error_state() {
+        let entropy = FailingEntropySource {
+            source: TestEntropySource::default(),
+            index: 0,
+            failures: 0..2,
+        };
+        let mut store = MemorySeedStore::new();
+        store.write_seed(&[1u8; 32]).expect("failed to write seed");
+        let mut rng = Rng::new(entropy, None);
+        assert_eq!(
+            rng.load_seed(&mut store),
+            Err(SeedError::Rng(Error::SourceFailure))
+        );
+        assert_eq!(store.read_seed(), Ok(Some([1u8; 32])));
+    }
 }
@@ -0,0 +1,295 @@
+use crate::crypto::aes::cmac::{
+    aes128cmac_generate, aes128cmac_verify, aes192cmac_generate, aes192cmac_verify,
+    aes256cmac_generate, aes256cmac_verify,
+};
+use crate::crypto::aes::{CMAC_TAG_SIZE, KEY128_SIZE, KEY192_SIZE, KEY256_SIZE};
+use crate::crypto::Error;
+
+/// Identifier of a secured PDU.
+pub type DataId = u16;
+
+/// Maximum size of the truncated freshness value in bytes.
+pub const MAX_FRESHNESS_SIZE: usize = core::mem::size_of::<u64>();
+/// Maximum size of the truncated MAC in bytes.
+pub const MAX_MAC_SIZE: usize = CMAC_TAG_SIZE;
+
+/// Layout of the AUTOSAR SecOC PDUs of a data ID.
+///
+/// A secured PDU is `payload || truncated freshness || truncated MAC`. The MAC is an AES-CMAC over
+/// `data ID (big-endian u16) || payload || full freshness value (big-endian u64)`. The truncated
+/// freshness consists of the least significant bytes of the freshness value and the truncated MAC
+/// of the leftmost bytes of the CMAC tag.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Profile {
+    /// Number of freshness value bytes transmitted in the secured PDU.
+    pub freshness_size: usize,
+    /// Number of MAC bytes transmitted in the secured PDU.
+    pub mac_size: usize,
+}
+
+impl Profile {
+    /// Whether the truncation sizes are supported.
+    pub const fn is_valid(&self) -> bool {
+        self.freshness_size <= MAX_FRESHNESS_SIZE
+            && self.mac_size > 0
+            && self.mac_size <= MAX_MAC_SIZE
+    }
+
+    /// Number of bytes a secured PDU adds to its payload.
+    pub const fn overhead(&self) -> usize {
+        self.freshness_size + self.mac_size
+    }
+}
+
+/// Builds a secured PDU from `payload` with the given `freshness` value.
+///
+/// # Errors
+///
+/// * `InvalidSymmetricKeySize`: The `key` is not an AES-128, AES-192 or AES-256 key.
+/// * `InvalidTagSize`: The truncation sizes of the `profile` are not supported.
+/// * `InvalidBufferSize`: `secured_pdu` cannot hold the payload plus the profile overhead.
+///
+/// Returns the size of the secured PDU.
+pub fn authenticate(
+    key: &[u8],
+    data_id: DataId,
+    freshness: u64,
+    profile: &Profile,
+    payload: &[u8],
+    secured_pdu: &mut [u8],
+) -> Result<usize, Error> {
+    if !profile.is_valid() {
+        return Err(Error::InvalidTagSize);
+    }
+    let size = payload.len() + profile.overhead();
+    if secured_pdu.len() < size {
+        return Err(Error::InvalidBufferSize);
+    }
+    let (pdu_payload, rest) = secured_pdu.split_at_mut(payload.len());
+    let (pdu_freshness, rest) = rest.split_at_mut(profile.freshness_size);
+    pdu_payload.copy_from_slice(payload);
+    pdu_freshness
+        .copy_from_slice(&freshness.to_be_bytes()[MAX_FRESHNESS_SIZE - profile.freshness_size..]);
+    let message = [
+        &data_id.to_be_bytes()[..],
+        payload,
+        &freshness.to_be_bytes()[..],
+    ];
+    let mac = &mut rest[..profile.mac_size];
+    match key.len() {
+        KEY128_SIZE => aes128cmac_generate(key, &message, mac)?,
+        KEY192_SIZE => aes192cmac_generate(key, &message, mac)?,
+        KEY256_SIZE => aes256cmac_generate(key, &message, mac)?,
+        _ => return Err(Error::InvalidSymmetricKeySize),
+    }
+    Ok(size)
+}
+
+/// Reconstructs the full freshness value from its `truncated` least significant bytes.
+///
+/// The most significant bytes are taken from the `latest` accepted freshness value. If the
+/// truncated value is not greater than the corresponding bytes of `latest`, the receiver assumes
+/// that the truncated value wrapped around and increments the most significant part.
+///
+/// # Errors
+///
+/// * `InvalidFreshness`: The reconstructed freshness value would exceed `u64::MAX`.
+pub fn reconstruct_freshness(
+    latest: u64,
+    truncated: u64,
+    freshness_size: usize,
+) -> Result<u64, Error> {
+    if freshness_size >= MAX_FRESHNESS_SIZE {
+        return Ok(truncated);
+    }
+    let modulus = 1u64 << (8 * freshness_size);
+    let lsb_mask = modulus - 1;
+    let msb = latest & !lsb_mask;
+    if truncated > latest & lsb_mask {
+        Ok(msb | truncated)
+    } else {
+        msb.checked_add(modulus)
+            .map(|msb| msb | truncated)
+            .ok_or(Error::InvalidFreshness)
+    }
+}
+
+/// Verifies a secured PDU against the `latest` accepted freshness value.
+///
+/// # Errors
+///
+/// * `InvalidSymmetricKeySize`: The `key` is not an AES-128, AES-192 or AES-256 key.
+/// * `InvalidTagSize`: The truncation sizes of the `profile` are not supported.
+/// * `InvalidBufferSize`: `secured_pdu` is shorter than the profile overhead.
+/// * `InvalidFreshness`: The freshness value is not newer than `latest`.
+/// * `InvalidSignature`: The MAC does not match.
+///
+/// Returns the payload and the full freshness value that should be stored as the new `latest`.
+pub fn verify<'a>(
+    key: &[u8],
+    data_id: DataId,
+    latest: u64,
+    profile: &Profile,
+    secured_pdu: &'a [u8],
+) -> Result<(&'a [u8], u64), Error> {
+    if !profile.is_valid() {
+        return Err(Error::InvalidTagSize);
+    }
+    let payload_size = secured_pdu
+        .len()
+        .checked_sub(profile.overhead())
+        .ok_or(Error::InvalidBufferSize)?;
+    let (payload, rest) = secured_pdu.split_at(payload_size);
+    let (pdu_freshness, mac) = rest.split_at(profile.freshness_size);
+    let mut truncated = [0u8; MAX_FRESHNESS_SIZE];
+    truncated[MAX_FRESHNESS_SIZE - profile.freshness_size..].copy_from_slice(pdu_freshness);
+    let freshness = reconstruct_freshness(
+        latest,
+        u64::from_be_bytes(truncated),
+        profile.freshness_size,
+    )?;
+    if freshness <= latest {
+        return Err(Error::InvalidFreshness);
+    }
+    let message = [
+        &data_id.to_be_bytes()[..],
+        payload,
+        &freshness.to_be_bytes()[..],
+    ];
+    match key.len() {
+        KEY128_SIZE => aes128cmac_verify(key, &message, mac)?,
+        KEY192_SIZE => aes192cmac_verify(key, &message, mac)?,
+        KEY256_SIZE => aes256cmac_verify(key, &message, mac)?,
+        _ => return Err(Error::InvalidSymmetricKeySize),
+    }
+    Ok((payload, freshness))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const KEY: &[u8; KEY128_SIZE] = b"Open sesame! ...";
+    const DATA_ID: DataId = 0x0123;
+    const PAYLOAD: &[u8] = b"wheel speed";
+    const PROFILE: Profile = Profile {
+        freshness_size: 1,
+        mac_size: 4,
+    };
+
+    #[test]
+    fn authenticate_layout() {
+        let mut secured_pdu = [0u8; 32];
+        let size = authenticate(KEY, DATA_ID, 0x1_02, &PROFILE, PAYLOAD, &mut secured_pdu)
+            .expect("failed to authenticate");
+        assert_eq!(size, PAYLOAD.len() + 5);
+        assert_eq!(&secured_pdu[..PAYLOAD.len()], PAYLOAD);
+        assert_eq!(secured_pdu[PAYLOAD.len()], 0x02);
+
+        // The MAC covers the full freshness value
+        let mut mac = [0u8; CMAC_TAG_SIZE];
+        aes128cmac_generate(
+            KEY,
+            &[&[0x01, 0x23], PAYLOAD, &[0, 0, 0, 0, 0, 0, 0x01, 0x02]],
+            &mut mac,
+        )
+        .unwrap();
+        assert_eq!(&secured_pdu[PAYLOAD.len() + 1..size], &mac[..4]);
+    }
+
+    #[test]
+    fn authenticate_verify() {
+        for (freshness_size, mac_size) in [(0, 1), (1, 4), (3, 8), (8, 16)] {
+            let profile = Profile {
+                freshness_size,
+                mac_size,
+            };
+            let mut secured_pdu = [0u8; 64];
+            let size = authenticate(KEY, DATA_ID, 0x1234, &profile, PAYLOAD, &mut secured_pdu)
+                .expect("failed to authenticate");
+            let (payload, freshness) = verify(KEY, DATA_ID, 0x1233, &profile, &secured_pdu[..size])
+                .expect("failed to verify");
+            assert_eq!(payload, PAYLOAD);
+            assert_eq!(freshness, 0x1234);
+        }
+    }
+
+    #[test]
+    fn reconstruct() {
+        assert_eq!(reconstruct_freshness(0x1_fe, 0xff, 1), Ok(0x1_ff));
+        assert_eq!(reconstruct_freshness(0x1_ff, 0x00, 1), Ok(0x2_00));
+        assert_eq!(reconstruct_freshness(0x1_05, 0x05, 1), Ok(0x2_05));
+        assert_eq!(reconstruct_freshness(0x1_05, 0x03, 1), Ok(0x2_03));
+        assert_eq!(reconstruct_freshness(7, 0, 0), Ok(8));
+        assert_eq!(reconstruct_freshness(7, 3, 8), Ok(3));
+        assert_eq!(
+            reconstruct_freshness(u64::MAX, 0xff, 1),
+            Err(Error::InvalidFreshness)
+        );
+    }
+
+    #[test]
+    fn reject_stale_freshness() {
+        let profile = Profile {
+            freshness_size: 8,
+            mac_size: 8,
+        };
+        let mut secured_pdu = [0u8; 32];
+        let size = authenticate(KEY, DATA_ID, 10, &profile, PAYLOAD, &mut secured_pdu).unwrap();
+        assert!(verify(KEY, DATA_ID, 9, &profile, &secured_pdu[..size]).is_ok());
+        assert_eq!(
+            verify(KEY, DATA_ID, 10, &profile, &secured_pdu[..size]),
+            Err(Error::InvalidFreshness)
+        );
+        assert_eq!(
+            verify(KEY, DATA_ID, 11, &profile, &secured_pdu[..size]),
+            Err(Error::InvalidFreshness)
+        );
+    }
+
+    #[test]
+    fn reject_modified_pdu() {
+        let mut secured_pdu = [0u8; 32];
+        let size = authenticate(KEY, DATA_ID, 5, &PROFILE, PAYLOAD, &mut secured_pdu).unwrap();
+        assert_eq!(
+            verify(KEY, DATA_ID + 1, 4, &PROFILE, &secured_pdu[..size]),
+            Err(Error::InvalidSignature)
+        );
+        secured_pdu[0] ^= 1;
+        assert_eq!(
+            verify(KEY, DATA_ID, 4, &PROFILE, &secured_pdu[..size]),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut secured_pdu = [0u8; 32];
+        assert_eq!(
+            authenticate(KEY, DATA_ID, 1, &PROFILE, PAYLOAD, &mut secured_pdu[..15]),
+            Err(Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            authenticate(&KEY[1..], DATA_ID, 1, &PROFILE, PAYLOAD, &mut secured_pdu),
+            Err(Error::InvalidSymmetricKeySize)
+        );
+        for (freshness_size, mac_size) in [(0, 0), (9, 4), (1, 17)] {
+            let profile = Profile {
+                freshness_size,
+                mac_size,
+            };
+            assert_eq!(
+                authenticate(KEY, DATA_ID, 1, &profile, PAYLOAD, &mut secured_pdu),
+                Err(Error::InvalidTagSize)
+            );
+            assert_eq!(
+                verify(KEY, DATA_ID, 0, &profile, &secured_pdu),
+                Err(Error::InvalidTagSize)
+            );
+        }
+        assert_eq!(
+            verify(KEY, DATA_ID, 0, &PROFILE, &secured_pdu[..4]),
+            Err(Error::InvalidBufferSize)
+        );
+    }
+}
@@ -0,0 +1,731 @@
+use crate::crypto::ecdsa::{nist_p256_sign, NIST_P256_SIGNATURE_SIZE};
+use crate::crypto::hash::SHA256_SIZE;
+use crate::crypto::Error;
+use sha2::{Digest, Sha256};
+
+/// SPDM version used in GET_VERSION and VERSION messages.
+pub const VERSION_1_0: u8 = 0x10;
+/// SPDM version supported by the responder.
+pub const VERSION_1_2: u8 = 0x12;
+
+/// Request codes supported by the responder.
+pub const GET_DIGESTS: u8 = 0x81;
+pub const GET_CERTIFICATE: u8 = 0x82;
+pub const CHALLENGE: u8 = 0x83;
+pub const GET_VERSION: u8 = 0x84;
+pub const GET_MEASUREMENTS: u8 = 0xE0;
+pub const GET_CAPABILITIES: u8 = 0xE1;
+pub const NEGOTIATE_ALGORITHMS: u8 = 0xE3;
+
+/// Response codes sent by the responder.
+pub const DIGESTS: u8 = 0x01;
+pub const CERTIFICATE: u8 = 0x02;
+pub const CHALLENGE_AUTH: u8 = 0x03;
+pub const VERSION: u8 = 0x04;
+pub const MEASUREMENTS: u8 = 0x60;
+pub const CAPABILITIES: u8 = 0x61;
+pub const ALGORITHMS: u8 = 0x63;
+pub const ERROR: u8 = 0x7F;
+
+/// Error codes of ERROR responses.
+pub const ERROR_INVALID_REQUEST: u8 = 0x01;
+pub const ERROR_UNEXPECTED_REQUEST: u8 = 0x04;
+pub const ERROR_UNSUPPORTED_REQUEST: u8 = 0x07;
+pub const ERROR_VERSION_MISMATCH: u8 = 0x41;
+
+/// Capability flags of the responder: CERT_CAP, CHAL_CAP and MEAS_CAP with signatures.
+pub const CAPABILITY_FLAGS: u32 = 1 << 1 | 1 << 2 | 2 << 3;
+/// Maximum size of a single SPDM message handled by the responder in bytes.
+pub const DATA_TRANSFER_SIZE: u32 = 1024;
+/// DMTF measurement specification.
+pub const MEASUREMENT_SPECIFICATION_DMTF: u8 = 1 << 0;
+/// TPM_ALG_ECDSA_ECC_NIST_P256 base asymmetric algorithm.
+pub const BASE_ASYM_ALGO_ECDSA_P256: u32 = 1 << 4;
+/// TPM_ALG_SHA_256 base hash algorithm.
+pub const BASE_HASH_ALGO_SHA_256: u32 = 1 << 0;
+/// TPM_ALG_SHA_256 measurement hash algorithm.
+pub const MEASUREMENT_HASH_ALGO_SHA_256: u32 = 1 << 1;
+
+/// Size of nonces in bytes.
+pub const NONCE_SIZE: usize = 32;
+/// Size of digests in bytes.
+pub const DIGEST_SIZE: usize = SHA256_SIZE;
+/// Size of signatures (`r||s`) in bytes.
+pub const SIGNATURE_SIZE: usize = NIST_P256_SIGNATURE_SIZE;
+/// Size of a DMTF measurement block holding a digest in bytes.
+pub const MEASUREMENT_BLOCK_SIZE: usize = 4 + 3 + DIGEST_SIZE;
+
+/// Signing context of CHALLENGE_AUTH responses.
+pub const CHALLENGE_AUTH_CONTEXT: &[u8] = b"responder-challenge_auth signing";
+/// Signing context of signed MEASUREMENTS responses.
+pub const MEASUREMENTS_CONTEXT: &[u8] = b"responder-measurements signing";
+
+const HEADER_SIZE: usize = 4;
+const SIGNING_PREFIX_SIZE: usize = 64 + 36;
+
+/// Measurement reported in MEASUREMENTS responses.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Measurement {
+    /// DMTF measurement value type, e.g. 0 for immutable ROM or 1 for mutable firmware.
+    pub value_type: u8,
+    /// SHA-256 digest of the measured component.
+    pub digest: [u8; DIGEST_SIZE],
+}
+
+impl Measurement {
+    /// Encodes the measurement as DMTF measurement block with the given index.
+    pub fn block(&self, index: u8) -> [u8; MEASUREMENT_BLOCK_SIZE] {
+        let mut block = [0u8; MEASUREMENT_BLOCK_SIZE];
+        block[0] = index;
+        block[1] = MEASUREMENT_SPECIFICATION_DMTF;
+        block[2..4].copy_from_slice(&((3 + DIGEST_SIZE) as u16).to_le_bytes());
+        block[4] = self.value_type;
+        block[5..7].copy_from_slice(&(DIGEST_SIZE as u16).to_le_bytes());
+        block[7..].copy_from_slice(&self.digest);
+        block
+    }
+}
+
+/// Data the responder authenticates with.
+pub struct Identity<'a> {
+    /// Certificate chain of slot 0 in SPDM format: `length || reserved || root hash ||
+    /// certificates`.
+    pub certificate_chain: &'a [u8],
+    /// NIST P-256 private key of the leaf certificate.
+    pub private_key: &'a [u8],
+    /// Measurements with the indices 1 to `measurements.len()`.
+    pub measurements: &'a [Measurement],
+}
+
+/// Message to be signed for the given signing context and transcript hash.
+///
+/// The message is `combined SPDM prefix || transcript hash` with the combined prefix consisting of
+/// the version string repeated four times, followed by the zero-padded signing context.
+pub fn signing_message(
+    context: &[u8],
+    transcript_hash: &[u8; DIGEST_SIZE],
+) -> [u8; SIGNING_PREFIX_SIZE + DIGEST_SIZE] {
+    let mut message = [0u8; SIGNING_PREFIX_SIZE + DIGEST_SIZE];
+    for chunk in message[..64].chunks_mut(16) {
+        chunk.copy_from_slice(b"dmtf-spdm-v1.2.*");
+    }
+    message[SIGNING_PREFIX_SIZE - context.len()..SIGNING_PREFIX_SIZE].copy_from_slice(context);
+    message[SIGNING_PREFIX_SIZE..].copy_from_slice(transcript_hash);
+    message
+}
+
+/// Digest over all measurement blocks as used in CHALLENGE_AUTH responses.
+pub fn measurement_summary_hash(measurements: &[Measurement]) -> [u8; DIGEST_SIZE] {
+    let mut hasher = Sha256::new();
+    for (index, measurement) in (1..=u8::MAX).zip(measurements) {
+        hasher.update(measurement.block(index));
+    }
+    hasher.finalize().into()
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum ConnectionState {
+    NotStarted,
+    AfterVersion,
+    AfterCapabilities,
+    Negotiated,
+}
+
+enum Failure {
+    /// Request is answered with an ERROR response.
+    Spdm(u8, u8),
+    /// Request cannot be answered at all.
+    Crypto(Error),
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        Failure::Crypto(error)
+    }
+}
+
+fn expect_size(request: &[u8], size: usize) -> Result<(), Failure> {
+    if request.len() != size {
+        return Err(Failure::Spdm(ERROR_INVALID_REQUEST, 0));
+    }
+    Ok(())
+}
+
+fn check_response_size(response: &[u8], size: usize) -> Result<(), Failure> {
+    if response.len() < size {
+        return Err(Failure::Crypto(Error::InvalidBufferSize));
+    }
+    Ok(())
+}
+
+/// SPDM 1.2 responder for device attestation.
+///
+/// The responder supports version, capability and algorithm negotiation, certificate retrieval,
+/// challenge authentication and (signed) measurements with ECDSA NIST P-256 and SHA-256.
+/// Protocol errors are answered with ERROR responses.
+pub struct Responder {
+    state: ConnectionState,
+    data_transfer_size: usize,
+    /// Transcript of the version, capability and algorithm negotiation
+    vca: Sha256,
+    /// Transcript signed in CHALLENGE_AUTH responses
+    m1: Sha256,
+    /// Transcript signed in MEASUREMENTS responses
+    l1: Sha256,
+}
+
+impl Default for Responder {
+    fn default() -> Self {
+        Responder {
+            state: ConnectionState::NotStarted,
+            data_transfer_size: DATA_TRANSFER_SIZE as usize,
+            vca: Sha256::new(),
+            m1: Sha256::new(),
+            l1: Sha256::new(),
+        }
+    }
+}
+
+impl Responder {
+    /// Processes an SPDM request and writes the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity`: The certificate chain, signing key and measurements of the responder.
+    /// * `nonce`: Fresh random nonce used in CHALLENGE_AUTH and MEASUREMENTS responses.
+    /// * `request`: The SPDM request message.
+    /// * `response`: Output buffer for the response message.
+    ///
+    /// returns: The response message. Malformed and unexpected requests are answered with an ERROR
+    /// response.
+    ///
+    /// # Errors
+    ///
+    /// The function returns an error if:
+    /// * `InvalidBufferSize`: The `response` buffer is too small.
+    /// * `InvalidPrivateKey`: The private key of the `identity` is not a NIST P-256 key.
+    pub fn respond<'a>(
+        &mut self,
+        identity: &Identity,
+        nonce: &[u8; NONCE_SIZE],
+        request: &[u8],
+        response: &'a mut [u8],
+    ) -> Result<&'a mut [u8], Error> {
+        if response.len() < HEADER_SIZE {
+            return Err(Error::InvalidBufferSize);
+        }
+        let code = request.get(1).copied().unwrap_or_default();
+        match self.dispatch(identity, nonce, request, response) {
+            Ok(size) => Ok(&mut response[..size]),
+            Err(Failure::Spdm(error_code, error_data)) => {
+                response[0] = if code == GET_VERSION || self.state == ConnectionState::NotStarted {
+                    VERSION_1_0
+                } else {
+                    VERSION_1_2
+                };
+                response[1] = ERROR;
+                response[2] = error_code;
+                response[3] = error_data;
+                Ok(&mut response[..HEADER_SIZE])
+            }
+            Err(Failure::Crypto(e)) => Err(e),
+        }
+    }
+
+    fn dispatch(
+        &mut self,
+        identity: &Identity,
+        nonce: &[u8; NONCE_SIZE],
+        request: &[u8],
+        response: &mut [u8],
+    ) -> Result<usize, Failure> {
+        if request.len() < HEADER_SIZE {
+            return Err(Failure::Spdm(ERROR_INVALID_REQUEST, 0));
+        }
+        let code = request[1];
+        if code == GET_VERSION {
+            return self.version(request, response);
+        }
+        if request[0] != VERSION_1_2 {
+            return Err(Failure::Spdm(ERROR_VERSION_MISMATCH, 0));
+        }
+        let expected_state = match code {
+            GET_CAPABILITIES => ConnectionState::AfterVersion,
+            NEGOTIATE_ALGORITHMS => ConnectionState::AfterCapabilities,
+            GET_DIGESTS | GET_CERTIFICATE | CHALLENGE | GET_MEASUREMENTS => {
+                ConnectionState::Negotiated
+            }
+            _ => return Err(Failure::Spdm(ERROR_UNSUPPORTED_REQUEST, code)),
+        };
+        if self.state != expected_state {
+            return Err(Failure::Spdm(ERROR_UNEXPECTED_REQUEST, 0));
+        }
+        // Measurement transcripts only span consecutive GET_MEASUREMENTS requests
+        if code != GET_MEASUREMENTS && self.state == ConnectionState::Negotiated {
+            self.l1 = self.vca.clone();
+        }
+        match code {
+            GET_CAPABILITIES => self.capabilities(request, response),
+            NEGOTIATE_ALGORITHMS => self.algorithms(request, response),
+            GET_DIGESTS => self.digests(identity, request, response),
+            GET_CERTIFICATE => self.certificate(identity, request, response),
+            CHALLENGE => self.challenge_auth(identity, nonce, request, response),
+            _ => self.measurements(identity, nonce, request, response),
+        }
+    }
+
+    fn version(&mut self, request: &[u8], response: &mut [u8]) -> Result<usize, Failure> {
+        expect_size(request, HEADER_SIZE)?;
+        if request[0] != VERSION_1_0 {
+            return Err(Failure::Spdm(ERROR_VERSION_MISMATCH, 0));
+        }
+        const SIZE: usize = HEADER_SIZE + 4;
+        check_response_size(response, SIZE)?;
+        response[..SIZE].copy_from_slice(&[VERSION_1_0, VERSION, 0, 0, 0, 1, 0x00, VERSION_1_2]);
+        // GET_VERSION restarts the connection
+        *self = Responder::default();
+        self.vca.update(request);
+        self.vca.update(&response[..SIZE]);
+        self.state = ConnectionState::AfterVersion;
+        Ok(SIZE)
+    }
+
+    fn capabilities(&mut self, request: &[u8], response: &mut [u8]) -> Result<usize, Failure> {
+        const SIZE: usize = 20;
+        expect_size(request, SIZE)?;
+        let requester_data_transfer_size =
+            u32::from_le_bytes([request[12], request[13], request[14], request[15]]);
+        // Smallest data transfer size allowed by SPDM 1.2
+        if requester_data_transfer_size < 42 {
+            return Err(Failure::Spdm(ERROR_INVALID_REQUEST, 0));
+        }
+        check_response_size(response, SIZE)?;
+        response[..8].copy_from_slice(&[VERSION_1_2, CAPABILITIES, 0, 0, 0, 0, 0, 0]);
+        response[8..12].copy_from_slice(&CAPABILITY_FLAGS.to_le_bytes());
+        response[12..16].copy_from_slice(&DATA_TRANSFER_SIZE.to_le_bytes());
+        response[16..20].copy_from_slice(&DATA_TRANSFER_SIZE.to_le_bytes());
+        self.data_transfer_size = requester_data_transfer_size.min(DATA_TRANSFER_SIZE) as usize;
+        self.vca.update(request);
+        self.vca.update(&response[..SIZE]);
+        self.state = ConnectionState::AfterCapabilities;
+        Ok(SIZE)
+    }
+
+    fn algorithms(&mut self, request: &[u8], response: &mut [u8]) -> Result<usize, Failure> {
+        const SIZE: usize = 36;
+        if request.len() < 32
+            || usize::from(u16::from_le_bytes([request[4], request[5]])) != request.len()
+        {
+            return Err(Failure::Spdm(ERROR_INVALID_REQUEST, 0));
+        }
+        let base_asym_algo = u32::from_le_bytes([request[8], request[9], request[10], request[11]]);
+        let base_hash_algo =
+            u32::from_le_bytes([request[12], request[13], request[14], request[15]]);
+        if request[6] & MEASUREMENT_SPECIFICATION_DMTF == 0
+            || base_asym_algo & BASE_ASYM_ALGO_ECDSA_P256 == 0
+            || base_hash_algo & BASE_HASH_ALGO_SHA_256 == 0
+        {
+            return Err(Failure::Spdm(ERROR_INVALID_REQUEST, 0));
+        }
+        check_response_size(response, SIZE)?;
+        response[..SIZE].fill(0);
+        response[..4].copy_from_slice(&[VERSION_1_2, ALGORITHMS, 0, 0]);
+        response[4..6].copy_from_slice(&(SIZE as u16).to_le_bytes());
+        response[6] = MEASUREMENT_SPECIFICATION_DMTF;
+        response[8..12].copy_from_slice(&MEASUREMENT_HASH_ALGO_SHA_256.to_le_bytes());
+        response[12..16].copy_from_slice(&BASE_ASYM_ALGO_ECDSA_P256.to_le_bytes());
+        response[16..20].copy_from_slice(&BASE_HASH_ALGO_SHA_256.to_le_bytes());
+        self.vca.update(request);
+        self.vca.update(&response[..SIZE]);
+        self.m1 = self.vca.clone();
+        self.l1 = self.vca.clone();
+        self.state = ConnectionState::Negotiated;
+        Ok(SIZE)
+    }
+
+    fn digests(
+        &mut self,
+        identity: &Identity,
+        request: &[u8],
+        response: &mut [u8],
+    ) -> Result<usize, Failure> {
+        const SIZE: usize = HEADER_SIZE + DIGEST_SIZE;
+        expect_size(request, HEADER_SIZE)?;
+        check_response_size(response, SIZE)?;
+        response[..HEADER_SIZE].copy_from_slice(&[VERSION_1_2, DIGESTS, 0, 0x01]);
+        response[HEADER_SIZE..SIZE].copy_from_slice(&Sha256::digest(identity.certificate_chain));
+        self.m1.update(request);
+        self.m1.update(&response[..SIZE]);
+        Ok(SIZE)
+    }
+
+    fn certificate(
+        &mut self,
+        identity: &Identity,
+        request: &[u8],
+        response: &mut [u8],
+    ) -> Result<usize, Failure> {
+        const PREFIX_SIZE: usize = HEADER_SIZE + 4;
+        expect_size(request, 8)?;
+        let chain = identity.certificate_chain;
+        let offset = usize::from(u16::from_le_bytes([request[4], request[5]]));
+        let length = usize::from(u16::from_le_bytes([request[6], request[7]]));
+        if request[2] & 0x0F != 0 || offset >= chain.len() {
+            return Err(Failure::Spdm(ERROR_INVALID_REQUEST, 0));
+        }
+        check_response_size(response, PREFIX_SIZE + 1)?;
+        let capacity = response.len().min(self.data_transfer_size) - PREFIX_SIZE;
+        let portion = length.min(chain.len() - offset).min(capacity);
+        let remainder = chain.len() - offset - portion;
+        response[..HEADER_SIZE].copy_from_slice(&[VERSION_1_2, CERTIFICATE, 0, 0]);
+        response[4..6].copy_from_slice(&(portion as u16).to_le_bytes());
+        response[6..8].copy_from_slice(&(remainder as u16).to_le_bytes());
+        response[PREFIX_SIZE..PREFIX_SIZE + portion]
+            .copy_from_slice(&chain[offset..offset + portion]);
+        self.m1.update(request);
+        self.m1.update(&response[..PREFIX_SIZE + portion]);
+        Ok(PREFIX_SIZE + portion)
+    }
+
+    fn challenge_auth(
+        &mut self,
+        identity: &Identity,
+        nonce: &[u8; NONCE_SIZE],
+        request: &[u8],
+        response: &mut [u8],
+    ) -> Result<usize, Failure> {
+        expect_size(request, HEADER_SIZE + NONCE_SIZE)?;
+        let summary_hash_type = request[3];
+        if request[2] != 0 || !matches!(summary_hash_type, 0x00 | 0x01 | 0xFF) {
+            return Err(Failure::Spdm(ERROR_INVALID_REQUEST, 0));
+        }
+        let summary_hash_size = if summary_hash_type == 0 {
+            0
+        } else {
+            DIGEST_SIZE
+        };
+        let signed_size = HEADER_SIZE + DIGEST_SIZE + NONCE_SIZE + summary_hash_size + 2;
+        check_response_size(response, signed_size + SIGNATURE_SIZE)?;
+        let (signed, signature) =
+            response[..signed_size + SIGNATURE_SIZE].split_at_mut(signed_size);
+        signed[..HEADER_SIZE].copy_from_slice(&[VERSION_1_2, CHALLENGE_AUTH, 0, 0x01]);
+        let (cert_chain_hash, rest) = signed[HEADER_SIZE..].split_at_mut(DIGEST_SIZE);
+        cert_chain_hash.copy_from_slice(&Sha256::digest(identity.certificate_chain));
+        let (responder_nonce, rest) = rest.split_at_mut(NONCE_SIZE);
+        responder_nonce.copy_from_slice(nonce);
+        let (summary_hash, opaque_data_length) = rest.split_at_mut(summary_hash_size);
+        if summary_hash_type != 0 {
+            // All measurements are considered to be part of the TCB
+            summary_hash.copy_from_slice(&measurement_summary_hash(identity.measurements));
+        }
+        opaque_data_length.fill(0);
+
+        let mut m1 = self.vca.clone();
+        core::mem::swap(&mut m1, &mut self.m1);
+        m1.update(request);
+        m1.update(&*signed);
+        let message = signing_message(CHALLENGE_AUTH_CONTEXT, &m1.finalize().into());
+        nist_p256_sign(identity.private_key, &message, signature)?;
+        Ok(signed_size + SIGNATURE_SIZE)
+    }
+
+    fn measurements(
+        &mut self,
+        identity: &Identity,
+        nonce: &[u8; NONCE_SIZE],
+        request: &[u8],
+        response: &mut [u8],
+    ) -> Result<usize, Failure> {
+        let signature_requested = request[2] & 0x01 != 0;
+        if signature_requested {
+            expect_size(request, HEADER_SIZE + NONCE_SIZE + 1)?;
+            if request[HEADER_SIZE + NONCE_SIZE] != 0 {
+                return Err(Failure::Spdm(ERROR_INVALID_REQUEST, 0));
+            }
+        } else {
+            expect_size(request, HEADER_SIZE)?;
+        }
+        let count = identity.measurements.len();
+        let (total, selected) = match request[3] {
+            0x00 => (count as u8, 0..0),
+            0xFF => (0, 0..count),
+            index if usize::from(index) <= count => (0, usize::from(index) - 1..usize::from(index)),
+            _ => return Err(Failure::Spdm(ERROR_INVALID_REQUEST, 0)),
+        };
+        let record_size = selected.len() * MEASUREMENT_BLOCK_SIZE;
+        let signed_size = HEADER_SIZE + 4 + record_size + NONCE_SIZE + 2;
+        let signature_size = if signature_requested {
+            SIGNATURE_SIZE
+        } else {
+            0
+        };
+        check_response_size(response, signed_size + signature_size)?;
+        let (signed, signature) =
+            response[..signed_size + signature_size].split_at_mut(signed_size);
+        signed[..HEADER_SIZE].copy_from_slice(&[VERSION_1_2, MEASUREMENTS, total, 0]);
+        signed[4] = selected.len() as u8;
+        signed[5..8].copy_from_slice(&(record_size as u32).to_le_bytes()[..3]);
+        for (block, index) in signed[8..8 + record_size]
+            .chunks_mut(MEASUREMENT_BLOCK_SIZE)
+            .zip(selected)
+        {
+            block.copy_from_slice(&identity.measurements[index].block(index as u8 + 1));
+        }
+        signed[8 + record_size..8 + record_size + NONCE_SIZE].copy_from_slice(nonce);
+        signed[8 + record_size + NONCE_SIZE..].fill(0);
+
+        self.l1.update(request);
+        self.l1.update(&*signed);
+        if signature_requested {
+            let mut l1 = self.vca.clone();
+            core::mem::swap(&mut l1, &mut self.l1);
+            let message = signing_message(MEASUREMENTS_CONTEXT, &l1.finalize().into());
+            nist_p256_sign(identity.private_key, &message, signature)?;
+        }
+        Ok(signed_size + signature_size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::ecdsa::nist_p256_generate_key_pair;
+    use crate::crypto::rng::{test::TestEntropySource, Rng};
+
+    const NONCE: [u8; NONCE_SIZE] = [0x5a; NONCE_SIZE];
+    const TEST_MEASUREMENTS: [Measurement; 2] = [
+        Measurement {
+            value_type: 0,
+            digest: [0x01; DIGEST_SIZE],
+        },
+        Measurement {
+            value_type: 1,
+            digest: [0x02; DIGEST_SIZE],
+        },
+    ];
+
+    fn negotiate(responder: &mut Responder, identity: &Identity) {
+        let mut response = [0u8; 64];
+        let mut get_capabilities = [0u8; 20];
+        get_capabilities[..2].copy_from_slice(&[VERSION_1_2, GET_CAPABILITIES]);
+        get_capabilities[12..16].copy_from_slice(&DATA_TRANSFER_SIZE.to_le_bytes());
+        let mut negotiate_algorithms = [0u8; 32];
+        negotiate_algorithms[..2].copy_from_slice(&[VERSION_1_2, NEGOTIATE_ALGORITHMS]);
+        negotiate_algorithms[4] = 32;
+        negotiate_algorithms[6] = MEASUREMENT_SPECIFICATION_DMTF;
+        negotiate_algorithms[8..12].copy_from_slice(&BASE_ASYM_ALGO_ECDSA_P256.to_le_bytes());
+        negotiate_algorithms[12..16].copy_from_slice(&BASE_HASH_ALGO_SHA_256.to_le_bytes());
+        for (request, code) in [
+            (&[VERSION_1_0, GET_VERSION, 0, 0][..], VERSION),
+            (&get_capabilities, CAPABILITIES),
+            (&negotiate_algorithms, ALGORITHMS),
+        ] {
+            let response = responder
+                .respond(identity, &NONCE, request, &mut response)
+                .expect("failed to respond");
+            assert_eq!(response[1], code);
+        }
+    }
+
+    #[test]
+    fn negotiation() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, _) = nist_p256_generate_key_pair(&mut rng);
+        let identity = Identity {
+            certificate_chain: &[0xCC; 100],
+            private_key: &private_key,
+            measurements: &TEST_MEASUREMENTS,
+        };
+        let mut responder = Responder::default();
+        let mut response = [0u8; 64];
+
+        // Requests before negotiation are unexpected
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_2, GET_DIGESTS, 0, 0],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(
+            response_message,
+            [VERSION_1_0, ERROR, ERROR_UNEXPECTED_REQUEST, 0]
+        );
+
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_0, GET_VERSION, 0, 0],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(
+            response_message,
+            [VERSION_1_0, VERSION, 0, 0, 0, 1, 0x00, VERSION_1_2]
+        );
+
+        // Only SPDM 1.2 is supported after GET_VERSION
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[0x11, GET_CAPABILITIES, 0, 0],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(
+            response_message,
+            [VERSION_1_2, ERROR, ERROR_VERSION_MISMATCH, 0]
+        );
+
+        negotiate(&mut responder, &identity);
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_2, GET_DIGESTS, 0, 0],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(response_message[..4], [VERSION_1_2, DIGESTS, 0, 0x01]);
+        assert_eq!(
+            response_message[4..],
+            Sha256::digest(identity.certificate_chain)[..]
+        );
+
+        // Unsupported requests
+        let response_message = responder
+            .respond(&identity, &NONCE, &[VERSION_1_2, 0xE4, 0, 0], &mut response)
+            .expect("failed to respond");
+        assert_eq!(
+            response_message,
+            [VERSION_1_2, ERROR, ERROR_UNSUPPORTED_REQUEST, 0xE4]
+        );
+        assert_eq!(
+            responder.respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_2, GET_DIGESTS, 0, 0],
+                &mut response[..HEADER_SIZE + DIGEST_SIZE - 1]
+            ),
+            Err(Error::InvalidBufferSize)
+        );
+    }
+
+    #[test]
+    fn certificate_portions() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, _) = nist_p256_generate_key_pair(&mut rng);
+        let mut certificate_chain = [0u8; 100];
+        for (i, byte) in certificate_chain.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let identity = Identity {
+            certificate_chain: &certificate_chain,
+            private_key: &private_key,
+            measurements: &TEST_MEASUREMENTS,
+        };
+        let mut responder = Responder::default();
+        negotiate(&mut responder, &identity);
+
+        // The portion is limited by the response buffer
+        let mut response = [0u8; 48];
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_2, GET_CERTIFICATE, 0, 0, 10, 0, 0xFF, 0xFF],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(response_message[4..8], [40, 0, 50, 0]);
+        assert_eq!(response_message[8..], certificate_chain[10..50]);
+
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_2, GET_CERTIFICATE, 0, 0, 90, 0, 20, 0],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(response_message[4..8], [10, 0, 0, 0]);
+        assert_eq!(response_message[8..], certificate_chain[90..]);
+
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_2, GET_CERTIFICATE, 0, 0, 100, 0, 20, 0],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(
+            response_message,
+            [VERSION_1_2, ERROR, ERROR_INVALID_REQUEST, 0]
+        );
+    }
+
+    #[test]
+    fn unsigned_measurements() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, _) = nist_p256_generate_key_pair(&mut rng);
+        let identity = Identity {
+            certificate_chain: &[0xCC; 100],
+            private_key: &private_key,
+            measurements: &TEST_MEASUREMENTS,
+        };
+        let mut responder = Responder::default();
+        negotiate(&mut responder, &identity);
+        let mut response = [0u8; 256];
+
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_2, GET_MEASUREMENTS, 0, 0x00],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(
+            response_message[..8],
+            [VERSION_1_2, MEASUREMENTS, 2, 0, 0, 0, 0, 0]
+        );
+
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_2, GET_MEASUREMENTS, 0, 0x02],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(
+            response_message.len(),
+            8 + MEASUREMENT_BLOCK_SIZE + NONCE_SIZE + 2
+        );
+        assert_eq!(
+            response_message[4..8],
+            [1, MEASUREMENT_BLOCK_SIZE as u8, 0, 0]
+        );
+        assert_eq!(
+            response_message[8..8 + MEASUREMENT_BLOCK_SIZE],
+            TEST_MEASUREMENTS[1].block(2)
+        );
+
+        let response_message = responder
+            .respond(
+                &identity,
+                &NONCE,
+                &[VERSION_1_2, GET_MEASUREMENTS, 0, 0x03],
+                &mut response,
+            )
+            .expect("failed to respond");
+        assert_eq!(
+            response_message,
+            [VERSION_1_2, ERROR, ERROR_INVALID_REQUEST, 0]
+        );
+    }
+}
@@ -0,0 +1,563 @@
+use crate::crypto::ecdh::derive_shared_secret;
+use crate::crypto::hkdf::{hkdf_sha256_expand, hkdf_sha256_extract, SHA256_PRK_SIZE};
+use crate::crypto::x25519::x25519_calculate_shared_secret;
+use crate::crypto::Error;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+/// Size of the hash used by the supported cipher suites (SHA-256) in bytes. Secrets and transcript
+/// hashes have this size.
+pub const HASH_SIZE: usize = SHA256_PRK_SIZE;
+/// Size of the per-record nonce base (IV) for the AEAD algorithms of TLS 1.3 in bytes.
+pub const IV_SIZE: usize = 12;
+/// Maximum size of a label without the "tls13 " prefix in bytes.
+pub const MAX_LABEL_SIZE: usize = 255 - LABEL_PREFIX.len();
+/// Maximum size of the context passed to HKDF-Expand-Label in bytes.
+pub const MAX_CONTEXT_SIZE: usize = 255;
+/// Size of the content covered by a CertificateVerify signature in bytes.
+pub const CERTIFICATE_VERIFY_CONTENT_SIZE: usize =
+    CERTIFICATE_VERIFY_PADDING.len() + SERVER_CONTEXT.len() + 1 + HASH_SIZE;
+/// Maximum size of a DER encoded ECDSA signature (P-384) in bytes.
+pub const MAX_DER_SIGNATURE_SIZE: usize = 104;
+
+const LABEL_PREFIX: &[u8] = b"tls13 ";
+const CERTIFICATE_VERIFY_PADDING: [u8; 64] = [0x20; 64];
+const SERVER_CONTEXT: &[u8] = b"TLS 1.3, server CertificateVerify";
+const CLIENT_CONTEXT: &[u8] = b"TLS 1.3, client CertificateVerify";
+const ZEROS: [u8; HASH_SIZE] = [0u8; HASH_SIZE];
+
+/// Groups supported for the (EC)DHE key exchange.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Group {
+    Secp256r1,
+    X25519,
+}
+
+/// Curves supported for CertificateVerify signatures.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SignatureScheme {
+    /// ecdsa_secp256r1_sha256
+    EcdsaSecp256r1Sha256,
+    /// ecdsa_secp384r1_sha384
+    EcdsaSecp384r1Sha384,
+}
+
+/// TLS 1.3 HKDF-Extract (RFC 8446, section 7.1).
+///
+/// # Arguments
+///
+/// * `salt`: The salt. `None` is replaced by a string of [HASH_SIZE] zero bytes.
+/// * `ikm`: The input keying material. `None` is replaced by a string of [HASH_SIZE] zero bytes.
+/// * `secret`: Output buffer for the extracted secret. Must be exactly [HASH_SIZE] bytes long.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidBufferSize`: The length of `secret` is not [HASH_SIZE] bytes.
+pub fn hkdf_extract(
+    salt: Option<&[u8]>,
+    ikm: Option<&[u8]>,
+    secret: &mut [u8],
+) -> Result<(), Error> {
+    hkdf_sha256_extract(salt.unwrap_or(&ZEROS), &[ikm.unwrap_or(&ZEROS)], secret)
+}
+
+/// TLS 1.3 HKDF-Expand-Label (RFC 8446, section 7.1).
+///
+/// # Arguments
+///
+/// * `secret`: The secret to expand. Must be exactly [HASH_SIZE] bytes long.
+/// * `label`: The label without the "tls13 " prefix. Must not exceed [MAX_LABEL_SIZE] bytes.
+/// * `context`: The context. Must not exceed [MAX_CONTEXT_SIZE] bytes.
+/// * `output`: Output buffer. Its length determines the amount of derived bytes.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidSymmetricKeySize`: The length of `secret` is not [HASH_SIZE] bytes.
+/// * `InvalidBufferSize`: The `label` or `context` is too long or the length of `output` exceeds
+///   the HKDF limit.
+pub fn hkdf_expand_label(
+    secret: &[u8],
+    label: &[u8],
+    context: &[u8],
+    output: &mut [u8],
+) -> Result<(), Error> {
+    if secret.len() != HASH_SIZE {
+        return Err(Error::InvalidSymmetricKeySize);
+    }
+    if label.len() > MAX_LABEL_SIZE || context.len() > MAX_CONTEXT_SIZE {
+        return Err(Error::InvalidBufferSize);
+    }
+    let length = u16::try_from(output.len()).map_err(|_| Error::InvalidBufferSize)?;
+    hkdf_sha256_expand(
+        secret,
+        &[
+            &length.to_be_bytes(),
+            &[(LABEL_PREFIX.len() + label.len()) as u8],
+            LABEL_PREFIX,
+            label,
+            &[context.len() as u8],
+            context,
+        ],
+        output,
+    )
+}
+
+/// TLS 1.3 Derive-Secret (RFC 8446, section 7.1).
+///
+/// # Arguments
+///
+/// * `secret`: The secret to derive from. Must be exactly [HASH_SIZE] bytes long.
+/// * `label`: The label without the "tls13 " prefix.
+/// * `transcript_hash`: Hash of the handshake messages. Must be exactly [HASH_SIZE] bytes long.
+/// * `output`: Output buffer for the derived secret. Must be exactly [HASH_SIZE] bytes long.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidDigestSize`: The length of `transcript_hash` is not [HASH_SIZE] bytes.
+/// * `InvalidBufferSize`: The length of `output` is not [HASH_SIZE] bytes.
+/// * Any error of [hkdf_expand_label].
+pub fn derive_secret(
+    secret: &[u8],
+    label: &[u8],
+    transcript_hash: &[u8],
+    output: &mut [u8],
+) -> Result<(), Error> {
+    if transcript_hash.len() != HASH_SIZE {
+        return Err(Error::InvalidDigestSize);
+    }
+    if output.len() != HASH_SIZE {
+        return Err(Error::InvalidBufferSize);
+    }
+    hkdf_expand_label(secret, label, transcript_hash, output)
+}
+
+/// Compute the verify data of a Finished message (RFC 8446, section 4.4.4).
+///
+/// # Arguments
+///
+/// * `base_key`: The handshake traffic secret of the sender. Must be exactly [HASH_SIZE] bytes.
+/// * `transcript_hash`: Hash of the handshake messages. Must be exactly [HASH_SIZE] bytes long.
+/// * `verify_data`: Output buffer for the verify data. Must be exactly [HASH_SIZE] bytes long.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidSymmetricKeySize`: The length of `base_key` is not [HASH_SIZE] bytes.
+/// * `InvalidDigestSize`: The length of `transcript_hash` is not [HASH_SIZE] bytes.
+/// * `InvalidBufferSize`: The length of `verify_data` is not [HASH_SIZE] bytes.
+pub fn finished_verify_data(
+    base_key: &[u8],
+    transcript_hash: &[u8],
+    verify_data: &mut [u8],
+) -> Result<(), Error> {
+    if transcript_hash.len() != HASH_SIZE {
+        return Err(Error::InvalidDigestSize);
+    }
+    if verify_data.len() != HASH_SIZE {
+        return Err(Error::InvalidBufferSize);
+    }
+    let mut finished_key = Zeroizing::new([0u8; HASH_SIZE]);
+    hkdf_expand_label(base_key, b"finished", &[], finished_key.as_mut_slice())?;
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(finished_key.as_slice())
+        .map_err(|_| Error::InvalidSymmetricKeySize)?;
+    mac.update(transcript_hash);
+    verify_data.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(())
+}
+
+/// Compute the (EC)DHE shared secret from a private key and the key exchange value of a
+/// KeyShareEntry (RFC 8446, section 4.2.8.2).
+///
+/// # Arguments
+///
+/// * `group`: The group of the key exchange.
+/// * `private_key`: The private key.
+/// * `key_exchange`: The peer's public value. An uncompressed SEC 1 point for
+///   [Group::Secp256r1] and 32 bytes for [Group::X25519].
+/// * `shared_secret`: Output buffer for the shared secret. Must be exactly 32 bytes long.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidPrivateKey`: The private key is invalid.
+/// * `InvalidPublicKey`: The key exchange value is invalid or leads to an all-zero secret.
+/// * `InvalidBufferSize`: The length of `shared_secret` is not 32 bytes.
+pub fn ecdhe_shared_secret(
+    group: Group,
+    private_key: &[u8],
+    key_exchange: &[u8],
+    shared_secret: &mut [u8],
+) -> Result<(), Error> {
+    match group {
+        Group::X25519 => {
+            x25519_calculate_shared_secret(private_key, key_exchange, shared_secret)?;
+            // Abort on an all-zero value (RFC 8446, section 7.4.2)
+            if shared_secret.iter().all(|byte| *byte == 0) {
+                return Err(Error::InvalidPublicKey);
+            }
+        }
+        Group::Secp256r1 => {
+            let private_key =
+                p256::SecretKey::from_slice(private_key).map_err(|_| Error::InvalidPrivateKey)?;
+            // Only the uncompressed point format is allowed (RFC 8446, section 4.2.8.2)
+            if key_exchange.first() != Some(&0x04) {
+                return Err(Error::InvalidPublicKey);
+            }
+            let public_key = p256::PublicKey::from_sec1_bytes(key_exchange)
+                .map_err(|_| Error::InvalidPublicKey)?;
+            if shared_secret.len() != HASH_SIZE {
+                return Err(Error::InvalidBufferSize);
+            }
+            shared_secret.copy_from_slice(
+                derive_shared_secret(&private_key, &public_key).raw_secret_bytes(),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Assemble the content covered by a CertificateVerify signature (RFC 8446, section 4.4.3).
+///
+/// # Arguments
+///
+/// * `server`: Whether the signature is created by the server or by the client.
+/// * `transcript_hash`: Hash of the handshake messages. Must be exactly [HASH_SIZE] bytes long.
+/// * `content`: Output buffer for the content.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidDigestSize`: The length of `transcript_hash` is not [HASH_SIZE] bytes.
+pub fn certificate_verify_content(
+    server: bool,
+    transcript_hash: &[u8],
+    content: &mut [u8; CERTIFICATE_VERIFY_CONTENT_SIZE],
+) -> Result<(), Error> {
+    if transcript_hash.len() != HASH_SIZE {
+        return Err(Error::InvalidDigestSize);
+    }
+    let context = if server {
+        SERVER_CONTEXT
+    } else {
+        CLIENT_CONTEXT
+    };
+    let (padding, rest) = content.split_at_mut(CERTIFICATE_VERIFY_PADDING.len());
+    let (context_buffer, rest) = rest.split_at_mut(context.len());
+    padding.copy_from_slice(&CERTIFICATE_VERIFY_PADDING);
+    context_buffer.copy_from_slice(context);
+    rest[0] = 0;
+    rest[1..].copy_from_slice(transcript_hash);
+    Ok(())
+}
+
+/// Encode a raw ECDSA signature (`r || s`) in the DER format used by TLS.
+///
+/// # Arguments
+///
+/// * `scheme`: The signature scheme that produced the signature.
+/// * `signature`: The raw signature.
+/// * `output`: Output buffer for the DER encoded signature. Must be large enough to hold the
+///   encoding. [MAX_DER_SIGNATURE_SIZE] bytes are always sufficient.
+///
+/// # Errors
+///
+/// The function returns an error if:
+/// * `InvalidSignature`: The `signature` could not be parsed.
+/// * `InvalidBufferSize`: The `output` is too small for the encoded signature.
+pub fn encode_signature_der<'a>(
+    scheme: SignatureScheme,
+    signature: &[u8],
+    output: &'a mut [u8],
+) -> Result<&'a [u8], Error> {
+    fn copy<'a>(encoded: &[u8], output: &'a mut [u8]) -> Result<&'a [u8], Error> {
+        let output = output
+            .get_mut(..encoded.len())
+            .ok_or(Error::InvalidBufferSize)?;
+        output.copy_from_slice(encoded);
+        Ok(output)
+    }
+
+    match scheme {
+        SignatureScheme::EcdsaSecp256r1Sha256 => copy(
+            p256::ecdsa::Signature::from_slice(signature)
+                .map_err(|_| Error::InvalidSignature)?
+                .to_der()
+                .as_bytes(),
+            output,
+        ),
+        SignatureScheme::EcdsaSecp384r1Sha384 => copy(
+            p384::ecdsa::Signature::from_slice(signature)
+                .map_err(|_| Error::InvalidSignature)?
+                .to_der()
+                .as_bytes(),
+            output,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::ecdsa::{
+        nist_p256_generate_key_pair, nist_p256_sign, nist_p256_verify, NIST_P256_SIGNATURE_SIZE,
+    };
+    use crate::crypto::rng::{test::TestEntropySource, Rng};
+    use sha2::Digest;
+
+    // Key schedule generated by OpenSSL for a TLS_AES_128_GCM_SHA256 handshake without PSK
+    const ECDHE_SECRET: &str = "e7b8fef8903b520cb9a18971b69dd45dca53ce2f12bf3bef9315e31271df4b40";
+    const HANDSHAKE_START_HASH: &str =
+        "ec147a06dea3c8846c02b2238e41bddc9d89f9aea17b5efd4d7482af75881c0a";
+    const HANDSHAKE_FULL_HASH: &str =
+        "751a3d4a14dfabeb68e92ca5918e2408b9bcb0748982ec9c3230ac30bbeb23e2";
+    const EARLY_SECRET: &str = "33ad0a1c607ec03b09e6cd9893680ce210adf300aa1f2660e1b22e10f170f92a";
+    const CLIENT_HANDSHAKE_TRAFFIC_SECRET: &str =
+        "617b35076b9d0e08cf731d94a86614784109ef255551921dd46e040135cf46ab";
+    const CLIENT_HANDSHAKE_KEY: &str = "62d0dd00f69619d3b8193ab4a09585a7";
+    const CLIENT_HANDSHAKE_IV: &str = "fff75df5ad35d5cb3c53f3a9";
+    const SERVER_HANDSHAKE_TRAFFIC_SECRET: &str =
+        "fcf7dfe64fa2c04f6235387f434e01422336d9c039de6847a0b9ddcf29a88759";
+    const SERVER_HANDSHAKE_KEY: &str = "0467f316a805b8c497ee67047bbcbc54";
+    const SERVER_HANDSHAKE_IV: &str = "de83a73e9d814b04c48b7809";
+    const CLIENT_APPLICATION_TRAFFIC_SECRET: &str =
+        "c14a6d7976d8102b5a0c9951493fee87dcaff82c24cab214e8be71a8206dbda5";
+    const CLIENT_APPLICATION_KEY: &str = "cc9f5f980b5f10306cbad7be98d7572e";
+    const CLIENT_APPLICATION_IV: &str = "b80929e8d02c70f61162ed6b";
+    const SERVER_APPLICATION_TRAFFIC_SECRET: &str =
+        "2c907738d3f83702d1e4598f4848531d9f9365491b9f7f52c822290d4c232192";
+    const SERVER_APPLICATION_KEY: &str = "0cb29562d8d88f48b02cbfbed7e62bb3";
+    const SERVER_APPLICATION_IV: &str = "0db28f988586a1b7e4d5c69c";
+    const CLIENT_FINISHED: &str =
+        "89853f5f37c35889b6f3e72c25689bc7d91fcdf0e5b34b30995e5a19dcd20378";
+
+    fn check_traffic_secret(
+        secret: &[u8],
+        label: &[u8],
+        transcript_hash: &[u8],
+        expected_secret: &str,
+        expected_key: &str,
+        expected_iv: &str,
+    ) {
+        let mut traffic_secret = [0u8; HASH_SIZE];
+        derive_secret(secret, label, transcript_hash, &mut traffic_secret)
+            .expect("failed to derive traffic secret");
+        assert_eq!(
+            traffic_secret.as_slice(),
+            hex::decode(expected_secret).unwrap()
+        );
+        let mut key = [0u8; 16];
+        hkdf_expand_label(&traffic_secret, b"key", &[], &mut key)
+            .expect("failed to derive traffic key");
+        assert_eq!(key.as_slice(), hex::decode(expected_key).unwrap());
+        let mut iv = [0u8; IV_SIZE];
+        hkdf_expand_label(&traffic_secret, b"iv", &[], &mut iv)
+            .expect("failed to derive traffic iv");
+        assert_eq!(iv.as_slice(), hex::decode(expected_iv).unwrap());
+    }
+
+    #[test]
+    fn key_schedule() {
+        let empty_hash = Sha256::digest([]);
+        let mut early_secret = [0u8; HASH_SIZE];
+        hkdf_extract(None, None, &mut early_secret).expect("failed to extract early secret");
+        assert_eq!(early_secret.as_slice(), hex::decode(EARLY_SECRET).unwrap());
+
+        let mut derived = [0u8; HASH_SIZE];
+        derive_secret(&early_secret, b"derived", &empty_hash, &mut derived)
+            .expect("failed to derive secret");
+        let mut handshake_secret = [0u8; HASH_SIZE];
+        hkdf_extract(
+            Some(&derived),
+            Some(&hex::decode(ECDHE_SECRET).unwrap()),
+            &mut handshake_secret,
+        )
+        .expect("failed to extract handshake secret");
+        let handshake_start_hash = hex::decode(HANDSHAKE_START_HASH).unwrap();
+        check_traffic_secret(
+            &handshake_secret,
+            b"c hs traffic",
+            &handshake_start_hash,
+            CLIENT_HANDSHAKE_TRAFFIC_SECRET,
+            CLIENT_HANDSHAKE_KEY,
+            CLIENT_HANDSHAKE_IV,
+        );
+        check_traffic_secret(
+            &handshake_secret,
+            b"s hs traffic",
+            &handshake_start_hash,
+            SERVER_HANDSHAKE_TRAFFIC_SECRET,
+            SERVER_HANDSHAKE_KEY,
+            SERVER_HANDSHAKE_IV,
+        );
+
+        derive_secret(&handshake_secret, b"derived", &empty_hash, &mut derived)
+            .expect("failed to derive secret");
+        let mut master_secret = [0u8; HASH_SIZE];
+        hkdf_extract(Some(&derived), None, &mut master_secret)
+            .expect("failed to extract master secret");
+        let handshake_full_hash = hex::decode(HANDSHAKE_FULL_HASH).unwrap();
+        check_traffic_secret(
+            &master_secret,
+            b"c ap traffic",
+            &handshake_full_hash,
+            CLIENT_APPLICATION_TRAFFIC_SECRET,
+            CLIENT_APPLICATION_KEY,
+            CLIENT_APPLICATION_IV,
+        );
+        check_traffic_secret(
+            &master_secret,
+            b"s ap traffic",
+            &handshake_full_hash,
+            SERVER_APPLICATION_TRAFFIC_SECRET,
+            SERVER_APPLICATION_KEY,
+            SERVER_APPLICATION_IV,
+        );
+    }
+
+    #[test]
+    fn finished() {
+        let mut verify_data = [0u8; HASH_SIZE];
+        finished_verify_data(
+            &hex::decode(CLIENT_HANDSHAKE_TRAFFIC_SECRET).unwrap(),
+            &hex::decode(HANDSHAKE_FULL_HASH).unwrap(),
+            &mut verify_data,
+        )
+        .expect("failed to compute verify data");
+        assert_eq!(
+            verify_data.as_slice(),
+            hex::decode(CLIENT_FINISHED).unwrap()
+        );
+    }
+
+    #[test]
+    fn ecdhe() {
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, public_key) = nist_p256_generate_key_pair(&mut rng);
+        let (peer_private_key, peer_public_key) = nist_p256_generate_key_pair(&mut rng);
+        let mut key_exchange = [0x04u8; 65];
+        key_exchange[1..].copy_from_slice(&public_key);
+        let mut peer_key_exchange = [0x04u8; 65];
+        peer_key_exchange[1..].copy_from_slice(&peer_public_key);
+
+        let mut shared_secret = [0u8; HASH_SIZE];
+        let mut peer_shared_secret = [0u8; HASH_SIZE];
+        ecdhe_shared_secret(
+            Group::Secp256r1,
+            &private_key,
+            &peer_key_exchange,
+            &mut shared_secret,
+        )
+        .expect("ECDHE failed");
+        ecdhe_shared_secret(
+            Group::Secp256r1,
+            &peer_private_key,
+            &key_exchange,
+            &mut peer_shared_secret,
+        )
+        .expect("ECDHE failed");
+        assert_eq!(shared_secret, peer_shared_secret);
+        assert_eq!(
+            ecdhe_shared_secret(
+                Group::Secp256r1,
+                &private_key,
+                &peer_key_exchange[1..],
+                &mut shared_secret,
+            ),
+            Err(Error::InvalidPublicKey)
+        );
+        assert_eq!(
+            ecdhe_shared_secret(Group::X25519, &[1u8; 32], &[0u8; 32], &mut shared_secret),
+            Err(Error::InvalidPublicKey)
+        );
+    }
+
+    #[test]
+    fn certificate_verify() {
+        let transcript_hash = [0xabu8; HASH_SIZE];
+        let mut content = [0u8; CERTIFICATE_VERIFY_CONTENT_SIZE];
+        certificate_verify_content(true, &transcript_hash, &mut content)
+            .expect("failed to assemble content");
+        assert_eq!(content[..64], [0x20u8; 64]);
+        assert_eq!(&content[64..97], b"TLS 1.3, server CertificateVerify");
+        assert_eq!(content[97], 0);
+        assert_eq!(content[98..], transcript_hash);
+        certificate_verify_content(false, &transcript_hash, &mut content)
+            .expect("failed to assemble content");
+        assert_eq!(&content[64..97], b"TLS 1.3, client CertificateVerify");
+        assert_eq!(
+            certificate_verify_content(false, &transcript_hash[1..], &mut content),
+            Err(Error::InvalidDigestSize)
+        );
+
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, public_key) = nist_p256_generate_key_pair(&mut rng);
+        let mut signature = [0u8; NIST_P256_SIGNATURE_SIZE];
+        nist_p256_sign(&private_key, &content, &mut signature).expect("signing failed");
+        let mut der = [0u8; MAX_DER_SIGNATURE_SIZE];
+        let der = encode_signature_der(SignatureScheme::EcdsaSecp256r1Sha256, &signature, &mut der)
+            .expect("DER encoding failed");
+        let decoded = p256::ecdsa::Signature::from_der(der).expect("invalid DER signature");
+        nist_p256_verify(&public_key, &content, &decoded.to_bytes()).expect("verification failed");
+    }
+
+    #[test]
+    fn der_encoding() {
+        // r has its top bit set and s has a leading zero byte
+        let mut signature = [0u8; NIST_P256_SIGNATURE_SIZE];
+        signature[0] = 0x80;
+        signature[31] = 0x01;
+        signature[33] = 0x7f;
+        let mut expected = [0u8; 70];
+        expected[..5].copy_from_slice(&[0x30, 0x44, 0x02, 0x21, 0x00]);
+        expected[5] = 0x80;
+        expected[36] = 0x01;
+        expected[37..40].copy_from_slice(&[0x02, 0x1f, 0x7f]);
+        let mut der = [0u8; MAX_DER_SIGNATURE_SIZE];
+        assert_eq!(
+            encode_signature_der(SignatureScheme::EcdsaSecp256r1Sha256, &signature, &mut der),
+            Ok(expected.as_slice())
+        );
+        assert_eq!(
+            encode_signature_der(
+                SignatureScheme::EcdsaSecp256r1Sha256,
+                &signature,
+                &mut der[..69]
+            ),
+            Err(Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            encode_signature_der(SignatureScheme::EcdsaSecp384r1Sha384, &signature, &mut der),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn expand_label_errors() {
+        let secret = [0u8; HASH_SIZE];
+        let mut output = [0u8; HASH_SIZE];
+        assert_eq!(
+            hkdf_expand_label(&secret[1..], b"key", &[], &mut output),
+            Err(Error::InvalidSymmetricKeySize)
+        );
+        assert_eq!(
+            hkdf_expand_label(&secret, &[0u8; MAX_LABEL_SIZE + 1], &[], &mut output),
+            Err(Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            hkdf_expand_label(&secret, b"key", &[0u8; MAX_CONTEXT_SIZE + 1], &mut output),
+            Err(Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            derive_secret(&secret, b"derived", &secret[1..], &mut output),
+            Err(Error::InvalidDigestSize)
+        );
+        assert_eq!(
+            derive_secret(&secret, b"derived", &secret, &mut output[1..]),
+            Err(Error::InvalidBufferSize)
+        );
+    }
+}
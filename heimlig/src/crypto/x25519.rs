@@ -1,4 +1,5 @@
 use crate::crypto::Error;
+use rand::{CryptoRng, RngCore};
 use x25519_dalek::{PublicKey, StaticSecret};
 
 /// X25519 key size in bytes.
@@ -75,6 +76,23 @@ pub fn x25519_calculate_public_key(private_key: &[u8], public_key: &mut [u8]) ->
     Ok(())
 }
 
+/// Generates an X25519 key pair.
+///
+/// # Arguments
+///
+/// * `rng`: Random number generator to use for key generation.
+///
+/// returns: The private key and the public key, both `KEY_SIZE` bytes long.
+pub fn x25519_generate_key_pair<R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> ([u8; KEY_SIZE], [u8; KEY_SIZE]) {
+    let private_key = StaticSecret::random_from_rng(rng);
+    (
+        private_key.to_bytes(),
+        PublicKey::from(&private_key).to_bytes(),
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,7 +1,7 @@
 use crate::common::jobs;
 use crate::common::jobs::{ClientId, Request, RequestId, RequestType, Response};
 use crate::hsm::keystore;
-use crate::hsm::keystore::KeyStore;
+use crate::hsm::keystore::{KeyAccess, KeyStore};
 use core::future::poll_fn;
 use core::ops::DerefMut;
 use core::pin::Pin;
@@ -401,7 +401,7 @@ impl<
         let Some(client) = self.clients.get(client_id.idx()) else {
             return Err(Error::Internal(InternalError::InvalidClientId(client_id)));
         };
-        let request = client
+        let mut request = client
             .requests
             .lock()
             .await
@@ -411,6 +411,10 @@ impl<
             .ok_or(Error::Internal(InternalError::EmptyClientRequestQueue(
                 client_id,
             )))?;
+
+        // Key access is checked against the client ID, which must not be chosen by the client
+        request.set_client_id(client_id);
+
         let response = match request {
             Request::IsKeyAvailable {
                 client_id,
@@ -419,7 +423,10 @@ impl<
             } => match self.key_store {
                 None => Ok(Self::no_key_store_response(client_id, request_id)),
                 Some(key_store) => {
-                    let is_available = key_store.lock().await.deref_mut().is_key_available(key_id);
+                    let key_store = key_store.lock().await;
+                    // Keys the client has no access to are reported like unconfigured keys
+                    let is_available = key_store.check_any_access(key_id, client_id).is_ok()
+                        && key_store.is_key_available(key_id);
                     Ok(Response::IsKeyAvailable {
                         client_id,
                         request_id,
@@ -427,6 +434,78 @@ impl<
                     })
                 }
             },
+            Request::GetKeyInfo {
+                client_id,
+                request_id,
+                key_id,
+            } => match self.key_store {
+                None => Ok(Self::no_key_store_response(client_id, request_id)),
+                Some(key_store) => {
+                    let key_store = key_store.lock().await;
+                    let result = key_store
+                        .check_any_access(key_id, client_id)
+                        .and_then(|()| key_store.get_key_info(key_id));
+                    match result {
+                        Ok(key_info) => Ok(Response::GetKeyInfo {
+                            client_id,
+                            request_id,
+                            key_info,
+                            is_available: key_store.is_key_available(key_id),
+                        }),
+                        Err(e) => Ok(Self::key_store_error_response(client_id, request_id, e)),
+                    }
+                }
+            },
+            Request::ListKeys {
+                client_id,
+                request_id,
+                offset,
+                key_ids,
+            } => match self.key_store {
+                None => Ok(Self::no_key_store_response(client_id, request_id)),
+                Some(key_store) => {
+                    let key_store = key_store.lock().await;
+                    // Keys the client has no access to are neither listed nor counted
+                    let visible_keys = (0..key_store.num_keys())
+                        .filter_map(|index| key_store.key_info_at(index))
+                        .filter(|key_info| key_info.access.allows_any(client_id));
+                    let mut num_keys = 0;
+                    let mut count = 0;
+                    for key_info in visible_keys {
+                        if num_keys >= offset && count < key_ids.len() {
+                            key_ids[count] = key_info.id;
+                            count += 1;
+                        }
+                        num_keys += 1;
+                    }
+                    Ok(Response::ListKeys {
+                        client_id,
+                        request_id,
+                        key_ids: &mut key_ids[..count],
+                        num_keys,
+                    })
+                }
+            },
+            Request::DeleteKey {
+                client_id,
+                request_id,
+                key_id,
+            } => match self.key_store {
+                None => Ok(Self::no_key_store_response(client_id, request_id)),
+                Some(key_store) => {
+                    let mut key_store = key_store.lock().await;
+                    let result = key_store
+                        .check_access(key_id, client_id, KeyAccess::Delete)
+                        .and_then(|()| key_store.delete(key_id));
+                    match result {
+                        Ok(()) => Ok(Response::DeleteKey {
+                            client_id,
+                            request_id,
+                        }),
+                        Err(e) => Ok(Self::key_store_error_response(client_id, request_id, e)),
+                    }
+                }
+            },
             Request::ImportSymmetricKey {
                 client_id,
                 request_id,
@@ -436,11 +515,10 @@ impl<
             } => match self.key_store {
                 None => Ok(Self::no_key_store_response(client_id, request_id)),
                 Some(key_store) => {
+                    let mut key_store = key_store.lock().await;
                     let result = key_store
-                        .lock()
-                        .await
-                        .deref_mut()
-                        .import_symmetric_key(key_id, data, overwrite);
+                        .check_access(key_id, client_id, KeyAccess::Import)
+                        .and_then(|()| key_store.import_symmetric_key(key_id, data, overwrite));
                     match result {
                         Ok(()) => Ok(Response::ImportSymmetricKey {
                             client_id,
@@ -460,12 +538,12 @@ impl<
             } => match self.key_store {
                 None => Ok(Self::no_key_store_response(client_id, request_id)),
                 Some(key_store) => {
-                    let result = key_store.lock().await.deref_mut().import_key_pair(
-                        key_id,
-                        public_key,
-                        private_key,
-                        overwrite,
-                    );
+                    let mut key_store = key_store.lock().await;
+                    let result = key_store
+                        .check_access(key_id, client_id, KeyAccess::Import)
+                        .and_then(|()| {
+                            key_store.import_key_pair(key_id, public_key, private_key, overwrite)
+                        });
                     match result {
                         Ok(()) => Ok(Response::ImportKeyPair {
                             client_id,
@@ -483,11 +561,10 @@ impl<
             } => match self.key_store {
                 None => Ok(Self::no_key_store_response(client_id, request_id)),
                 Some(key_store) => {
+                    let key_store = key_store.lock().await;
                     let exported_key = key_store
-                        .lock()
-                        .await
-                        .deref_mut()
-                        .export_symmetric_key(key_id, data);
+                        .check_access(key_id, client_id, KeyAccess::Export)
+                        .and_then(|()| key_store.export_symmetric_key(key_id, data));
                     match exported_key {
                         Ok(written) => {
                             let written_len = written.len();
@@ -509,11 +586,10 @@ impl<
             } => match self.key_store {
                 None => Ok(Self::no_key_store_response(client_id, request_id)),
                 Some(key_store) => {
+                    let key_store = key_store.lock().await;
                     let exported_key = key_store
-                        .lock()
-                        .await
-                        .deref_mut()
-                        .export_public_key(key_id, public_key);
+                        .check_access(key_id, client_id, KeyAccess::Export)
+                        .and_then(|()| key_store.export_public_key(key_id, public_key));
                     match exported_key {
                         Ok(written) => {
                             let exported_key_len = written.len();
@@ -535,11 +611,10 @@ impl<
             } => match self.key_store {
                 None => Ok(Self::no_key_store_response(client_id, request_id)),
                 Some(key_store) => {
+                    let key_store = key_store.lock().await;
                     let exported_key = key_store
-                        .lock()
-                        .await
-                        .deref_mut()
-                        .export_private_key(key_id, private_key);
+                        .check_access(key_id, client_id, KeyAccess::Export)
+                        .and_then(|()| key_store.export_private_key(key_id, private_key));
                     match exported_key {
                         Ok(written) => {
                             let written_len = written.len();
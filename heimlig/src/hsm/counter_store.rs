@@ -0,0 +1,60 @@
+/// Identifier to reference HSM counters
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct CounterId(pub u32);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The requested counter was not found.
+    CounterNotFound,
+    /// A counter with the requested ID already exists.
+    CounterExists,
+    /// The counter store cannot handle the amount of requested counters.
+    CounterStoreTooSmall,
+    /// The counter would exceed its maximum value.
+    MaxValueExceeded,
+    /// The underlying storage failed to read or write the counter.
+    Storage,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CounterInfo {
+    pub id: CounterId,
+    /// Current value of the counter.
+    pub value: u64,
+    /// Value the counter can never exceed.
+    pub max_value: u64,
+}
+
+impl From<CounterId> for u32 {
+    fn from(value: CounterId) -> Self {
+        value.0
+    }
+}
+
+impl From<u32> for CounterId {
+    fn from(value: u32) -> Self {
+        CounterId(value)
+    }
+}
+
+/// Storage for monotonic counters.
+///
+/// Counters can only be created and incremented. There is intentionally no way to decrement,
+/// reset or delete a counter, so that implementations can guarantee that a counter never goes back
+/// to a previous value.
+pub trait CounterStore {
+    /// Create a counter that starts at zero.
+    ///
+    /// return: An error, if a counter with the same ID already exists or the store is full.
+    fn create(&mut self, id: CounterId, max_value: u64) -> Result<(), Error>;
+
+    /// Get the current value and the maximum value of a counter.
+    fn get_counter_info(&self, id: CounterId) -> Result<CounterInfo, Error>;
+
+    /// Increase the value of a counter by `amount`.
+    ///
+    /// The counter is left unchanged if the new value would exceed its maximum value.
+    ///
+    /// returns: The new value of the counter or an error.
+    fn increment(&mut self, id: CounterId, amount: u64) -> Result<u64, Error>;
+}
@@ -1,5 +1,9 @@
+use crate::common::jobs::ClientId;
+use crate::hsm::core::MAX_CLIENTS;
+
 /// Identifier to reference HSM keys
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+#[repr(transparent)]
 pub struct KeyId(pub u32);
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -18,6 +22,12 @@ pub enum Error {
     InvalidKeyType,
     /// Size of the provided buffer is invalid.
     InvalidBufferSize,
+    /// The underlying storage failed to read or write the key.
+    Storage,
+    /// The stored key material or its metadata was modified.
+    IntegrityViolation,
+    /// The access control list of the key does not allow the client to access it.
+    AccessDenied,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -27,6 +37,8 @@ pub enum KeyType {
     Symmetric256Bits,
     EccKeypairNistP256,
     EccKeypairNistP384,
+    EccKeypairEd25519,
+    EccKeypairX25519,
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -42,11 +54,100 @@ pub struct KeyPermissions {
     pub delete: bool,
 }
 
+/// Cryptographic operation a worker uses a key for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum KeyUsage {
+    Encrypt,
+    Decrypt,
+    Sign,
+    Verify,
+    /// Derive other keys or secrets, e.g. with a KDF or a key agreement.
+    Derive,
+    Wrap,
+    Unwrap,
+    Mac,
+}
+
+/// The operations workers may use a key for. Unlike `KeyPermissions`, these flags restrict how
+/// key material is used inside Heimlig.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct KeyUsageFlags {
+    pub encrypt: bool,
+    pub decrypt: bool,
+    pub sign: bool,
+    pub verify: bool,
+    pub derive: bool,
+    pub wrap: bool,
+    pub unwrap: bool,
+    pub mac: bool,
+}
+
+/// Algorithm a key can be bound to. Curves and key sizes are determined by the `KeyType`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum KeyAlgorithm {
+    AesGcm,
+    AesCbc,
+    AesCmac,
+    ChaCha20Poly1305,
+    /// Signatures of arbitrary messages with the ECC worker: ECDSA with SHA-256 (NIST P-256) or
+    /// SHA-384 (NIST P-384), or Ed25519.
+    Signature,
+    /// Elliptic curve Diffie-Hellman key agreement, e.g. in TLS or Noise handshakes.
+    Ecdh,
+    Hpke,
+    Ecies,
+    Hkdf,
+    /// JSON Web Signatures.
+    Jws,
+    /// Signatures of attestation quotes.
+    AttestationQuote,
+    /// Signatures of SPDM responses, e.g. CHALLENGE_AUTH and MEASUREMENTS.
+    Spdm,
+    /// Signatures of TLS 1.3 CertificateVerify messages.
+    TlsCertificateVerify,
+    /// Verification of the manifests of boot images.
+    BootManifest,
+}
+
+/// Access of a client to a key that is subject to the access control list of the key.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum KeyAccess {
+    /// Use the key in a worker, e.g. to encrypt data or to sign a message.
+    Use,
+    /// Set the key, either by importing it or by generating or deriving it in a worker.
+    Import,
+    /// Export the key, including the public key of key pairs.
+    Export,
+    Delete,
+}
+
+/// Set of clients, one bit per `ClientId`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ClientSet(pub u32);
+
+// Every client must be representable in a `ClientSet`
+const _: () = assert!(MAX_CLIENTS <= u32::BITS as usize);
+
+/// Clients that may access a key, per type of access.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct KeyAccessControl {
+    pub use_key: ClientSet,
+    pub import: ClientSet,
+    pub export: ClientSet,
+    pub delete: ClientSet,
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct KeyInfo {
     pub id: KeyId,
     pub ty: KeyType,
     pub permissions: KeyPermissions,
+    pub usage: KeyUsageFlags,
+    /// The only algorithm workers may use the key with. If `None`, the key may be used with any
+    /// algorithm that matches its type.
+    pub algorithm: Option<KeyAlgorithm>,
+    /// The clients that may access the key. Permissions and usage flags apply in addition.
+    pub access: KeyAccessControl,
 }
 
 impl From<KeyId> for u32 {
@@ -61,11 +162,170 @@ impl From<u32> for KeyId {
     }
 }
 
+impl KeyInfo {
+    /// Size of the serialized key info.
+    pub const SERIALIZED_SIZE: usize = 24;
+
+    /// Serialize the key info, e.g. to store it next to the key material or to bind it to the key
+    /// material cryptographically.
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_SIZE] {
+        let mut bytes = [0u8; Self::SERIALIZED_SIZE];
+        bytes[0..4].copy_from_slice(&self.id.0.to_le_bytes());
+        bytes[4] = self.ty.code();
+        bytes[5] = self.permissions.bits();
+        bytes[6] = self.usage.bits();
+        bytes[7] = match self.algorithm {
+            None => 0,
+            Some(algorithm) => algorithm.code(),
+        };
+        for (bytes, clients) in bytes[8..].chunks_exact_mut(4).zip([
+            self.access.use_key,
+            self.access.import,
+            self.access.export,
+            self.access.delete,
+        ]) {
+            bytes.copy_from_slice(&clients.0.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+impl KeyPermissions {
+    /// Encode the permissions as bit mask: `import` (bit 0), `export_private` (bit 1),
+    /// `overwrite` (bit 2) and `delete` (bit 3).
+    pub const fn bits(&self) -> u8 {
+        (self.import as u8)
+            | (self.export_private as u8) << 1
+            | (self.overwrite as u8) << 2
+            | (self.delete as u8) << 3
+    }
+}
+
+impl KeyUsageFlags {
+    /// Whether or not the key may be used for `usage`.
+    pub const fn allows(&self, usage: KeyUsage) -> bool {
+        match usage {
+            KeyUsage::Encrypt => self.encrypt,
+            KeyUsage::Decrypt => self.decrypt,
+            KeyUsage::Sign => self.sign,
+            KeyUsage::Verify => self.verify,
+            KeyUsage::Derive => self.derive,
+            KeyUsage::Wrap => self.wrap,
+            KeyUsage::Unwrap => self.unwrap,
+            KeyUsage::Mac => self.mac,
+        }
+    }
+
+    /// Encode the usage flags as bit mask in the order of the `KeyUsage` variants, starting with
+    /// `encrypt` (bit 0).
+    pub const fn bits(&self) -> u8 {
+        (self.encrypt as u8)
+            | (self.decrypt as u8) << 1
+            | (self.sign as u8) << 2
+            | (self.verify as u8) << 3
+            | (self.derive as u8) << 4
+            | (self.wrap as u8) << 5
+            | (self.unwrap as u8) << 6
+            | (self.mac as u8) << 7
+    }
+}
+
+impl ClientSet {
+    pub const ALL: ClientSet = ClientSet(u32::MAX);
+    pub const NONE: ClientSet = ClientSet(0);
+
+    /// Create a set of the given clients. Client IDs that cannot be represented are ignored.
+    pub const fn of(clients: &[ClientId]) -> Self {
+        let mut bits = 0u32;
+        let mut i = 0;
+        while i < clients.len() {
+            if clients[i].0 < u32::BITS {
+                bits |= 1 << clients[i].0;
+            }
+            i += 1;
+        }
+        ClientSet(bits)
+    }
+
+    pub const fn contains(&self, client_id: ClientId) -> bool {
+        client_id.0 < u32::BITS && self.0 & (1 << client_id.0) != 0
+    }
+}
+
+impl KeyAccessControl {
+    /// Access control list that allows every client every access.
+    pub const ALL: KeyAccessControl = KeyAccessControl::only(ClientSet::ALL);
+
+    /// Access control list that allows every access to the given clients only.
+    pub const fn only(clients: ClientSet) -> Self {
+        KeyAccessControl {
+            use_key: clients,
+            import: clients,
+            export: clients,
+            delete: clients,
+        }
+    }
+
+    /// Whether or not `client_id` may access the key with `access`.
+    pub const fn allows(&self, client_id: ClientId, access: KeyAccess) -> bool {
+        match access {
+            KeyAccess::Use => self.use_key.contains(client_id),
+            KeyAccess::Import => self.import.contains(client_id),
+            KeyAccess::Export => self.export.contains(client_id),
+            KeyAccess::Delete => self.delete.contains(client_id),
+        }
+    }
+
+    /// Whether or not `client_id` may access the key in any way. Other clients must not learn
+    /// about the key.
+    pub const fn allows_any(&self, client_id: ClientId) -> bool {
+        self.use_key.contains(client_id)
+            || self.import.contains(client_id)
+            || self.export.contains(client_id)
+            || self.delete.contains(client_id)
+    }
+}
+
+impl KeyAlgorithm {
+    /// Numeric code identifying the algorithm, starting at 1 so that 0 can denote no algorithm.
+    pub const fn code(&self) -> u8 {
+        match self {
+            KeyAlgorithm::AesGcm => 1,
+            KeyAlgorithm::AesCbc => 2,
+            KeyAlgorithm::AesCmac => 3,
+            KeyAlgorithm::ChaCha20Poly1305 => 4,
+            KeyAlgorithm::Signature => 5,
+            KeyAlgorithm::Ecdh => 6,
+            KeyAlgorithm::Hpke => 7,
+            KeyAlgorithm::Ecies => 8,
+            KeyAlgorithm::Hkdf => 9,
+            KeyAlgorithm::Jws => 10,
+            KeyAlgorithm::AttestationQuote => 11,
+            KeyAlgorithm::Spdm => 12,
+            KeyAlgorithm::TlsCertificateVerify => 13,
+            KeyAlgorithm::BootManifest => 14,
+        }
+    }
+}
+
 impl KeyType {
     pub const MAX_SYMMETRIC_KEY_SIZE: usize = KeyType::Symmetric256Bits.key_size();
     pub const MAX_PUBLIC_KEY_SIZE: usize = KeyType::EccKeypairNistP384.public_key_size();
     pub const MAX_PRIVATE_KEY_SIZE: usize = KeyType::EccKeypairNistP384.private_key_size();
 
+    /// Numeric code identifying the key type, e.g. in serialized key infos or raw responses.
+    pub const fn code(&self) -> u8 {
+        match self {
+            KeyType::Symmetric128Bits => 0,
+            KeyType::Symmetric192Bits => 1,
+            KeyType::Symmetric256Bits => 2,
+            KeyType::EccKeypairNistP256 => 3,
+            KeyType::EccKeypairNistP384 => 4,
+            KeyType::EccKeypairEd25519 => 5,
+            KeyType::EccKeypairX25519 => 6,
+        }
+    }
+
     pub const fn is_symmetric(&self) -> bool {
         matches!(
             self,
@@ -81,12 +341,18 @@ impl KeyType {
         match self {
             KeyType::EccKeypairNistP256 => 32,
             KeyType::EccKeypairNistP384 => 48,
+            KeyType::EccKeypairEd25519 => 32,
+            KeyType::EccKeypairX25519 => 32,
             _ => 0,
         }
     }
 
     pub const fn public_key_size(&self) -> usize {
-        2 * self.curve_size()
+        match self {
+            // Edwards and Montgomery curve public keys are compressed points
+            KeyType::EccKeypairEd25519 | KeyType::EccKeypairX25519 => self.curve_size(),
+            _ => 2 * self.curve_size(),
+        }
     }
 
     pub const fn private_key_size(&self) -> usize {
@@ -190,4 +456,58 @@ pub trait KeyStore {
 
     /// Get the size of a key.
     fn size(&self, id: KeyId) -> Result<usize, Error>;
+
+    /// Check whether the access control list of the key for given ID allows `client_id` to access
+    /// the key with `access`.
+    ///
+    /// return: `AccessDenied`, if the client is not allowed to access the key.
+    fn check_access(&self, id: KeyId, client_id: ClientId, access: KeyAccess) -> Result<(), Error> {
+        if !self.get_key_info(id)?.access.allows(client_id, access) {
+            return Err(Error::AccessDenied);
+        }
+        Ok(())
+    }
+
+    /// Check whether the client with `client_id` may access the key for given ID in any way, i.e.
+    /// whether the client may query information about the key.
+    ///
+    /// return: `InvalidKeyId`, if the key is not configured or the client is not allowed any
+    /// access to it, so that clients cannot tell which keys exist.
+    fn check_any_access(&self, id: KeyId, client_id: ClientId) -> Result<(), Error> {
+        if !self.get_key_info(id)?.access.allows_any(client_id) {
+            return Err(Error::InvalidKeyId);
+        }
+        Ok(())
+    }
+
+    /// Check whether workers may use the key for given ID on behalf of `client_id` for `usage`
+    /// with `algorithm`. Workers are supposed to call this before exporting a key with
+    /// `export_*_unchecked()`.
+    ///
+    /// return: `AccessDenied`, if the client is not allowed to use the key, or `NotAllowed`, if
+    /// the usage flags of the key do not allow `usage` or the key is bound to another algorithm.
+    fn check_usage(
+        &self,
+        id: KeyId,
+        client_id: ClientId,
+        usage: KeyUsage,
+        algorithm: KeyAlgorithm,
+    ) -> Result<(), Error> {
+        let info = self.get_key_info(id)?;
+        if !info.access.allows(client_id, KeyAccess::Use) {
+            return Err(Error::AccessDenied);
+        }
+        if !info.usage.allows(usage) || info.algorithm.is_some_and(|bound| bound != algorithm) {
+            return Err(Error::NotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of key slots configured in the store.
+    fn num_keys(&self) -> usize;
+
+    /// Get the information about the key slot at `index`. Slots are ordered by their `KeyId`.
+    ///
+    /// returns: `None`, if `index` is not smaller than `num_keys()`.
+    fn key_info_at(&self, index: usize) -> Option<KeyInfo>;
 }
@@ -1,3 +1,4 @@
 pub mod core;
+pub mod counter_store;
 pub mod keystore;
 pub mod workers;
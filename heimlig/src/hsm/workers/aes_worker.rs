@@ -10,7 +10,7 @@ use crate::crypto::aes::gcm::{
 };
 use crate::crypto::aes::{KEY128_SIZE, KEY192_SIZE, KEY256_SIZE};
 use crate::hsm::keystore;
-use crate::hsm::keystore::{KeyId, KeyInfo, KeyStore, KeyType};
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyInfo, KeyStore, KeyType, KeyUsage};
 use cbc::cipher::block_padding::Pkcs7;
 use embassy_sync::blocking_mutex::raw::RawMutex;
 use embassy_sync::mutex::Mutex;
@@ -161,7 +161,13 @@ impl<
     ) -> Response<'data> {
         let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_SYMMETRIC_KEY_SIZE]);
         let key_and_info = self
-            .export_key_and_key_info(key_id, key_buffer.as_mut_slice())
+            .export_key_and_key_info(
+                key_id,
+                client_id,
+                KeyUsage::Encrypt,
+                KeyAlgorithm::AesGcm,
+                key_buffer.as_mut_slice(),
+            )
             .await;
         let result = match key_and_info {
             Err(e) => {
@@ -252,7 +258,13 @@ impl<
     ) -> Response<'data> {
         let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_SYMMETRIC_KEY_SIZE]);
         let key_and_info = self
-            .export_key_and_key_info(key_id, key_buffer.as_mut_slice())
+            .export_key_and_key_info(
+                key_id,
+                client_id,
+                KeyUsage::Decrypt,
+                KeyAlgorithm::AesGcm,
+                key_buffer.as_mut_slice(),
+            )
             .await;
         let result = match key_and_info {
             Err(e) => {
@@ -339,7 +351,13 @@ impl<
     ) -> Response<'data> {
         let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_SYMMETRIC_KEY_SIZE]);
         let key_and_info = self
-            .export_key_and_key_info(key_id, key_buffer.as_mut_slice())
+            .export_key_and_key_info(
+                key_id,
+                client_id,
+                KeyUsage::Encrypt,
+                KeyAlgorithm::AesCbc,
+                key_buffer.as_mut_slice(),
+            )
             .await;
         let result = match key_and_info {
             Err(e) => {
@@ -433,7 +451,13 @@ impl<
     ) -> Response<'data> {
         let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_SYMMETRIC_KEY_SIZE]);
         let key_and_info = self
-            .export_key_and_key_info(key_id, key_buffer.as_mut_slice())
+            .export_key_and_key_info(
+                key_id,
+                client_id,
+                KeyUsage::Decrypt,
+                KeyAlgorithm::AesCbc,
+                key_buffer.as_mut_slice(),
+            )
             .await;
         let result = match key_and_info {
             Err(e) => {
@@ -513,10 +537,14 @@ impl<
     async fn export_key_and_key_info<'a>(
         &mut self,
         key_id: KeyId,
+        client_id: ClientId,
+        usage: KeyUsage,
+        algorithm: KeyAlgorithm,
         key_buffer: &'a mut [u8],
     ) -> Result<(&'a [u8], KeyInfo), keystore::Error> {
         // Lock keystore only once
         let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(key_id, client_id, usage, algorithm)?;
         Ok((
             locked_key_store.export_symmetric_key_unchecked(key_id, key_buffer)?,
             locked_key_store.get_key_info(key_id)?,
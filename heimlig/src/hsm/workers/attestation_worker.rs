@@ -0,0 +1,218 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
+use crate::crypto;
+use crate::crypto::attestation::REGISTER_SIZE;
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyInfo, KeyStore, KeyType, KeyUsage};
+use crate::hsm::workers::jws_worker::algorithm_for_key_type;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use zeroize::Zeroizing;
+
+/// Worker for measurement registers and signed attestation quotes.
+///
+/// The registers start zeroed and can only be extended. Quotes are signed with the configured
+/// attestation key, which is not selectable by clients.
+pub struct AttestationWorker<
+    'data,
+    'keystore,
+    M: RawMutex,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+    const NUM_REGISTERS: usize,
+> {
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    /// Key used to sign quotes.
+    pub attestation_key_id: KeyId,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+    pub registers: [[u8; REGISTER_SIZE]; NUM_REGISTERS],
+}
+
+impl<
+        'data,
+        'keystore,
+        M: RawMutex,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+        const NUM_REGISTERS: usize,
+    > AttestationWorker<'data, 'keystore, M, ReqSrc, RespSink, NUM_REGISTERS>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::ExtendMeasurement {
+                client_id,
+                request_id,
+                register,
+                digest,
+            } => self.extend(client_id, request_id, register, digest),
+            Request::ReadMeasurement {
+                client_id,
+                request_id,
+                register,
+                value,
+            } => self.read(client_id, request_id, register, value),
+            Request::QuoteMeasurements {
+                client_id,
+                request_id,
+                selection,
+                nonce,
+                quote,
+            } => {
+                self.quote(client_id, request_id, selection, nonce, quote)
+                    .await
+            }
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    fn extend(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        register: u32,
+        digest: &[u8],
+    ) -> Response<'data> {
+        let result = self.register(register).and_then(|register| {
+            crypto::attestation::extend(register, digest).map_err(Error::Crypto)
+        });
+        match result {
+            Ok(()) => Response::ExtendMeasurement {
+                client_id,
+                request_id,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    fn read(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        register: u32,
+        value: &'data mut [u8],
+    ) -> Response<'data> {
+        let register = match self.register(register) {
+            Ok(register) => register,
+            Err(error) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error,
+                }
+            }
+        };
+        if value.len() != REGISTER_SIZE {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(crypto::Error::InvalidBufferSize),
+            };
+        }
+        value.copy_from_slice(register);
+        Response::ReadMeasurement {
+            client_id,
+            request_id,
+            value,
+        }
+    }
+
+    async fn quote(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        selection: u32,
+        nonce: &[u8],
+        quote: &'data mut [u8],
+    ) -> Response<'data> {
+        if NUM_REGISTERS < crypto::attestation::MAX_REGISTERS && selection >> NUM_REGISTERS != 0 {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::InvalidRegister,
+            };
+        }
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
+        let (private_key, key_info) = match self
+            .export_private_key_and_key_info(
+                self.attestation_key_id,
+                client_id,
+                key_buffer.as_mut_slice(),
+            )
+            .await
+        {
+            Ok(private_key_and_info) => private_key_and_info,
+            Err(e) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(e),
+                }
+            }
+        };
+        let Some(algorithm) = algorithm_for_key_type(key_info.ty) else {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::KeyStore(keystore::Error::InvalidKeyType),
+            };
+        };
+        match crypto::attestation::sign_quote(
+            algorithm,
+            private_key,
+            selection,
+            &self.registers,
+            nonce,
+            quote,
+        ) {
+            Ok(quote) => Response::QuoteMeasurements {
+                client_id,
+                request_id,
+                quote,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    fn register(&mut self, register: u32) -> Result<&mut [u8; REGISTER_SIZE], Error> {
+        self.registers
+            .get_mut(register as usize)
+            .ok_or(Error::InvalidRegister)
+    }
+
+    async fn export_private_key_and_key_info<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        key_buffer: &'a mut [u8],
+    ) -> Result<(&'a [u8], KeyInfo), keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(
+            key_id,
+            client_id,
+            KeyUsage::Sign,
+            KeyAlgorithm::AttestationQuote,
+        )?;
+
+        Ok((
+            locked_key_store.export_private_key_unchecked(key_id, key_buffer)?,
+            locked_key_store.get_key_info(key_id)?,
+        ))
+    }
+}
@@ -0,0 +1,279 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response, SessionId};
+use crate::crypto;
+use crate::crypto::manifest::Manifest;
+use crate::hsm::counter_store::{CounterId, CounterStore};
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyInfo, KeyStore, KeyType, KeyUsage};
+use crate::hsm::workers::jws_worker::algorithm_for_key_type;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use heapless::Vec;
+use sha2::{Digest, Sha256};
+
+struct Verification {
+    id: SessionId,
+    client_id: ClientId,
+    manifest: Manifest,
+    hasher: Sha256,
+    hashed_size: u64,
+}
+
+/// Bounded table of the image verification sessions of a [BootWorker].
+pub struct ImageVerifications<const MAX_SESSIONS: usize> {
+    verifications: Vec<Verification, MAX_SESSIONS>,
+    next_id: SessionId,
+}
+
+impl<const MAX_SESSIONS: usize> Default for ImageVerifications<MAX_SESSIONS> {
+    fn default() -> Self {
+        ImageVerifications {
+            verifications: Vec::new(),
+            next_id: SessionId::default(),
+        }
+    }
+}
+
+impl<const MAX_SESSIONS: usize> ImageVerifications<MAX_SESSIONS> {
+    fn insert(&mut self, client_id: ClientId, manifest: Manifest) -> Result<SessionId, Error> {
+        if self.verifications.is_full() {
+            return Err(Error::TooManySessions);
+        }
+        // Skip handles that are still in use after the counter wrapped around
+        while self.position(self.next_id).is_some() {
+            self.next_id.0 = self.next_id.0.wrapping_add(1);
+        }
+        let id = self.next_id;
+        self.next_id.0 = self.next_id.0.wrapping_add(1);
+        self.verifications
+            .push(Verification {
+                id,
+                client_id,
+                manifest,
+                hasher: Sha256::new(),
+                hashed_size: 0,
+            })
+            .map_err(|_| Error::TooManySessions)?;
+        Ok(id)
+    }
+
+    fn get(&mut self, client_id: ClientId, id: SessionId) -> Result<&mut Verification, Error> {
+        self.verifications
+            .iter_mut()
+            .find(|verification| verification.id == id && verification.client_id == client_id)
+            .ok_or(Error::InvalidSession)
+    }
+
+    fn remove(&mut self, client_id: ClientId, id: SessionId) -> Result<Verification, Error> {
+        let index = self
+            .position(id)
+            .filter(|index| self.verifications[*index].client_id == client_id)
+            .ok_or(Error::InvalidSession)?;
+        Ok(self.verifications.swap_remove(index))
+    }
+
+    fn position(&self, id: SessionId) -> Option<usize> {
+        self.verifications
+            .iter()
+            .position(|verification| verification.id == id)
+    }
+}
+
+/// Worker for the verification of firmware images against signed manifests.
+///
+/// A verification is only started if the manifest is signed by the requested key, the key is one
+/// of the trusted root or intermediate keys, and the manifest's security version is not lower than
+/// the value of the anti-rollback counter. The image is then hashed in chunks of arbitrary size and
+/// is verified if its size and hash match the manifest.
+pub struct BootWorker<
+    'data,
+    'keystore,
+    'counterstore,
+    'trust,
+    M: RawMutex,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+    const MAX_SESSIONS: usize,
+> {
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    pub counter_store: &'counterstore Mutex<M, &'counterstore mut (dyn CounterStore + Send)>,
+    /// Counter holding the minimum security version of accepted images.
+    pub security_version_counter: CounterId,
+    /// Root and intermediate keys that manifests may be signed with. Requests for any other key
+    /// are rejected, so that clients cannot verify images against keys they imported themselves.
+    pub trusted_keys: &'trust [KeyId],
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+    pub verifications: ImageVerifications<MAX_SESSIONS>,
+}
+
+impl<
+        'data,
+        'keystore,
+        'counterstore,
+        'trust,
+        M: RawMutex,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+        const MAX_SESSIONS: usize,
+    > BootWorker<'data, 'keystore, 'counterstore, 'trust, M, ReqSrc, RespSink, MAX_SESSIONS>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::VerifyImageStart {
+                client_id,
+                request_id,
+                key_id,
+                manifest,
+            } => self.start(client_id, request_id, key_id, manifest).await,
+            Request::VerifyImageUpdate {
+                client_id,
+                request_id,
+                session,
+                chunk,
+            } => self.update(client_id, request_id, session, chunk),
+            Request::VerifyImageFinish {
+                client_id,
+                request_id,
+                session,
+            } => self.finish(client_id, request_id, session),
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    async fn start(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        manifest: &[u8],
+    ) -> Response<'data> {
+        let result = match self.verify_manifest(client_id, key_id, manifest).await {
+            Ok(manifest) => self.verifications.insert(client_id, manifest),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(session) => Response::VerifyImageStart {
+                client_id,
+                request_id,
+                session,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    fn update(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        chunk: &[u8],
+    ) -> Response<'data> {
+        match self.verifications.get(client_id, session) {
+            Ok(verification) => {
+                verification.hasher.update(chunk);
+                verification.hashed_size =
+                    verification.hashed_size.saturating_add(chunk.len() as u64);
+                Response::VerifyImageUpdate {
+                    client_id,
+                    request_id,
+                }
+            }
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    fn finish(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+    ) -> Response<'data> {
+        match self.verifications.remove(client_id, session) {
+            Ok(verification) => {
+                let hash = verification.hasher.finalize();
+                let verified = verification.hashed_size
+                    == u64::from(verification.manifest.image_size)
+                    && hash.as_slice() == verification.manifest.image_hash;
+                Response::VerifyImageFinish {
+                    client_id,
+                    request_id,
+                    verified,
+                }
+            }
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    /// Verify the manifest signature and enforce the minimum security version.
+    async fn verify_manifest(
+        &mut self,
+        client_id: ClientId,
+        key_id: KeyId,
+        manifest: &[u8],
+    ) -> Result<Manifest, Error> {
+        if !self.trusted_keys.contains(&key_id) {
+            return Err(Error::KeyStore(keystore::Error::NotAllowed));
+        }
+        let mut key_buffer = [0u8; KeyType::MAX_PUBLIC_KEY_SIZE];
+        let (public_key, key_info) = self
+            .export_public_key_and_key_info(key_id, client_id, key_buffer.as_mut_slice())
+            .await
+            .map_err(Error::KeyStore)?;
+        let algorithm = algorithm_for_key_type(key_info.ty)
+            .ok_or(Error::KeyStore(keystore::Error::InvalidKeyType))?;
+        let manifest =
+            crypto::manifest::verify(algorithm, public_key, manifest).map_err(Error::Crypto)?;
+        let min_security_version = self
+            .counter_store
+            .lock()
+            .await
+            .get_counter_info(self.security_version_counter)
+            .map_err(Error::CounterStore)?
+            .value;
+        if u64::from(manifest.security_version) < min_security_version {
+            return Err(Error::SecurityVersionTooLow);
+        }
+        Ok(manifest)
+    }
+
+    async fn export_public_key_and_key_info<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        key_buffer: &'a mut [u8],
+    ) -> Result<(&'a [u8], KeyInfo), keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(
+            key_id,
+            client_id,
+            KeyUsage::Verify,
+            KeyAlgorithm::BootManifest,
+        )?;
+
+        Ok((
+            locked_key_store.export_public_key(key_id, key_buffer)?,
+            locked_key_store.get_key_info(key_id)?,
+        ))
+    }
+}
@@ -1,8 +1,8 @@
 use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
 use crate::crypto;
 use crate::crypto::chacha20poly1305::KEY_SIZE;
-use crate::hsm::keystore::{KeyId, KeyStore};
-use core::ops::DerefMut;
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyStore, KeyUsage};
 use embassy_sync::blocking_mutex::raw::RawMutex;
 use embassy_sync::mutex::Mutex;
 use futures::{Sink, SinkExt, Stream, StreamExt};
@@ -102,11 +102,13 @@ impl<
     ) -> Response<'data> {
         let mut key_buffer = Zeroizing::new([0u8; KEY_SIZE]);
         let export = self
-            .key_store
-            .lock()
-            .await
-            .deref_mut()
-            .export_symmetric_key_unchecked(key_id, key_buffer.as_mut_slice());
+            .export_key(
+                key_id,
+                client_id,
+                KeyUsage::Encrypt,
+                key_buffer.as_mut_slice(),
+            )
+            .await;
         match export {
             Ok(key) => self.encrypt(client_id, request_id, key, nonce, aad, plaintext, tag),
             Err(e) => Response::Error {
@@ -130,11 +132,13 @@ impl<
     ) -> Response<'data> {
         let mut key_buffer = Zeroizing::new([0u8; KEY_SIZE]);
         let export = self
-            .key_store
-            .lock()
-            .await
-            .deref_mut()
-            .export_symmetric_key_unchecked(key_id, key_buffer.as_mut_slice());
+            .export_key(
+                key_id,
+                client_id,
+                KeyUsage::Decrypt,
+                key_buffer.as_mut_slice(),
+            )
+            .await;
         match export {
             Ok(key) => self.decrypt(client_id, request_id, key, nonce, aad, ciphertext, tag),
             Err(e) => Response::Error {
@@ -223,4 +227,17 @@ impl<
             },
         }
     }
+
+    async fn export_key<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        usage: KeyUsage,
+        key_buffer: &'a mut [u8],
+    ) -> Result<&'a [u8], keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(key_id, client_id, usage, KeyAlgorithm::ChaCha20Poly1305)?;
+        locked_key_store.export_symmetric_key_unchecked(key_id, key_buffer)
+    }
 }
@@ -0,0 +1,135 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
+use crate::hsm::counter_store::{CounterId, CounterStore};
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+
+/// Worker for monotonic counters kept in a [CounterStore].
+pub struct CounterWorker<
+    'data,
+    'counterstore,
+    M: RawMutex,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+> {
+    pub counter_store: &'counterstore Mutex<M, &'counterstore mut (dyn CounterStore + Send)>,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+}
+
+impl<
+        'data,
+        'counterstore,
+        M: RawMutex,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+    > CounterWorker<'data, 'counterstore, M, ReqSrc, RespSink>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::CreateCounter {
+                client_id,
+                request_id,
+                counter_id,
+                max_value,
+            } => {
+                self.create_counter(client_id, request_id, counter_id, max_value)
+                    .await
+            }
+            Request::ReadCounter {
+                client_id,
+                request_id,
+                counter_id,
+            } => self.read_counter(client_id, request_id, counter_id).await,
+            Request::IncrementCounter {
+                client_id,
+                request_id,
+                counter_id,
+                amount,
+            } => {
+                self.increment_counter(client_id, request_id, counter_id, amount)
+                    .await
+            }
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    async fn create_counter(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        counter_id: CounterId,
+        max_value: u64,
+    ) -> Response<'data> {
+        match self
+            .counter_store
+            .lock()
+            .await
+            .create(counter_id, max_value)
+        {
+            Ok(()) => Response::CreateCounter {
+                client_id,
+                request_id,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::CounterStore(e),
+            },
+        }
+    }
+
+    async fn read_counter(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        counter_id: CounterId,
+    ) -> Response<'data> {
+        match self.counter_store.lock().await.get_counter_info(counter_id) {
+            Ok(info) => Response::ReadCounter {
+                client_id,
+                request_id,
+                value: info.value,
+                max_value: info.max_value,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::CounterStore(e),
+            },
+        }
+    }
+
+    async fn increment_counter(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        counter_id: CounterId,
+        amount: u64,
+    ) -> Response<'data> {
+        match self
+            .counter_store
+            .lock()
+            .await
+            .increment(counter_id, amount)
+        {
+            Ok(value) => Response::IncrementCounter {
+                client_id,
+                request_id,
+                value,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::CounterStore(e),
+            },
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
+use crate::crypto;
+use crate::hsm::keystore::{KeyAccess, KeyAlgorithm, KeyId, KeyStore, KeyType, KeyUsage};
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use zeroize::Zeroizing;
+
+/// Worker for DICE layered identity derivation.
+///
+/// The Compound Device Identifier of the next layer is derived from the Unique Device Secret (UDS)
+/// and the measurement of the next layer. The alias key pair derived from it is stored in the
+/// configured key slot and certified with the DeviceID key, which is derived from the UDS alone.
+///
+/// The measurement is only as trustworthy as the client reporting it. Alias keys are therefore
+/// derived for the configured boot client only, which is supposed to be the layer measuring and
+/// starting the next layer, e.g. the boot loader.
+pub struct DiceWorker<
+    'data,
+    'keystore,
+    M: RawMutex,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+> {
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    /// Symmetric key holding the Unique Device Secret.
+    pub uds_key_id: KeyId,
+    /// NIST P-256 key slot the alias key pair of the next layer is stored in. The slot needs the
+    /// `import` and `overwrite` permissions for the alias key to be derived more than once.
+    pub alias_key_id: KeyId,
+    /// Client allowed to report measurements of the next layer. If `None`, `DeriveDiceAlias`
+    /// requests are rejected for all clients.
+    pub boot_client: Option<ClientId>,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+}
+
+impl<
+        'data,
+        'keystore,
+        M: RawMutex,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+    > DiceWorker<'data, 'keystore, M, ReqSrc, RespSink>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::DeriveDiceAlias {
+                client_id,
+                request_id,
+                measurement,
+                certificate,
+            } => {
+                self.derive_alias(client_id, request_id, measurement, certificate)
+                    .await
+            }
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    async fn derive_alias(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        measurement: &[u8],
+        certificate: &'data mut [u8],
+    ) -> Response<'data> {
+        if self.boot_client != Some(client_id) {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::AccessDenied,
+            };
+        }
+        match self
+            .derive_and_certify(client_id, measurement, certificate)
+            .await
+        {
+            Ok(certificate) => Response::DeriveDiceAlias {
+                client_id,
+                request_id,
+                certificate,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    async fn derive_and_certify(
+        &mut self,
+        client_id: ClientId,
+        measurement: &[u8],
+        certificate: &'data mut [u8],
+    ) -> Result<&'data mut [u8], Error> {
+        // Lock keystore only once
+        let mut locked_key_store = self.key_store.lock().await;
+
+        let mut uds_buffer = Zeroizing::new([0u8; KeyType::MAX_SYMMETRIC_KEY_SIZE]);
+        locked_key_store
+            .check_usage(
+                self.uds_key_id,
+                client_id,
+                KeyUsage::Derive,
+                KeyAlgorithm::Hkdf,
+            )
+            .map_err(Error::KeyStore)?;
+        locked_key_store
+            .check_access(self.alias_key_id, client_id, KeyAccess::Import)
+            .map_err(Error::KeyStore)?;
+        let uds = locked_key_store
+            .export_symmetric_key_unchecked(self.uds_key_id, uds_buffer.as_mut_slice())
+            .map_err(Error::KeyStore)?;
+        let device_id = crypto::dice::derive_device_id(uds).map_err(Error::Crypto)?;
+        let cdi = crypto::dice::derive_cdi(uds, measurement).map_err(Error::Crypto)?;
+        let alias = crypto::dice::derive_alias(&cdi).map_err(Error::Crypto)?;
+        locked_key_store
+            .import_key_pair(
+                self.alias_key_id,
+                &alias.public_key,
+                alias.private_key.as_slice(),
+                true,
+            )
+            .map_err(Error::KeyStore)?;
+        crypto::dice::issue_alias_certificate(
+            device_id.private_key.as_slice(),
+            measurement,
+            &alias.public_key,
+            certificate,
+        )
+        .map_err(Error::Crypto)
+    }
+}
@@ -1,14 +1,17 @@
 use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
 use crate::crypto;
+use crate::crypto::drbg::Drbg;
 use crate::crypto::ecdsa::{
     nist_p256_generate_key_pair, nist_p256_sign, nist_p256_sign_prehashed, nist_p256_verify,
     nist_p256_verify_prehashed, nist_p384_generate_key_pair, nist_p384_sign,
     nist_p384_sign_prehashed, nist_p384_verify, nist_p384_verify_prehashed,
 };
-use crate::crypto::rng::{EntropySource, Rng};
+use crate::crypto::ed25519::ed25519_generate_key_pair;
+use crate::crypto::rng::{Rng, TryEntropySource};
+use crate::crypto::x25519::x25519_generate_key_pair;
 use crate::hsm::keystore;
-use crate::hsm::keystore::{KeyId, KeyInfo, KeyStore, KeyType};
-use core::ops::{Deref, DerefMut};
+use crate::hsm::keystore::{KeyAccess, KeyAlgorithm, KeyId, KeyInfo, KeyStore, KeyType, KeyUsage};
+use core::ops::DerefMut;
 use embassy_sync::blocking_mutex::raw::RawMutex;
 use embassy_sync::mutex::Mutex;
 use futures::{Sink, SinkExt, Stream, StreamExt};
@@ -19,11 +22,12 @@ pub struct EccWorker<
     'rng,
     'keystore,
     M: RawMutex,
-    E: EntropySource,
+    E: TryEntropySource,
+    D: Drbg,
     ReqSrc: Stream<Item = Request<'data>>,
     RespSink: Sink<Response<'data>>,
 > {
-    pub rng: &'rng Mutex<M, Rng<E>>,
+    pub rng: &'rng Mutex<M, Rng<E, D>>,
     pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
     pub requests: ReqSrc,
     pub responses: RespSink,
@@ -34,10 +38,11 @@ impl<
         'rng,
         'keystore,
         M: RawMutex,
-        E: EntropySource,
+        E: TryEntropySource,
+        D: Drbg,
         ReqSrc: Stream<Item = Request<'data>> + Unpin,
         RespSink: Sink<Response<'data>> + Unpin,
-    > EccWorker<'data, 'rng, 'keystore, M, E, ReqSrc, RespSink>
+    > EccWorker<'data, 'rng, 'keystore, M, E, D, ReqSrc, RespSink>
 {
     /// Drive the worker to process the next request.
     /// This method is supposed to be called by a system task that owns this worker.
@@ -122,9 +127,12 @@ impl<
         overwrite: bool,
     ) -> Response<'data> {
         let mut locked_key_store = self.key_store.lock().await;
-        let key_info = locked_key_store.deref().get_key_info(key_id);
+        let key_info = locked_key_store
+            .check_access(key_id, client_id, KeyAccess::Import)
+            .and_then(|()| locked_key_store.get_key_info(key_id));
         let mut private_key_bytes = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
         let mut public_key_bytes = Zeroizing::new([0u8; KeyType::MAX_PUBLIC_KEY_SIZE]);
+        let mut rng = self.rng.lock().await;
 
         let ((private_key, public_key), key_info) = match key_info {
             Err(e) => {
@@ -136,8 +144,7 @@ impl<
             }
             Ok(key_info) => match key_info.ty {
                 KeyType::EccKeypairNistP256 => {
-                    let (private_key, public_key) =
-                        nist_p256_generate_key_pair(self.rng.lock().await.deref_mut());
+                    let (private_key, public_key) = nist_p256_generate_key_pair(rng.deref_mut());
                     (
                         move_key_pair(
                             private_key,
@@ -149,8 +156,31 @@ impl<
                     )
                 }
                 KeyType::EccKeypairNistP384 => {
-                    let (private_key, public_key) =
-                        nist_p384_generate_key_pair(self.rng.lock().await.deref_mut());
+                    let (private_key, public_key) = nist_p384_generate_key_pair(rng.deref_mut());
+                    (
+                        move_key_pair(
+                            private_key,
+                            public_key,
+                            private_key_bytes.as_mut_slice(),
+                            public_key_bytes.as_mut_slice(),
+                        ),
+                        key_info,
+                    )
+                }
+                KeyType::EccKeypairEd25519 => {
+                    let (private_key, public_key) = ed25519_generate_key_pair(rng.deref_mut());
+                    (
+                        move_key_pair(
+                            private_key,
+                            public_key,
+                            private_key_bytes.as_mut_slice(),
+                            public_key_bytes.as_mut_slice(),
+                        ),
+                        key_info,
+                    )
+                }
+                KeyType::EccKeypairX25519 => {
+                    let (private_key, public_key) = x25519_generate_key_pair(rng.deref_mut());
                     (
                         move_key_pair(
                             private_key,
@@ -170,6 +200,14 @@ impl<
                 }
             },
         };
+        if let Some(e) = rng.error() {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: e.into(),
+            };
+        }
+        drop(rng);
 
         match locked_key_store.import_key_pair(key_info.id, public_key, private_key, overwrite) {
             Ok(()) => Response::GenerateKeyPair {
@@ -195,7 +233,7 @@ impl<
     ) -> Response<'data> {
         let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
         let private_key_and_info = self
-            .export_private_key_and_key_info(key_id, key_buffer.as_mut_slice())
+            .export_private_key_and_key_info(key_id, client_id, key_buffer.as_mut_slice())
             .await;
 
         let result = match private_key_and_info {
@@ -303,7 +341,7 @@ impl<
     ) -> Response<'data> {
         let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PUBLIC_KEY_SIZE]);
         let public_key_and_info = self
-            .export_public_key_and_key_info(key_id, key_buffer.as_mut_slice())
+            .export_public_key_and_key_info(key_id, client_id, key_buffer.as_mut_slice())
             .await;
 
         let result = match public_key_and_info {
@@ -413,10 +451,12 @@ impl<
     async fn export_private_key_and_key_info<'a>(
         &mut self,
         key_id: KeyId,
+        client_id: ClientId,
         key_buffer: &'a mut [u8],
     ) -> Result<(&'a [u8], KeyInfo), keystore::Error> {
         // Lock keystore only once
         let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(key_id, client_id, KeyUsage::Sign, KeyAlgorithm::Signature)?;
 
         Ok((
             locked_key_store.export_private_key_unchecked(key_id, key_buffer)?,
@@ -427,10 +467,17 @@ impl<
     async fn export_public_key_and_key_info<'a>(
         &mut self,
         key_id: KeyId,
+        client_id: ClientId,
         key_buffer: &'a mut [u8],
     ) -> Result<(&'a [u8], KeyInfo), keystore::Error> {
         // Lock keystore only once
         let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(
+            key_id,
+            client_id,
+            KeyUsage::Verify,
+            KeyAlgorithm::Signature,
+        )?;
 
         Ok((
             locked_key_store.export_public_key(key_id, key_buffer)?,
@@ -0,0 +1,256 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
+use crate::crypto;
+use crate::crypto::drbg::Drbg;
+use crate::crypto::ecies::{Cipher, Curve, Kdf};
+use crate::crypto::rng::{Rng, TryEntropySource};
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyInfo, KeyStore, KeyType, KeyUsage};
+use core::ops::DerefMut;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use zeroize::Zeroizing;
+
+/// Worker for elliptic curve integrated encryption scheme (ECIES) operations.
+pub struct EciesWorker<
+    'data,
+    'rng,
+    'keystore,
+    M: RawMutex,
+    E: TryEntropySource,
+    D: Drbg,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+> {
+    pub rng: &'rng Mutex<M, Rng<E, D>>,
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+}
+
+impl<
+        'data,
+        'rng,
+        'keystore,
+        M: RawMutex,
+        E: TryEntropySource,
+        D: Drbg,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+    > EciesWorker<'data, 'rng, 'keystore, M, E, D, ReqSrc, RespSink>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::EciesEncrypt {
+                client_id,
+                request_id,
+                kdf,
+                cipher,
+                recipient_public_key,
+                shared_info,
+                aad,
+                buffer,
+                ephemeral_public_key,
+                tag,
+            } => {
+                self.encrypt(
+                    client_id,
+                    request_id,
+                    kdf,
+                    cipher,
+                    recipient_public_key,
+                    shared_info,
+                    aad,
+                    buffer,
+                    ephemeral_public_key,
+                    tag,
+                )
+                .await
+            }
+            Request::EciesDecrypt {
+                client_id,
+                request_id,
+                key_id,
+                kdf,
+                cipher,
+                ephemeral_public_key,
+                shared_info,
+                aad,
+                buffer,
+                tag,
+            } => {
+                self.decrypt(
+                    client_id,
+                    request_id,
+                    key_id,
+                    kdf,
+                    cipher,
+                    ephemeral_public_key,
+                    shared_info,
+                    aad,
+                    buffer,
+                    tag,
+                )
+                .await
+            }
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn encrypt(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        kdf: Kdf,
+        cipher: Cipher,
+        recipient_public_key: &[u8],
+        shared_info: &[u8],
+        aad: &[u8],
+        buffer: &'data mut [u8],
+        ephemeral_public_key: &'data mut [u8],
+        tag: &'data mut [u8],
+    ) -> Response<'data> {
+        let Some(curve) = Curve::from_public_key_size(recipient_public_key.len()) else {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(crypto::Error::InvalidPublicKey),
+            };
+        };
+        if ephemeral_public_key.len() < curve.ephemeral_public_key_size() {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(crypto::Error::InvalidBufferSize),
+            };
+        }
+        let ephemeral_public_key = &mut ephemeral_public_key[..curve.ephemeral_public_key_size()];
+        let ephemeral_private_key = {
+            let mut rng = self.rng.lock().await;
+            let ephemeral_private_key =
+                crypto::ecies::generate_ephemeral_private_key(curve, rng.deref_mut());
+            if let Some(e) = rng.error() {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: e.into(),
+                };
+            }
+            ephemeral_private_key
+        };
+
+        match crypto::ecies::encrypt(
+            curve,
+            kdf,
+            cipher,
+            &ephemeral_private_key[..curve.private_key_size()],
+            recipient_public_key,
+            shared_info,
+            aad,
+            buffer,
+            ephemeral_public_key,
+            tag,
+        ) {
+            Ok(()) => Response::EciesEncrypt {
+                client_id,
+                request_id,
+                ephemeral_public_key,
+                buffer,
+                tag,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn decrypt(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        kdf: Kdf,
+        cipher: Cipher,
+        ephemeral_public_key: &[u8],
+        shared_info: &[u8],
+        aad: &[u8],
+        buffer: &'data mut [u8],
+        tag: &[u8],
+    ) -> Response<'data> {
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
+        let (private_key, key_info) = match self
+            .export_private_key_and_key_info(key_id, client_id, key_buffer.as_mut_slice())
+            .await
+        {
+            Ok(private_key_and_info) => private_key_and_info,
+            Err(e) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(e),
+                }
+            }
+        };
+        let curve = match key_info.ty {
+            KeyType::EccKeypairNistP256 => Curve::NistP256,
+            KeyType::EccKeypairNistP384 => Curve::NistP384,
+            _ => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(keystore::Error::InvalidKeyType),
+                }
+            }
+        };
+
+        match crypto::ecies::decrypt(
+            curve,
+            kdf,
+            cipher,
+            private_key,
+            ephemeral_public_key,
+            shared_info,
+            aad,
+            buffer,
+            tag,
+        ) {
+            Ok(()) => Response::EciesDecrypt {
+                client_id,
+                request_id,
+                plaintext: buffer,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    async fn export_private_key_and_key_info<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        key_buffer: &'a mut [u8],
+    ) -> Result<(&'a [u8], KeyInfo), keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(key_id, client_id, KeyUsage::Decrypt, KeyAlgorithm::Ecies)?;
+
+        Ok((
+            locked_key_store.export_private_key_unchecked(key_id, key_buffer)?,
+            locked_key_store.get_key_info(key_id)?,
+        ))
+    }
+}
@@ -0,0 +1,304 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
+use crate::crypto;
+use crate::crypto::drbg::Drbg;
+use crate::crypto::hpke::{Aead, Kem};
+use crate::crypto::rng::{Rng, TryEntropySource};
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyStore, KeyType, KeyUsage};
+use core::ops::DerefMut;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use zeroize::Zeroizing;
+
+/// Worker for hybrid public key encryption (HPKE, RFC 9180) in base and auth mode.
+pub struct HpkeWorker<
+    'data,
+    'rng,
+    'keystore,
+    M: RawMutex,
+    E: TryEntropySource,
+    D: Drbg,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+> {
+    pub rng: &'rng Mutex<M, Rng<E, D>>,
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+}
+
+impl<
+        'data,
+        'rng,
+        'keystore,
+        M: RawMutex,
+        E: TryEntropySource,
+        D: Drbg,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+    > HpkeWorker<'data, 'rng, 'keystore, M, E, D, ReqSrc, RespSink>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::HpkeSeal {
+                client_id,
+                request_id,
+                sender_key_id,
+                aead,
+                recipient_public_key,
+                info,
+                aad,
+                buffer,
+                encapsulated_key,
+                tag,
+            } => {
+                self.seal(
+                    client_id,
+                    request_id,
+                    sender_key_id,
+                    aead,
+                    recipient_public_key,
+                    info,
+                    aad,
+                    buffer,
+                    encapsulated_key,
+                    tag,
+                )
+                .await
+            }
+            Request::HpkeOpen {
+                client_id,
+                request_id,
+                key_id,
+                sender_public_key,
+                aead,
+                encapsulated_key,
+                info,
+                aad,
+                buffer,
+                tag,
+            } => {
+                self.open(
+                    client_id,
+                    request_id,
+                    key_id,
+                    sender_public_key,
+                    aead,
+                    encapsulated_key,
+                    info,
+                    aad,
+                    buffer,
+                    tag,
+                )
+                .await
+            }
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn seal(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        sender_key_id: Option<KeyId>,
+        aead: Aead,
+        recipient_public_key: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        buffer: &'data mut [u8],
+        encapsulated_key: &'data mut [u8],
+        tag: &'data mut [u8],
+    ) -> Response<'data> {
+        let Some(kem) = Kem::from_public_key_size(recipient_public_key.len()) else {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(crypto::Error::InvalidPublicKey),
+            };
+        };
+        if encapsulated_key.len() < kem.encapsulated_key_size() {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(crypto::Error::InvalidBufferSize),
+            };
+        }
+        let encapsulated_key = &mut encapsulated_key[..kem.encapsulated_key_size()];
+
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
+        let sender_private_key = match sender_key_id {
+            None => None,
+            Some(key_id) => match self
+                .export_private_key(
+                    key_id,
+                    client_id,
+                    kem,
+                    KeyUsage::Derive,
+                    key_buffer.as_mut_slice(),
+                )
+                .await
+            {
+                Ok(private_key) => Some(private_key),
+                Err(e) => {
+                    return Response::Error {
+                        client_id,
+                        request_id,
+                        error: Error::KeyStore(e),
+                    }
+                }
+            },
+        };
+        let ephemeral_private_key = {
+            let mut rng = self.rng.lock().await;
+            let ephemeral_private_key = crypto::hpke::generate_private_key(kem, rng.deref_mut());
+            if let Some(e) = rng.error() {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: e.into(),
+                };
+            }
+            ephemeral_private_key
+        };
+
+        match crypto::hpke::seal(
+            kem,
+            aead,
+            ephemeral_private_key.as_slice(),
+            recipient_public_key,
+            sender_private_key,
+            info,
+            aad,
+            buffer,
+            encapsulated_key,
+            tag,
+        ) {
+            Ok(()) => Response::HpkeSeal {
+                client_id,
+                request_id,
+                encapsulated_key,
+                buffer,
+                tag,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn open(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        sender_public_key: Option<&[u8]>,
+        aead: Aead,
+        encapsulated_key: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        buffer: &'data mut [u8],
+        tag: &[u8],
+    ) -> Response<'data> {
+        let kem = match self.key_store.lock().await.get_key_info(key_id) {
+            Ok(key_info) => match kem_for_key_type(key_info.ty) {
+                Some(kem) => kem,
+                None => {
+                    return Response::Error {
+                        client_id,
+                        request_id,
+                        error: Error::KeyStore(keystore::Error::InvalidKeyType),
+                    }
+                }
+            },
+            Err(e) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(e),
+                }
+            }
+        };
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
+        let recipient_private_key = match self
+            .export_private_key(
+                key_id,
+                client_id,
+                kem,
+                KeyUsage::Decrypt,
+                key_buffer.as_mut_slice(),
+            )
+            .await
+        {
+            Ok(private_key) => private_key,
+            Err(e) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(e),
+                }
+            }
+        };
+
+        match crypto::hpke::open(
+            kem,
+            aead,
+            recipient_private_key,
+            sender_public_key,
+            encapsulated_key,
+            info,
+            aad,
+            buffer,
+            tag,
+        ) {
+            Ok(()) => Response::HpkeOpen {
+                client_id,
+                request_id,
+                plaintext: buffer,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    /// Export a private key and check that it can be used with the given KEM and for `usage`.
+    async fn export_private_key<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        kem: Kem,
+        usage: KeyUsage,
+        key_buffer: &'a mut [u8],
+    ) -> Result<&'a [u8], keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+
+        if kem_for_key_type(locked_key_store.get_key_info(key_id)?.ty) != Some(kem) {
+            return Err(keystore::Error::InvalidKeyType);
+        }
+        locked_key_store.check_usage(key_id, client_id, usage, KeyAlgorithm::Hpke)?;
+        locked_key_store.export_private_key_unchecked(key_id, key_buffer)
+    }
+}
+
+fn kem_for_key_type(key_type: KeyType) -> Option<Kem> {
+    match key_type {
+        KeyType::EccKeypairX25519 => Some(Kem::X25519HkdfSha256),
+        KeyType::EccKeypairNistP256 => Some(Kem::P256HkdfSha256),
+        _ => None,
+    }
+}
@@ -0,0 +1,240 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
+use crate::crypto;
+use crate::crypto::jws::Algorithm;
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyInfo, KeyStore, KeyType, KeyUsage};
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use zeroize::Zeroizing;
+
+/// Worker creating and verifying JSON Web Signatures (JWS) in compact serialization.
+pub struct JwsWorker<
+    'data,
+    'keystore,
+    M: RawMutex,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+> {
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+}
+
+impl<
+        'data,
+        'keystore,
+        M: RawMutex,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+    > JwsWorker<'data, 'keystore, M, ReqSrc, RespSink>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::SignJws {
+                client_id,
+                request_id,
+                key_id,
+                header,
+                payload,
+                token,
+            } => {
+                self.sign(client_id, request_id, key_id, header, payload, token)
+                    .await
+            }
+            Request::VerifyJws {
+                client_id,
+                request_id,
+                key_id,
+                token,
+            } => self.verify(client_id, request_id, key_id, token).await,
+            Request::VerifyJwsExternalKey {
+                client_id,
+                request_id,
+                public_key,
+                token,
+            } => self.verify_external_key(client_id, request_id, public_key, token),
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    async fn sign(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        header: &[u8],
+        payload: &[u8],
+        token: &'data mut [u8],
+    ) -> Response<'data> {
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
+        let (private_key, key_info) = match self
+            .export_private_key_and_key_info(key_id, client_id, key_buffer.as_mut_slice())
+            .await
+        {
+            Ok(private_key_and_info) => private_key_and_info,
+            Err(e) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(e),
+                }
+            }
+        };
+        let Some(algorithm) = algorithm_for_key_type(key_info.ty) else {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::KeyStore(keystore::Error::InvalidKeyType),
+            };
+        };
+
+        match crypto::jws::sign(algorithm, private_key, header, payload, token) {
+            Ok(token) => Response::SignJws {
+                client_id,
+                request_id,
+                token,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    async fn verify(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        token: &[u8],
+    ) -> Response<'data> {
+        let mut key_buffer = [0u8; KeyType::MAX_PUBLIC_KEY_SIZE];
+        let (public_key, key_info) = match self
+            .export_public_key_and_key_info(key_id, client_id, key_buffer.as_mut_slice())
+            .await
+        {
+            Ok(public_key_and_info) => public_key_and_info,
+            Err(e) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(e),
+                }
+            }
+        };
+        let Some(algorithm) = algorithm_for_key_type(key_info.ty) else {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::KeyStore(keystore::Error::InvalidKeyType),
+            };
+        };
+
+        verify_response(
+            client_id,
+            request_id,
+            crypto::jws::verify(algorithm, public_key, token),
+        )
+    }
+
+    fn verify_external_key(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        public_key: &[u8],
+        token: &[u8],
+    ) -> Response<'data> {
+        let algorithm = match public_key.len() {
+            crypto::ecdsa::NIST_P256_PUBLIC_KEY_SIZE => Algorithm::Es256,
+            crypto::ecdsa::NIST_P384_PUBLIC_KEY_SIZE => Algorithm::Es384,
+            crypto::ed25519::PUBLIC_KEY_SIZE => Algorithm::EdDsa,
+            _ => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::Crypto(crypto::Error::InvalidPublicKey),
+                };
+            }
+        };
+
+        verify_response(
+            client_id,
+            request_id,
+            crypto::jws::verify(algorithm, public_key, token),
+        )
+    }
+
+    async fn export_private_key_and_key_info<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        key_buffer: &'a mut [u8],
+    ) -> Result<(&'a [u8], KeyInfo), keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(key_id, client_id, KeyUsage::Sign, KeyAlgorithm::Jws)?;
+
+        Ok((
+            locked_key_store.export_private_key_unchecked(key_id, key_buffer)?,
+            locked_key_store.get_key_info(key_id)?,
+        ))
+    }
+
+    async fn export_public_key_and_key_info<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        key_buffer: &'a mut [u8],
+    ) -> Result<(&'a [u8], KeyInfo), keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(key_id, client_id, KeyUsage::Verify, KeyAlgorithm::Jws)?;
+
+        Ok((
+            locked_key_store.export_public_key(key_id, key_buffer)?,
+            locked_key_store.get_key_info(key_id)?,
+        ))
+    }
+}
+
+pub(crate) fn algorithm_for_key_type(key_type: KeyType) -> Option<Algorithm> {
+    match key_type {
+        KeyType::EccKeypairNistP256 => Some(Algorithm::Es256),
+        KeyType::EccKeypairNistP384 => Some(Algorithm::Es384),
+        KeyType::EccKeypairEd25519 => Some(Algorithm::EdDsa),
+        _ => None,
+    }
+}
+
+fn verify_response<'data>(
+    client_id: ClientId,
+    request_id: RequestId,
+    result: Result<(), crypto::Error>,
+) -> Response<'data> {
+    match result {
+        Err(crypto::Error::InvalidSignature) => Response::VerifyJws {
+            client_id,
+            request_id,
+            verified: false,
+        },
+        Err(e) => Response::Error {
+            client_id,
+            request_id,
+            error: Error::Crypto(e),
+        },
+        Ok(_) => Response::VerifyJws {
+            client_id,
+            request_id,
+            verified: true,
+        },
+    }
+}
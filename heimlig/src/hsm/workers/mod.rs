@@ -1,4 +1,15 @@
 pub mod aes_worker;
+pub mod attestation_worker;
+pub mod boot_worker;
 pub mod chachapoly_worker;
+pub mod counter_worker;
+pub mod dice_worker;
 pub mod ecc_worker;
+pub mod ecies_worker;
+pub mod hpke_worker;
+pub mod jws_worker;
+pub mod noise_worker;
 pub mod rng_worker;
+pub mod secoc_worker;
+pub mod spdm_worker;
+pub mod tls_worker;
@@ -0,0 +1,456 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response, SessionId};
+use crate::crypto;
+use crate::crypto::drbg::Drbg;
+use crate::crypto::noise::{HandshakeState, TransportState, DH_SIZE, HASH_SIZE};
+use crate::crypto::rng::{Rng, TryEntropySource};
+use crate::crypto::x25519::x25519_generate_key_pair;
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyStore, KeyType, KeyUsage};
+use core::ops::DerefMut;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use heapless::Vec;
+use zeroize::Zeroizing;
+
+enum State {
+    Handshake(HandshakeState),
+    Transport(TransportState),
+}
+
+struct Session {
+    id: SessionId,
+    client_id: ClientId,
+    state: State,
+}
+
+/// Bounded table of the Noise sessions of a [NoiseWorker].
+pub struct NoiseSessions<const MAX_SESSIONS: usize> {
+    sessions: Vec<Session, MAX_SESSIONS>,
+    next_id: SessionId,
+}
+
+impl<const MAX_SESSIONS: usize> Default for NoiseSessions<MAX_SESSIONS> {
+    fn default() -> Self {
+        NoiseSessions {
+            sessions: Vec::new(),
+            next_id: SessionId::default(),
+        }
+    }
+}
+
+impl<const MAX_SESSIONS: usize> NoiseSessions<MAX_SESSIONS> {
+    fn insert(&mut self, client_id: ClientId, state: State) -> Result<SessionId, Error> {
+        if self.sessions.is_full() {
+            return Err(Error::TooManySessions);
+        }
+        // Skip handles that are still in use after the counter wrapped around
+        while self.position(self.next_id).is_some() {
+            self.next_id.0 = self.next_id.0.wrapping_add(1);
+        }
+        let id = self.next_id;
+        self.next_id.0 = self.next_id.0.wrapping_add(1);
+        self.sessions
+            .push(Session {
+                id,
+                client_id,
+                state,
+            })
+            .map_err(|_| Error::TooManySessions)?;
+        Ok(id)
+    }
+
+    fn get(&mut self, client_id: ClientId, id: SessionId) -> Result<&mut Session, Error> {
+        self.sessions
+            .iter_mut()
+            .find(|session| session.id == id && session.client_id == client_id)
+            .ok_or(Error::InvalidSession)
+    }
+
+    fn remove(&mut self, client_id: ClientId, id: SessionId) -> Result<Session, Error> {
+        let index = self
+            .position(id)
+            .filter(|index| self.sessions[*index].client_id == client_id)
+            .ok_or(Error::InvalidSession)?;
+        Ok(self.sessions.swap_remove(index))
+    }
+
+    fn position(&self, id: SessionId) -> Option<usize> {
+        self.sessions.iter().position(|session| session.id == id)
+    }
+}
+
+/// Worker for Noise_XX_25519_ChaChaPoly_SHA256 sessions.
+///
+/// The static keys stay in the key store. Handshake and transport states are kept in a table of
+/// at most `MAX_SESSIONS` sessions that can only be used by the client that started them. A
+/// session is discarded if reading or writing a handshake message fails.
+pub struct NoiseWorker<
+    'data,
+    'rng,
+    'keystore,
+    M: RawMutex,
+    E: TryEntropySource,
+    D: Drbg,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+    const MAX_SESSIONS: usize,
+> {
+    pub rng: &'rng Mutex<M, Rng<E, D>>,
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+    pub sessions: NoiseSessions<MAX_SESSIONS>,
+}
+
+impl<
+        'data,
+        'rng,
+        'keystore,
+        M: RawMutex,
+        E: TryEntropySource,
+        D: Drbg,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+        const MAX_SESSIONS: usize,
+    > NoiseWorker<'data, 'rng, 'keystore, M, E, D, ReqSrc, RespSink, MAX_SESSIONS>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::NoiseStartHandshake {
+                client_id,
+                request_id,
+                key_id,
+                initiator,
+                prologue,
+            } => {
+                self.start_handshake(client_id, request_id, key_id, initiator, prologue)
+                    .await
+            }
+            Request::NoiseWriteMessage {
+                client_id,
+                request_id,
+                session,
+                payload,
+                message,
+            } => self.write_message(client_id, request_id, session, payload, message),
+            Request::NoiseReadMessage {
+                client_id,
+                request_id,
+                session,
+                message,
+                payload,
+            } => self.read_message(client_id, request_id, session, message, payload),
+            Request::NoiseSessionInfo {
+                client_id,
+                request_id,
+                session,
+                remote_static_public_key,
+                handshake_hash,
+            } => self.session_info(
+                client_id,
+                request_id,
+                session,
+                remote_static_public_key,
+                handshake_hash,
+            ),
+            Request::NoiseEncrypt {
+                client_id,
+                request_id,
+                session,
+                buffer,
+                tag,
+            } => self.encrypt(client_id, request_id, session, buffer, tag),
+            Request::NoiseDecrypt {
+                client_id,
+                request_id,
+                session,
+                buffer,
+                tag,
+            } => self.decrypt(client_id, request_id, session, buffer, tag),
+            Request::NoiseCloseSession {
+                client_id,
+                request_id,
+                session,
+            } => match self.sessions.remove(client_id, session) {
+                Ok(_) => Response::NoiseCloseSession {
+                    client_id,
+                    request_id,
+                },
+                Err(error) => Response::Error {
+                    client_id,
+                    request_id,
+                    error,
+                },
+            },
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    async fn start_handshake(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        initiator: bool,
+        prologue: &[u8],
+    ) -> Response<'data> {
+        let mut static_private_key = Zeroizing::new([0u8; DH_SIZE]);
+        if let Err(e) = self
+            .export_static_private_key(key_id, client_id, static_private_key.as_mut_slice())
+            .await
+        {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::KeyStore(e),
+            };
+        }
+        let ephemeral_private_key = {
+            let mut rng = self.rng.lock().await;
+            let ephemeral_private_key = Zeroizing::new(x25519_generate_key_pair(rng.deref_mut()).0);
+            if let Some(e) = rng.error() {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: e.into(),
+                };
+            }
+            ephemeral_private_key
+        };
+        let handshake = match HandshakeState::new(
+            initiator,
+            prologue,
+            static_private_key.as_slice(),
+            ephemeral_private_key.as_slice(),
+        ) {
+            Ok(handshake) => handshake,
+            Err(e) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::Crypto(e),
+                }
+            }
+        };
+
+        match self.sessions.insert(client_id, State::Handshake(handshake)) {
+            Ok(session) => Response::NoiseStartHandshake {
+                client_id,
+                request_id,
+                session,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    fn write_message(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        payload: &[u8],
+        message: &'data mut [u8],
+    ) -> Response<'data> {
+        match self.process_handshake_message(client_id, session, |handshake| {
+            handshake.write_message(payload, message)
+        }) {
+            Ok((size, handshake_finished)) => Response::NoiseWriteMessage {
+                client_id,
+                request_id,
+                message: &mut message[..size],
+                handshake_finished,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    fn read_message(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        message: &[u8],
+        payload: &'data mut [u8],
+    ) -> Response<'data> {
+        match self.process_handshake_message(client_id, session, |handshake| {
+            handshake.read_message(message, payload)
+        }) {
+            Ok((size, handshake_finished)) => Response::NoiseReadMessage {
+                client_id,
+                request_id,
+                payload: &mut payload[..size],
+                handshake_finished,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    fn session_info(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        remote_static_public_key: &'data mut [u8],
+        handshake_hash: &'data mut [u8],
+    ) -> Response<'data> {
+        let transport = match self.transport(client_id, session) {
+            Ok(transport) => transport,
+            Err(error) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error,
+                }
+            }
+        };
+        if remote_static_public_key.len() != DH_SIZE || handshake_hash.len() != HASH_SIZE {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(crypto::Error::InvalidBufferSize),
+            };
+        }
+        remote_static_public_key.copy_from_slice(transport.remote_static_public_key());
+        handshake_hash.copy_from_slice(transport.handshake_hash());
+        Response::NoiseSessionInfo {
+            client_id,
+            request_id,
+            remote_static_public_key,
+            handshake_hash,
+        }
+    }
+
+    fn encrypt(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        buffer: &'data mut [u8],
+        tag: &'data mut [u8],
+    ) -> Response<'data> {
+        let result = self
+            .transport(client_id, session)
+            .and_then(|transport| transport.encrypt(buffer, tag).map_err(Error::Crypto));
+        match result {
+            Ok(()) => Response::NoiseEncrypt {
+                client_id,
+                request_id,
+                buffer,
+                tag,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    fn decrypt(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        session: SessionId,
+        buffer: &'data mut [u8],
+        tag: &[u8],
+    ) -> Response<'data> {
+        let result = self
+            .transport(client_id, session)
+            .and_then(|transport| transport.decrypt(buffer, tag).map_err(Error::Crypto));
+        match result {
+            Ok(()) => Response::NoiseDecrypt {
+                client_id,
+                request_id,
+                plaintext: buffer,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    /// Apply a handshake operation to a session. The session is discarded if the operation fails
+    /// and switched to transport mode once the handshake is finished.
+    ///
+    /// returns: The result of the operation and whether the handshake is finished.
+    fn process_handshake_message(
+        &mut self,
+        client_id: ClientId,
+        session: SessionId,
+        operation: impl FnOnce(&mut HandshakeState) -> Result<usize, crypto::Error>,
+    ) -> Result<(usize, bool), Error> {
+        let entry = self.sessions.get(client_id, session)?;
+        let State::Handshake(handshake) = &mut entry.state else {
+            return Err(Error::Crypto(crypto::Error::InvalidState));
+        };
+        let result = operation(handshake).and_then(|size| {
+            let transport = if handshake.is_finished() {
+                Some(handshake.split()?)
+            } else {
+                None
+            };
+            Ok((size, transport))
+        });
+        match result {
+            Ok((size, None)) => Ok((size, false)),
+            Ok((size, Some(transport))) => {
+                entry.state = State::Transport(transport);
+                Ok((size, true))
+            }
+            Err(e) => {
+                self.sessions.remove(client_id, session)?;
+                Err(Error::Crypto(e))
+            }
+        }
+    }
+
+    fn transport(
+        &mut self,
+        client_id: ClientId,
+        session: SessionId,
+    ) -> Result<&mut TransportState, Error> {
+        match &mut self.sessions.get(client_id, session)?.state {
+            State::Transport(transport) => Ok(transport),
+            State::Handshake(_) => Err(Error::Crypto(crypto::Error::InvalidState)),
+        }
+    }
+
+    async fn export_static_private_key(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        key_buffer: &mut [u8],
+    ) -> Result<(), keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+
+        if locked_key_store.get_key_info(key_id)?.ty != KeyType::EccKeypairX25519 {
+            return Err(keystore::Error::InvalidKeyType);
+        }
+        locked_key_store.check_usage(key_id, client_id, KeyUsage::Derive, KeyAlgorithm::Ecdh)?;
+        locked_key_store.export_private_key_unchecked(key_id, key_buffer)?;
+        Ok(())
+    }
+}
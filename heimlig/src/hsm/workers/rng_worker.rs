@@ -1,26 +1,29 @@
 use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
 use crate::common::limits::MAX_RANDOM_SIZE;
-use crate::crypto::rng::{EntropySource, Rng};
+use crate::crypto::drbg::Drbg;
+use crate::crypto::rng::{Rng, TryEntropySource};
 use crate::hsm::keystore;
-use crate::hsm::keystore::{KeyId, KeyStore};
-use core::ops::Deref;
+use crate::hsm::keystore::{KeyAccess, KeyId, KeyStore};
 use embassy_sync::blocking_mutex::raw::RawMutex;
 use embassy_sync::mutex::Mutex;
 use futures::{Sink, SinkExt, Stream, StreamExt};
-use rand_core::RngCore;
 
 pub struct RngWorker<
     'data,
     'rng,
     'keystore,
     M: RawMutex,
-    E: EntropySource,
+    E: TryEntropySource,
+    D: Drbg,
     ReqSrc: Stream<Item = Request<'data>>,
     RespSink: Sink<Response<'data>>,
 > {
-    pub rng: &'rng Mutex<M, Rng<E>>,
+    pub rng: &'rng Mutex<M, Rng<E, D>>,
     // TODO: Move sym. key generation to own worker and get rid of key store here?
     pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    /// Client allowed to force reseeds of the random number generator. If `None`, `ReseedRng`
+    /// requests are rejected for all clients.
+    pub reseed_client: Option<ClientId>,
     pub requests: ReqSrc,
     pub responses: RespSink,
 }
@@ -30,10 +33,11 @@ impl<
         'rng,
         'keystore,
         M: RawMutex,
-        E: EntropySource,
+        E: TryEntropySource,
+        D: Drbg,
         ReqSrc: Stream<Item = Request<'data>> + Unpin,
         RespSink: Sink<Response<'data>> + Unpin,
-    > RngWorker<'data, 'rng, 'keystore, M, E, ReqSrc, RespSink>
+    > RngWorker<'data, 'rng, 'keystore, M, E, D, ReqSrc, RespSink>
 {
     /// Drive the worker to process the next request.
     /// This method is supposed to be called by a system task that owns this worker.
@@ -44,7 +48,18 @@ impl<
                 client_id,
                 request_id,
                 output,
-            } => self.get_random(client_id, request_id, output).await,
+                prediction_resistance,
+                additional_input,
+            } => {
+                self.get_random(
+                    client_id,
+                    request_id,
+                    output,
+                    prediction_resistance,
+                    additional_input,
+                )
+                .await
+            }
             Request::GenerateSymmetricKey {
                 client_id,
                 request_id,
@@ -54,6 +69,10 @@ impl<
                 self.generate_symmetric_key(client_id, request_id, key_id, overwrite)
                     .await
             }
+            Request::ReseedRng {
+                client_id,
+                request_id,
+            } => self.reseed(client_id, request_id).await,
             _ => Err(Error::UnexpectedRequestType)?,
         };
         self.responses
@@ -67,6 +86,8 @@ impl<
         client_id: ClientId,
         request_id: RequestId,
         output: &'data mut [u8],
+        prediction_resistance: bool,
+        additional_input: &[u8],
     ) -> Response<'data> {
         if output.len() >= MAX_RANDOM_SIZE {
             return Response::Error {
@@ -75,11 +96,43 @@ impl<
                 error: Error::RequestTooLarge,
             };
         }
-        self.rng.lock().await.fill_bytes(output);
-        Response::GetRandom {
-            client_id,
-            request_id,
-            data: output,
+        let result = self.rng.lock().await.try_fill_with_input(
+            output,
+            prediction_resistance,
+            additional_input,
+        );
+        match result {
+            Ok(()) => Response::GetRandom {
+                client_id,
+                request_id,
+                data: output,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: e.into(),
+            },
+        }
+    }
+
+    async fn reseed(&mut self, client_id: ClientId, request_id: RequestId) -> Response<'data> {
+        if self.reseed_client != Some(client_id) {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::AccessDenied,
+            };
+        }
+        match self.rng.lock().await.reseed() {
+            Ok(()) => Response::ReseedRng {
+                client_id,
+                request_id,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: e.into(),
+            },
         }
     }
 
@@ -91,7 +144,12 @@ impl<
         overwrite: bool,
     ) -> Response<'data> {
         // Own variable needed to break mutex lock immediately
-        let key_info = self.key_store.lock().await.deref().get_key_info(key_id);
+        let key_info = {
+            let locked_key_store = self.key_store.lock().await;
+            locked_key_store
+                .check_access(key_id, client_id, KeyAccess::Import)
+                .and_then(|()| locked_key_store.get_key_info(key_id))
+        };
         match key_info {
             Err(e) => Response::Error {
                 client_id,
@@ -101,7 +159,13 @@ impl<
             Ok(key_info) => {
                 let mut key = [0u8; keystore::KeyType::MAX_SYMMETRIC_KEY_SIZE];
                 let key = &mut key[0..key_info.ty.key_size()];
-                self.rng.lock().await.fill_bytes(key);
+                if let Err(e) = self.fill_random(key).await {
+                    return Response::Error {
+                        client_id,
+                        request_id,
+                        error: e,
+                    };
+                }
                 match self
                     .key_store
                     .lock()
@@ -121,4 +185,9 @@ impl<
             }
         }
     }
+
+    /// Fill `dest` with random bytes unless the random number generator is in its error state.
+    async fn fill_random(&self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.lock().await.try_fill(dest).map_err(Error::from)
+    }
 }
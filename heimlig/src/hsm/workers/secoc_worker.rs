@@ -0,0 +1,276 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
+use crate::crypto;
+use crate::crypto::secoc::{DataId, Profile};
+use crate::hsm::counter_store;
+use crate::hsm::counter_store::{CounterId, CounterStore};
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyStore, KeyType, KeyUsage};
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::{Mutex, MutexGuard};
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use heapless::Vec;
+use zeroize::Zeroizing;
+
+/// Configuration of a data ID served by a [SecOcWorker].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DataIdConfig {
+    /// Identifier of the secured PDUs.
+    pub data_id: DataId,
+    /// Symmetric key used to compute the AES-CMAC of the secured PDUs.
+    pub key_id: KeyId,
+    /// Truncation sizes of the secured PDUs.
+    pub profile: Profile,
+    /// Monotonic counter holding the freshness value of the data ID. The counter has to be
+    /// created before the data ID is used and must not be shared with other data IDs.
+    pub freshness_counter: CounterId,
+}
+
+/// Bounded table of the configured data IDs.
+pub struct DataIdTable<const MAX_DATA_IDS: usize> {
+    configs: Vec<DataIdConfig, MAX_DATA_IDS>,
+}
+
+impl<const MAX_DATA_IDS: usize> DataIdTable<MAX_DATA_IDS> {
+    /// Create a table of the given data IDs.
+    ///
+    /// Returns `InvalidDataId` if a data ID or freshness counter is configured more than once or
+    /// if there are more than `MAX_DATA_IDS` data IDs and `Crypto(InvalidTagSize)` if a profile is
+    /// not supported.
+    pub fn try_new(configs: &[DataIdConfig]) -> Result<Self, Error> {
+        let mut table = Vec::new();
+        for (i, config) in configs.iter().enumerate() {
+            if !config.profile.is_valid() {
+                return Err(Error::Crypto(crypto::Error::InvalidTagSize));
+            }
+            if configs[..i].iter().any(|c| {
+                c.data_id == config.data_id || c.freshness_counter == config.freshness_counter
+            }) {
+                return Err(Error::InvalidDataId);
+            }
+            table.push(*config).map_err(|_| Error::InvalidDataId)?;
+        }
+        Ok(DataIdTable { configs: table })
+    }
+
+    fn get(&self, data_id: DataId) -> Result<DataIdConfig, Error> {
+        self.configs
+            .iter()
+            .find(|config| config.data_id == data_id)
+            .copied()
+            .ok_or(Error::InvalidDataId)
+    }
+}
+
+/// Worker for AUTOSAR SecOC secured PDUs.
+///
+/// Keys and freshness values of the configured data IDs are kept inside the HSM. The freshness
+/// values are stored in monotonic counters, so that they survive a reset. For transmitted data IDs
+/// the freshness value is the one used for the latest secured PDU. For received data IDs it is the
+/// latest accepted one. A secured PDU is only accepted if its freshness value is newer.
+pub struct SecOcWorker<
+    'data,
+    'keystore,
+    'counterstore,
+    M: RawMutex,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+    const MAX_DATA_IDS: usize,
+> {
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    pub counter_store: &'counterstore Mutex<M, &'counterstore mut (dyn CounterStore + Send)>,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+    pub data_ids: DataIdTable<MAX_DATA_IDS>,
+}
+
+impl<
+        'data,
+        'keystore,
+        'counterstore,
+        M: RawMutex,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+        const MAX_DATA_IDS: usize,
+    > SecOcWorker<'data, 'keystore, 'counterstore, M, ReqSrc, RespSink, MAX_DATA_IDS>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::SecOcAuthenticate {
+                client_id,
+                request_id,
+                data_id,
+                payload,
+                secured_pdu,
+            } => {
+                self.authenticate(client_id, request_id, data_id, payload, secured_pdu)
+                    .await
+            }
+            Request::SecOcVerify {
+                client_id,
+                request_id,
+                data_id,
+                secured_pdu,
+            } => {
+                self.verify(client_id, request_id, data_id, secured_pdu)
+                    .await
+            }
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    async fn authenticate(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        data_id: DataId,
+        payload: &[u8],
+        secured_pdu: &'data mut [u8],
+    ) -> Response<'data> {
+        match self
+            .authenticate_with_next_freshness(client_id, data_id, payload, secured_pdu)
+            .await
+        {
+            Ok(size) => Response::SecOcAuthenticate {
+                client_id,
+                request_id,
+                secured_pdu: &mut secured_pdu[..size],
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    async fn verify(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        data_id: DataId,
+        secured_pdu: &[u8],
+    ) -> Response<'data> {
+        match self
+            .verify_and_update_freshness(client_id, data_id, secured_pdu)
+            .await
+        {
+            Ok(()) => Response::SecOcVerify {
+                client_id,
+                request_id,
+                verified: true,
+            },
+            Err(Error::Crypto(crypto::Error::InvalidSignature))
+            | Err(Error::Crypto(crypto::Error::InvalidFreshness)) => Response::SecOcVerify {
+                client_id,
+                request_id,
+                verified: false,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    /// Build a secured PDU and advance the freshness value of the data ID if successful.
+    ///
+    /// returns: The size of the secured PDU.
+    async fn authenticate_with_next_freshness(
+        &mut self,
+        client_id: ClientId,
+        data_id: DataId,
+        payload: &[u8],
+        secured_pdu: &mut [u8],
+    ) -> Result<usize, Error> {
+        let config = self.data_ids.get(data_id)?;
+        // Hold the counter store lock until the new freshness value is stored
+        let counter_store = self.counter_store.lock().await;
+        let freshness = counter_store
+            .get_counter_info(config.freshness_counter)
+            .map_err(Error::CounterStore)?
+            .value
+            .checked_add(1)
+            .ok_or(Error::Crypto(crypto::Error::InvalidFreshness))?;
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_SYMMETRIC_KEY_SIZE]);
+        let key = self
+            .export_key(config.key_id, client_id, key_buffer.as_mut_slice())
+            .await
+            .map_err(Error::KeyStore)?;
+        let size = crypto::secoc::authenticate(
+            key,
+            data_id,
+            freshness,
+            &config.profile,
+            payload,
+            secured_pdu,
+        )
+        .map_err(Error::Crypto)?;
+        Self::store_freshness(counter_store, config.freshness_counter, 1)?;
+        Ok(size)
+    }
+
+    /// Verify a secured PDU and store its freshness value as the latest one if successful.
+    async fn verify_and_update_freshness(
+        &mut self,
+        client_id: ClientId,
+        data_id: DataId,
+        secured_pdu: &[u8],
+    ) -> Result<(), Error> {
+        let config = self.data_ids.get(data_id)?;
+        // Hold the counter store lock until the new freshness value is stored
+        let counter_store = self.counter_store.lock().await;
+        let latest = counter_store
+            .get_counter_info(config.freshness_counter)
+            .map_err(Error::CounterStore)?
+            .value;
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_SYMMETRIC_KEY_SIZE]);
+        let key = self
+            .export_key(config.key_id, client_id, key_buffer.as_mut_slice())
+            .await
+            .map_err(Error::KeyStore)?;
+        let (_, freshness) =
+            crypto::secoc::verify(key, data_id, latest, &config.profile, secured_pdu)
+                .map_err(Error::Crypto)?;
+        Self::store_freshness(counter_store, config.freshness_counter, freshness - latest)
+    }
+
+    /// Advance a freshness counter by `amount`. A counter at its maximum value is treated as an
+    /// exhausted freshness value.
+    fn store_freshness(
+        mut counter_store: MutexGuard<'_, M, &mut (dyn CounterStore + Send)>,
+        counter: CounterId,
+        amount: u64,
+    ) -> Result<(), Error> {
+        match counter_store.increment(counter, amount) {
+            Ok(_) => Ok(()),
+            Err(counter_store::Error::MaxValueExceeded) => {
+                Err(Error::Crypto(crypto::Error::InvalidFreshness))
+            }
+            Err(e) => Err(Error::CounterStore(e)),
+        }
+    }
+
+    async fn export_key<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        key_buffer: &'a mut [u8],
+    ) -> Result<&'a [u8], keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+
+        if !locked_key_store.get_key_info(key_id)?.ty.is_symmetric() {
+            return Err(keystore::Error::InvalidKeyType);
+        }
+        locked_key_store.check_usage(key_id, client_id, KeyUsage::Mac, KeyAlgorithm::AesCmac)?;
+        locked_key_store.export_symmetric_key_unchecked(key_id, key_buffer)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
+use crate::crypto::drbg::Drbg;
+use crate::crypto::rng::{Rng, TryEntropySource};
+use crate::crypto::spdm::{Identity, Measurement, Responder, NONCE_SIZE};
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAlgorithm, KeyId, KeyStore, KeyType, KeyUsage};
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use zeroize::Zeroizing;
+
+/// Worker answering SPDM requests for device attestation.
+///
+/// All clients share a single SPDM connection. The worker is supposed to be reachable by the
+/// client that forwards the messages of the SPDM requester only.
+pub struct SpdmWorker<
+    'data,
+    'rng,
+    'keystore,
+    'chain,
+    M: RawMutex,
+    E: TryEntropySource,
+    D: Drbg,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+    const NUM_MEASUREMENTS: usize,
+> {
+    pub rng: &'rng Mutex<M, Rng<E, D>>,
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    /// NIST P-256 key pair of the leaf certificate of the certificate chain.
+    pub key_id: KeyId,
+    /// Certificate chain of slot 0 in SPDM format.
+    pub certificate_chain: &'chain [u8],
+    /// Measurements reported with the indices 1 to `NUM_MEASUREMENTS`.
+    pub measurements: [Measurement; NUM_MEASUREMENTS],
+    pub responder: Responder,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+}
+
+impl<
+        'data,
+        'rng,
+        'keystore,
+        'chain,
+        M: RawMutex,
+        E: TryEntropySource,
+        D: Drbg,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+        const NUM_MEASUREMENTS: usize,
+    > SpdmWorker<'data, 'rng, 'keystore, 'chain, M, E, D, ReqSrc, RespSink, NUM_MEASUREMENTS>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::ProcessSpdmMessage {
+                client_id,
+                request_id,
+                message,
+                response,
+            } => {
+                self.process_message(client_id, request_id, message, response)
+                    .await
+            }
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    async fn process_message(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        message: &[u8],
+        response: &'data mut [u8],
+    ) -> Response<'data> {
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
+        let private_key = match self
+            .export_private_key(self.key_id, client_id, key_buffer.as_mut_slice())
+            .await
+        {
+            Ok(private_key) => private_key,
+            Err(e) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(e),
+                }
+            }
+        };
+        let mut nonce = [0u8; NONCE_SIZE];
+        if let Err(e) = self.rng.lock().await.try_fill(&mut nonce) {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: e.into(),
+            };
+        }
+        let identity = Identity {
+            certificate_chain: self.certificate_chain,
+            private_key,
+            measurements: &self.measurements,
+        };
+        match self.responder.respond(&identity, &nonce, message, response) {
+            Ok(response) => Response::ProcessSpdmMessage {
+                client_id,
+                request_id,
+                response,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    async fn export_private_key<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        key_buffer: &'a mut [u8],
+    ) -> Result<&'a [u8], keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+
+        if locked_key_store.get_key_info(key_id)?.ty != KeyType::EccKeypairNistP256 {
+            return Err(keystore::Error::InvalidKeyType);
+        }
+        locked_key_store.check_usage(key_id, client_id, KeyUsage::Sign, KeyAlgorithm::Spdm)?;
+        locked_key_store.export_private_key_unchecked(key_id, key_buffer)
+    }
+}
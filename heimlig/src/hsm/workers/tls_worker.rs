@@ -0,0 +1,616 @@
+use crate::common::jobs::{ClientId, Error, Request, RequestId, Response};
+use crate::crypto;
+use crate::crypto::ecdsa::{nist_p256_sign, nist_p384_sign, NIST_P384_SIGNATURE_SIZE};
+use crate::crypto::tls::{
+    Group, SignatureScheme, CERTIFICATE_VERIFY_CONTENT_SIZE, HASH_SIZE, IV_SIZE,
+};
+use crate::hsm::keystore;
+use crate::hsm::keystore::{KeyAccess, KeyAlgorithm, KeyId, KeyInfo, KeyStore, KeyType, KeyUsage};
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use zeroize::Zeroizing;
+
+/// Key type of the slots holding TLS 1.3 secrets.
+const SECRET_KEY_TYPE: KeyType = KeyType::Symmetric256Bits;
+/// The only label whose output may be written to a slot that allows exporting its content.
+const TRAFFIC_KEY_LABEL: &[u8] = b"key";
+
+/// Worker for the TLS 1.3 key schedule (RFC 8446, section 7.1) and CertificateVerify signing.
+///
+/// All secrets of the key schedule are kept in symmetric key slots of type `Symmetric256Bits`
+/// that must not allow exporting their content. Only traffic keys derived with the "key" label
+/// may be written to exportable slots.
+pub struct TlsWorker<
+    'data,
+    'keystore,
+    M: RawMutex,
+    ReqSrc: Stream<Item = Request<'data>>,
+    RespSink: Sink<Response<'data>>,
+> {
+    pub key_store: &'keystore Mutex<M, &'keystore mut (dyn KeyStore + Send)>,
+    pub requests: ReqSrc,
+    pub responses: RespSink,
+}
+
+impl<
+        'data,
+        'keystore,
+        M: RawMutex,
+        ReqSrc: Stream<Item = Request<'data>> + Unpin,
+        RespSink: Sink<Response<'data>> + Unpin,
+    > TlsWorker<'data, 'keystore, M, ReqSrc, RespSink>
+{
+    /// Drive the worker to process the next request.
+    /// This method is supposed to be called by a system task that owns this worker.
+    pub async fn execute(&mut self) -> Result<(), Error> {
+        let request = self.requests.next().await.ok_or(Error::StreamTerminated)?;
+        let response = match request {
+            Request::TlsHkdfExtract {
+                client_id,
+                request_id,
+                salt_key_id,
+                ikm_key_id,
+                peer_key_exchange,
+                output_key_id,
+                overwrite,
+            } => {
+                self.hkdf_extract(
+                    client_id,
+                    request_id,
+                    salt_key_id,
+                    ikm_key_id,
+                    peer_key_exchange,
+                    output_key_id,
+                    overwrite,
+                )
+                .await
+            }
+            Request::TlsHkdfExpandLabel {
+                client_id,
+                request_id,
+                secret_key_id,
+                label,
+                context,
+                output_key_id,
+                overwrite,
+            } => {
+                self.hkdf_expand_label(
+                    client_id,
+                    request_id,
+                    secret_key_id,
+                    label,
+                    context,
+                    output_key_id,
+                    overwrite,
+                )
+                .await
+            }
+            Request::TlsDeriveSecret {
+                client_id,
+                request_id,
+                secret_key_id,
+                label,
+                transcript_hash,
+                output_key_id,
+                overwrite,
+            } => {
+                self.derive_secret(
+                    client_id,
+                    request_id,
+                    secret_key_id,
+                    label,
+                    transcript_hash,
+                    output_key_id,
+                    overwrite,
+                )
+                .await
+            }
+            Request::TlsDeriveIv {
+                client_id,
+                request_id,
+                secret_key_id,
+                iv,
+            } => {
+                self.derive_iv(client_id, request_id, secret_key_id, iv)
+                    .await
+            }
+            Request::TlsFinished {
+                client_id,
+                request_id,
+                base_key_id,
+                transcript_hash,
+                verify_data,
+            } => {
+                self.finished(
+                    client_id,
+                    request_id,
+                    base_key_id,
+                    transcript_hash,
+                    verify_data,
+                )
+                .await
+            }
+            Request::TlsSignCertificateVerify {
+                client_id,
+                request_id,
+                key_id,
+                server,
+                transcript_hash,
+                signature,
+            } => {
+                self.sign_certificate_verify(
+                    client_id,
+                    request_id,
+                    key_id,
+                    server,
+                    transcript_hash,
+                    signature,
+                )
+                .await
+            }
+            _ => Err(Error::UnexpectedRequestType)?,
+        };
+        self.responses
+            .send(response)
+            .await
+            .map_err(|_e| Error::Send)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn hkdf_extract(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        salt_key_id: Option<KeyId>,
+        ikm_key_id: Option<KeyId>,
+        peer_key_exchange: Option<&[u8]>,
+        output_key_id: KeyId,
+        overwrite: bool,
+    ) -> Response<'data> {
+        match self
+            .extract_to_slot(
+                client_id,
+                salt_key_id,
+                ikm_key_id,
+                peer_key_exchange,
+                output_key_id,
+                overwrite,
+            )
+            .await
+        {
+            Ok(()) => Response::TlsHkdfExtract {
+                client_id,
+                request_id,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn hkdf_expand_label(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        secret_key_id: KeyId,
+        label: &[u8],
+        context: &[u8],
+        output_key_id: KeyId,
+        overwrite: bool,
+    ) -> Response<'data> {
+        match self
+            .expand_label_to_slot(
+                client_id,
+                secret_key_id,
+                label,
+                context,
+                output_key_id,
+                overwrite,
+            )
+            .await
+        {
+            Ok(()) => Response::TlsHkdfExpandLabel {
+                client_id,
+                request_id,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn derive_secret(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        secret_key_id: KeyId,
+        label: &[u8],
+        transcript_hash: &[u8],
+        output_key_id: KeyId,
+        overwrite: bool,
+    ) -> Response<'data> {
+        let mut secret_buffer = Zeroizing::new([0u8; HASH_SIZE]);
+        let secret = match self
+            .export_secret(secret_key_id, client_id, secret_buffer.as_mut_slice())
+            .await
+        {
+            Ok(secret) => secret,
+            Err(error) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error,
+                }
+            }
+        };
+        let mut output = Zeroizing::new([0u8; HASH_SIZE]);
+        if let Err(e) =
+            crypto::tls::derive_secret(secret, label, transcript_hash, output.as_mut_slice())
+        {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            };
+        }
+
+        match self
+            .store_secret(client_id, output_key_id, output.as_slice(), overwrite)
+            .await
+        {
+            Ok(()) => Response::TlsDeriveSecret {
+                client_id,
+                request_id,
+            },
+            Err(error) => Response::Error {
+                client_id,
+                request_id,
+                error,
+            },
+        }
+    }
+
+    async fn derive_iv(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        secret_key_id: KeyId,
+        iv: &'data mut [u8],
+    ) -> Response<'data> {
+        if iv.len() != IV_SIZE {
+            return Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(crypto::Error::InvalidBufferSize),
+            };
+        }
+        let mut secret_buffer = Zeroizing::new([0u8; HASH_SIZE]);
+        let secret = match self
+            .export_secret(secret_key_id, client_id, secret_buffer.as_mut_slice())
+            .await
+        {
+            Ok(secret) => secret,
+            Err(error) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error,
+                }
+            }
+        };
+
+        match crypto::tls::hkdf_expand_label(secret, b"iv", &[], iv) {
+            Ok(()) => Response::TlsDeriveIv {
+                client_id,
+                request_id,
+                iv,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    async fn finished(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        base_key_id: KeyId,
+        transcript_hash: &[u8],
+        verify_data: &'data mut [u8],
+    ) -> Response<'data> {
+        let mut secret_buffer = Zeroizing::new([0u8; HASH_SIZE]);
+        let base_key = match self
+            .export_secret(base_key_id, client_id, secret_buffer.as_mut_slice())
+            .await
+        {
+            Ok(base_key) => base_key,
+            Err(error) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error,
+                }
+            }
+        };
+
+        match crypto::tls::finished_verify_data(base_key, transcript_hash, verify_data) {
+            Ok(()) => Response::TlsFinished {
+                client_id,
+                request_id,
+                verify_data,
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    async fn sign_certificate_verify(
+        &mut self,
+        client_id: ClientId,
+        request_id: RequestId,
+        key_id: KeyId,
+        server: bool,
+        transcript_hash: &[u8],
+        signature: &'data mut [u8],
+    ) -> Response<'data> {
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
+        let (private_key, key_info) = match self
+            .export_private_key_and_key_info(
+                key_id,
+                client_id,
+                KeyUsage::Sign,
+                KeyAlgorithm::TlsCertificateVerify,
+                key_buffer.as_mut_slice(),
+            )
+            .await
+        {
+            Ok(private_key_and_info) => private_key_and_info,
+            Err(e) => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(e),
+                }
+            }
+        };
+        let scheme = match key_info.ty {
+            KeyType::EccKeypairNistP256 => SignatureScheme::EcdsaSecp256r1Sha256,
+            KeyType::EccKeypairNistP384 => SignatureScheme::EcdsaSecp384r1Sha384,
+            _ => {
+                return Response::Error {
+                    client_id,
+                    request_id,
+                    error: Error::KeyStore(keystore::Error::InvalidKeyType),
+                }
+            }
+        };
+
+        let mut content = [0u8; CERTIFICATE_VERIFY_CONTENT_SIZE];
+        let mut raw_signature = [0u8; NIST_P384_SIGNATURE_SIZE];
+        let raw_signature = &mut raw_signature[..key_info.ty.signature_size()];
+        let result = crypto::tls::certificate_verify_content(server, transcript_hash, &mut content)
+            .and_then(|_| match scheme {
+                SignatureScheme::EcdsaSecp256r1Sha256 => {
+                    nist_p256_sign(private_key, &content, raw_signature)
+                }
+                SignatureScheme::EcdsaSecp384r1Sha384 => {
+                    nist_p384_sign(private_key, &content, raw_signature)
+                }
+            })
+            .and_then(|_| crypto::tls::encode_signature_der(scheme, raw_signature, signature))
+            .map(|encoded| encoded.len());
+
+        match result {
+            Ok(size) => Response::TlsSignCertificateVerify {
+                client_id,
+                request_id,
+                signature: &mut signature[..size],
+            },
+            Err(e) => Response::Error {
+                client_id,
+                request_id,
+                error: Error::Crypto(e),
+            },
+        }
+    }
+
+    async fn extract_to_slot(
+        &mut self,
+        client_id: ClientId,
+        salt_key_id: Option<KeyId>,
+        ikm_key_id: Option<KeyId>,
+        peer_key_exchange: Option<&[u8]>,
+        output_key_id: KeyId,
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let mut salt_buffer = Zeroizing::new([0u8; HASH_SIZE]);
+        let salt = match salt_key_id {
+            None => None,
+            Some(key_id) => Some(
+                self.export_secret(key_id, client_id, salt_buffer.as_mut_slice())
+                    .await?,
+            ),
+        };
+        let mut ikm_buffer = Zeroizing::new([0u8; HASH_SIZE]);
+        let ikm = match (ikm_key_id, peer_key_exchange) {
+            (None, None) => None,
+            (Some(key_id), None) => Some(
+                self.export_secret(key_id, client_id, ikm_buffer.as_mut_slice())
+                    .await?,
+            ),
+            (Some(key_id), Some(peer_key_exchange)) => {
+                self.ecdhe(
+                    client_id,
+                    key_id,
+                    peer_key_exchange,
+                    ikm_buffer.as_mut_slice(),
+                )
+                .await?;
+                Some(ikm_buffer.as_slice())
+            }
+            // A key share requires a local key pair
+            (None, Some(_)) => Err(Error::KeyStore(keystore::Error::InvalidKeyId))?,
+        };
+        let mut secret = Zeroizing::new([0u8; HASH_SIZE]);
+        crypto::tls::hkdf_extract(salt, ikm, secret.as_mut_slice()).map_err(Error::Crypto)?;
+        self.store_secret(client_id, output_key_id, secret.as_slice(), overwrite)
+            .await
+    }
+
+    async fn expand_label_to_slot(
+        &mut self,
+        client_id: ClientId,
+        secret_key_id: KeyId,
+        label: &[u8],
+        context: &[u8],
+        output_key_id: KeyId,
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let output_key_info = {
+            let locked_key_store = self.key_store.lock().await;
+            locked_key_store
+                .check_access(output_key_id, client_id, KeyAccess::Import)
+                .map_err(Error::KeyStore)?;
+            locked_key_store
+                .get_key_info(output_key_id)
+                .map_err(Error::KeyStore)?
+        };
+        if !output_key_info.ty.is_symmetric() {
+            return Err(Error::KeyStore(keystore::Error::InvalidKeyType));
+        }
+        // Only traffic keys may be written to exportable slots
+        if output_key_info.permissions.export_private
+            && (label != TRAFFIC_KEY_LABEL || !context.is_empty())
+        {
+            return Err(Error::KeyStore(keystore::Error::NotAllowed));
+        }
+        let mut secret_buffer = Zeroizing::new([0u8; HASH_SIZE]);
+        let secret = self
+            .export_secret(secret_key_id, client_id, secret_buffer.as_mut_slice())
+            .await?;
+        let mut output = Zeroizing::new([0u8; KeyType::MAX_SYMMETRIC_KEY_SIZE]);
+        let output = &mut output[..output_key_info.ty.key_size()];
+        crypto::tls::hkdf_expand_label(secret, label, context, output).map_err(Error::Crypto)?;
+        self.key_store
+            .lock()
+            .await
+            .import_symmetric_key(output_key_id, output, overwrite)
+            .map_err(Error::KeyStore)
+    }
+
+    /// Export a key schedule secret. Secrets must be held in non-exportable slots.
+    async fn export_secret<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        secret_buffer: &'a mut [u8],
+    ) -> Result<&'a [u8], Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+
+        check_secret_key_info(
+            locked_key_store
+                .get_key_info(key_id)
+                .map_err(Error::KeyStore)?,
+        )?;
+        locked_key_store
+            .check_usage(key_id, client_id, KeyUsage::Derive, KeyAlgorithm::Hkdf)
+            .map_err(Error::KeyStore)?;
+        locked_key_store
+            .export_symmetric_key_unchecked(key_id, secret_buffer)
+            .map_err(Error::KeyStore)
+    }
+
+    /// Store a key schedule secret. Secrets must be held in non-exportable slots.
+    async fn store_secret(
+        &mut self,
+        client_id: ClientId,
+        key_id: KeyId,
+        secret: &[u8],
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        // Lock keystore only once
+        let mut locked_key_store = self.key_store.lock().await;
+
+        check_secret_key_info(
+            locked_key_store
+                .get_key_info(key_id)
+                .map_err(Error::KeyStore)?,
+        )?;
+        locked_key_store
+            .check_access(key_id, client_id, KeyAccess::Import)
+            .map_err(Error::KeyStore)?;
+        locked_key_store
+            .import_symmetric_key(key_id, secret, overwrite)
+            .map_err(Error::KeyStore)
+    }
+
+    /// Compute the (EC)DHE shared secret between a stored key pair and the peer's key share.
+    async fn ecdhe(
+        &mut self,
+        client_id: ClientId,
+        key_id: KeyId,
+        peer_key_exchange: &[u8],
+        shared_secret: &mut [u8],
+    ) -> Result<(), Error> {
+        let mut key_buffer = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
+        let (private_key, key_info) = self
+            .export_private_key_and_key_info(
+                key_id,
+                client_id,
+                KeyUsage::Derive,
+                KeyAlgorithm::Ecdh,
+                key_buffer.as_mut_slice(),
+            )
+            .await
+            .map_err(Error::KeyStore)?;
+        let group = match key_info.ty {
+            KeyType::EccKeypairNistP256 => Group::Secp256r1,
+            KeyType::EccKeypairX25519 => Group::X25519,
+            _ => Err(Error::KeyStore(keystore::Error::InvalidKeyType))?,
+        };
+        crypto::tls::ecdhe_shared_secret(group, private_key, peer_key_exchange, shared_secret)
+            .map_err(Error::Crypto)
+    }
+
+    async fn export_private_key_and_key_info<'a>(
+        &mut self,
+        key_id: KeyId,
+        client_id: ClientId,
+        usage: KeyUsage,
+        algorithm: KeyAlgorithm,
+        key_buffer: &'a mut [u8],
+    ) -> Result<(&'a [u8], KeyInfo), keystore::Error> {
+        // Lock keystore only once
+        let locked_key_store = self.key_store.lock().await;
+        locked_key_store.check_usage(key_id, client_id, usage, algorithm)?;
+
+        Ok((
+            locked_key_store.export_private_key_unchecked(key_id, key_buffer)?,
+            locked_key_store.get_key_info(key_id)?,
+        ))
+    }
+}
+
+fn check_secret_key_info(key_info: KeyInfo) -> Result<(), Error> {
+    if key_info.ty != SECRET_KEY_TYPE {
+        return Err(Error::KeyStore(keystore::Error::InvalidKeyType));
+    }
+    if key_info.permissions.export_private {
+        return Err(Error::KeyStore(keystore::Error::NotAllowed));
+    }
+    Ok(())
+}
@@ -0,0 +1,602 @@
+use crate::crypto::aes::gcm::{
+    aes256gcm_decrypt_in_place_detached, aes256gcm_encrypt_in_place_detached,
+};
+use crate::crypto::aes::{GCM_IV_SIZE, GCM_TAG_SIZE, KEY256_SIZE};
+use crate::crypto::hkdf::{hkdf_sha256_expand, hkdf_sha256_extract};
+use crate::hsm::keystore::{Error, KeyId, KeyInfo, KeyStore, KeyType};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Size of the hardware-unique key.
+pub const HARDWARE_UNIQUE_KEY_SIZE: usize = 32;
+
+/// Size of the seal that is stored after every encrypted key: nonce and tag.
+pub const SEAL_SIZE: usize = GCM_IV_SIZE + GCM_TAG_SIZE;
+
+/// Maximum size of an encrypted symmetric or private key together with its seal.
+const MAX_SEALED_KEY_SIZE: usize = KeyType::MAX_PRIVATE_KEY_SIZE + SEAL_SIZE;
+
+/// Maximum size of the additional authenticated data: key info and public key.
+const MAX_AAD_SIZE: usize = KeyInfo::SERIALIZED_SIZE + KeyType::MAX_PUBLIC_KEY_SIZE;
+
+/// Secret that is unique to a device and does not leave its hardware, e.g. a key stored in
+/// one-time programmable memory or derived from a physically unclonable function.
+pub trait HardwareUniqueKey {
+    fn hardware_unique_key(&self) -> [u8; HARDWARE_UNIQUE_KEY_SIZE];
+}
+
+/// Key store wrapper that encrypts symmetric and private keys with AES-256-GCM before they are
+/// passed to the inner key store. Public keys are stored in plaintext but authenticated.
+///
+/// The key encryption key is derived from a hardware-unique key. The key info of a key is bound to
+/// its key material as additional authenticated data, so that key material cannot be moved to
+/// other slots. The nonce is derived from the key info and the key material, so that no random
+/// number generator is needed and a nonce is never reused for different key material.
+///
+/// The nonce and tag are stored after the encrypted key in the same slot, so that a single import
+/// into the inner key store updates both. The inner key store has to be created with a trailer of
+/// [SEAL_SIZE] bytes, e.g. with `MemoryKeyStore::try_new_with_trailer()` or
+/// `FlashKeyStore::try_new_with_trailer()`. Keys whose slots were modified are reported with
+/// [Error::IntegrityViolation].
+pub struct EncryptedKeyStore<S: KeyStore> {
+    inner: S,
+    encryption_key: Zeroizing<[u8; KEY256_SIZE]>,
+    nonce_key: Zeroizing<[u8; KEY256_SIZE]>,
+}
+
+impl<S: KeyStore> EncryptedKeyStore<S> {
+    pub fn new(inner: S, hardware_unique_key: &dyn HardwareUniqueKey) -> Self {
+        let hardware_unique_key = Zeroizing::new(hardware_unique_key.hardware_unique_key());
+        let mut prk = Zeroizing::new([0u8; KEY256_SIZE]);
+        let mut encryption_key = Zeroizing::new([0u8; KEY256_SIZE]);
+        let mut nonce_key = Zeroizing::new([0u8; KEY256_SIZE]);
+        hkdf_sha256_extract(
+            b"heimlig key store",
+            &[hardware_unique_key.as_slice()],
+            prk.as_mut_slice(),
+        )
+        .expect("invalid PRK size");
+        hkdf_sha256_expand(
+            prk.as_slice(),
+            &[b"key encryption key"],
+            encryption_key.as_mut_slice(),
+        )
+        .expect("invalid key encryption key size");
+        hkdf_sha256_expand(prk.as_slice(), &[b"nonce key"], nonce_key.as_mut_slice())
+            .expect("invalid nonce key size");
+        Self {
+            inner,
+            encryption_key,
+            nonce_key,
+        }
+    }
+
+    /// Encrypt `key` into a sealed key, i.e. the ciphertext followed by its nonce and tag.
+    ///
+    /// returns: The buffer holding the sealed key and its size or an error.
+    fn seal(
+        &self,
+        info: &KeyInfo,
+        public_key: &[u8],
+        key: &[u8],
+    ) -> Result<(Zeroizing<[u8; MAX_SEALED_KEY_SIZE]>, usize), Error> {
+        let (aad, aad_size) = Self::aad(info, public_key)?;
+        let aad = &aad[..aad_size];
+        let mut sealed_key = Zeroizing::new([0u8; MAX_SEALED_KEY_SIZE]);
+        let sealed_key_size = key.len() + SEAL_SIZE;
+        let (ciphertext, seal) = sealed_key
+            .get_mut(..sealed_key_size)
+            .ok_or(Error::InvalidBufferSize)?
+            .split_at_mut(key.len());
+        let (nonce, tag) = seal.split_at_mut(GCM_IV_SIZE);
+        ciphertext.copy_from_slice(key);
+        hkdf_sha256_expand(self.nonce_key.as_slice(), &[aad, key], nonce)
+            .map_err(|_| Error::InvalidBufferSize)?;
+        aes256gcm_encrypt_in_place_detached(
+            self.encryption_key.as_slice(),
+            nonce,
+            aad,
+            ciphertext,
+            tag,
+        )
+        .map_err(|_| Error::InvalidBufferSize)?;
+        Ok((sealed_key, sealed_key_size))
+    }
+
+    /// Decrypt a sealed key that was exported from the inner key store into `dest`.
+    fn open<'data>(
+        &self,
+        info: &KeyInfo,
+        public_key: &[u8],
+        sealed_key: &[u8],
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let (aad, aad_size) = Self::aad(info, public_key)?;
+        let key_size = sealed_key
+            .len()
+            .checked_sub(SEAL_SIZE)
+            .ok_or(Error::IntegrityViolation)?;
+        let (ciphertext, seal) = sealed_key.split_at(key_size);
+        let key = dest.get_mut(..key_size).ok_or(Error::InvalidBufferSize)?;
+        key.copy_from_slice(ciphertext);
+        let result = aes256gcm_decrypt_in_place_detached(
+            self.encryption_key.as_slice(),
+            &seal[..GCM_IV_SIZE],
+            &aad[..aad_size],
+            key,
+            &seal[GCM_IV_SIZE..],
+        );
+        if result.is_err() {
+            key.zeroize();
+            return Err(Error::IntegrityViolation);
+        }
+        Ok(key)
+    }
+
+    /// Concatenate the key info and the public key.
+    fn aad(info: &KeyInfo, public_key: &[u8]) -> Result<([u8; MAX_AAD_SIZE], usize), Error> {
+        let mut aad = [0u8; MAX_AAD_SIZE];
+        let aad_size = KeyInfo::SERIALIZED_SIZE + public_key.len();
+        if aad_size > MAX_AAD_SIZE {
+            return Err(Error::InvalidBufferSize);
+        }
+        aad[..KeyInfo::SERIALIZED_SIZE].copy_from_slice(&info.to_bytes());
+        aad[KeyInfo::SERIALIZED_SIZE..aad_size].copy_from_slice(public_key);
+        Ok((aad, aad_size))
+    }
+
+    /// Decrypt a sealed private key that was exported from the inner key store into `dest`.
+    fn open_private_key<'data>(
+        &self,
+        id: KeyId,
+        sealed_key: &[u8],
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let info = self.inner.get_key_info(id)?;
+        let mut public_key = [0u8; KeyType::MAX_PUBLIC_KEY_SIZE];
+        let public_key = self.inner.export_public_key(id, &mut public_key)?;
+        self.open(&info, public_key, sealed_key, dest)
+    }
+}
+
+impl<S: KeyStore> KeyStore for EncryptedKeyStore<S> {
+    fn get_key_info(&self, id: KeyId) -> Result<KeyInfo, Error> {
+        self.inner.get_key_info(id)
+    }
+
+    fn import_symmetric_key(
+        &mut self,
+        id: KeyId,
+        data: &[u8],
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let info = self.inner.get_key_info(id)?;
+        let (sealed_key, size) = self.seal(&info, &[], data)?;
+        self.inner
+            .import_symmetric_key(id, &sealed_key[..size], overwrite)
+    }
+
+    fn import_key_pair(
+        &mut self,
+        id: KeyId,
+        public_key: &[u8],
+        private_key: &[u8],
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let info = self.inner.get_key_info(id)?;
+        let (sealed_key, size) = self.seal(&info, public_key, private_key)?;
+        self.inner
+            .import_key_pair(id, public_key, &sealed_key[..size], overwrite)
+    }
+
+    fn export_symmetric_key<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let info = self.inner.get_key_info(id)?;
+        let mut sealed_key = Zeroizing::new([0u8; MAX_SEALED_KEY_SIZE]);
+        let sealed_key = self
+            .inner
+            .export_symmetric_key(id, sealed_key.as_mut_slice())?;
+        self.open(&info, &[], sealed_key, dest)
+    }
+
+    fn export_public_key<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let info = self.inner.get_key_info(id)?;
+        let size = self.inner.export_public_key(id, dest)?.len();
+        let public_key = &dest[..size];
+        // Public keys are only authenticated together with their private keys
+        let mut sealed_key = Zeroizing::new([0u8; MAX_SEALED_KEY_SIZE]);
+        let sealed_key = self
+            .inner
+            .export_private_key_unchecked(id, sealed_key.as_mut_slice())?;
+        let mut private_key = Zeroizing::new([0u8; KeyType::MAX_PRIVATE_KEY_SIZE]);
+        self.open(&info, public_key, sealed_key, private_key.as_mut_slice())?;
+        Ok(public_key)
+    }
+
+    fn export_private_key<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let mut sealed_key = Zeroizing::new([0u8; MAX_SEALED_KEY_SIZE]);
+        let sealed_key = self
+            .inner
+            .export_private_key(id, sealed_key.as_mut_slice())?;
+        self.open_private_key(id, sealed_key, dest)
+    }
+
+    fn export_symmetric_key_unchecked<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let info = self.inner.get_key_info(id)?;
+        let mut sealed_key = Zeroizing::new([0u8; MAX_SEALED_KEY_SIZE]);
+        let sealed_key = self
+            .inner
+            .export_symmetric_key_unchecked(id, sealed_key.as_mut_slice())?;
+        self.open(&info, &[], sealed_key, dest)
+    }
+
+    fn export_private_key_unchecked<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let mut sealed_key = Zeroizing::new([0u8; MAX_SEALED_KEY_SIZE]);
+        let sealed_key = self
+            .inner
+            .export_private_key_unchecked(id, sealed_key.as_mut_slice())?;
+        self.open_private_key(id, sealed_key, dest)
+    }
+
+    fn delete(&mut self, id: KeyId) -> Result<(), Error> {
+        self.inner.delete(id)
+    }
+
+    fn is_key_available(&self, id: KeyId) -> bool {
+        self.inner.is_key_available(id)
+    }
+
+    fn size(&self, id: KeyId) -> Result<usize, Error> {
+        self.inner
+            .size(id)
+            .map(|size| size.saturating_sub(SEAL_SIZE))
+    }
+
+    fn num_keys(&self) -> usize {
+        self.inner.num_keys()
+    }
+
+    fn key_info_at(&self, index: usize) -> Option<KeyInfo> {
+        self.inner.key_info_at(index)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::hsm::keystore::{KeyAccessControl, KeyPermissions, KeyUsageFlags};
+    use crate::integration::flash_key_store::FlashKeyStore;
+    use crate::integration::memory_key_store::MemoryKeyStore;
+    use crate::integration::ram_flash::RamFlash;
+    use embedded_storage::nor_flash::ReadNorFlash;
+
+    struct TestHardwareUniqueKey(u8);
+
+    impl HardwareUniqueKey for TestHardwareUniqueKey {
+        fn hardware_unique_key(&self) -> [u8; HARDWARE_UNIQUE_KEY_SIZE] {
+            [self.0; HARDWARE_UNIQUE_KEY_SIZE]
+        }
+    }
+
+    const PERMISSIONS: KeyPermissions = KeyPermissions {
+        import: true,
+        export_private: true,
+        overwrite: false,
+        delete: true,
+    };
+    const USAGE: KeyUsageFlags = KeyUsageFlags {
+        encrypt: true,
+        decrypt: true,
+        sign: true,
+        verify: true,
+        derive: false,
+        wrap: false,
+        unwrap: false,
+        mac: false,
+    };
+    const KEY1_INFO: KeyInfo = KeyInfo {
+        id: KeyId(1),
+        ty: KeyType::Symmetric256Bits,
+        permissions: PERMISSIONS,
+        usage: USAGE,
+        algorithm: None,
+        access: KeyAccessControl::ALL,
+    };
+    const KEY2_INFO: KeyInfo = KeyInfo {
+        id: KeyId(2),
+        ty: KeyType::Symmetric256Bits,
+        permissions: PERMISSIONS,
+        usage: USAGE,
+        algorithm: None,
+        access: KeyAccessControl::ALL,
+    };
+    const KEY3_INFO: KeyInfo = KeyInfo {
+        id: KeyId(3),
+        ty: KeyType::EccKeypairNistP256,
+        permissions: PERMISSIONS,
+        usage: USAGE,
+        algorithm: None,
+        access: KeyAccessControl::ALL,
+    };
+    const KEY_INFOS: [KeyInfo; 3] = [KEY1_INFO, KEY2_INFO, KEY3_INFO];
+    const STORAGE_SIZE: usize =
+        2 * KEY1_INFO.ty.key_size() + KEY3_INFO.ty.key_size() + KEY_INFOS.len() * SEAL_SIZE;
+    const SYMMETRIC_KEY: [u8; KEY1_INFO.ty.key_size()] = [1; KEY1_INFO.ty.key_size()];
+    const PUBLIC_KEY: [u8; KEY3_INFO.ty.public_key_size()] = [2; KEY3_INFO.ty.public_key_size()];
+    const PRIVATE_KEY: [u8; KEY3_INFO.ty.private_key_size()] = [3; KEY3_INFO.ty.private_key_size()];
+
+    type InnerKeyStore = MemoryKeyStore<STORAGE_SIZE, 3>;
+
+    fn key_store(hardware_unique_key: u8) -> EncryptedKeyStore<InnerKeyStore> {
+        let inner = InnerKeyStore::try_new_with_trailer(&KEY_INFOS, SEAL_SIZE)
+            .expect("failed to create key store");
+        EncryptedKeyStore::new(inner, &TestHardwareUniqueKey(hardware_unique_key))
+    }
+
+    fn import_keys(key_store: &mut dyn KeyStore) {
+        key_store
+            .import_symmetric_key(KEY1_INFO.id, &SYMMETRIC_KEY, false)
+            .expect("failed to import key");
+        key_store
+            .import_key_pair(KEY3_INFO.id, &PUBLIC_KEY, &PRIVATE_KEY, false)
+            .expect("failed to import key pair");
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let mut key_store = key_store(0);
+        import_keys(&mut key_store);
+        let mut dest = [0u8; KeyType::MAX_PUBLIC_KEY_SIZE];
+        assert_eq!(
+            key_store.export_symmetric_key(KEY1_INFO.id, &mut dest),
+            Ok(SYMMETRIC_KEY.as_slice())
+        );
+        assert_eq!(
+            key_store.export_public_key(KEY3_INFO.id, &mut dest),
+            Ok(PUBLIC_KEY.as_slice())
+        );
+        assert_eq!(
+            key_store.export_private_key(KEY3_INFO.id, &mut dest),
+            Ok(PRIVATE_KEY.as_slice())
+        );
+        assert_eq!(
+            key_store.export_private_key_unchecked(KEY3_INFO.id, &mut dest),
+            Ok(PRIVATE_KEY.as_slice())
+        );
+        assert_eq!(key_store.size(KEY1_INFO.id), Ok(SYMMETRIC_KEY.len()));
+        assert_eq!(key_store.size(KEY3_INFO.id), Ok(KEY3_INFO.ty.key_size()));
+
+        // Only ciphertexts and their seals are stored in the inner key store
+        let sealed_key = key_store
+            .inner
+            .export_symmetric_key(KEY1_INFO.id, &mut dest)
+            .expect("failed to export key");
+        assert_eq!(sealed_key.len(), SYMMETRIC_KEY.len() + SEAL_SIZE);
+        assert_ne!(&sealed_key[..SYMMETRIC_KEY.len()], SYMMETRIC_KEY);
+        let sealed_key = key_store
+            .inner
+            .export_private_key(KEY3_INFO.id, &mut dest)
+            .expect("failed to export private key");
+        assert_eq!(sealed_key.len(), PRIVATE_KEY.len() + SEAL_SIZE);
+        assert_ne!(&sealed_key[..PRIVATE_KEY.len()], PRIVATE_KEY);
+
+        key_store
+            .delete(KEY1_INFO.id)
+            .expect("failed to delete key");
+        assert!(!key_store.is_key_available(KEY1_INFO.id));
+    }
+
+    #[test]
+    fn swapped_slots() {
+        let mut key_store = key_store(0);
+        import_keys(&mut key_store);
+
+        // Copy the sealed key to another slot of the same type
+        let mut sealed_key = [0u8; KEY1_INFO.ty.key_size() + SEAL_SIZE];
+        key_store
+            .inner
+            .export_symmetric_key(KEY1_INFO.id, &mut sealed_key)
+            .expect("failed to export key");
+        key_store
+            .inner
+            .import_symmetric_key(KEY2_INFO.id, &sealed_key, false)
+            .expect("failed to import key");
+
+        let mut dest = [0u8; KEY2_INFO.ty.key_size()];
+        assert_eq!(
+            key_store.export_symmetric_key(KEY2_INFO.id, &mut dest),
+            Err(Error::IntegrityViolation)
+        );
+        assert_eq!(dest, [0u8; KEY2_INFO.ty.key_size()]);
+    }
+
+    #[test]
+    fn wrong_hardware_unique_key() {
+        let mut key_store = key_store(0);
+        import_keys(&mut key_store);
+        let key_store = EncryptedKeyStore::new(key_store.inner, &TestHardwareUniqueKey(1));
+        let mut dest = [0u8; KeyType::MAX_PUBLIC_KEY_SIZE];
+        assert_eq!(
+            key_store.export_symmetric_key(KEY1_INFO.id, &mut dest),
+            Err(Error::IntegrityViolation)
+        );
+        assert_eq!(
+            key_store.export_public_key(KEY3_INFO.id, &mut dest),
+            Err(Error::IntegrityViolation)
+        );
+    }
+
+    #[test]
+    fn missing_trailer() {
+        let inner = MemoryKeyStore::<STORAGE_SIZE, 3>::try_new(&KEY_INFOS)
+            .expect("failed to create key store");
+        let mut key_store = EncryptedKeyStore::new(inner, &TestHardwareUniqueKey(0));
+        assert_eq!(
+            key_store.import_symmetric_key(KEY1_INFO.id, &SYMMETRIC_KEY, false),
+            Err(Error::InvalidBufferSize)
+        );
+    }
+
+    #[test]
+    fn permissions() {
+        let mut key_store = key_store(0);
+        import_keys(&mut key_store);
+        assert_eq!(
+            key_store.import_symmetric_key(KEY1_INFO.id, &[4; KEY1_INFO.ty.key_size()], true),
+            Err(Error::NotAllowed)
+        );
+        let mut dest = [0u8; KEY1_INFO.ty.key_size()];
+        assert_eq!(
+            key_store.export_symmetric_key(KEY1_INFO.id, &mut dest),
+            Ok(SYMMETRIC_KEY.as_slice())
+        );
+    }
+
+    #[test]
+    fn flash_key_store() {
+        const SECTOR_SIZE: usize = 1024;
+        let mut flash = RamFlash::<{ 2 * SECTOR_SIZE }, SECTOR_SIZE>::new();
+        let inner = FlashKeyStore::<_, 3>::try_new_with_trailer(&mut flash, &KEY_INFOS, SEAL_SIZE)
+            .expect("failed to create key store");
+        let mut key_store = EncryptedKeyStore::new(inner, &TestHardwareUniqueKey(0));
+        import_keys(&mut key_store);
+        drop(key_store);
+
+        let mut memory = [0u8; 2 * SECTOR_SIZE];
+        flash.read(0, &mut memory).expect("failed to read flash");
+        assert!(!memory
+            .windows(SYMMETRIC_KEY.len())
+            .any(|window| window == SYMMETRIC_KEY));
+        assert!(!memory
+            .windows(PRIVATE_KEY.len())
+            .any(|window| window == PRIVATE_KEY));
+
+        let inner = FlashKeyStore::<_, 3>::try_new_with_trailer(&mut flash, &KEY_INFOS, SEAL_SIZE)
+            .expect("failed to mount key store");
+        let key_store = EncryptedKeyStore::new(inner, &TestHardwareUniqueKey(0));
+        let mut dest = [0u8; KeyType::MAX_PUBLIC_KEY_SIZE];
+        assert_eq!(
+            key_store.export_symmetric_key(KEY1_INFO.id, &mut dest),
+            Ok(SYMMETRIC_KEY.as_slice())
+        );
+        assert_eq!(
+            key_store.export_private_key(KEY3_INFO.id, &mut dest),
+            Ok(PRIVATE_KEY.as_slice())
+        );
+    }
+
+    #[test]
+    fn power_cuts() {
+        const SECTOR_SIZE: usize = 1024;
+        const NUM_UPDATES: u8 = 12;
+        const OVERWRITE: KeyPermissions = KeyPermissions {
+            overwrite: true,
+            ..PERMISSIONS
+        };
+        const KEY_INFOS: [KeyInfo; 2] = [
+            KeyInfo {
+                permissions: OVERWRITE,
+                ..KEY1_INFO
+            },
+            KeyInfo {
+                permissions: OVERWRITE,
+                ..KEY3_INFO
+            },
+        ];
+        type Flash = RamFlash<{ 2 * SECTOR_SIZE }, SECTOR_SIZE>;
+        type TestKeyStore<'a> = EncryptedKeyStore<FlashKeyStore<&'a mut Flash, 2>>;
+
+        fn key_store(flash: &mut Flash) -> Result<TestKeyStore<'_>, Error> {
+            let inner = FlashKeyStore::try_new_with_trailer(flash, &KEY_INFOS, SEAL_SIZE)?;
+            Ok(EncryptedKeyStore::new(inner, &TestHardwareUniqueKey(0)))
+        }
+
+        fn update(key_store: &mut TestKeyStore, value: u8) -> Result<(), Error> {
+            key_store.import_symmetric_key(
+                KEY1_INFO.id,
+                &[value; KEY1_INFO.ty.key_size()],
+                true,
+            )?;
+            key_store.import_key_pair(
+                KEY3_INFO.id,
+                &[value; KEY3_INFO.ty.public_key_size()],
+                &[value; KEY3_INFO.ty.private_key_size()],
+                true,
+            )
+        }
+
+        /// Values of the two keys or `None` if a key is not present.
+        fn state(key_store: &TestKeyStore) -> [Option<u8>; 2] {
+            let mut dest = [0u8; KeyType::MAX_PUBLIC_KEY_SIZE];
+            let mut state = [None; 2];
+            if key_store.is_key_available(KEY1_INFO.id) {
+                let key = key_store
+                    .export_symmetric_key(KEY1_INFO.id, &mut dest)
+                    .expect("failed to open key");
+                assert!(key.iter().all(|byte| *byte == key[0]));
+                state[0] = Some(key[0]);
+            }
+            if key_store.is_key_available(KEY3_INFO.id) {
+                let value = key_store
+                    .export_public_key(KEY3_INFO.id, &mut dest)
+                    .expect("failed to open public key")[0];
+                let private_key = key_store
+                    .export_private_key(KEY3_INFO.id, &mut dest)
+                    .expect("failed to open private key");
+                assert!(private_key.iter().all(|byte| *byte == value));
+                state[1] = Some(value);
+            }
+            state
+        }
+
+        // Count the flash operations of the uninterrupted updates
+        let mut flash = Flash::new();
+        let mut uninterrupted_key_store =
+            key_store(&mut flash).expect("failed to create key store");
+        for value in 0..NUM_UPDATES {
+            update(&mut uninterrupted_key_store, value).expect("failed to update keys");
+        }
+        drop(uninterrupted_key_store);
+        let num_operations = flash.operations();
+
+        for cut in 0..num_operations {
+            for completed_bytes in (0..SECTOR_SIZE).step_by(16) {
+                let mut flash = Flash::new();
+                flash.cut_power(cut, completed_bytes);
+                let mut value = 0;
+                if let Ok(mut key_store) = key_store(&mut flash) {
+                    while value < NUM_UPDATES && update(&mut key_store, value).is_ok() {
+                        value += 1;
+                    }
+                }
+                assert!(!flash.is_powered());
+
+                // Every key is intact and in its state before or after the interrupted update
+                flash.restore_power();
+                let key_store = key_store(&mut flash).expect("failed to mount key store");
+                let before = value.checked_sub(1);
+                for key_value in state(&key_store) {
+                    assert!(key_value == before || key_value == Some(value));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1056 @@
+use crate::hsm::keystore::{Error, KeyId, KeyInfo, KeyStore, KeyType};
+use core::cell::RefCell;
+use core::ops::Range;
+use embedded_storage::nor_flash::NorFlash;
+use heapless::Vec;
+use zeroize::Zeroizing;
+
+/// Marks a sector that is in use.
+const SECTOR_MAGIC: u32 = 0x4B53_4543;
+/// Marks a record holding a key.
+const RECORD_MAGIC: u32 = 0x4B45_5952;
+/// Marks a record announcing that a sector is about to be erased.
+const ERASE_MAGIC: u32 = 0x4B45_5241;
+/// Value of erased flash memory.
+const ERASED: u8 = 0xFF;
+/// Size of the sector header: magic, sequence number and CRC.
+const SECTOR_HEADER_SIZE: usize = 12;
+/// Size of the key metadata in the record header.
+const METADATA_SIZE: usize = KeyInfo::SERIALIZED_SIZE;
+/// Offsets of the record header fields following the magic and the record sequence number.
+const METADATA_OFFSET: usize = 8;
+const KEY_SIZE_OFFSET: usize = METADATA_OFFSET + METADATA_SIZE;
+const CRC_OFFSET: usize = KEY_SIZE_OFFSET + 2;
+/// Size of the record header: magic, record sequence number, key metadata, size of the key
+/// material and CRC.
+const RECORD_HEADER_SIZE: usize = CRC_OFFSET + 4;
+/// Maximum size of a record including the padding to the write size of the flash.
+const MAX_RECORD_SIZE: usize = 256;
+
+/// Key store that persists keys in NOR flash.
+///
+/// Keys are appended as records to a log that spans all sectors of the flash. Each record holds
+/// the metadata of its key next to the key material, so that records of a key whose definition
+/// changed (e.g. after a firmware update) are not mistaken for valid keys. Overwriting a key
+/// appends a new record and deleting a key appends an empty record. The record with the highest
+/// sequence number is the valid one.
+///
+/// Once the current sector is full, writing continues in the next erased sector. When the last
+/// erased sector is taken, the valid records of the oldest sector are moved to it and the oldest
+/// sector is erased. Sectors are thus written and erased in turn, which levels the wear of the
+/// flash. Outdated records remain in flash until their sector is erased.
+///
+/// Updates are safe against power loss. The previous record of a key stays valid until the new
+/// record has been written completely, which is verified with a CRC. When the store is mounted,
+/// incomplete records are ignored and interrupted sector changes are either rolled back or
+/// completed, so that every key is in the state before or after the interrupted operation.
+pub struct FlashKeyStore<F: NorFlash, const MAX_KEYS: usize> {
+    flash: RefCell<F>,
+    /// Key definitions sorted by key ID
+    slots: Vec<Slot, MAX_KEYS>,
+    num_sectors: u32,
+    /// Sector that records are appended to.
+    head: u32,
+    /// Sequence number of the head sector. Sectors that were started later have higher numbers.
+    sector_sequence: u32,
+    /// Sequence number of the next record.
+    record_sequence: u32,
+    /// Address of the next record.
+    offset: u32,
+    /// Number of bytes stored after every symmetric and private key.
+    trailer_size: usize,
+}
+
+/// Key definition together with the location of its latest record.
+#[derive(Copy, Clone, Debug)]
+struct Slot {
+    info: KeyInfo,
+    /// Address of the latest record or `None` if the key is not present.
+    address: Option<u32>,
+    /// Size of the stored key material.
+    size: usize,
+    /// Sequence number of the latest record or 0 if there is none.
+    sequence: u32,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum SectorState {
+    Erased,
+    Used { sequence: u32 },
+    Invalid,
+}
+
+impl<F: NorFlash, const MAX_KEYS: usize> FlashKeyStore<F, MAX_KEYS> {
+    /// Mount the key store on `flash` and load the keys stored in it. An empty or unformatted
+    /// flash is formatted.
+    pub fn try_new(flash: F, key_infos: &[KeyInfo]) -> Result<Self, Error> {
+        Self::try_new_with_trailer(flash, key_infos, 0)
+    }
+
+    /// Mount a key store that stores `trailer_size` bytes after every symmetric and private key,
+    /// e.g. the nonce and tag of an encrypted key. The trailer is written in the same record as
+    /// the key, so that both are always updated at once.
+    pub fn try_new_with_trailer(
+        flash: F,
+        key_infos: &[KeyInfo],
+        trailer_size: usize,
+    ) -> Result<Self, Error> {
+        if key_infos.len() > MAX_KEYS {
+            return Err(Error::KeyStoreTooSmall);
+        }
+        let mut slots: Vec<Slot, MAX_KEYS> = key_infos
+            .iter()
+            .map(|info| Slot {
+                info: *info,
+                address: None,
+                size: 0,
+                sequence: 0,
+            })
+            .collect();
+        slots.sort_unstable_by_key(|slot| slot.info.id);
+        if slots.windows(2).any(|w| w[0].info.id == w[1].info.id) {
+            return Err(Error::DuplicateIds);
+        }
+
+        // Every record has to fit into the record buffer. All keys, the record that is about to
+        // be written and the erase announcement have to fit into a single sector, so that moving
+        // the valid records of a sector never runs out of space.
+        let max_record_size = Self::record_size(
+            KeyType::MAX_PUBLIC_KEY_SIZE + KeyType::MAX_PRIVATE_KEY_SIZE + trailer_size,
+        );
+        if max_record_size > MAX_RECORD_SIZE || F::ERASE_SIZE % F::WRITE_SIZE != 0 {
+            return Err(Error::Storage);
+        }
+        let num_sectors = flash.capacity() / F::ERASE_SIZE;
+        let usable_size = F::ERASE_SIZE.saturating_sub(Self::data_start() as usize);
+        let largest_record = slots
+            .iter()
+            .map(|slot| Self::record_size(slot.info.ty.key_size() + trailer_size))
+            .max()
+            .unwrap_or(0);
+        let total_size: usize = slots
+            .iter()
+            .map(|slot| Self::record_size(slot.info.ty.key_size() + trailer_size))
+            .sum();
+        if num_sectors < 2 || total_size + largest_record + Self::record_size(0) > usable_size {
+            return Err(Error::KeyStoreTooSmall);
+        }
+
+        let mut key_store = Self {
+            flash: RefCell::new(flash),
+            slots,
+            num_sectors: num_sectors as u32,
+            head: 0,
+            sector_sequence: 0,
+            record_sequence: 1,
+            offset: 0,
+            trailer_size,
+        };
+        key_store.mount()?;
+        Ok(key_store)
+    }
+
+    fn mount(&mut self) -> Result<(), Error> {
+        for sector in 0..self.num_sectors {
+            match self.sector_state(sector)? {
+                SectorState::Used { .. } => {}
+                SectorState::Erased => {
+                    if !self.is_erased(self.sector_range(sector))? {
+                        self.erase_sector(sector)?;
+                    }
+                }
+                SectorState::Invalid => self.erase_sector(sector)?,
+            }
+        }
+        self.load()?;
+        if self.spare_sector()?.is_none() {
+            // A sector change was interrupted while the valid records of the oldest sector were
+            // moved to the head sector. They are still valid in the oldest sector, so the head
+            // sector is discarded and the sector change is repeated by the next write.
+            self.erase_sector(self.head)?;
+            self.load()?;
+        }
+        Ok(())
+    }
+
+    /// Load the latest records of all keys, starting with the newest sector.
+    fn load(&mut self) -> Result<(), Error> {
+        for slot in self.slots.iter_mut() {
+            slot.address = None;
+            slot.size = 0;
+            slot.sequence = 0;
+        }
+        self.record_sequence = 1;
+        let Some((sequence, head)) = self.newest_sector(None)? else {
+            return self.start_sector(0, 0);
+        };
+        self.head = head;
+        self.sector_sequence = sequence;
+
+        let mut erased_sequence = None;
+        let mut next = Some((sequence, head));
+        while let Some((sequence, sector)) = next {
+            next = self.newest_sector(Some(sequence))?;
+            if erased_sequence.is_some_and(|erased_sequence| sequence <= erased_sequence) {
+                // Complete an interrupted erase
+                self.erase_sector(sector)?;
+            } else {
+                self.replay(sector, &mut erased_sequence)?;
+            }
+        }
+
+        // Nothing can be appended after data of an interrupted write
+        let end = self.head_end();
+        if !self.is_erased(self.offset..end)? {
+            self.offset = end;
+        }
+        Ok(())
+    }
+
+    /// Load the records of a sector into the key slots. Erase announcements raise
+    /// `erased_sequence` to the sequence number of the announced sector.
+    fn replay(&mut self, sector: u32, erased_sequence: &mut Option<u32>) -> Result<(), Error> {
+        let end = self.sector_range(sector).end;
+        let header_size = Self::aligned(RECORD_HEADER_SIZE);
+        let mut buffer = Zeroizing::new([0u8; MAX_RECORD_SIZE]);
+        let mut address = Self::sector_address(sector) + Self::data_start();
+        while address as usize + header_size <= end as usize {
+            self.read(address, &mut buffer[..header_size])?;
+            let magic = read_u32(&buffer[0..4]);
+            if magic == u32::MAX {
+                break;
+            }
+            let key_size =
+                u16::from_le_bytes([buffer[KEY_SIZE_OFFSET], buffer[KEY_SIZE_OFFSET + 1]]) as usize;
+            let size = Self::record_size(key_size);
+            if (magic != RECORD_MAGIC && magic != ERASE_MAGIC)
+                || size > MAX_RECORD_SIZE
+                || address as usize + size > end as usize
+            {
+                address = end;
+                break;
+            }
+            self.read(address, &mut buffer[..size])?;
+            if read_u32(&buffer[CRC_OFFSET..])
+                != record_crc(&buffer[..RECORD_HEADER_SIZE + key_size])
+            {
+                // Record of an interrupted write. Nothing was written after it.
+                address = end;
+                break;
+            }
+            let sequence = read_u32(&buffer[4..8]);
+            self.record_sequence = self.record_sequence.max(sequence.wrapping_add(1));
+            let id = read_u32(&buffer[METADATA_OFFSET..]);
+            if magic == ERASE_MAGIC {
+                *erased_sequence = Some(erased_sequence.map_or(id, |erased| erased.max(id)));
+            } else if let Ok(index) = self.slot_index(KeyId(id)) {
+                let trailer_size = self.trailer_size;
+                let slot = &mut self.slots[index];
+                if sequence > slot.sequence
+                    && (key_size == 0 || key_size == slot.info.ty.key_size() + trailer_size)
+                    && buffer[METADATA_OFFSET..KEY_SIZE_OFFSET] == slot.info.to_bytes()
+                {
+                    slot.address = (key_size > 0).then_some(address);
+                    slot.size = key_size;
+                    slot.sequence = sequence;
+                }
+            }
+            address += size as u32;
+        }
+        if sector == self.head {
+            self.offset = address;
+        }
+        Ok(())
+    }
+
+    /// Append a record for the key described by `info`. The key material is the concatenation of
+    /// `parts`.
+    ///
+    /// returns: The address of the new record or an error.
+    fn append(&mut self, info: &KeyInfo, parts: &[&[u8]]) -> Result<u32, Error> {
+        let key_size = parts.iter().map(|part| part.len()).sum();
+        if self.offset as usize + Self::record_size(key_size) > self.head_end() as usize {
+            self.advance()?;
+        }
+        self.write_record(RECORD_MAGIC, &info.to_bytes(), parts)
+    }
+
+    /// Write a record to the head sector.
+    ///
+    /// returns: The address of the new record or an error.
+    fn write_record(
+        &mut self,
+        magic: u32,
+        metadata: &[u8; METADATA_SIZE],
+        parts: &[&[u8]],
+    ) -> Result<u32, Error> {
+        let key_size = parts.iter().map(|part| part.len()).sum();
+        let size = Self::record_size(key_size);
+        if self.offset as usize + size > self.head_end() as usize {
+            return Err(Error::KeyStoreTooSmall);
+        }
+        let mut buffer = Zeroizing::new([ERASED; MAX_RECORD_SIZE]);
+        buffer[0..4].copy_from_slice(&magic.to_le_bytes());
+        buffer[4..8].copy_from_slice(&self.record_sequence.to_le_bytes());
+        buffer[METADATA_OFFSET..KEY_SIZE_OFFSET].copy_from_slice(metadata);
+        buffer[KEY_SIZE_OFFSET..CRC_OFFSET].copy_from_slice(&(key_size as u16).to_le_bytes());
+        let mut position = RECORD_HEADER_SIZE;
+        for part in parts {
+            buffer[position..position + part.len()].copy_from_slice(part);
+            position += part.len();
+        }
+        let crc = record_crc(&buffer[..RECORD_HEADER_SIZE + key_size]);
+        buffer[CRC_OFFSET..RECORD_HEADER_SIZE].copy_from_slice(&crc.to_le_bytes());
+        let address = self.offset;
+        self.write(address, &buffer[..size])?;
+        self.offset += size as u32;
+        self.record_sequence = self.record_sequence.wrapping_add(1);
+        Ok(address)
+    }
+
+    /// Continue in the next erased sector. The oldest sector is freed if the last erased sector
+    /// was taken.
+    fn advance(&mut self) -> Result<(), Error> {
+        let spare = self.spare_sector()?.ok_or(Error::Storage)?;
+        self.start_sector(spare, self.sector_sequence.wrapping_add(1))?;
+        if self.spare_sector()?.is_none() {
+            self.collect()?;
+        }
+        Ok(())
+    }
+
+    /// Move the valid records of the oldest sector to the head sector and erase the oldest
+    /// sector.
+    fn collect(&mut self) -> Result<(), Error> {
+        let Some((sequence, oldest)) = self.oldest_sector()? else {
+            return Ok(());
+        };
+        if oldest == self.head {
+            return Ok(());
+        }
+        let sector = self.sector_range(oldest);
+        let mut buffer = Zeroizing::new([0u8; MAX_RECORD_SIZE]);
+        for index in 0..self.slots.len() {
+            let slot = self.slots[index];
+            let Some(address) = slot.address.filter(|address| sector.contains(address)) else {
+                continue;
+            };
+            // Records are moved with their sequence number
+            let size = Self::record_size(slot.size);
+            if self.offset as usize + size > self.head_end() as usize {
+                return Err(Error::KeyStoreTooSmall);
+            }
+            self.read(address, &mut buffer[..size])?;
+            self.write(self.offset, &buffer[..size])?;
+            self.slots[index].address = Some(self.offset);
+            self.offset += size as u32;
+        }
+        // Outdated records of the oldest sector must not become valid again if the erase is
+        // interrupted, so the erase is announced first.
+        let mut metadata = [0u8; METADATA_SIZE];
+        metadata[0..4].copy_from_slice(&sequence.to_le_bytes());
+        self.write_record(ERASE_MAGIC, &metadata, &[])?;
+        self.erase_sector(oldest)
+    }
+
+    /// Make `sector` the head sector.
+    fn start_sector(&mut self, sector: u32, sequence: u32) -> Result<(), Error> {
+        let mut header = [ERASED; MAX_RECORD_SIZE];
+        header[0..4].copy_from_slice(&SECTOR_MAGIC.to_le_bytes());
+        header[4..8].copy_from_slice(&sequence.to_le_bytes());
+        let crc = crc32(&[&header[0..8]]);
+        header[8..12].copy_from_slice(&crc.to_le_bytes());
+        self.write(
+            Self::sector_address(sector),
+            &header[..Self::data_start() as usize],
+        )?;
+        self.head = sector;
+        self.sector_sequence = sequence;
+        self.offset = Self::sector_address(sector) + Self::data_start();
+        Ok(())
+    }
+
+    fn sector_state(&self, sector: u32) -> Result<SectorState, Error> {
+        let mut header = [0u8; MAX_RECORD_SIZE];
+        let header = &mut header[..Self::data_start() as usize];
+        self.read(Self::sector_address(sector), header)?;
+        if header.iter().all(|byte| *byte == ERASED) {
+            Ok(SectorState::Erased)
+        } else if read_u32(&header[0..4]) == SECTOR_MAGIC
+            && read_u32(&header[8..12]) == crc32(&[&header[0..8]])
+        {
+            Ok(SectorState::Used {
+                sequence: read_u32(&header[4..8]),
+            })
+        } else {
+            Ok(SectorState::Invalid)
+        }
+    }
+
+    /// Find the used sector with the lowest sequence number.
+    ///
+    /// returns: The sequence number and index of the sector, if there is one.
+    fn oldest_sector(&self) -> Result<Option<(u32, u32)>, Error> {
+        let mut oldest: Option<(u32, u32)> = None;
+        for sector in 0..self.num_sectors {
+            if let SectorState::Used { sequence } = self.sector_state(sector)? {
+                if oldest.is_none_or(|(oldest_sequence, _)| sequence < oldest_sequence) {
+                    oldest = Some((sequence, sector));
+                }
+            }
+        }
+        Ok(oldest)
+    }
+
+    /// Find the used sector with the highest sequence number below `below`.
+    ///
+    /// returns: The sequence number and index of the sector, if there is one.
+    fn newest_sector(&self, below: Option<u32>) -> Result<Option<(u32, u32)>, Error> {
+        let mut newest: Option<(u32, u32)> = None;
+        for sector in 0..self.num_sectors {
+            if let SectorState::Used { sequence } = self.sector_state(sector)? {
+                if below.is_none_or(|below| sequence < below)
+                    && newest.is_none_or(|(newest_sequence, _)| sequence > newest_sequence)
+                {
+                    newest = Some((sequence, sector));
+                }
+            }
+        }
+        Ok(newest)
+    }
+
+    /// Find the first erased sector after the head sector.
+    fn spare_sector(&self) -> Result<Option<u32>, Error> {
+        for offset in 1..self.num_sectors {
+            let sector = (self.head + offset) % self.num_sectors;
+            if self.sector_state(sector)? == SectorState::Erased {
+                return Ok(Some(sector));
+            }
+        }
+        Ok(None)
+    }
+
+    fn is_erased(&self, range: Range<u32>) -> Result<bool, Error> {
+        let mut buffer = [0u8; MAX_RECORD_SIZE];
+        let mut address = range.start;
+        while address < range.end {
+            let chunk = &mut buffer[..MAX_RECORD_SIZE.min((range.end - address) as usize)];
+            self.read(address, chunk)?;
+            if chunk.iter().any(|byte| *byte != ERASED) {
+                return Ok(false);
+            }
+            address += chunk.len() as u32;
+        }
+        Ok(true)
+    }
+
+    fn slot_index(&self, id: KeyId) -> Result<usize, Error> {
+        self.slots
+            .binary_search_by_key(&id, |slot| slot.info.id)
+            .map_err(|_| Error::InvalidKeyId)
+    }
+
+    /// Copy `range` of the key material of `slot` to `dest`.
+    fn read_key<'data>(
+        &self,
+        slot: &Slot,
+        range: Range<usize>,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let address = slot.address.ok_or(Error::KeyNotFound)?;
+        if dest.len() < range.len() {
+            return Err(Error::InvalidBufferSize);
+        }
+        let mut buffer = Zeroizing::new([0u8; MAX_RECORD_SIZE]);
+        self.read(address, &mut buffer[..Self::record_size(slot.size)])?;
+        let dest = &mut dest[..range.len()];
+        dest.copy_from_slice(&buffer[RECORD_HEADER_SIZE..][range]);
+        Ok(dest)
+    }
+
+    fn read(&self, address: u32, dest: &mut [u8]) -> Result<(), Error> {
+        self.flash
+            .borrow_mut()
+            .read(address, dest)
+            .map_err(|_| Error::Storage)
+    }
+
+    fn write(&self, address: u32, data: &[u8]) -> Result<(), Error> {
+        self.flash
+            .borrow_mut()
+            .write(address, data)
+            .map_err(|_| Error::Storage)
+    }
+
+    fn erase_sector(&self, sector: u32) -> Result<(), Error> {
+        let sector = self.sector_range(sector);
+        self.flash
+            .borrow_mut()
+            .erase(sector.start, sector.end)
+            .map_err(|_| Error::Storage)
+    }
+
+    fn head_end(&self) -> u32 {
+        self.sector_range(self.head).end
+    }
+
+    fn sector_range(&self, sector: u32) -> Range<u32> {
+        let start = Self::sector_address(sector);
+        start..start + F::ERASE_SIZE as u32
+    }
+
+    fn sector_address(sector: u32) -> u32 {
+        sector * F::ERASE_SIZE as u32
+    }
+
+    /// Offset of the first record in a sector.
+    fn data_start() -> u32 {
+        Self::aligned(SECTOR_HEADER_SIZE) as u32
+    }
+
+    fn record_size(key_size: usize) -> usize {
+        Self::aligned(RECORD_HEADER_SIZE + key_size)
+    }
+
+    /// Round `size` up to a multiple of the read and write sizes of the flash.
+    fn aligned(size: usize) -> usize {
+        let alignment = F::READ_SIZE.max(F::WRITE_SIZE);
+        size.div_ceil(alignment) * alignment
+    }
+}
+
+impl<F: NorFlash, const MAX_KEYS: usize> KeyStore for FlashKeyStore<F, MAX_KEYS> {
+    fn get_key_info(&self, id: KeyId) -> Result<KeyInfo, Error> {
+        Ok(self.slots[self.slot_index(id)?].info)
+    }
+
+    fn import_symmetric_key(
+        &mut self,
+        id: KeyId,
+        data: &[u8],
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let index = self.slot_index(id)?;
+        let slot = self.slots[index];
+        if !slot.info.ty.is_symmetric() {
+            return Err(Error::InvalidKeyType);
+        }
+        if !slot.info.permissions.import {
+            return Err(Error::NotAllowed);
+        }
+        if slot.address.is_some() && (!overwrite || !slot.info.permissions.overwrite) {
+            return Err(Error::NotAllowed);
+        }
+        if data.len() != slot.info.ty.key_size() + self.trailer_size {
+            return Err(Error::InvalidBufferSize);
+        }
+        let address = self.append(&slot.info, &[data])?;
+        self.slots[index].address = Some(address);
+        self.slots[index].size = data.len();
+        Ok(())
+    }
+
+    fn import_key_pair(
+        &mut self,
+        id: KeyId,
+        public_key: &[u8],
+        private_key: &[u8],
+        overwrite: bool,
+    ) -> Result<(), Error> {
+        let index = self.slot_index(id)?;
+        let slot = self.slots[index];
+        if !slot.info.ty.is_asymmetric() {
+            return Err(Error::InvalidKeyType);
+        }
+        if !slot.info.permissions.import {
+            return Err(Error::NotAllowed);
+        }
+        if slot.address.is_some() && (!overwrite || !slot.info.permissions.overwrite) {
+            return Err(Error::NotAllowed);
+        }
+        if (public_key.len() != slot.info.ty.public_key_size())
+            || (private_key.len() != slot.info.ty.private_key_size() + self.trailer_size)
+        {
+            return Err(Error::InvalidBufferSize);
+        }
+        let address = self.append(&slot.info, &[public_key, private_key])?;
+        self.slots[index].address = Some(address);
+        self.slots[index].size = public_key.len() + private_key.len();
+        Ok(())
+    }
+
+    fn export_symmetric_key<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let slot = &self.slots[self.slot_index(id)?];
+        if !slot.info.permissions.export_private {
+            return Err(Error::NotAllowed);
+        }
+        self.export_symmetric_key_unchecked(id, dest)
+    }
+
+    fn export_public_key<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let slot = &self.slots[self.slot_index(id)?];
+        if !slot.info.ty.is_asymmetric() {
+            return Err(Error::InvalidKeyType);
+        }
+        self.read_key(slot, 0..slot.info.ty.public_key_size(), dest)
+    }
+
+    fn export_private_key<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let slot = &self.slots[self.slot_index(id)?];
+        if !slot.info.permissions.export_private {
+            return Err(Error::NotAllowed);
+        }
+        self.export_private_key_unchecked(id, dest)
+    }
+
+    fn export_symmetric_key_unchecked<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let slot = &self.slots[self.slot_index(id)?];
+        if !slot.info.ty.is_symmetric() {
+            return Err(Error::InvalidKeyType);
+        }
+        self.read_key(slot, 0..slot.size, dest)
+    }
+
+    fn export_private_key_unchecked<'data>(
+        &self,
+        id: KeyId,
+        dest: &'data mut [u8],
+    ) -> Result<&'data [u8], Error> {
+        let slot = &self.slots[self.slot_index(id)?];
+        if !slot.info.ty.is_asymmetric() {
+            return Err(Error::InvalidKeyType);
+        }
+        let public_key_size = slot.info.ty.public_key_size();
+        self.read_key(slot, public_key_size..slot.size, dest)
+    }
+
+    fn delete(&mut self, id: KeyId) -> Result<(), Error> {
+        let index = self.slot_index(id)?;
+        let slot = self.slots[index];
+        if !slot.info.permissions.delete {
+            return Err(Error::NotAllowed);
+        }
+        if slot.address.is_none() {
+            return Err(Error::KeyNotFound);
+        }
+        self.append(&slot.info, &[])?;
+        self.slots[index].address = None;
+        self.slots[index].size = 0;
+        Ok(())
+    }
+
+    fn is_key_available(&self, id: KeyId) -> bool {
+        match self.slot_index(id) {
+            Err(_) => false,
+            Ok(index) => self.slots[index].address.is_some(),
+        }
+    }
+
+    fn size(&self, id: KeyId) -> Result<usize, Error> {
+        let slot = &self.slots[self.slot_index(id)?];
+        if slot.address.is_none() {
+            return Err(Error::KeyNotFound);
+        }
+        Ok(slot.size)
+    }
+
+    fn num_keys(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn key_info_at(&self, index: usize) -> Option<KeyInfo> {
+        self.slots.get(index).map(|slot| slot.info)
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// CRC of a record covering the header without the CRC field and the key material.
+fn record_crc(record: &[u8]) -> u32 {
+    crc32(&[&record[..CRC_OFFSET], &record[RECORD_HEADER_SIZE..]])
+}
+
+/// CRC-32 (IEEE 802.3) of the concatenation of `parts`.
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in parts.iter().flat_map(|part| part.iter()) {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::hsm::keystore::{KeyAccessControl, KeyPermissions, KeyUsageFlags};
+    use crate::integration::ram_flash::RamFlash;
+    use embedded_storage::nor_flash::{ErrorType, ReadNorFlash};
+
+    const SECTOR_SIZE: usize = 512;
+    const NUM_SECTORS: usize = 4;
+    type Flash = RamFlash<{ NUM_SECTORS * SECTOR_SIZE }, SECTOR_SIZE>;
+
+    const PERMISSIONS: KeyPermissions = KeyPermissions {
+        import: true,
+        export_private: true,
+        overwrite: true,
+        delete: true,
+    };
+    const USAGE: KeyUsageFlags = KeyUsageFlags {
+        encrypt: true,
+        decrypt: true,
+        sign: true,
+        verify: true,
+        derive: false,
+        wrap: false,
+        unwrap: false,
+        mac: false,
+    };
+    const KEY1_INFO: KeyInfo = KeyInfo {
+        id: KeyId(5),
+        ty: KeyType::Symmetric128Bits,
+        permissions: PERMISSIONS,
+        usage: USAGE,
+        algorithm: None,
+        access: KeyAccessControl::ALL,
+    };
+    const KEY2_INFO: KeyInfo = KeyInfo {
+        id: KeyId(3),
+        ty: KeyType::EccKeypairNistP256,
+        permissions: PERMISSIONS,
+        usage: USAGE,
+        algorithm: None,
+        access: KeyAccessControl::ALL,
+    };
+    const KEY_INFOS: [KeyInfo; 2] = [KEY1_INFO, KEY2_INFO];
+
+    /// Flash wrapper counting the erase operations per sector.
+    struct WearCountingFlash<'a> {
+        flash: &'a mut Flash,
+        erase_counts: [u32; NUM_SECTORS],
+    }
+
+    impl ErrorType for WearCountingFlash<'_> {
+        type Error = <Flash as ErrorType>::Error;
+    }
+
+    impl ReadNorFlash for WearCountingFlash<'_> {
+        const READ_SIZE: usize = Flash::READ_SIZE;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            self.flash.read(offset, bytes)
+        }
+
+        fn capacity(&self) -> usize {
+            self.flash.capacity()
+        }
+    }
+
+    impl NorFlash for WearCountingFlash<'_> {
+        const WRITE_SIZE: usize = Flash::WRITE_SIZE;
+        const ERASE_SIZE: usize = Flash::ERASE_SIZE;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            for sector in from as usize / SECTOR_SIZE..to as usize / SECTOR_SIZE {
+                self.erase_counts[sector] += 1;
+            }
+            self.flash.erase(from, to)
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            self.flash.write(offset, bytes)
+        }
+    }
+
+    fn import_keys(key_store: &mut dyn KeyStore, value: u8) {
+        let key = [value; KEY2_INFO.ty.key_size()];
+        key_store
+            .import_symmetric_key(KEY1_INFO.id, &key[..KEY1_INFO.ty.key_size()], true)
+            .expect("failed to import key");
+        key_store
+            .import_key_pair(
+                KEY2_INFO.id,
+                &key[..KEY2_INFO.ty.public_key_size()],
+                &key[KEY2_INFO.ty.public_key_size()..],
+                true,
+            )
+            .expect("failed to import key pair");
+    }
+
+    fn check_keys(key_store: &dyn KeyStore, value: u8) {
+        let mut dest = [0u8; KEY2_INFO.ty.key_size()];
+        let key = key_store
+            .export_symmetric_key(KEY1_INFO.id, &mut dest)
+            .expect("failed to export key");
+        assert_eq!(key, [value; KEY1_INFO.ty.key_size()]);
+        let key = key_store
+            .export_public_key(KEY2_INFO.id, &mut dest)
+            .expect("failed to export public key");
+        assert_eq!(key, [value; KEY2_INFO.ty.public_key_size()]);
+        let key = key_store
+            .export_private_key(KEY2_INFO.id, &mut dest)
+            .expect("failed to export private key");
+        assert_eq!(key, [value; KEY2_INFO.ty.private_key_size()]);
+    }
+
+    #[test]
+    fn persistence() {
+        let mut flash = Flash::new();
+        let mut key_store = FlashKeyStore::<_, 2>::try_new(&mut flash, &KEY_INFOS)
+            .expect("failed to create key store");
+        assert!(!key_store.is_key_available(KEY1_INFO.id));
+        assert!(!key_store.is_key_available(KEY2_INFO.id));
+        import_keys(&mut key_store, 1);
+        import_keys(&mut key_store, 2);
+        check_keys(&key_store, 2);
+        drop(key_store);
+
+        let mut key_store = FlashKeyStore::<_, 2>::try_new(&mut flash, &KEY_INFOS)
+            .expect("failed to mount key store");
+        check_keys(&key_store, 2);
+        assert_eq!(key_store.size(KEY2_INFO.id), Ok(KEY2_INFO.ty.key_size()));
+        key_store
+            .delete(KEY1_INFO.id)
+            .expect("failed to delete key");
+        assert_eq!(key_store.delete(KEY1_INFO.id), Err(Error::KeyNotFound));
+        drop(key_store);
+
+        let key_store = FlashKeyStore::<_, 2>::try_new(&mut flash, &KEY_INFOS)
+            .expect("failed to mount key store");
+        assert!(!key_store.is_key_available(KEY1_INFO.id));
+        assert!(key_store.is_key_available(KEY2_INFO.id));
+    }
+
+    #[test]
+    fn wear_leveling() {
+        let mut flash = Flash::new();
+        let mut wear_counting_flash = WearCountingFlash {
+            flash: &mut flash,
+            erase_counts: [0; NUM_SECTORS],
+        };
+        let mut key_store = FlashKeyStore::<_, 2>::try_new(&mut wear_counting_flash, &KEY_INFOS)
+            .expect("failed to create key store");
+        for value in 0..200 {
+            import_keys(&mut key_store, value);
+            check_keys(&key_store, value);
+        }
+        drop(key_store);
+        let erase_counts = wear_counting_flash.erase_counts;
+        let min = erase_counts.iter().min().expect("no sectors");
+        let max = erase_counts.iter().max().expect("no sectors");
+        assert!(*min > 0);
+        assert!(max - min <= 1);
+
+        let key_store = FlashKeyStore::<_, 2>::try_new(&mut flash, &KEY_INFOS)
+            .expect("failed to mount key store");
+        check_keys(&key_store, 199);
+    }
+
+    #[test]
+    fn changed_key_definition() {
+        let mut flash = Flash::new();
+        let mut key_store = FlashKeyStore::<_, 2>::try_new(&mut flash, &KEY_INFOS)
+            .expect("failed to create key store");
+        import_keys(&mut key_store, 1);
+        drop(key_store);
+
+        let changed_key_infos = [
+            KeyInfo {
+                ty: KeyType::Symmetric256Bits,
+                ..KEY1_INFO
+            },
+            KeyInfo {
+                permissions: KeyPermissions {
+                    delete: false,
+                    ..PERMISSIONS
+                },
+                ..KEY2_INFO
+            },
+        ];
+        let key_store = FlashKeyStore::<_, 2>::try_new(&mut flash, &changed_key_infos)
+            .expect("failed to mount key store");
+        assert!(!key_store.is_key_available(KEY1_INFO.id));
+        assert!(!key_store.is_key_available(KEY2_INFO.id));
+    }
+
+    #[test]
+    fn invalid_configuration() {
+        let mut flash = Flash::new();
+        assert_eq!(
+            FlashKeyStore::<_, 1>::try_new(&mut flash, &KEY_INFOS).err(),
+            Some(Error::KeyStoreTooSmall)
+        );
+        assert_eq!(
+            FlashKeyStore::<_, 2>::try_new(&mut flash, &[KEY1_INFO, KEY1_INFO]).err(),
+            Some(Error::DuplicateIds)
+        );
+        let mut small_flash = RamFlash::<{ NUM_SECTORS * 128 }, 128>::new();
+        assert_eq!(
+            FlashKeyStore::<_, 2>::try_new(&mut small_flash, &KEY_INFOS).err(),
+            Some(Error::KeyStoreTooSmall)
+        );
+    }
+
+    #[test]
+    fn crc() {
+        assert_eq!(crc32(&[b"1234", b"56789"]), 0xCBF4_3926);
+    }
+
+    /// Number of steps of the power cut scenario.
+    const POWER_CUT_STEPS: usize = 60;
+
+    #[derive(Copy, Clone, Debug)]
+    enum Operation {
+        Import { key: usize, value: u8 },
+        Delete { key: usize },
+    }
+
+    /// Values of the two keys or `None` if a key is not present.
+    type State = [Option<u8>; 2];
+
+    /// Operation in step `step` of the power cut scenario. The operations are chosen such that
+    /// they always succeed in absence of power cuts. The first key is deleted right after it was
+    /// imported and stays deleted while the flash is written more than once.
+    fn operation(step: usize) -> Operation {
+        let value = step as u8 + 1;
+        match step % 30 {
+            0 => Operation::Import { key: 0, value },
+            1 => Operation::Delete { key: 0 },
+            15 => Operation::Delete { key: 1 },
+            _ => Operation::Import { key: 1, value },
+        }
+    }
+
+    fn apply(key_store: &mut dyn KeyStore, operation: Operation) -> Result<(), Error> {
+        match operation {
+            Operation::Import { key: 0, value } => key_store.import_symmetric_key(
+                KEY1_INFO.id,
+                &[value; KEY1_INFO.ty.key_size()],
+                true,
+            ),
+            Operation::Import { value, .. } => key_store.import_key_pair(
+                KEY2_INFO.id,
+                &[value; KEY2_INFO.ty.public_key_size()],
+                &[value; KEY2_INFO.ty.private_key_size()],
+                true,
+            ),
+            Operation::Delete { key } => key_store.delete(KEY_INFOS[key].id),
+        }
+    }
+
+    fn expected_state(steps: usize) -> State {
+        let mut state = [None; 2];
+        for step in 0..steps {
+            match operation(step) {
+                Operation::Import { key, value } => state[key] = Some(value),
+                Operation::Delete { key } => state[key] = None,
+            }
+        }
+        state
+    }
+
+    fn state(key_store: &dyn KeyStore) -> State {
+        let mut state = [None; 2];
+        let mut dest = [0u8; KEY2_INFO.ty.key_size()];
+        if key_store.is_key_available(KEY1_INFO.id) {
+            let key = key_store
+                .export_symmetric_key(KEY1_INFO.id, &mut dest)
+                .expect("failed to export key");
+            assert!(key.iter().all(|byte| *byte == key[0]));
+            state[0] = Some(key[0]);
+        }
+        if key_store.is_key_available(KEY2_INFO.id) {
+            let public_key = key_store
+                .export_public_key(KEY2_INFO.id, &mut dest)
+                .expect("failed to export public key");
+            let value = public_key[0];
+            assert!(public_key.iter().all(|byte| *byte == value));
+            let private_key = key_store
+                .export_private_key(KEY2_INFO.id, &mut dest)
+                .expect("failed to export private key");
+            assert!(private_key.iter().all(|byte| *byte == value));
+            state[1] = Some(value);
+        }
+        state
+    }
+
+    /// Mount the key store while cutting the power at every operation of the recovery itself.
+    fn recover(flash: &mut Flash, completed_bytes: usize) -> State {
+        for cut in 0..16 {
+            flash.restore_power();
+            flash.cut_power(cut, completed_bytes);
+            if let Ok(key_store) = FlashKeyStore::<_, 2>::try_new(&mut *flash, &KEY_INFOS) {
+                if key_store.flash.borrow().is_powered() {
+                    return state(&key_store);
+                }
+            }
+        }
+        panic!("failed to recover key store");
+    }
+
+    #[test]
+    fn power_cuts() {
+        // Count the flash operations of the uninterrupted scenario
+        let mut flash = Flash::new();
+        let mut key_store = FlashKeyStore::<_, 2>::try_new(&mut flash, &KEY_INFOS)
+            .expect("failed to create key store");
+        for step in 0..POWER_CUT_STEPS {
+            apply(&mut key_store, operation(step)).expect("failed to apply operation");
+        }
+        assert_eq!(state(&key_store), expected_state(POWER_CUT_STEPS));
+        drop(key_store);
+        let num_operations = flash.operations();
+
+        for cut in 0..num_operations {
+            // Torn headers and interrupted erases that leave every possible number of records
+            for completed_bytes in [4, 8].into_iter().chain((0..SECTOR_SIZE).step_by(16)) {
+                let mut flash = Flash::new();
+                flash.cut_power(cut, completed_bytes);
+                let mut step = 0;
+                if let Ok(mut key_store) = FlashKeyStore::<_, 2>::try_new(&mut flash, &KEY_INFOS) {
+                    while step < POWER_CUT_STEPS && apply(&mut key_store, operation(step)).is_ok() {
+                        step += 1;
+                    }
+                }
+                assert!(!flash.is_powered());
+
+                // Every key is in its state before or after the interrupted operation
+                let recovered_state = recover(&mut flash, completed_bytes);
+                if recovered_state != expected_state(step) {
+                    assert_eq!(recovered_state, expected_state(step + 1));
+                    step += 1;
+                }
+
+                // The recovered key store remains usable
+                flash.restore_power();
+                let mut key_store = FlashKeyStore::<_, 2>::try_new(&mut flash, &KEY_INFOS)
+                    .expect("failed to mount key store");
+                for step in step..POWER_CUT_STEPS {
+                    apply(&mut key_store, operation(step)).expect("failed to apply operation");
+                }
+                assert_eq!(state(&key_store), expected_state(POWER_CUT_STEPS));
+            }
+        }
+    }
+}
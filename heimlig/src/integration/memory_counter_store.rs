@@ -0,0 +1,135 @@
+use crate::hsm::counter_store::{CounterId, CounterInfo, CounterStore, Error};
+use heapless::Vec;
+
+/// Volatile counter store. Counters are lost on reset, so it is only suitable for testing.
+#[derive(Default)]
+pub struct MemoryCounterStore<const MAX_COUNTERS: usize> {
+    counters: Vec<CounterInfo, MAX_COUNTERS>,
+}
+
+impl<const MAX_COUNTERS: usize> MemoryCounterStore<MAX_COUNTERS> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get_mut(&mut self, id: CounterId) -> Result<&mut CounterInfo, Error> {
+        self.counters
+            .iter_mut()
+            .find(|counter| counter.id == id)
+            .ok_or(Error::CounterNotFound)
+    }
+}
+
+impl<const MAX_COUNTERS: usize> CounterStore for MemoryCounterStore<MAX_COUNTERS> {
+    fn create(&mut self, id: CounterId, max_value: u64) -> Result<(), Error> {
+        if self.counters.iter().any(|counter| counter.id == id) {
+            return Err(Error::CounterExists);
+        }
+        self.counters
+            .push(CounterInfo {
+                id,
+                value: 0,
+                max_value,
+            })
+            .map_err(|_| Error::CounterStoreTooSmall)
+    }
+
+    fn get_counter_info(&self, id: CounterId) -> Result<CounterInfo, Error> {
+        self.counters
+            .iter()
+            .find(|counter| counter.id == id)
+            .copied()
+            .ok_or(Error::CounterNotFound)
+    }
+
+    fn increment(&mut self, id: CounterId, amount: u64) -> Result<u64, Error> {
+        let counter = self.get_mut(id)?;
+        let value = counter
+            .value
+            .checked_add(amount)
+            .filter(|value| *value <= counter.max_value)
+            .ok_or(Error::MaxValueExceeded)?;
+        counter.value = value;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    #[test]
+    fn create_increment() {
+        const COUNTER1: CounterId = CounterId(7);
+        const COUNTER2: CounterId = CounterId(2);
+
+        let mut counter_store = MemoryCounterStore::<2>::new();
+        assert_eq!(
+            counter_store.get_counter_info(COUNTER1),
+            Err(Error::CounterNotFound)
+        );
+        assert_eq!(
+            counter_store.increment(COUNTER1, 1),
+            Err(Error::CounterNotFound)
+        );
+        counter_store
+            .create(COUNTER1, 10)
+            .expect("failed to create counter");
+        assert_eq!(
+            counter_store.create(COUNTER1, 20),
+            Err(Error::CounterExists)
+        );
+        counter_store
+            .create(COUNTER2, u64::MAX)
+            .expect("failed to create counter");
+        assert_eq!(
+            counter_store.create(CounterId(3), 1),
+            Err(Error::CounterStoreTooSmall)
+        );
+
+        assert_eq!(counter_store.increment(COUNTER1, 1), Ok(1));
+        assert_eq!(counter_store.increment(COUNTER1, 8), Ok(9));
+        assert_eq!(counter_store.increment(COUNTER2, 3), Ok(3));
+        assert_eq!(
+            counter_store.get_counter_info(COUNTER1),
+            Ok(CounterInfo {
+                id: COUNTER1,
+                value: 9,
+                max_value: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn max_value() {
+        const COUNTER: CounterId = CounterId(1);
+
+        let mut counter_store = MemoryCounterStore::<1>::new();
+        counter_store
+            .create(COUNTER, u64::MAX - 1)
+            .expect("failed to create counter");
+        assert_eq!(
+            counter_store.increment(COUNTER, u64::MAX),
+            Err(Error::MaxValueExceeded)
+        );
+        assert_eq!(
+            counter_store.increment(COUNTER, u64::MAX - 2),
+            Ok(u64::MAX - 2)
+        );
+        assert_eq!(
+            counter_store.increment(COUNTER, 2),
+            Err(Error::MaxValueExceeded)
+        );
+        assert_eq!(counter_store.increment(COUNTER, 1), Ok(u64::MAX - 1));
+        assert_eq!(
+            counter_store.increment(COUNTER, 1),
+            Err(Error::MaxValueExceeded)
+        );
+        assert_eq!(
+            counter_store
+                .get_counter_info(COUNTER)
+                .map(|info| info.value),
+            Ok(u64::MAX - 1)
+        );
+    }
+}
@@ -4,13 +4,23 @@ use heapless::Vec;
 pub struct MemoryKeyStore<const STORAGE_SIZE: usize, const MAX_KEYS: usize> {
     storage: [u8; STORAGE_SIZE],
     layout: SortedKeyStoreLayout<STORAGE_SIZE, MAX_KEYS>,
+    /// Number of bytes stored after every symmetric and private key.
+    trailer_size: usize,
 }
 
 impl<const STORAGE_SIZE: usize, const MAX_KEYS: usize> MemoryKeyStore<STORAGE_SIZE, MAX_KEYS> {
     pub fn try_new(key_infos: &[KeyInfo]) -> Result<Self, Error> {
+        Self::try_new_with_trailer(key_infos, 0)
+    }
+
+    /// Create a key store that stores `trailer_size` bytes after every symmetric and private key,
+    /// e.g. the nonce and tag of an encrypted key. The trailer is imported and exported together
+    /// with the key, so that both are always updated at once.
+    pub fn try_new_with_trailer(key_infos: &[KeyInfo], trailer_size: usize) -> Result<Self, Error> {
         Ok(Self {
             storage: [0u8; STORAGE_SIZE],
-            layout: SortedKeyStoreLayout::try_from(key_infos)?,
+            layout: SortedKeyStoreLayout::try_new(key_infos, trailer_size)?,
+            trailer_size,
         })
     }
 }
@@ -30,6 +40,7 @@ impl<const STORAGE_SIZE: usize, const NUM_KEYS: usize> KeyStore
         overwrite: bool,
     ) -> Result<(), Error> {
         let key_exists = self.is_key_available(id);
+        let trailer_size = self.trailer_size;
         let key_layout = self.layout.get_mut(id).ok_or(Error::InvalidKeyId)?;
         if !key_layout.info.ty.is_symmetric() {
             return Err(Error::InvalidKeyType);
@@ -40,7 +51,7 @@ impl<const STORAGE_SIZE: usize, const NUM_KEYS: usize> KeyStore
         if key_exists && (!overwrite || !key_layout.info.permissions.overwrite) {
             return Err(Error::NotAllowed);
         }
-        if data.len() != key_layout.info.ty.key_size() {
+        if data.len() != key_layout.info.ty.key_size() + trailer_size {
             return Err(Error::InvalidBufferSize);
         }
         let offset = key_layout.offset;
@@ -59,6 +70,7 @@ impl<const STORAGE_SIZE: usize, const NUM_KEYS: usize> KeyStore
         overwrite: bool,
     ) -> Result<(), Error> {
         let key_exists = self.is_key_available(id);
+        let trailer_size = self.trailer_size;
         let key_layout = self.layout.get_mut(id).ok_or(Error::InvalidKeyId)?;
         if !key_layout.info.ty.is_asymmetric() {
             return Err(Error::InvalidKeyType);
@@ -69,8 +81,8 @@ impl<const STORAGE_SIZE: usize, const NUM_KEYS: usize> KeyStore
         if key_exists && (!overwrite || !key_layout.info.permissions.overwrite) {
             return Err(Error::NotAllowed);
         }
-        if (public_key.len() != 2 * private_key.len())
-            || (public_key.len() + private_key.len() != key_layout.info.ty.key_size())
+        if (public_key.len() != key_layout.info.ty.public_key_size())
+            || (private_key.len() != key_layout.info.ty.private_key_size() + trailer_size)
         {
             return Err(Error::InvalidBufferSize);
         }
@@ -116,10 +128,7 @@ impl<const STORAGE_SIZE: usize, const NUM_KEYS: usize> KeyStore
         if key_layout.actual_size == 0 {
             return Err(Error::KeyNotFound);
         }
-        // When saving a key, we ensure the public key is twice as long as the private key
-        assert_eq!(key_layout.actual_size % 3, 0);
-        let private_key_size = key_layout.actual_size / 3;
-        let public_key_size = 2 * private_key_size;
+        let public_key_size = key_layout.info.ty.public_key_size();
         if dest.len() < public_key_size {
             return Err(Error::InvalidBufferSize);
         }
@@ -177,10 +186,8 @@ impl<const STORAGE_SIZE: usize, const NUM_KEYS: usize> KeyStore
         if key_layout.actual_size == 0 {
             return Err(Error::KeyNotFound);
         }
-        // When saving a key, we ensure the public key is twice as long as the private key
-        assert_eq!(key_layout.actual_size % 3, 0);
-        let private_key_size = key_layout.actual_size / 3;
-        let public_key_size = 2 * private_key_size;
+        let private_key_size = key_layout.info.ty.private_key_size() + self.trailer_size;
+        let public_key_size = key_layout.info.ty.public_key_size();
         if dest.len() < private_key_size {
             return Err(Error::InvalidBufferSize);
         }
@@ -221,6 +228,17 @@ impl<const STORAGE_SIZE: usize, const NUM_KEYS: usize> KeyStore
         }
         Ok(key_layout.actual_size)
     }
+
+    fn num_keys(&self) -> usize {
+        self.layout.inner.len()
+    }
+
+    fn key_info_at(&self, index: usize) -> Option<KeyInfo> {
+        self.layout
+            .inner
+            .get(index)
+            .map(|key_layout| key_layout.info)
+    }
 }
 
 /// Internal layout data structure of the key store. Keys are saved at an offset in the internal key
@@ -259,18 +277,13 @@ impl<const STORAGE_SIZE: usize, const MAX_KEYS: usize>
             .ok()?;
         self.inner.get_mut(index)
     }
-}
 
-impl<const STORAGE_SIZE: usize, const MAX_KEYS: usize> TryFrom<&[KeyInfo]>
-    for SortedKeyStoreLayout<STORAGE_SIZE, MAX_KEYS>
-{
-    type Error = Error;
-
-    fn try_from(key_infos: &[KeyInfo]) -> Result<Self, Self::Error> {
+    /// Create a layout that reserves `trailer_size` bytes after every key.
+    pub fn try_new(key_infos: &[KeyInfo], trailer_size: usize) -> Result<Self, Error> {
         // Check input sizes
         let total_size: usize = key_infos
             .iter()
-            .map(|key_info| key_info.ty.key_size())
+            .map(|key_info| key_info.ty.key_size() + trailer_size)
             .sum();
         if key_infos.len() > MAX_KEYS || total_size > STORAGE_SIZE {
             return Err(Error::KeyStoreTooSmall);
@@ -297,7 +310,7 @@ impl<const STORAGE_SIZE: usize, const MAX_KEYS: usize> TryFrom<&[KeyInfo]>
             ret.inner
                 .push(key_layout)
                 .expect("too many key definitions");
-            offset += key_info.ty.key_size();
+            offset += key_info.ty.key_size() + trailer_size;
         }
         Ok(ret)
     }
@@ -306,9 +319,21 @@ impl<const STORAGE_SIZE: usize, const MAX_KEYS: usize> TryFrom<&[KeyInfo]>
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
-    use crate::hsm::keystore::{Error, KeyId, KeyInfo, KeyPermissions, KeyStore, KeyType};
+    use crate::hsm::keystore::{
+        Error, KeyAccessControl, KeyId, KeyInfo, KeyPermissions, KeyStore, KeyType, KeyUsageFlags,
+    };
 
     const TOTAL_KEY_SIZE: usize = KEY1_INFO.ty.key_size() + KEY2_INFO.ty.key_size();
+    const USAGE: KeyUsageFlags = KeyUsageFlags {
+        encrypt: true,
+        decrypt: true,
+        sign: true,
+        verify: true,
+        derive: false,
+        wrap: false,
+        unwrap: false,
+        mac: false,
+    };
     const KEY1_INFO: KeyInfo = KeyInfo {
         id: KeyId(5),
         ty: KeyType::Symmetric128Bits,
@@ -318,6 +343,9 @@ pub(crate) mod test {
             overwrite: false,
             delete: true,
         },
+        usage: USAGE,
+        algorithm: None,
+        access: KeyAccessControl::ALL,
     };
     const KEY2_INFO: KeyInfo = KeyInfo {
         id: KeyId(3),
@@ -328,6 +356,9 @@ pub(crate) mod test {
             overwrite: false,
             delete: true,
         },
+        usage: USAGE,
+        algorithm: None,
+        access: KeyAccessControl::ALL,
     };
 
     #[test]
@@ -429,6 +460,9 @@ pub(crate) mod test {
                 overwrite: false,
                 delete: false,
             },
+            usage: USAGE,
+            algorithm: None,
+            access: KeyAccessControl::ALL,
         };
         let key_infos: [KeyInfo; 1] = [NO_EXPORT_NO_OVERWRITE_NO_DELETE];
         let src_buffer = [0u8; NO_EXPORT_NO_OVERWRITE_NO_DELETE.ty.key_size()];
@@ -460,6 +494,9 @@ pub(crate) mod test {
                 overwrite: true,
                 delete: false,
             },
+            usage: USAGE,
+            algorithm: None,
+            access: KeyAccessControl::ALL,
         };
         let key_infos: [KeyInfo; 1] = [NO_EXPORT_OVERWRITE_NO_DELETE];
         let src_buffer = [0u8; NO_EXPORT_OVERWRITE_NO_DELETE.ty.key_size()];
@@ -476,4 +513,15 @@ pub(crate) mod test {
             .import_symmetric_key(NO_EXPORT_OVERWRITE_NO_DELETE.id, &src_buffer, true)
             .is_ok());
     }
+
+    #[test]
+    fn enumerate_keys() {
+        let key_infos: [KeyInfo; 2] = [KEY1_INFO, KEY2_INFO];
+        let key_store = MemoryKeyStore::<{ TOTAL_KEY_SIZE }, 2>::try_new(&key_infos)
+            .expect("failed to create key store");
+        assert_eq!(key_store.num_keys(), 2);
+        let key_ids: [Option<KeyId>; 3] =
+            core::array::from_fn(|index| key_store.key_info_at(index).map(|info| info.id));
+        assert_eq!(key_ids, [Some(KEY2_INFO.id), Some(KEY1_INFO.id), None]);
+    }
 }
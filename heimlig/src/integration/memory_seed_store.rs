@@ -0,0 +1,24 @@
+use crate::crypto::rng::{SeedError, SeedStore, SEED_FILE_SIZE};
+
+/// Volatile seed store. The seed is lost on reset, so it is only suitable for testing.
+#[derive(Default)]
+pub struct MemorySeedStore {
+    seed: Option<[u8; SEED_FILE_SIZE]>,
+}
+
+impl MemorySeedStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SeedStore for MemorySeedStore {
+    fn read_seed(&mut self) -> Result<Option<[u8; SEED_FILE_SIZE]>, SeedError> {
+        Ok(self.seed)
+    }
+
+    fn write_seed(&mut self, seed: &[u8; SEED_FILE_SIZE]) -> Result<(), SeedError> {
+        self.seed = Some(*seed);
+        Ok(())
+    }
+}
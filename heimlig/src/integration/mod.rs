@@ -1,4 +1,9 @@
 pub mod embassy;
+pub mod encrypted_key_store;
+pub mod flash_key_store;
+pub mod memory_counter_store;
 pub mod memory_key_store;
+pub mod memory_seed_store;
+pub mod ram_flash;
 pub mod raw_errors;
 pub mod raw_jobs;
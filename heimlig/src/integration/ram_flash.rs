@@ -0,0 +1,221 @@
+use embedded_storage::nor_flash::{
+    check_erase, check_read, check_write, ErrorType, NorFlash, NorFlashErrorKind, ReadNorFlash,
+};
+
+/// Value of erased flash memory.
+const ERASED: u8 = 0xFF;
+
+/// NOR flash simulated in RAM. Its content is lost on reset, so it is only suitable for testing.
+///
+/// Like real NOR flash, memory has to be erased in blocks of `ERASE_SIZE` bytes before it can be
+/// written again. Writing to memory that was not erased fails, so that misuse of the flash is
+/// detected in tests.
+///
+/// Power cuts can be simulated to test the behavior of flash users when a write or erase operation
+/// is interrupted.
+pub struct RamFlash<const CAPACITY: usize, const ERASE_SIZE: usize, const WRITE_SIZE: usize = 4> {
+    memory: [u8; CAPACITY],
+    /// Number of write and erase operations since creation.
+    operations: usize,
+    power_cut: Option<PowerCut>,
+    powered: bool,
+}
+
+/// Power cut scheduled for a future write or erase operation.
+#[derive(Copy, Clone, Debug)]
+struct PowerCut {
+    /// Number of operations that still complete before the power is cut.
+    remaining_operations: usize,
+    /// Number of bytes the interrupted operation changes.
+    completed_bytes: usize,
+}
+
+impl<const CAPACITY: usize, const ERASE_SIZE: usize, const WRITE_SIZE: usize>
+    RamFlash<CAPACITY, ERASE_SIZE, WRITE_SIZE>
+{
+    /// Create an erased flash.
+    pub fn new() -> Self {
+        Self {
+            memory: [ERASED; CAPACITY],
+            operations: 0,
+            power_cut: None,
+            powered: true,
+        }
+    }
+
+    /// Number of write and erase operations since creation.
+    pub fn operations(&self) -> usize {
+        self.operations
+    }
+
+    /// Cut the power during the write or erase operation following the next `after_operations`
+    /// operations and fail all later operations until the power is restored.
+    ///
+    /// An interrupted write only writes its first `completed_bytes` bytes. An interrupted erase
+    /// only erases the last `completed_bytes` bytes of its range, so that data at the start of the
+    /// range, such as headers, remains.
+    pub fn cut_power(&mut self, after_operations: usize, completed_bytes: usize) {
+        self.power_cut = Some(PowerCut {
+            remaining_operations: after_operations,
+            completed_bytes,
+        });
+    }
+
+    /// Restore the power and cancel a scheduled power cut.
+    pub fn restore_power(&mut self) {
+        self.power_cut = None;
+        self.powered = true;
+    }
+
+    /// Whether the power was not cut.
+    pub fn is_powered(&self) -> bool {
+        self.powered
+    }
+
+    /// Start a write or erase operation of `len` bytes.
+    ///
+    /// returns: The number of bytes the operation changes before the power is cut, if it is.
+    fn start_operation(&mut self, len: usize) -> Result<Option<usize>, NorFlashErrorKind> {
+        if !self.powered {
+            return Err(NorFlashErrorKind::Other);
+        }
+        self.operations += 1;
+        match &mut self.power_cut {
+            Some(power_cut) if power_cut.remaining_operations == 0 => {
+                self.powered = false;
+                Ok(Some(power_cut.completed_bytes.min(len)))
+            }
+            Some(power_cut) => {
+                power_cut.remaining_operations -= 1;
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl<const CAPACITY: usize, const ERASE_SIZE: usize, const WRITE_SIZE: usize> Default
+    for RamFlash<CAPACITY, ERASE_SIZE, WRITE_SIZE>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const CAPACITY: usize, const ERASE_SIZE: usize, const WRITE_SIZE: usize> ErrorType
+    for RamFlash<CAPACITY, ERASE_SIZE, WRITE_SIZE>
+{
+    type Error = NorFlashErrorKind;
+}
+
+impl<const CAPACITY: usize, const ERASE_SIZE: usize, const WRITE_SIZE: usize> ReadNorFlash
+    for RamFlash<CAPACITY, ERASE_SIZE, WRITE_SIZE>
+{
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        if !self.powered {
+            return Err(NorFlashErrorKind::Other);
+        }
+        check_read(self, offset, bytes.len())?;
+        let offset = offset as usize;
+        bytes.copy_from_slice(&self.memory[offset..offset + bytes.len()]);
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        CAPACITY
+    }
+}
+
+impl<const CAPACITY: usize, const ERASE_SIZE: usize, const WRITE_SIZE: usize> NorFlash
+    for RamFlash<CAPACITY, ERASE_SIZE, WRITE_SIZE>
+{
+    const WRITE_SIZE: usize = WRITE_SIZE;
+    const ERASE_SIZE: usize = ERASE_SIZE;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        check_erase(self, from, to)?;
+        let (from, to) = (from as usize, to as usize);
+        if let Some(completed_bytes) = self.start_operation(to - from)? {
+            self.memory[to - completed_bytes..to].fill(ERASED);
+            return Err(NorFlashErrorKind::Other);
+        }
+        self.memory[from..to].fill(ERASED);
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        check_write(self, offset, bytes.len())?;
+        let offset = offset as usize;
+        if self.memory[offset..offset + bytes.len()]
+            .iter()
+            .any(|byte| *byte != ERASED)
+        {
+            return Err(NorFlashErrorKind::Other);
+        }
+        let completed_bytes = self.start_operation(bytes.len())?;
+        let len = completed_bytes.unwrap_or(bytes.len());
+        self.memory[offset..offset + len].copy_from_slice(&bytes[..len]);
+        match completed_bytes {
+            Some(_) => Err(NorFlashErrorKind::Other),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    #[test]
+    fn write_erase() {
+        let mut flash = RamFlash::<64, 32>::new();
+        let mut data = [0u8; 8];
+        flash.read(0, &mut data).expect("failed to read");
+        assert_eq!(data, [ERASED; 8]);
+        flash.write(4, &[1u8; 8]).expect("failed to write");
+        flash.read(4, &mut data).expect("failed to read");
+        assert_eq!(data, [1u8; 8]);
+        assert_eq!(flash.write(8, &[2u8; 4]), Err(NorFlashErrorKind::Other));
+        assert_eq!(
+            flash.write(2, &[2u8; 4]),
+            Err(NorFlashErrorKind::NotAligned)
+        );
+        assert_eq!(flash.erase(0, 16), Err(NorFlashErrorKind::NotAligned));
+        assert_eq!(flash.erase(32, 96), Err(NorFlashErrorKind::OutOfBounds));
+        flash.erase(0, 32).expect("failed to erase");
+        flash.read(4, &mut data).expect("failed to read");
+        assert_eq!(data, [ERASED; 8]);
+        flash.write(8, &[2u8; 4]).expect("failed to write");
+        assert_eq!(flash.operations(), 3);
+    }
+
+    #[test]
+    fn power_cut() {
+        let mut flash = RamFlash::<64, 32>::new();
+        flash.cut_power(1, 4);
+        flash.write(0, &[1u8; 8]).expect("failed to write");
+        assert_eq!(flash.write(8, &[2u8; 8]), Err(NorFlashErrorKind::Other));
+        assert!(!flash.is_powered());
+        let mut data = [0u8; 16];
+        assert_eq!(flash.read(0, &mut data), Err(NorFlashErrorKind::Other));
+        assert_eq!(flash.erase(0, 32), Err(NorFlashErrorKind::Other));
+
+        flash.restore_power();
+        flash.read(0, &mut data).expect("failed to read");
+        assert_eq!(data[..12], [1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2]);
+        assert_eq!(data[12..], [ERASED; 4]);
+        flash.cut_power(0, 8);
+        assert_eq!(flash.erase(0, 32), Err(NorFlashErrorKind::Other));
+        flash.restore_power();
+        flash.read(0, &mut data).expect("failed to read");
+        assert_eq!(data[..12], [1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2]);
+        flash.cut_power(0, 24);
+        assert_eq!(flash.erase(0, 32), Err(NorFlashErrorKind::Other));
+        flash.restore_power();
+        flash.read(0, &mut data).expect("failed to read");
+        assert_eq!(data[..8], [1u8; 8]);
+        assert_eq!(data[8..], [ERASED; 8]);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::common::jobs;
 use crate::crypto;
+use crate::hsm::counter_store;
 use crate::hsm::keystore;
 
 /// Raw version of jobs::Error
@@ -22,6 +23,24 @@ pub enum JobErrorRaw {
     Crypto(CryptoErrorRaw),
     /// A key store error occurred.
     KeyStore(KeyStoreErrorRaw),
+    /// The session does not exist or belongs to another client.
+    InvalidSession,
+    /// The maximum number of concurrent sessions has been reached.
+    TooManySessions,
+    /// The data ID is not configured or is configured more than once.
+    InvalidDataId,
+    /// A counter store error occurred.
+    CounterStore(CounterStoreErrorRaw),
+    /// The security version of the image is lower than the minimum security version.
+    SecurityVersionTooLow,
+    /// The measurement register does not exist.
+    InvalidRegister,
+    /// The health tests of the entropy source failed.
+    HealthTestFailure,
+    /// The entropy source could not provide a seed.
+    EntropySourceFailure,
+    /// The client is not allowed to send this request.
+    AccessDenied,
 }
 
 /// Raw version of crypto::Error
@@ -52,6 +71,14 @@ pub enum CryptoErrorRaw {
     InvalidSignature,
     /// Invalid size of the digest.
     InvalidDigestSize,
+    /// Provided data is not correctly encoded.
+    InvalidEncoding,
+    /// The requested algorithm is not supported or does not match the key.
+    InvalidAlgorithm,
+    /// The operation is not allowed in the current state of the protocol.
+    InvalidState,
+    /// The freshness value is not newer than the latest accepted one or is exhausted.
+    InvalidFreshness,
 }
 
 /// Raw version of keystore::Error
@@ -72,6 +99,28 @@ pub enum KeyStoreErrorRaw {
     InvalidKeyType,
     /// Size of the provided buffer is invalid.
     InvalidBufferSize,
+    /// The underlying storage failed to read or write the key.
+    Storage,
+    /// The stored key material or its metadata was modified.
+    IntegrityViolation,
+    /// The access control list of the key does not allow the client to access it.
+    AccessDenied,
+}
+
+/// Raw version of counter_store::Error
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CounterStoreErrorRaw {
+    /// The requested counter was not found.
+    CounterNotFound,
+    /// A counter with the requested ID already exists.
+    CounterExists,
+    /// The counter store cannot handle the amount of requested counters.
+    CounterStoreTooSmall,
+    /// The counter would exceed its maximum value.
+    MaxValueExceeded,
+    /// The underlying storage failed to read or write the counter.
+    Storage,
 }
 
 impl From<jobs::Error> for JobErrorRaw {
@@ -85,6 +134,15 @@ impl From<jobs::Error> for JobErrorRaw {
             jobs::Error::StreamTerminated => JobErrorRaw::StreamTerminated,
             jobs::Error::Crypto(e) => JobErrorRaw::Crypto(e.into()),
             jobs::Error::KeyStore(e) => JobErrorRaw::KeyStore(e.into()),
+            jobs::Error::InvalidSession => JobErrorRaw::InvalidSession,
+            jobs::Error::TooManySessions => JobErrorRaw::TooManySessions,
+            jobs::Error::InvalidDataId => JobErrorRaw::InvalidDataId,
+            jobs::Error::CounterStore(e) => JobErrorRaw::CounterStore(e.into()),
+            jobs::Error::SecurityVersionTooLow => JobErrorRaw::SecurityVersionTooLow,
+            jobs::Error::InvalidRegister => JobErrorRaw::InvalidRegister,
+            jobs::Error::HealthTestFailure => JobErrorRaw::HealthTestFailure,
+            jobs::Error::EntropySourceFailure => JobErrorRaw::EntropySourceFailure,
+            jobs::Error::AccessDenied => JobErrorRaw::AccessDenied,
         }
     }
 }
@@ -104,6 +162,10 @@ impl From<crypto::Error> for CryptoErrorRaw {
             crypto::Error::InvalidSignatureSize => CryptoErrorRaw::InvalidSignatureSize,
             crypto::Error::InvalidSignature => CryptoErrorRaw::InvalidSignature,
             crypto::Error::InvalidDigestSize => CryptoErrorRaw::InvalidDigestSize,
+            crypto::Error::InvalidEncoding => CryptoErrorRaw::InvalidEncoding,
+            crypto::Error::InvalidAlgorithm => CryptoErrorRaw::InvalidAlgorithm,
+            crypto::Error::InvalidState => CryptoErrorRaw::InvalidState,
+            crypto::Error::InvalidFreshness => CryptoErrorRaw::InvalidFreshness,
         }
     }
 }
@@ -118,6 +180,23 @@ impl From<keystore::Error> for KeyStoreErrorRaw {
             keystore::Error::InvalidKeyId => KeyStoreErrorRaw::InvalidKeyId,
             keystore::Error::InvalidKeyType => KeyStoreErrorRaw::InvalidKeyType,
             keystore::Error::InvalidBufferSize => KeyStoreErrorRaw::InvalidBufferSize,
+            keystore::Error::Storage => KeyStoreErrorRaw::Storage,
+            keystore::Error::IntegrityViolation => KeyStoreErrorRaw::IntegrityViolation,
+            keystore::Error::AccessDenied => KeyStoreErrorRaw::AccessDenied,
+        }
+    }
+}
+
+impl From<counter_store::Error> for CounterStoreErrorRaw {
+    fn from(value: counter_store::Error) -> Self {
+        match value {
+            counter_store::Error::CounterNotFound => CounterStoreErrorRaw::CounterNotFound,
+            counter_store::Error::CounterExists => CounterStoreErrorRaw::CounterExists,
+            counter_store::Error::CounterStoreTooSmall => {
+                CounterStoreErrorRaw::CounterStoreTooSmall
+            }
+            counter_store::Error::MaxValueExceeded => CounterStoreErrorRaw::MaxValueExceeded,
+            counter_store::Error::Storage => CounterStoreErrorRaw::Storage,
         }
     }
 }
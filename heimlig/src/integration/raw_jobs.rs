@@ -1,10 +1,14 @@
 use crate::common::jobs::{Request, Response};
+use crate::crypto::{ecies, hpke};
+use crate::hsm::keystore::KeyId;
 use crate::integration::raw_errors::JobErrorRaw;
-use core::slice;
+use core::{mem, ptr, slice};
 
 type ClientIdRaw = u32;
 type RequestIdRaw = u32;
 type KeyIdRaw = u32;
+type SessionIdRaw = u32;
+type CounterIdRaw = u32;
 type BoolRaw = u32; // 0 == false, 1 == true
 
 // TODO: replace with core::mem::variant_count::<RequestRaw>(); once it is stable
@@ -30,6 +34,9 @@ pub enum RequestRaw {
         request_id: RequestIdRaw,
         output_data: *mut u8,
         output_size: u32,
+        prediction_resistance: BoolRaw,
+        additional_input_data: *const u8,
+        additional_input_size: u32,
     },
     GenerateSymmetricKey {
         client_id: ClientIdRaw,
@@ -277,6 +284,334 @@ pub enum RequestRaw {
         signature_data: *const u8,
         signature_size: u32,
     },
+    SignJws {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+        header_data: *const u8,
+        header_size: u32,
+        payload_data: *const u8,
+        payload_size: u32,
+        token_data: *mut u8,
+        token_size: u32,
+    },
+    VerifyJws {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+        token_data: *const u8,
+        token_size: u32,
+    },
+    VerifyJwsExternalKey {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        public_key_data: *const u8,
+        public_key_size: u32,
+        token_data: *const u8,
+        token_size: u32,
+    },
+    HpkeSeal {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        has_sender_key_id: BoolRaw,
+        sender_key_id: KeyIdRaw,
+        aead: u32,
+        recipient_public_key_data: *const u8,
+        recipient_public_key_size: u32,
+        info_data: *const u8,
+        info_size: u32,
+        aad_data: *const u8,
+        aad_size: u32,
+        buffer_data: *mut u8,
+        buffer_size: u32,
+        encapsulated_key_data: *mut u8,
+        encapsulated_key_size: u32,
+        tag_data: *mut u8,
+        tag_size: u32,
+    },
+    HpkeOpen {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+        sender_public_key_data: *const u8,
+        sender_public_key_size: u32,
+        aead: u32,
+        encapsulated_key_data: *const u8,
+        encapsulated_key_size: u32,
+        info_data: *const u8,
+        info_size: u32,
+        aad_data: *const u8,
+        aad_size: u32,
+        buffer_data: *mut u8,
+        buffer_size: u32,
+        tag_data: *const u8,
+        tag_size: u32,
+    },
+    EciesEncrypt {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        kdf: u32,
+        cipher: u32,
+        recipient_public_key_data: *const u8,
+        recipient_public_key_size: u32,
+        shared_info_data: *const u8,
+        shared_info_size: u32,
+        aad_data: *const u8,
+        aad_size: u32,
+        buffer_data: *mut u8,
+        buffer_size: u32,
+        ephemeral_public_key_data: *mut u8,
+        ephemeral_public_key_size: u32,
+        tag_data: *mut u8,
+        tag_size: u32,
+    },
+    EciesDecrypt {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+        kdf: u32,
+        cipher: u32,
+        ephemeral_public_key_data: *const u8,
+        ephemeral_public_key_size: u32,
+        shared_info_data: *const u8,
+        shared_info_size: u32,
+        aad_data: *const u8,
+        aad_size: u32,
+        buffer_data: *mut u8,
+        buffer_size: u32,
+        tag_data: *const u8,
+        tag_size: u32,
+    },
+    TlsHkdfExtract {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        has_salt_key_id: BoolRaw,
+        salt_key_id: KeyIdRaw,
+        has_ikm_key_id: BoolRaw,
+        ikm_key_id: KeyIdRaw,
+        peer_key_exchange_data: *const u8,
+        peer_key_exchange_size: u32,
+        output_key_id: KeyIdRaw,
+        overwrite: BoolRaw,
+    },
+    TlsHkdfExpandLabel {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        secret_key_id: KeyIdRaw,
+        label_data: *const u8,
+        label_size: u32,
+        context_data: *const u8,
+        context_size: u32,
+        output_key_id: KeyIdRaw,
+        overwrite: BoolRaw,
+    },
+    TlsDeriveSecret {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        secret_key_id: KeyIdRaw,
+        label_data: *const u8,
+        label_size: u32,
+        transcript_hash_data: *const u8,
+        transcript_hash_size: u32,
+        output_key_id: KeyIdRaw,
+        overwrite: BoolRaw,
+    },
+    TlsDeriveIv {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        secret_key_id: KeyIdRaw,
+        iv_data: *mut u8,
+        iv_size: u32,
+    },
+    TlsFinished {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        base_key_id: KeyIdRaw,
+        transcript_hash_data: *const u8,
+        transcript_hash_size: u32,
+        verify_data_data: *mut u8,
+        verify_data_size: u32,
+    },
+    TlsSignCertificateVerify {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+        server: BoolRaw,
+        transcript_hash_data: *const u8,
+        transcript_hash_size: u32,
+        signature_data: *mut u8,
+        signature_size: u32,
+    },
+    NoiseStartHandshake {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+        initiator: BoolRaw,
+        prologue_data: *const u8,
+        prologue_size: u32,
+    },
+    NoiseWriteMessage {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+        payload_data: *const u8,
+        payload_size: u32,
+        message_data: *mut u8,
+        message_size: u32,
+    },
+    NoiseReadMessage {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+        message_data: *const u8,
+        message_size: u32,
+        payload_data: *mut u8,
+        payload_size: u32,
+    },
+    NoiseSessionInfo {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+        remote_static_public_key_data: *mut u8,
+        remote_static_public_key_size: u32,
+        handshake_hash_data: *mut u8,
+        handshake_hash_size: u32,
+    },
+    NoiseEncrypt {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+        buffer_data: *mut u8,
+        buffer_size: u32,
+        tag_data: *mut u8,
+        tag_size: u32,
+    },
+    NoiseDecrypt {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+        buffer_data: *mut u8,
+        buffer_size: u32,
+        tag_data: *const u8,
+        tag_size: u32,
+    },
+    NoiseCloseSession {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+    },
+    SecOcAuthenticate {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        data_id: u16,
+        payload_data: *const u8,
+        payload_size: u32,
+        secured_pdu_data: *mut u8,
+        secured_pdu_size: u32,
+    },
+    SecOcVerify {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        data_id: u16,
+        secured_pdu_data: *const u8,
+        secured_pdu_size: u32,
+    },
+    CreateCounter {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        counter_id: CounterIdRaw,
+        max_value: u64,
+    },
+    ReadCounter {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        counter_id: CounterIdRaw,
+    },
+    IncrementCounter {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        counter_id: CounterIdRaw,
+        amount: u64,
+    },
+    VerifyImageStart {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+        manifest_data: *const u8,
+        manifest_size: u32,
+    },
+    VerifyImageUpdate {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+        chunk_data: *const u8,
+        chunk_size: u32,
+    },
+    VerifyImageFinish {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+    },
+    ExtendMeasurement {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        register: u32,
+        digest_data: *const u8,
+        digest_size: u32,
+    },
+    ReadMeasurement {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        register: u32,
+        value_data: *mut u8,
+        value_size: u32,
+    },
+    QuoteMeasurements {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        selection: u32,
+        nonce_data: *const u8,
+        nonce_size: u32,
+        quote_data: *mut u8,
+        quote_size: u32,
+    },
+    DeriveDiceAlias {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        measurement_data: *const u8,
+        measurement_size: u32,
+        certificate_data: *mut u8,
+        certificate_size: u32,
+    },
+    ProcessSpdmMessage {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        message_data: *const u8,
+        message_size: u32,
+        response_data: *mut u8,
+        response_size: u32,
+    },
+    ReseedRng {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    DeleteKey {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+    },
+    GetKeyInfo {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+    },
+    ListKeys {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        offset: u32,
+        key_ids_data: *mut KeyIdRaw,
+        key_ids_size: u32,
+    },
 }
 
 /// Raw response as it is written by clients to shared memory. This type is supposed to be synced
@@ -385,95 +720,320 @@ pub enum ResponseRaw {
         request_id: RequestIdRaw,
         verified: BoolRaw,
     },
-}
-
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum ValidationError {
-    InvalidPointer,
-    InvalidTagValue,
-}
-
-impl RequestRaw {
-    /// Create a `RequestRaw` from a raw pointer.
-    ///
-    /// # Arguments
-    ///
-    /// * `request`: The pointer to the first byte of the `RequestRaw`
-    /// * `validator`: Function that will be called to check the `request` pointer.
-    /// A valid pointer should fall into a specific address range and be properly aligned.
-    /// These checks (other than null) must be provided by the integrator.
-    ///
-    /// returns: Result<RequestRaw, ()>
-    ///
-    /// # Safety
-    ///
-    /// The provided pointer must be valid (non-null, aligned, point to valid memory) and point to a
-    /// valid `RequestRaw` instance.  
-    ///
-    pub unsafe fn from_raw(request_addr: *const u8) -> Result<Self, ValidationError> {
-        // Unsafe: Pointer and size must be checked by integrator
-        let tag: u8 = unsafe { *request_addr };
-
-        // Validate tag value. Invalid tag values cause UB when transmuted into an enum.
-        if tag >= REQUEST_RAW_VARIANTS {
-            return Err(ValidationError::InvalidTagValue);
-        }
-
-        // Safety: All members of RequestRaw are valid for all possible values found in memory
-        Ok(*request_addr.cast::<RequestRaw>())
-    }
-
-    pub fn verify<'data>(
-        self,
-        validator: &impl Fn(*const u8, u32) -> bool,
-    ) -> Result<Request<'data>, ValidationError> {
-        let request = match self {
-            RequestRaw::GetRandom {
-                client_id,
-                request_id,
-                output_data,
-                output_size,
-            } => Request::GetRandom {
-                client_id: client_id.into(),
-                request_id: request_id.into(),
-                output: check_mut_pointer_and_size(output_data, output_size, &validator)?,
-            },
-            RequestRaw::GenerateSymmetricKey {
-                client_id,
-                request_id,
-                key_id,
-                overwrite,
-            } => Request::GenerateSymmetricKey {
-                client_id: client_id.into(),
-                request_id: request_id.into(),
-                key_id: key_id.into(),
-                overwrite: bool_raw_to_bool(overwrite),
-            },
-            RequestRaw::GenerateKeyPair {
-                client_id,
-                request_id,
-                key_id,
-                overwrite,
-            } => Request::GenerateKeyPair {
-                client_id: client_id.into(),
-                request_id: request_id.into(),
-                key_id: key_id.into(),
-                overwrite: bool_raw_to_bool(overwrite),
-            },
-            RequestRaw::ImportSymmetricKey {
-                client_id,
-                request_id,
-                key_id,
-                data_data,
-                data_size,
-                overwrite,
-            } => Request::ImportSymmetricKey {
-                client_id: client_id.into(),
-                request_id: request_id.into(),
-                key_id: key_id.into(),
-                data: check_pointer_and_size(data_data, data_size, &validator)?,
-                overwrite: bool_raw_to_bool(overwrite),
-            },
+    SignJws {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        token_data: *mut u8,
+        token_size: u32,
+    },
+    VerifyJws {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        verified: BoolRaw,
+    },
+    HpkeSeal {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        encapsulated_key_data: *mut u8,
+        encapsulated_key_size: u32,
+        buffer_data: *mut u8,
+        buffer_size: u32,
+        tag_data: *mut u8,
+        tag_size: u32,
+    },
+    HpkeOpen {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        plaintext_data: *mut u8,
+        plaintext_size: u32,
+    },
+    EciesEncrypt {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        ephemeral_public_key_data: *mut u8,
+        ephemeral_public_key_size: u32,
+        buffer_data: *mut u8,
+        buffer_size: u32,
+        tag_data: *mut u8,
+        tag_size: u32,
+    },
+    EciesDecrypt {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        plaintext_data: *mut u8,
+        plaintext_size: u32,
+    },
+    TlsHkdfExtract {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    TlsHkdfExpandLabel {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    TlsDeriveSecret {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    TlsDeriveIv {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        iv_data: *mut u8,
+        iv_size: u32,
+    },
+    TlsFinished {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        verify_data_data: *mut u8,
+        verify_data_size: u32,
+    },
+    TlsSignCertificateVerify {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        signature_data: *mut u8,
+        signature_size: u32,
+    },
+    NoiseStartHandshake {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+    },
+    NoiseWriteMessage {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        message_data: *mut u8,
+        message_size: u32,
+        handshake_finished: BoolRaw,
+    },
+    NoiseReadMessage {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        payload_data: *mut u8,
+        payload_size: u32,
+        handshake_finished: BoolRaw,
+    },
+    NoiseSessionInfo {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        remote_static_public_key_data: *mut u8,
+        remote_static_public_key_size: u32,
+        handshake_hash_data: *mut u8,
+        handshake_hash_size: u32,
+    },
+    NoiseEncrypt {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        buffer_data: *mut u8,
+        buffer_size: u32,
+        tag_data: *mut u8,
+        tag_size: u32,
+    },
+    NoiseDecrypt {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        plaintext_data: *mut u8,
+        plaintext_size: u32,
+    },
+    NoiseCloseSession {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    SecOcAuthenticate {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        secured_pdu_data: *mut u8,
+        secured_pdu_size: u32,
+    },
+    SecOcVerify {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        verified: BoolRaw,
+    },
+    CreateCounter {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    ReadCounter {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        value: u64,
+        max_value: u64,
+    },
+    IncrementCounter {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        value: u64,
+    },
+    VerifyImageStart {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        session: SessionIdRaw,
+    },
+    VerifyImageUpdate {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    VerifyImageFinish {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        verified: BoolRaw,
+    },
+    ExtendMeasurement {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    ReadMeasurement {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        value_data: *mut u8,
+        value_size: u32,
+    },
+    QuoteMeasurements {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        quote_data: *mut u8,
+        quote_size: u32,
+    },
+    DeriveDiceAlias {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        certificate_data: *mut u8,
+        certificate_size: u32,
+    },
+    ProcessSpdmMessage {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        response_data: *mut u8,
+        response_size: u32,
+    },
+    ReseedRng {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    DeleteKey {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+    },
+    GetKeyInfo {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_id: KeyIdRaw,
+        key_type: u32,
+        permissions: u32,
+        usage: u32,
+        algorithm: u32,
+        use_clients: u32,
+        import_clients: u32,
+        export_clients: u32,
+        delete_clients: u32,
+        is_available: BoolRaw,
+    },
+    ListKeys {
+        client_id: ClientIdRaw,
+        request_id: RequestIdRaw,
+        key_ids_data: *mut KeyIdRaw,
+        key_ids_size: u32,
+        num_keys: u32,
+    },
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+    InvalidPointer,
+    InvalidTagValue,
+    InvalidEnumValue,
+}
+
+impl RequestRaw {
+    /// Create a `RequestRaw` from a raw pointer.
+    ///
+    /// # Arguments
+    ///
+    /// * `request`: The pointer to the first byte of the `RequestRaw`
+    /// * `validator`: Function that will be called to check the `request` pointer.
+    /// A valid pointer should fall into a specific address range and be properly aligned.
+    /// These checks (other than null) must be provided by the integrator.
+    ///
+    /// returns: Result<RequestRaw, ()>
+    ///
+    /// # Safety
+    ///
+    /// The provided pointer must be valid (non-null, aligned, point to valid memory) and point to a
+    /// valid `RequestRaw` instance.  
+    ///
+    pub unsafe fn from_raw(request_addr: *const u8) -> Result<Self, ValidationError> {
+        // Unsafe: Pointer and size must be checked by integrator
+        let tag: u8 = unsafe { *request_addr };
+
+        // Validate tag value. Invalid tag values cause UB when transmuted into an enum.
+        if tag >= REQUEST_RAW_VARIANTS {
+            return Err(ValidationError::InvalidTagValue);
+        }
+
+        // Safety: All members of RequestRaw are valid for all possible values found in memory
+        Ok(*request_addr.cast::<RequestRaw>())
+    }
+
+    pub fn verify<'data>(
+        self,
+        validator: &impl Fn(*const u8, u32) -> bool,
+    ) -> Result<Request<'data>, ValidationError> {
+        let request = match self {
+            RequestRaw::GetRandom {
+                client_id,
+                request_id,
+                output_data,
+                output_size,
+                prediction_resistance,
+                additional_input_data,
+                additional_input_size,
+            } => Request::GetRandom {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                output: check_mut_pointer_and_size(output_data, output_size, &validator)?,
+                prediction_resistance: bool_raw_to_bool(prediction_resistance),
+                additional_input: check_pointer_and_size(
+                    additional_input_data,
+                    additional_input_size,
+                    &validator,
+                )?,
+            },
+            RequestRaw::GenerateSymmetricKey {
+                client_id,
+                request_id,
+                key_id,
+                overwrite,
+            } => Request::GenerateSymmetricKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                overwrite: bool_raw_to_bool(overwrite),
+            },
+            RequestRaw::GenerateKeyPair {
+                client_id,
+                request_id,
+                key_id,
+                overwrite,
+            } => Request::GenerateKeyPair {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                overwrite: bool_raw_to_bool(overwrite),
+            },
+            RequestRaw::ImportSymmetricKey {
+                client_id,
+                request_id,
+                key_id,
+                data_data,
+                data_size,
+                overwrite,
+            } => Request::ImportSymmetricKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                data: check_pointer_and_size(data_data, data_size, &validator)?,
+                overwrite: bool_raw_to_bool(overwrite),
+            },
             RequestRaw::ImportKeyPair {
                 client_id,
                 request_id,
@@ -856,430 +1416,1623 @@ impl RequestRaw {
                 prehashed: bool_raw_to_bool(prehashed),
                 signature: check_pointer_and_size(signature_data, signature_size, &validator)?,
             },
-        };
-        Ok(request)
-    }
-}
-
-// Outgoing direction. No validation needed.
-impl From<Request<'_>> for RequestRaw {
-    fn from(request: Request) -> Self {
-        match request {
-            Request::GetRandom {
+            RequestRaw::SignJws {
                 client_id,
                 request_id,
-                output,
-            } => RequestRaw::GetRandom {
+                key_id,
+                header_data,
+                header_size,
+                payload_data,
+                payload_size,
+                token_data,
+                token_size,
+            } => Request::SignJws {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                output_data: output.as_mut_ptr(),
-                output_size: output.len() as u32,
+                key_id: key_id.into(),
+                header: check_pointer_and_size(header_data, header_size, &validator)?,
+                payload: check_pointer_and_size(payload_data, payload_size, &validator)?,
+                token: check_mut_pointer_and_size(token_data, token_size, &validator)?,
             },
-            Request::GenerateSymmetricKey {
+            RequestRaw::VerifyJws {
                 client_id,
                 request_id,
                 key_id,
-                overwrite,
-            } => RequestRaw::GenerateSymmetricKey {
+                token_data,
+                token_size,
+            } => Request::VerifyJws {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
                 key_id: key_id.into(),
-                overwrite: overwrite.into(),
+                token: check_pointer_and_size(token_data, token_size, &validator)?,
             },
-            Request::GenerateKeyPair {
+            RequestRaw::VerifyJwsExternalKey {
                 client_id,
                 request_id,
-                key_id,
-                overwrite,
-            } => RequestRaw::GenerateKeyPair {
+                public_key_data,
+                public_key_size,
+                token_data,
+                token_size,
+            } => Request::VerifyJwsExternalKey {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
-                overwrite: overwrite.into(),
+                public_key: check_pointer_and_size(public_key_data, public_key_size, &validator)?,
+                token: check_pointer_and_size(token_data, token_size, &validator)?,
             },
-            Request::ImportSymmetricKey {
+            RequestRaw::HpkeSeal {
                 client_id,
                 request_id,
-                key_id,
-                data,
-                overwrite,
-            } => RequestRaw::ImportSymmetricKey {
+                has_sender_key_id,
+                sender_key_id,
+                aead,
+                recipient_public_key_data,
+                recipient_public_key_size,
+                info_data,
+                info_size,
+                aad_data,
+                aad_size,
+                buffer_data,
+                buffer_size,
+                encapsulated_key_data,
+                encapsulated_key_size,
+                tag_data,
+                tag_size,
+            } => Request::HpkeSeal {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                sender_key_id: bool_raw_to_bool(has_sender_key_id).then_some(sender_key_id.into()),
+                aead: hpke_aead_from_raw(aead)?,
+                recipient_public_key: check_pointer_and_size(
+                    recipient_public_key_data,
+                    recipient_public_key_size,
+                    &validator,
+                )?,
+                info: check_pointer_and_size(info_data, info_size, &validator)?,
+                aad: check_pointer_and_size(aad_data, aad_size, &validator)?,
+                buffer: check_mut_pointer_and_size(buffer_data, buffer_size, &validator)?,
+                encapsulated_key: check_mut_pointer_and_size(
+                    encapsulated_key_data,
+                    encapsulated_key_size,
+                    &validator,
+                )?,
+                tag: check_mut_pointer_and_size(tag_data, tag_size, &validator)?,
+            },
+            RequestRaw::HpkeOpen {
+                client_id,
+                request_id,
+                key_id,
+                sender_public_key_data,
+                sender_public_key_size,
+                aead,
+                encapsulated_key_data,
+                encapsulated_key_size,
+                info_data,
+                info_size,
+                aad_data,
+                aad_size,
+                buffer_data,
+                buffer_size,
+                tag_data,
+                tag_size,
+            } => Request::HpkeOpen {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                sender_public_key: check_optional_pointer_and_size(
+                    sender_public_key_data,
+                    sender_public_key_size,
+                    &validator,
+                )?,
+                aead: hpke_aead_from_raw(aead)?,
+                encapsulated_key: check_pointer_and_size(
+                    encapsulated_key_data,
+                    encapsulated_key_size,
+                    &validator,
+                )?,
+                info: check_pointer_and_size(info_data, info_size, &validator)?,
+                aad: check_pointer_and_size(aad_data, aad_size, &validator)?,
+                buffer: check_mut_pointer_and_size(buffer_data, buffer_size, &validator)?,
+                tag: check_pointer_and_size(tag_data, tag_size, &validator)?,
+            },
+            RequestRaw::EciesEncrypt {
+                client_id,
+                request_id,
+                kdf,
+                cipher,
+                recipient_public_key_data,
+                recipient_public_key_size,
+                shared_info_data,
+                shared_info_size,
+                aad_data,
+                aad_size,
+                buffer_data,
+                buffer_size,
+                ephemeral_public_key_data,
+                ephemeral_public_key_size,
+                tag_data,
+                tag_size,
+            } => Request::EciesEncrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                kdf: ecies_kdf_from_raw(kdf)?,
+                cipher: ecies_cipher_from_raw(cipher)?,
+                recipient_public_key: check_pointer_and_size(
+                    recipient_public_key_data,
+                    recipient_public_key_size,
+                    &validator,
+                )?,
+                shared_info: check_pointer_and_size(
+                    shared_info_data,
+                    shared_info_size,
+                    &validator,
+                )?,
+                aad: check_pointer_and_size(aad_data, aad_size, &validator)?,
+                buffer: check_mut_pointer_and_size(buffer_data, buffer_size, &validator)?,
+                ephemeral_public_key: check_mut_pointer_and_size(
+                    ephemeral_public_key_data,
+                    ephemeral_public_key_size,
+                    &validator,
+                )?,
+                tag: check_mut_pointer_and_size(tag_data, tag_size, &validator)?,
+            },
+            RequestRaw::EciesDecrypt {
+                client_id,
+                request_id,
+                key_id,
+                kdf,
+                cipher,
+                ephemeral_public_key_data,
+                ephemeral_public_key_size,
+                shared_info_data,
+                shared_info_size,
+                aad_data,
+                aad_size,
+                buffer_data,
+                buffer_size,
+                tag_data,
+                tag_size,
+            } => Request::EciesDecrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                kdf: ecies_kdf_from_raw(kdf)?,
+                cipher: ecies_cipher_from_raw(cipher)?,
+                ephemeral_public_key: check_pointer_and_size(
+                    ephemeral_public_key_data,
+                    ephemeral_public_key_size,
+                    &validator,
+                )?,
+                shared_info: check_pointer_and_size(
+                    shared_info_data,
+                    shared_info_size,
+                    &validator,
+                )?,
+                aad: check_pointer_and_size(aad_data, aad_size, &validator)?,
+                buffer: check_mut_pointer_and_size(buffer_data, buffer_size, &validator)?,
+                tag: check_pointer_and_size(tag_data, tag_size, &validator)?,
+            },
+            RequestRaw::TlsHkdfExtract {
+                client_id,
+                request_id,
+                has_salt_key_id,
+                salt_key_id,
+                has_ikm_key_id,
+                ikm_key_id,
+                peer_key_exchange_data,
+                peer_key_exchange_size,
+                output_key_id,
+                overwrite,
+            } => Request::TlsHkdfExtract {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                salt_key_id: bool_raw_to_bool(has_salt_key_id).then_some(salt_key_id.into()),
+                ikm_key_id: bool_raw_to_bool(has_ikm_key_id).then_some(ikm_key_id.into()),
+                peer_key_exchange: check_optional_pointer_and_size(
+                    peer_key_exchange_data,
+                    peer_key_exchange_size,
+                    &validator,
+                )?,
+                output_key_id: output_key_id.into(),
+                overwrite: bool_raw_to_bool(overwrite),
+            },
+            RequestRaw::TlsHkdfExpandLabel {
+                client_id,
+                request_id,
+                secret_key_id,
+                label_data,
+                label_size,
+                context_data,
+                context_size,
+                output_key_id,
+                overwrite,
+            } => Request::TlsHkdfExpandLabel {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                secret_key_id: secret_key_id.into(),
+                label: check_pointer_and_size(label_data, label_size, &validator)?,
+                context: check_pointer_and_size(context_data, context_size, &validator)?,
+                output_key_id: output_key_id.into(),
+                overwrite: bool_raw_to_bool(overwrite),
+            },
+            RequestRaw::TlsDeriveSecret {
+                client_id,
+                request_id,
+                secret_key_id,
+                label_data,
+                label_size,
+                transcript_hash_data,
+                transcript_hash_size,
+                output_key_id,
+                overwrite,
+            } => Request::TlsDeriveSecret {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                secret_key_id: secret_key_id.into(),
+                label: check_pointer_and_size(label_data, label_size, &validator)?,
+                transcript_hash: check_pointer_and_size(
+                    transcript_hash_data,
+                    transcript_hash_size,
+                    &validator,
+                )?,
+                output_key_id: output_key_id.into(),
+                overwrite: bool_raw_to_bool(overwrite),
+            },
+            RequestRaw::TlsDeriveIv {
+                client_id,
+                request_id,
+                secret_key_id,
+                iv_data,
+                iv_size,
+            } => Request::TlsDeriveIv {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                secret_key_id: secret_key_id.into(),
+                iv: check_mut_pointer_and_size(iv_data, iv_size, &validator)?,
+            },
+            RequestRaw::TlsFinished {
+                client_id,
+                request_id,
+                base_key_id,
+                transcript_hash_data,
+                transcript_hash_size,
+                verify_data_data,
+                verify_data_size,
+            } => Request::TlsFinished {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                base_key_id: base_key_id.into(),
+                transcript_hash: check_pointer_and_size(
+                    transcript_hash_data,
+                    transcript_hash_size,
+                    &validator,
+                )?,
+                verify_data: check_mut_pointer_and_size(
+                    verify_data_data,
+                    verify_data_size,
+                    &validator,
+                )?,
+            },
+            RequestRaw::TlsSignCertificateVerify {
+                client_id,
+                request_id,
+                key_id,
+                server,
+                transcript_hash_data,
+                transcript_hash_size,
+                signature_data,
+                signature_size,
+            } => Request::TlsSignCertificateVerify {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                server: bool_raw_to_bool(server),
+                transcript_hash: check_pointer_and_size(
+                    transcript_hash_data,
+                    transcript_hash_size,
+                    &validator,
+                )?,
+                signature: check_mut_pointer_and_size(signature_data, signature_size, &validator)?,
+            },
+            RequestRaw::NoiseStartHandshake {
+                client_id,
+                request_id,
+                key_id,
+                initiator,
+                prologue_data,
+                prologue_size,
+            } => Request::NoiseStartHandshake {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                initiator: bool_raw_to_bool(initiator),
+                prologue: check_pointer_and_size(prologue_data, prologue_size, &validator)?,
+            },
+            RequestRaw::NoiseWriteMessage {
+                client_id,
+                request_id,
+                session,
+                payload_data,
+                payload_size,
+                message_data,
+                message_size,
+            } => Request::NoiseWriteMessage {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+                payload: check_pointer_and_size(payload_data, payload_size, &validator)?,
+                message: check_mut_pointer_and_size(message_data, message_size, &validator)?,
+            },
+            RequestRaw::NoiseReadMessage {
+                client_id,
+                request_id,
+                session,
+                message_data,
+                message_size,
+                payload_data,
+                payload_size,
+            } => Request::NoiseReadMessage {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+                message: check_pointer_and_size(message_data, message_size, &validator)?,
+                payload: check_mut_pointer_and_size(payload_data, payload_size, &validator)?,
+            },
+            RequestRaw::NoiseSessionInfo {
+                client_id,
+                request_id,
+                session,
+                remote_static_public_key_data,
+                remote_static_public_key_size,
+                handshake_hash_data,
+                handshake_hash_size,
+            } => Request::NoiseSessionInfo {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+                remote_static_public_key: check_mut_pointer_and_size(
+                    remote_static_public_key_data,
+                    remote_static_public_key_size,
+                    &validator,
+                )?,
+                handshake_hash: check_mut_pointer_and_size(
+                    handshake_hash_data,
+                    handshake_hash_size,
+                    &validator,
+                )?,
+            },
+            RequestRaw::NoiseEncrypt {
+                client_id,
+                request_id,
+                session,
+                buffer_data,
+                buffer_size,
+                tag_data,
+                tag_size,
+            } => Request::NoiseEncrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+                buffer: check_mut_pointer_and_size(buffer_data, buffer_size, &validator)?,
+                tag: check_mut_pointer_and_size(tag_data, tag_size, &validator)?,
+            },
+            RequestRaw::NoiseDecrypt {
+                client_id,
+                request_id,
+                session,
+                buffer_data,
+                buffer_size,
+                tag_data,
+                tag_size,
+            } => Request::NoiseDecrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+                buffer: check_mut_pointer_and_size(buffer_data, buffer_size, &validator)?,
+                tag: check_pointer_and_size(tag_data, tag_size, &validator)?,
+            },
+            RequestRaw::NoiseCloseSession {
+                client_id,
+                request_id,
+                session,
+            } => Request::NoiseCloseSession {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+            },
+            RequestRaw::SecOcAuthenticate {
+                client_id,
+                request_id,
+                data_id,
+                payload_data,
+                payload_size,
+                secured_pdu_data,
+                secured_pdu_size,
+            } => Request::SecOcAuthenticate {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                data_id,
+                payload: check_pointer_and_size(payload_data, payload_size, &validator)?,
+                secured_pdu: check_mut_pointer_and_size(
+                    secured_pdu_data,
+                    secured_pdu_size,
+                    &validator,
+                )?,
+            },
+            RequestRaw::SecOcVerify {
+                client_id,
+                request_id,
+                data_id,
+                secured_pdu_data,
+                secured_pdu_size,
+            } => Request::SecOcVerify {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                data_id,
+                secured_pdu: check_pointer_and_size(
+                    secured_pdu_data,
+                    secured_pdu_size,
+                    &validator,
+                )?,
+            },
+            RequestRaw::CreateCounter {
+                client_id,
+                request_id,
+                counter_id,
+                max_value,
+            } => Request::CreateCounter {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                counter_id: counter_id.into(),
+                max_value,
+            },
+            RequestRaw::ReadCounter {
+                client_id,
+                request_id,
+                counter_id,
+            } => Request::ReadCounter {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                counter_id: counter_id.into(),
+            },
+            RequestRaw::IncrementCounter {
+                client_id,
+                request_id,
+                counter_id,
+                amount,
+            } => Request::IncrementCounter {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                counter_id: counter_id.into(),
+                amount,
+            },
+            RequestRaw::VerifyImageStart {
+                client_id,
+                request_id,
+                key_id,
+                manifest_data,
+                manifest_size,
+            } => Request::VerifyImageStart {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                manifest: check_pointer_and_size(manifest_data, manifest_size, &validator)?,
+            },
+            RequestRaw::VerifyImageUpdate {
+                client_id,
+                request_id,
+                session,
+                chunk_data,
+                chunk_size,
+            } => Request::VerifyImageUpdate {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+                chunk: check_pointer_and_size(chunk_data, chunk_size, &validator)?,
+            },
+            RequestRaw::VerifyImageFinish {
+                client_id,
+                request_id,
+                session,
+            } => Request::VerifyImageFinish {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+            },
+            RequestRaw::ExtendMeasurement {
+                client_id,
+                request_id,
+                register,
+                digest_data,
+                digest_size,
+            } => Request::ExtendMeasurement {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                register,
+                digest: check_pointer_and_size(digest_data, digest_size, &validator)?,
+            },
+            RequestRaw::ReadMeasurement {
+                client_id,
+                request_id,
+                register,
+                value_data,
+                value_size,
+            } => Request::ReadMeasurement {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                register,
+                value: check_mut_pointer_and_size(value_data, value_size, &validator)?,
+            },
+            RequestRaw::QuoteMeasurements {
+                client_id,
+                request_id,
+                selection,
+                nonce_data,
+                nonce_size,
+                quote_data,
+                quote_size,
+            } => Request::QuoteMeasurements {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                selection,
+                nonce: check_pointer_and_size(nonce_data, nonce_size, &validator)?,
+                quote: check_mut_pointer_and_size(quote_data, quote_size, &validator)?,
+            },
+            RequestRaw::DeriveDiceAlias {
+                client_id,
+                request_id,
+                measurement_data,
+                measurement_size,
+                certificate_data,
+                certificate_size,
+            } => Request::DeriveDiceAlias {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                measurement: check_pointer_and_size(
+                    measurement_data,
+                    measurement_size,
+                    &validator,
+                )?,
+                certificate: check_mut_pointer_and_size(
+                    certificate_data,
+                    certificate_size,
+                    &validator,
+                )?,
+            },
+            RequestRaw::ProcessSpdmMessage {
+                client_id,
+                request_id,
+                message_data,
+                message_size,
+                response_data,
+                response_size,
+            } => Request::ProcessSpdmMessage {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                message: check_pointer_and_size(message_data, message_size, &validator)?,
+                response: check_mut_pointer_and_size(response_data, response_size, &validator)?,
+            },
+            RequestRaw::ReseedRng {
+                client_id,
+                request_id,
+            } => Request::ReseedRng {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            RequestRaw::DeleteKey {
+                client_id,
+                request_id,
+                key_id,
+            } => Request::DeleteKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+            },
+            RequestRaw::GetKeyInfo {
+                client_id,
+                request_id,
+                key_id,
+            } => Request::GetKeyInfo {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+            },
+            RequestRaw::ListKeys {
+                client_id,
+                request_id,
+                offset,
+                key_ids_data,
+                key_ids_size,
+            } => Request::ListKeys {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                offset: offset as usize,
+                key_ids: check_mut_key_ids(key_ids_data, key_ids_size, &validator)?,
+            },
+        };
+        Ok(request)
+    }
+}
+
+// Outgoing direction. No validation needed.
+impl From<Request<'_>> for RequestRaw {
+    fn from(request: Request) -> Self {
+        match request {
+            Request::GetRandom {
+                client_id,
+                request_id,
+                output,
+                prediction_resistance,
+                additional_input,
+            } => RequestRaw::GetRandom {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                output_data: output.as_mut_ptr(),
+                output_size: output.len() as u32,
+                prediction_resistance: prediction_resistance.into(),
+                additional_input_data: additional_input.as_ptr(),
+                additional_input_size: additional_input.len() as u32,
+            },
+            Request::GenerateSymmetricKey {
+                client_id,
+                request_id,
+                key_id,
+                overwrite,
+            } => RequestRaw::GenerateSymmetricKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                overwrite: overwrite.into(),
+            },
+            Request::GenerateKeyPair {
+                client_id,
+                request_id,
+                key_id,
+                overwrite,
+            } => RequestRaw::GenerateKeyPair {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                overwrite: overwrite.into(),
+            },
+            Request::ImportSymmetricKey {
+                client_id,
+                request_id,
+                key_id,
+                data,
+                overwrite,
+            } => RequestRaw::ImportSymmetricKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                data_data: data.as_ptr(),
+                data_size: data.len() as u32,
+                overwrite: overwrite.into(),
+            },
+            Request::ImportKeyPair {
+                client_id,
+                request_id,
+                key_id,
+                public_key,
+                private_key,
+                overwrite,
+            } => RequestRaw::ImportKeyPair {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                public_key_data: public_key.as_ptr(),
+                public_key_size: public_key.len() as u32,
+                private_key_data: private_key.as_ptr(),
+                private_key_size: private_key.len() as u32,
+                overwrite: overwrite.into(),
+            },
+            Request::ExportSymmetricKey {
+                client_id,
+                request_id,
+                key_id,
+                data,
+            } => RequestRaw::ExportSymmetricKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                data_data: data.as_mut_ptr(),
+                data_size: data.len() as u32,
+            },
+            Request::ExportPublicKey {
+                client_id,
+                request_id,
+                key_id,
+                public_key,
+            } => RequestRaw::ExportPublicKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                public_key_data: public_key.as_mut_ptr(),
+                public_key_size: public_key.len() as u32,
+            },
+            Request::ExportPrivateKey {
+                client_id,
+                request_id,
+                key_id,
+                private_key,
+            } => RequestRaw::ExportPrivateKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                private_key_data: private_key.as_mut_ptr(),
+                private_key_size: private_key.len() as u32,
+            },
+            Request::IsKeyAvailable {
+                client_id,
+                request_id,
+                key_id,
+            } => RequestRaw::IsKeyAvailable {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+            },
+            Request::EncryptChaChaPoly {
+                client_id,
+                request_id,
+                key_id,
+                nonce,
+                buffer,
+                aad,
+                tag,
+            } => RequestRaw::EncryptChaChaPoly {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                nonce_data: nonce.as_ptr(),
+                nonce_size: nonce.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                tag_data: tag.as_mut_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::EncryptChaChaPolyExternalKey {
+                client_id,
+                request_id,
+                key,
+                nonce,
+                buffer,
+                aad,
+                tag,
+            } => RequestRaw::EncryptChaChaPolyExternalKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_data: key.as_ptr(),
+                key_size: key.len() as u32,
+                nonce_data: nonce.as_ptr(),
+                nonce_size: nonce.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                tag_data: tag.as_mut_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::DecryptChaChaPoly {
+                client_id,
+                request_id,
+                key_id,
+                nonce,
+                buffer,
+                aad,
+                tag,
+            } => RequestRaw::DecryptChaChaPoly {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                nonce_data: nonce.as_ptr(),
+                nonce_size: nonce.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                tag_data: tag.as_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::DecryptChaChaPolyExternalKey {
+                client_id,
+                request_id,
+                key,
+                nonce,
+                buffer,
+                aad,
+                tag,
+            } => RequestRaw::DecryptChaChaPolyExternalKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_data: key.as_ptr(),
+                key_size: key.len() as u32,
+                nonce_data: nonce.as_ptr(),
+                nonce_size: nonce.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                tag_data: tag.as_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::EncryptAesGcm {
+                client_id,
+                request_id,
+                key_id,
+                iv,
+                buffer,
+                aad,
+                tag,
+            } => RequestRaw::EncryptAesGcm {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                iv_data: iv.as_ptr(),
+                iv_size: iv.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                tag_data: tag.as_mut_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::EncryptAesGcmExternalKey {
+                client_id,
+                request_id,
+                key,
+                iv,
+                buffer,
+                aad,
+                tag,
+            } => RequestRaw::EncryptAesGcmExternalKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_data: key.as_ptr(),
+                key_size: key.len() as u32,
+                iv_data: iv.as_ptr(),
+                iv_size: iv.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                tag_data: tag.as_mut_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::DecryptAesGcm {
+                client_id,
+                request_id,
+                key_id,
+                iv,
+                buffer,
+                aad,
+                tag,
+            } => RequestRaw::DecryptAesGcm {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                iv_data: iv.as_ptr(),
+                iv_size: iv.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                tag_data: tag.as_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::DecryptAesGcmExternalKey {
+                client_id,
+                request_id,
+                key,
+                iv,
+                buffer,
+                aad,
+                tag,
+            } => RequestRaw::DecryptAesGcmExternalKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_data: key.as_ptr(),
+                key_size: key.len() as u32,
+                iv_data: iv.as_ptr(),
+                iv_size: iv.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                tag_data: tag.as_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::EncryptAesCbc {
+                client_id,
+                request_id,
+                key_id,
+                iv,
+                buffer,
+                plaintext_size,
+            } => RequestRaw::EncryptAesCbc {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
                 key_id: key_id.into(),
-                data_data: data.as_ptr(),
-                data_size: data.len() as u32,
-                overwrite: overwrite.into(),
+                iv_data: iv.as_ptr(),
+                iv_size: iv.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                plaintext_size: plaintext_size as u32,
             },
-            Request::ImportKeyPair {
+            Request::EncryptAesCbcExternalKey {
+                client_id,
+                request_id,
+                key,
+                iv,
+                buffer,
+                plaintext_size,
+            } => RequestRaw::EncryptAesCbcExternalKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_data: key.as_ptr(),
+                key_size: key.len() as u32,
+                iv_data: iv.as_ptr(),
+                iv_size: iv.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                plaintext_size: plaintext_size as u32,
+            },
+            Request::DecryptAesCbc {
                 client_id,
                 request_id,
                 key_id,
-                public_key,
-                private_key,
-                overwrite,
-            } => RequestRaw::ImportKeyPair {
+                iv,
+                buffer,
+            } => RequestRaw::DecryptAesCbc {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                iv_data: iv.as_ptr(),
+                iv_size: iv.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+            },
+            Request::DecryptAesCbcExternalKey {
+                client_id,
+                request_id,
+                key,
+                iv,
+                buffer,
+            } => RequestRaw::DecryptAesCbcExternalKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_data: key.as_ptr(),
+                key_size: key.len() as u32,
+                iv_data: iv.as_ptr(),
+                iv_size: iv.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+            },
+            Request::Sign {
+                client_id,
+                request_id,
+                key_id,
+                message,
+                prehashed,
+                signature,
+            } => RequestRaw::Sign {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                message_data: message.as_ptr(),
+                message_size: message.len() as u32,
+                prehashed: prehashed.into(),
+                signature_data: signature.as_mut_ptr(),
+                signature_size: signature.len() as u32,
+            },
+            Request::SignExternalKey {
+                client_id,
+                request_id,
+                private_key: key,
+                message,
+                prehashed,
+                signature,
+            } => RequestRaw::SignExternalKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_data: key.as_ptr(),
+                key_size: key.len() as u32,
+                message_data: message.as_ptr(),
+                message_size: message.len() as u32,
+                prehashed: prehashed.into(),
+                signature_data: signature.as_mut_ptr(),
+                signature_size: signature.len() as u32,
+            },
+            Request::Verify {
+                client_id,
+                request_id,
+                key_id,
+                message,
+                prehashed,
+                signature,
+            } => RequestRaw::Verify {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                message_data: message.as_ptr(),
+                message_size: message.len() as u32,
+                prehashed: prehashed.into(),
+                signature_data: signature.as_ptr(),
+                signature_size: signature.len() as u32,
+            },
+            Request::VerifyExternalKey {
+                client_id,
+                request_id,
+                public_key: key,
+                message,
+                prehashed,
+                signature,
+            } => RequestRaw::VerifyExternalKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_data: key.as_ptr(),
+                key_size: key.len() as u32,
+                message_data: message.as_ptr(),
+                message_size: message.len() as u32,
+                prehashed: prehashed.into(),
+                signature_data: signature.as_ptr(),
+                signature_size: signature.len() as u32,
+            },
+            Request::SignJws {
+                client_id,
+                request_id,
+                key_id,
+                header,
+                payload,
+                token,
+            } => RequestRaw::SignJws {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                header_data: header.as_ptr(),
+                header_size: header.len() as u32,
+                payload_data: payload.as_ptr(),
+                payload_size: payload.len() as u32,
+                token_data: token.as_mut_ptr(),
+                token_size: token.len() as u32,
+            },
+            Request::VerifyJws {
+                client_id,
+                request_id,
+                key_id,
+                token,
+            } => RequestRaw::VerifyJws {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
                 key_id: key_id.into(),
+                token_data: token.as_ptr(),
+                token_size: token.len() as u32,
+            },
+            Request::VerifyJwsExternalKey {
+                client_id,
+                request_id,
+                public_key,
+                token,
+            } => RequestRaw::VerifyJwsExternalKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
                 public_key_data: public_key.as_ptr(),
                 public_key_size: public_key.len() as u32,
-                private_key_data: private_key.as_ptr(),
-                private_key_size: private_key.len() as u32,
+                token_data: token.as_ptr(),
+                token_size: token.len() as u32,
+            },
+            Request::HpkeSeal {
+                client_id,
+                request_id,
+                sender_key_id,
+                aead,
+                recipient_public_key,
+                info,
+                aad,
+                buffer,
+                encapsulated_key,
+                tag,
+            } => RequestRaw::HpkeSeal {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                has_sender_key_id: sender_key_id.is_some().into(),
+                sender_key_id: sender_key_id.unwrap_or_default().into(),
+                aead: aead.id().into(),
+                recipient_public_key_data: recipient_public_key.as_ptr(),
+                recipient_public_key_size: recipient_public_key.len() as u32,
+                info_data: info.as_ptr(),
+                info_size: info.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                encapsulated_key_data: encapsulated_key.as_mut_ptr(),
+                encapsulated_key_size: encapsulated_key.len() as u32,
+                tag_data: tag.as_mut_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::HpkeOpen {
+                client_id,
+                request_id,
+                key_id,
+                sender_public_key,
+                aead,
+                encapsulated_key,
+                info,
+                aad,
+                buffer,
+                tag,
+            } => RequestRaw::HpkeOpen {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                sender_public_key_data: sender_public_key
+                    .map_or(ptr::null(), |sender_public_key| sender_public_key.as_ptr()),
+                sender_public_key_size: sender_public_key
+                    .map_or(0, |sender_public_key| sender_public_key.len() as u32),
+                aead: aead.id().into(),
+                encapsulated_key_data: encapsulated_key.as_ptr(),
+                encapsulated_key_size: encapsulated_key.len() as u32,
+                info_data: info.as_ptr(),
+                info_size: info.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                tag_data: tag.as_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::EciesEncrypt {
+                client_id,
+                request_id,
+                kdf,
+                cipher,
+                recipient_public_key,
+                shared_info,
+                aad,
+                buffer,
+                ephemeral_public_key,
+                tag,
+            } => RequestRaw::EciesEncrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                kdf: kdf as u32,
+                cipher: cipher as u32,
+                recipient_public_key_data: recipient_public_key.as_ptr(),
+                recipient_public_key_size: recipient_public_key.len() as u32,
+                shared_info_data: shared_info.as_ptr(),
+                shared_info_size: shared_info.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                ephemeral_public_key_data: ephemeral_public_key.as_mut_ptr(),
+                ephemeral_public_key_size: ephemeral_public_key.len() as u32,
+                tag_data: tag.as_mut_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::EciesDecrypt {
+                client_id,
+                request_id,
+                key_id,
+                kdf,
+                cipher,
+                ephemeral_public_key,
+                shared_info,
+                aad,
+                buffer,
+                tag,
+            } => RequestRaw::EciesDecrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                kdf: kdf as u32,
+                cipher: cipher as u32,
+                ephemeral_public_key_data: ephemeral_public_key.as_ptr(),
+                ephemeral_public_key_size: ephemeral_public_key.len() as u32,
+                shared_info_data: shared_info.as_ptr(),
+                shared_info_size: shared_info.len() as u32,
+                aad_data: aad.as_ptr(),
+                aad_size: aad.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                tag_data: tag.as_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Request::TlsHkdfExtract {
+                client_id,
+                request_id,
+                salt_key_id,
+                ikm_key_id,
+                peer_key_exchange,
+                output_key_id,
+                overwrite,
+            } => RequestRaw::TlsHkdfExtract {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                has_salt_key_id: salt_key_id.is_some().into(),
+                salt_key_id: salt_key_id.unwrap_or_default().into(),
+                has_ikm_key_id: ikm_key_id.is_some().into(),
+                ikm_key_id: ikm_key_id.unwrap_or_default().into(),
+                peer_key_exchange_data: peer_key_exchange
+                    .map_or(ptr::null(), |peer_key_exchange| peer_key_exchange.as_ptr()),
+                peer_key_exchange_size: peer_key_exchange
+                    .map_or(0, |peer_key_exchange| peer_key_exchange.len() as u32),
+                output_key_id: output_key_id.into(),
+                overwrite: overwrite.into(),
+            },
+            Request::TlsHkdfExpandLabel {
+                client_id,
+                request_id,
+                secret_key_id,
+                label,
+                context,
+                output_key_id,
+                overwrite,
+            } => RequestRaw::TlsHkdfExpandLabel {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                secret_key_id: secret_key_id.into(),
+                label_data: label.as_ptr(),
+                label_size: label.len() as u32,
+                context_data: context.as_ptr(),
+                context_size: context.len() as u32,
+                output_key_id: output_key_id.into(),
+                overwrite: overwrite.into(),
+            },
+            Request::TlsDeriveSecret {
+                client_id,
+                request_id,
+                secret_key_id,
+                label,
+                transcript_hash,
+                output_key_id,
+                overwrite,
+            } => RequestRaw::TlsDeriveSecret {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                secret_key_id: secret_key_id.into(),
+                label_data: label.as_ptr(),
+                label_size: label.len() as u32,
+                transcript_hash_data: transcript_hash.as_ptr(),
+                transcript_hash_size: transcript_hash.len() as u32,
+                output_key_id: output_key_id.into(),
                 overwrite: overwrite.into(),
             },
-            Request::ExportSymmetricKey {
+            Request::TlsDeriveIv {
+                client_id,
+                request_id,
+                secret_key_id,
+                iv,
+            } => RequestRaw::TlsDeriveIv {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                secret_key_id: secret_key_id.into(),
+                iv_data: iv.as_mut_ptr(),
+                iv_size: iv.len() as u32,
+            },
+            Request::TlsFinished {
+                client_id,
+                request_id,
+                base_key_id,
+                transcript_hash,
+                verify_data,
+            } => RequestRaw::TlsFinished {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                base_key_id: base_key_id.into(),
+                transcript_hash_data: transcript_hash.as_ptr(),
+                transcript_hash_size: transcript_hash.len() as u32,
+                verify_data_data: verify_data.as_mut_ptr(),
+                verify_data_size: verify_data.len() as u32,
+            },
+            Request::TlsSignCertificateVerify {
+                client_id,
+                request_id,
+                key_id,
+                server,
+                transcript_hash,
+                signature,
+            } => RequestRaw::TlsSignCertificateVerify {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+                server: server.into(),
+                transcript_hash_data: transcript_hash.as_ptr(),
+                transcript_hash_size: transcript_hash.len() as u32,
+                signature_data: signature.as_mut_ptr(),
+                signature_size: signature.len() as u32,
+            },
+            Request::NoiseStartHandshake {
                 client_id,
                 request_id,
                 key_id,
-                data,
-            } => RequestRaw::ExportSymmetricKey {
+                initiator,
+                prologue,
+            } => RequestRaw::NoiseStartHandshake {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
                 key_id: key_id.into(),
-                data_data: data.as_mut_ptr(),
-                data_size: data.len() as u32,
+                initiator: initiator.into(),
+                prologue_data: prologue.as_ptr(),
+                prologue_size: prologue.len() as u32,
             },
-            Request::ExportPublicKey {
+            Request::NoiseWriteMessage {
                 client_id,
                 request_id,
-                key_id,
-                public_key,
-            } => RequestRaw::ExportPublicKey {
+                session,
+                payload,
+                message,
+            } => RequestRaw::NoiseWriteMessage {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
-                public_key_data: public_key.as_mut_ptr(),
-                public_key_size: public_key.len() as u32,
+                session: session.into(),
+                payload_data: payload.as_ptr(),
+                payload_size: payload.len() as u32,
+                message_data: message.as_mut_ptr(),
+                message_size: message.len() as u32,
             },
-            Request::ExportPrivateKey {
+            Request::NoiseReadMessage {
                 client_id,
                 request_id,
-                key_id,
-                private_key,
-            } => RequestRaw::ExportPrivateKey {
+                session,
+                message,
+                payload,
+            } => RequestRaw::NoiseReadMessage {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
-                private_key_data: private_key.as_mut_ptr(),
-                private_key_size: private_key.len() as u32,
+                session: session.into(),
+                message_data: message.as_ptr(),
+                message_size: message.len() as u32,
+                payload_data: payload.as_mut_ptr(),
+                payload_size: payload.len() as u32,
             },
-            Request::IsKeyAvailable {
+            Request::NoiseSessionInfo {
                 client_id,
                 request_id,
-                key_id,
-            } => RequestRaw::IsKeyAvailable {
+                session,
+                remote_static_public_key,
+                handshake_hash,
+            } => RequestRaw::NoiseSessionInfo {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
+                session: session.into(),
+                remote_static_public_key_data: remote_static_public_key.as_mut_ptr(),
+                remote_static_public_key_size: remote_static_public_key.len() as u32,
+                handshake_hash_data: handshake_hash.as_mut_ptr(),
+                handshake_hash_size: handshake_hash.len() as u32,
             },
-            Request::EncryptChaChaPoly {
+            Request::NoiseEncrypt {
                 client_id,
                 request_id,
-                key_id,
-                nonce,
+                session,
                 buffer,
-                aad,
                 tag,
-            } => RequestRaw::EncryptChaChaPoly {
+            } => RequestRaw::NoiseEncrypt {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
-                nonce_data: nonce.as_ptr(),
-                nonce_size: nonce.len() as u32,
+                session: session.into(),
                 buffer_data: buffer.as_mut_ptr(),
                 buffer_size: buffer.len() as u32,
-                aad_data: aad.as_ptr(),
-                aad_size: aad.len() as u32,
                 tag_data: tag.as_mut_ptr(),
                 tag_size: tag.len() as u32,
             },
-            Request::EncryptChaChaPolyExternalKey {
+            Request::NoiseDecrypt {
                 client_id,
                 request_id,
-                key,
-                nonce,
+                session,
                 buffer,
-                aad,
                 tag,
-            } => RequestRaw::EncryptChaChaPolyExternalKey {
+            } => RequestRaw::NoiseDecrypt {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_data: key.as_ptr(),
-                key_size: key.len() as u32,
-                nonce_data: nonce.as_ptr(),
-                nonce_size: nonce.len() as u32,
+                session: session.into(),
                 buffer_data: buffer.as_mut_ptr(),
                 buffer_size: buffer.len() as u32,
-                aad_data: aad.as_ptr(),
-                aad_size: aad.len() as u32,
-                tag_data: tag.as_mut_ptr(),
+                tag_data: tag.as_ptr(),
                 tag_size: tag.len() as u32,
             },
-            Request::DecryptChaChaPoly {
+            Request::NoiseCloseSession {
                 client_id,
                 request_id,
-                key_id,
-                nonce,
-                buffer,
-                aad,
-                tag,
-            } => RequestRaw::DecryptChaChaPoly {
+                session,
+            } => RequestRaw::NoiseCloseSession {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
-                nonce_data: nonce.as_ptr(),
-                nonce_size: nonce.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
-                aad_data: aad.as_ptr(),
-                aad_size: aad.len() as u32,
-                tag_data: tag.as_ptr(),
-                tag_size: tag.len() as u32,
+                session: session.into(),
             },
-            Request::DecryptChaChaPolyExternalKey {
+            Request::SecOcAuthenticate {
                 client_id,
                 request_id,
-                key,
-                nonce,
-                buffer,
-                aad,
-                tag,
-            } => RequestRaw::DecryptChaChaPolyExternalKey {
+                data_id,
+                payload,
+                secured_pdu,
+            } => RequestRaw::SecOcAuthenticate {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_data: key.as_ptr(),
-                key_size: key.len() as u32,
-                nonce_data: nonce.as_ptr(),
-                nonce_size: nonce.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
-                aad_data: aad.as_ptr(),
-                aad_size: aad.len() as u32,
-                tag_data: tag.as_ptr(),
-                tag_size: tag.len() as u32,
+                data_id,
+                payload_data: payload.as_ptr(),
+                payload_size: payload.len() as u32,
+                secured_pdu_data: secured_pdu.as_mut_ptr(),
+                secured_pdu_size: secured_pdu.len() as u32,
             },
-            Request::EncryptAesGcm {
+            Request::SecOcVerify {
                 client_id,
                 request_id,
-                key_id,
-                iv,
-                buffer,
-                aad,
-                tag,
-            } => RequestRaw::EncryptAesGcm {
+                data_id,
+                secured_pdu,
+            } => RequestRaw::SecOcVerify {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
-                iv_data: iv.as_ptr(),
-                iv_size: iv.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
-                aad_data: aad.as_ptr(),
-                aad_size: aad.len() as u32,
-                tag_data: tag.as_mut_ptr(),
-                tag_size: tag.len() as u32,
+                data_id,
+                secured_pdu_data: secured_pdu.as_ptr(),
+                secured_pdu_size: secured_pdu.len() as u32,
             },
-            Request::EncryptAesGcmExternalKey {
+            Request::CreateCounter {
                 client_id,
                 request_id,
-                key,
-                iv,
-                buffer,
-                aad,
-                tag,
-            } => RequestRaw::EncryptAesGcmExternalKey {
+                counter_id,
+                max_value,
+            } => RequestRaw::CreateCounter {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_data: key.as_ptr(),
-                key_size: key.len() as u32,
-                iv_data: iv.as_ptr(),
-                iv_size: iv.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
-                aad_data: aad.as_ptr(),
-                aad_size: aad.len() as u32,
-                tag_data: tag.as_mut_ptr(),
-                tag_size: tag.len() as u32,
+                counter_id: counter_id.into(),
+                max_value,
             },
-            Request::DecryptAesGcm {
+            Request::ReadCounter {
                 client_id,
                 request_id,
-                key_id,
-                iv,
-                buffer,
-                aad,
-                tag,
-            } => RequestRaw::DecryptAesGcm {
+                counter_id,
+            } => RequestRaw::ReadCounter {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
-                iv_data: iv.as_ptr(),
-                iv_size: iv.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
-                aad_data: aad.as_ptr(),
-                aad_size: aad.len() as u32,
-                tag_data: tag.as_ptr(),
-                tag_size: tag.len() as u32,
+                counter_id: counter_id.into(),
             },
-            Request::DecryptAesGcmExternalKey {
+            Request::IncrementCounter {
                 client_id,
                 request_id,
-                key,
-                iv,
-                buffer,
-                aad,
-                tag,
-            } => RequestRaw::DecryptAesGcmExternalKey {
+                counter_id,
+                amount,
+            } => RequestRaw::IncrementCounter {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_data: key.as_ptr(),
-                key_size: key.len() as u32,
-                iv_data: iv.as_ptr(),
-                iv_size: iv.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
-                aad_data: aad.as_ptr(),
-                aad_size: aad.len() as u32,
-                tag_data: tag.as_ptr(),
-                tag_size: tag.len() as u32,
+                counter_id: counter_id.into(),
+                amount,
             },
-            Request::EncryptAesCbc {
+            Request::VerifyImageStart {
                 client_id,
                 request_id,
                 key_id,
-                iv,
-                buffer,
-                plaintext_size,
-            } => RequestRaw::EncryptAesCbc {
+                manifest,
+            } => RequestRaw::VerifyImageStart {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
                 key_id: key_id.into(),
-                iv_data: iv.as_ptr(),
-                iv_size: iv.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
-                plaintext_size: plaintext_size as u32,
+                manifest_data: manifest.as_ptr(),
+                manifest_size: manifest.len() as u32,
             },
-            Request::EncryptAesCbcExternalKey {
+            Request::VerifyImageUpdate {
                 client_id,
                 request_id,
-                key,
-                iv,
-                buffer,
-                plaintext_size,
-            } => RequestRaw::EncryptAesCbcExternalKey {
+                session,
+                chunk,
+            } => RequestRaw::VerifyImageUpdate {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_data: key.as_ptr(),
-                key_size: key.len() as u32,
-                iv_data: iv.as_ptr(),
-                iv_size: iv.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
-                plaintext_size: plaintext_size as u32,
+                session: session.into(),
+                chunk_data: chunk.as_ptr(),
+                chunk_size: chunk.len() as u32,
             },
-            Request::DecryptAesCbc {
+            Request::VerifyImageFinish {
                 client_id,
                 request_id,
-                key_id,
-                iv,
-                buffer,
-            } => RequestRaw::DecryptAesCbc {
+                session,
+            } => RequestRaw::VerifyImageFinish {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
-                iv_data: iv.as_ptr(),
-                iv_size: iv.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
+                session: session.into(),
             },
-            Request::DecryptAesCbcExternalKey {
+            Request::ExtendMeasurement {
                 client_id,
                 request_id,
-                key,
-                iv,
-                buffer,
-            } => RequestRaw::DecryptAesCbcExternalKey {
+                register,
+                digest,
+            } => RequestRaw::ExtendMeasurement {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_data: key.as_ptr(),
-                key_size: key.len() as u32,
-                iv_data: iv.as_ptr(),
-                iv_size: iv.len() as u32,
-                buffer_data: buffer.as_mut_ptr(),
-                buffer_size: buffer.len() as u32,
+                register,
+                digest_data: digest.as_ptr(),
+                digest_size: digest.len() as u32,
             },
-            Request::Sign {
+            Request::ReadMeasurement {
                 client_id,
                 request_id,
-                key_id,
-                message,
-                prehashed,
-                signature,
-            } => RequestRaw::Sign {
+                register,
+                value,
+            } => RequestRaw::ReadMeasurement {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_id: key_id.into(),
-                message_data: message.as_ptr(),
-                message_size: message.len() as u32,
-                prehashed: prehashed.into(),
-                signature_data: signature.as_mut_ptr(),
-                signature_size: signature.len() as u32,
+                register,
+                value_data: value.as_mut_ptr(),
+                value_size: value.len() as u32,
             },
-            Request::SignExternalKey {
+            Request::QuoteMeasurements {
+                client_id,
+                request_id,
+                selection,
+                nonce,
+                quote,
+            } => RequestRaw::QuoteMeasurements {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                selection,
+                nonce_data: nonce.as_ptr(),
+                nonce_size: nonce.len() as u32,
+                quote_data: quote.as_mut_ptr(),
+                quote_size: quote.len() as u32,
+            },
+            Request::DeriveDiceAlias {
+                client_id,
+                request_id,
+                measurement,
+                certificate,
+            } => RequestRaw::DeriveDiceAlias {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                measurement_data: measurement.as_ptr(),
+                measurement_size: measurement.len() as u32,
+                certificate_data: certificate.as_mut_ptr(),
+                certificate_size: certificate.len() as u32,
+            },
+            Request::ProcessSpdmMessage {
                 client_id,
                 request_id,
-                private_key: key,
                 message,
-                prehashed,
-                signature,
-            } => RequestRaw::SignExternalKey {
+                response,
+            } => RequestRaw::ProcessSpdmMessage {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_data: key.as_ptr(),
-                key_size: key.len() as u32,
                 message_data: message.as_ptr(),
                 message_size: message.len() as u32,
-                prehashed: prehashed.into(),
-                signature_data: signature.as_mut_ptr(),
-                signature_size: signature.len() as u32,
+                response_data: response.as_mut_ptr(),
+                response_size: response.len() as u32,
             },
-            Request::Verify {
+            Request::ReseedRng {
+                client_id,
+                request_id,
+            } => RequestRaw::ReseedRng {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Request::DeleteKey {
                 client_id,
                 request_id,
                 key_id,
-                message,
-                prehashed,
-                signature,
-            } => RequestRaw::Verify {
+            } => RequestRaw::DeleteKey {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
                 key_id: key_id.into(),
-                message_data: message.as_ptr(),
-                message_size: message.len() as u32,
-                prehashed: prehashed.into(),
-                signature_data: signature.as_ptr(),
-                signature_size: signature.len() as u32,
             },
-            Request::VerifyExternalKey {
+            Request::GetKeyInfo {
                 client_id,
                 request_id,
-                public_key: key,
-                message,
-                prehashed,
-                signature,
-            } => RequestRaw::VerifyExternalKey {
+                key_id,
+            } => RequestRaw::GetKeyInfo {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_id.into(),
+            },
+            Request::ListKeys {
+                client_id,
+                request_id,
+                offset,
+                key_ids,
+            } => RequestRaw::ListKeys {
                 client_id: client_id.into(),
                 request_id: request_id.into(),
-                key_data: key.as_ptr(),
-                key_size: key.len() as u32,
-                message_data: message.as_ptr(),
-                message_size: message.len() as u32,
-                prehashed: prehashed.into(),
-                signature_data: signature.as_ptr(),
-                signature_size: signature.len() as u32,
+                offset: offset as u32,
+                key_ids_data: key_ids.as_mut_ptr() as *mut KeyIdRaw,
+                key_ids_size: key_ids.len() as u32,
             },
         }
     }
@@ -1460,6 +3213,369 @@ impl From<Response<'_>> for ResponseRaw {
                 request_id: request_id.into(),
                 verified: verified.into(),
             },
+            Response::SignJws {
+                client_id,
+                request_id,
+                token,
+            } => ResponseRaw::SignJws {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                token_data: token.as_mut_ptr(),
+                token_size: token.len() as u32,
+            },
+            Response::VerifyJws {
+                client_id,
+                request_id,
+                verified,
+            } => ResponseRaw::VerifyJws {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                verified: verified.into(),
+            },
+            Response::HpkeSeal {
+                client_id,
+                request_id,
+                encapsulated_key,
+                buffer,
+                tag,
+            } => ResponseRaw::HpkeSeal {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                encapsulated_key_data: encapsulated_key.as_mut_ptr(),
+                encapsulated_key_size: encapsulated_key.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                tag_data: tag.as_mut_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Response::HpkeOpen {
+                client_id,
+                request_id,
+                plaintext,
+            } => ResponseRaw::HpkeOpen {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                plaintext_data: plaintext.as_mut_ptr(),
+                plaintext_size: plaintext.len() as u32,
+            },
+            Response::EciesEncrypt {
+                client_id,
+                request_id,
+                ephemeral_public_key,
+                buffer,
+                tag,
+            } => ResponseRaw::EciesEncrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                ephemeral_public_key_data: ephemeral_public_key.as_mut_ptr(),
+                ephemeral_public_key_size: ephemeral_public_key.len() as u32,
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                tag_data: tag.as_mut_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Response::EciesDecrypt {
+                client_id,
+                request_id,
+                plaintext,
+            } => ResponseRaw::EciesDecrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                plaintext_data: plaintext.as_mut_ptr(),
+                plaintext_size: plaintext.len() as u32,
+            },
+            Response::TlsHkdfExtract {
+                client_id,
+                request_id,
+            } => ResponseRaw::TlsHkdfExtract {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Response::TlsHkdfExpandLabel {
+                client_id,
+                request_id,
+            } => ResponseRaw::TlsHkdfExpandLabel {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Response::TlsDeriveSecret {
+                client_id,
+                request_id,
+            } => ResponseRaw::TlsDeriveSecret {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Response::TlsDeriveIv {
+                client_id,
+                request_id,
+                iv,
+            } => ResponseRaw::TlsDeriveIv {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                iv_data: iv.as_mut_ptr(),
+                iv_size: iv.len() as u32,
+            },
+            Response::TlsFinished {
+                client_id,
+                request_id,
+                verify_data,
+            } => ResponseRaw::TlsFinished {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                verify_data_data: verify_data.as_mut_ptr(),
+                verify_data_size: verify_data.len() as u32,
+            },
+            Response::TlsSignCertificateVerify {
+                client_id,
+                request_id,
+                signature,
+            } => ResponseRaw::TlsSignCertificateVerify {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                signature_data: signature.as_mut_ptr(),
+                signature_size: signature.len() as u32,
+            },
+            Response::NoiseStartHandshake {
+                client_id,
+                request_id,
+                session,
+            } => ResponseRaw::NoiseStartHandshake {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+            },
+            Response::NoiseWriteMessage {
+                client_id,
+                request_id,
+                message,
+                handshake_finished,
+            } => ResponseRaw::NoiseWriteMessage {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                message_data: message.as_mut_ptr(),
+                message_size: message.len() as u32,
+                handshake_finished: handshake_finished.into(),
+            },
+            Response::NoiseReadMessage {
+                client_id,
+                request_id,
+                payload,
+                handshake_finished,
+            } => ResponseRaw::NoiseReadMessage {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                payload_data: payload.as_mut_ptr(),
+                payload_size: payload.len() as u32,
+                handshake_finished: handshake_finished.into(),
+            },
+            Response::NoiseSessionInfo {
+                client_id,
+                request_id,
+                remote_static_public_key,
+                handshake_hash,
+            } => ResponseRaw::NoiseSessionInfo {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                remote_static_public_key_data: remote_static_public_key.as_mut_ptr(),
+                remote_static_public_key_size: remote_static_public_key.len() as u32,
+                handshake_hash_data: handshake_hash.as_mut_ptr(),
+                handshake_hash_size: handshake_hash.len() as u32,
+            },
+            Response::NoiseEncrypt {
+                client_id,
+                request_id,
+                buffer,
+                tag,
+            } => ResponseRaw::NoiseEncrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                buffer_data: buffer.as_mut_ptr(),
+                buffer_size: buffer.len() as u32,
+                tag_data: tag.as_mut_ptr(),
+                tag_size: tag.len() as u32,
+            },
+            Response::NoiseDecrypt {
+                client_id,
+                request_id,
+                plaintext,
+            } => ResponseRaw::NoiseDecrypt {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                plaintext_data: plaintext.as_mut_ptr(),
+                plaintext_size: plaintext.len() as u32,
+            },
+            Response::NoiseCloseSession {
+                client_id,
+                request_id,
+            } => ResponseRaw::NoiseCloseSession {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Response::SecOcAuthenticate {
+                client_id,
+                request_id,
+                secured_pdu,
+            } => ResponseRaw::SecOcAuthenticate {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                secured_pdu_data: secured_pdu.as_mut_ptr(),
+                secured_pdu_size: secured_pdu.len() as u32,
+            },
+            Response::SecOcVerify {
+                client_id,
+                request_id,
+                verified,
+            } => ResponseRaw::SecOcVerify {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                verified: verified.into(),
+            },
+            Response::CreateCounter {
+                client_id,
+                request_id,
+            } => ResponseRaw::CreateCounter {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Response::ReadCounter {
+                client_id,
+                request_id,
+                value,
+                max_value,
+            } => ResponseRaw::ReadCounter {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                value,
+                max_value,
+            },
+            Response::IncrementCounter {
+                client_id,
+                request_id,
+                value,
+            } => ResponseRaw::IncrementCounter {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                value,
+            },
+            Response::VerifyImageStart {
+                client_id,
+                request_id,
+                session,
+            } => ResponseRaw::VerifyImageStart {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                session: session.into(),
+            },
+            Response::VerifyImageUpdate {
+                client_id,
+                request_id,
+            } => ResponseRaw::VerifyImageUpdate {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Response::VerifyImageFinish {
+                client_id,
+                request_id,
+                verified,
+            } => ResponseRaw::VerifyImageFinish {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                verified: verified.into(),
+            },
+            Response::ExtendMeasurement {
+                client_id,
+                request_id,
+            } => ResponseRaw::ExtendMeasurement {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Response::ReadMeasurement {
+                client_id,
+                request_id,
+                value,
+            } => ResponseRaw::ReadMeasurement {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                value_data: value.as_mut_ptr(),
+                value_size: value.len() as u32,
+            },
+            Response::QuoteMeasurements {
+                client_id,
+                request_id,
+                quote,
+            } => ResponseRaw::QuoteMeasurements {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                quote_data: quote.as_mut_ptr(),
+                quote_size: quote.len() as u32,
+            },
+            Response::DeriveDiceAlias {
+                client_id,
+                request_id,
+                certificate,
+            } => ResponseRaw::DeriveDiceAlias {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                certificate_data: certificate.as_mut_ptr(),
+                certificate_size: certificate.len() as u32,
+            },
+            Response::ProcessSpdmMessage {
+                client_id,
+                request_id,
+                response,
+            } => ResponseRaw::ProcessSpdmMessage {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                response_data: response.as_mut_ptr(),
+                response_size: response.len() as u32,
+            },
+            Response::ReseedRng {
+                client_id,
+                request_id,
+            } => ResponseRaw::ReseedRng {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Response::DeleteKey {
+                client_id,
+                request_id,
+            } => ResponseRaw::DeleteKey {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+            },
+            Response::GetKeyInfo {
+                client_id,
+                request_id,
+                key_info,
+                is_available,
+            } => ResponseRaw::GetKeyInfo {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_id: key_info.id.into(),
+                key_type: key_info.ty.code().into(),
+                permissions: key_info.permissions.bits().into(),
+                usage: key_info.usage.bits().into(),
+                algorithm: key_info
+                    .algorithm
+                    .map_or(0, |algorithm| algorithm.code().into()),
+                use_clients: key_info.access.use_key.0,
+                import_clients: key_info.access.import.0,
+                export_clients: key_info.access.export.0,
+                delete_clients: key_info.access.delete.0,
+                is_available: is_available.into(),
+            },
+            Response::ListKeys {
+                client_id,
+                request_id,
+                key_ids,
+                num_keys,
+            } => ResponseRaw::ListKeys {
+                client_id: client_id.into(),
+                request_id: request_id.into(),
+                key_ids_data: key_ids.as_mut_ptr() as *mut KeyIdRaw,
+                key_ids_size: key_ids.len() as u32,
+                num_keys: num_keys as u32,
+            },
         }
     }
 }
@@ -1494,6 +3610,64 @@ fn check_mut_pointer_and_size<'a>(
     Ok(unsafe { slice::from_raw_parts_mut(data, size as usize) })
 }
 
+/// Check an untrusted pointer to `size` key IDs using a provided validator function.
+fn check_mut_key_ids<'a>(
+    data: *mut KeyIdRaw,
+    size: u32,
+    validator: &impl Fn(*const u8, u32) -> bool,
+) -> Result<&'a mut [KeyId], ValidationError> {
+    if data.is_null() || !data.is_aligned() {
+        return Err(ValidationError::InvalidPointer);
+    }
+    let byte_size = size
+        .checked_mul(mem::size_of::<KeyIdRaw>() as u32)
+        .ok_or(ValidationError::InvalidPointer)?;
+    if !validator(data as *const u8, byte_size) {
+        return Err(ValidationError::InvalidPointer);
+    }
+    // `KeyId` is a transparent wrapper around `KeyIdRaw`
+    Ok(unsafe { slice::from_raw_parts_mut(data as *mut KeyId, size as usize) })
+}
+
+/// Check an optional untrusted pointer and size pair. A null pointer denotes an absent value.
+fn check_optional_pointer_and_size<'a>(
+    data: *const u8,
+    size: u32,
+    validator: &impl Fn(*const u8, u32) -> bool,
+) -> Result<Option<&'a [u8]>, ValidationError> {
+    if data.is_null() {
+        return Ok(None);
+    }
+    check_pointer_and_size(data, size, validator).map(Some)
+}
+
+/// Convert an IANA HPKE AEAD identifier to the corresponding enum value.
+fn hpke_aead_from_raw(aead: u32) -> Result<hpke::Aead, ValidationError> {
+    u16::try_from(aead)
+        .ok()
+        .and_then(hpke::Aead::from_id)
+        .ok_or(ValidationError::InvalidEnumValue)
+}
+
+/// Convert a raw ECIES KDF value to the corresponding enum value.
+fn ecies_kdf_from_raw(kdf: u32) -> Result<ecies::Kdf, ValidationError> {
+    match kdf {
+        0 => Ok(ecies::Kdf::AnsiX963Sha256),
+        1 => Ok(ecies::Kdf::HkdfSha256),
+        _ => Err(ValidationError::InvalidEnumValue),
+    }
+}
+
+/// Convert a raw ECIES cipher value to the corresponding enum value.
+fn ecies_cipher_from_raw(cipher: u32) -> Result<ecies::Cipher, ValidationError> {
+    match cipher {
+        0 => Ok(ecies::Cipher::Aes128Gcm),
+        1 => Ok(ecies::Cipher::Aes256Gcm),
+        2 => Ok(ecies::Cipher::ChaCha20Poly1305),
+        _ => Err(ValidationError::InvalidEnumValue),
+    }
+}
+
 fn bool_raw_to_bool(overwrite: BoolRaw) -> bool {
     overwrite != 0
 }
@@ -1520,6 +3694,8 @@ mod test {
             client_id,
             request_id,
             output: &mut shared_memory,
+            prediction_resistance: false,
+            additional_input: &[],
         };
         let request_raw: RequestRaw = request.into();
         let request_raw_ptr = &request_raw as *const RequestRaw as *const u8;
@@ -1534,6 +3710,7 @@ mod test {
                 client_id: reconstructed_client_id,
                 request_id: reconstructed_request_id,
                 output: reconstructed_output,
+                ..
             } => {
                 assert_eq!(reconstructed_client_id, client_id);
                 assert_eq!(reconstructed_request_id, request_id);
@@ -1558,6 +3735,8 @@ mod test {
             client_id,
             request_id,
             output: unsafe { slice::from_raw_parts_mut(output_start, OUTPUT_SIZE) },
+            prediction_resistance: false,
+            additional_input: &[],
         };
         let request_raw: RequestRaw = request.into();
         unsafe {
@@ -1599,6 +3778,8 @@ mod test {
             client_id,
             request_id,
             output: unsafe { slice::from_raw_parts_mut(output_start, OUTPUT_SIZE) },
+            prediction_resistance: false,
+            additional_input: &[],
         };
         let request_raw: RequestRaw = request.into();
         unsafe {
@@ -1619,4 +3800,44 @@ mod test {
             },
         }
     }
+
+    #[test]
+    fn test_key_ids() {
+        let client_id = ClientId(5);
+        let request_id = RequestId(7);
+        let mut key_ids = [KeyId::default(); 4];
+        let key_ids_ptr = key_ids.as_ptr();
+        let request = Request::ListKeys {
+            client_id,
+            request_id,
+            offset: 2,
+            key_ids: &mut key_ids,
+        };
+        let request_raw: RequestRaw = request.into();
+        let always_valid = |_data: *const u8, _size: u32| true;
+        let Request::ListKeys {
+            offset, key_ids, ..
+        } = request_raw
+            .verify(&always_valid)
+            .expect("failed to verify raw request")
+        else {
+            panic!("Unexpected reconstructed request type")
+        };
+        assert_eq!(offset, 2);
+        assert_eq!(key_ids.as_ptr(), key_ids_ptr);
+        assert_eq!(key_ids.len(), 4);
+
+        let mut aligned = [0u32; 2];
+        let request_raw = RequestRaw::ListKeys {
+            client_id: client_id.into(),
+            request_id: request_id.into(),
+            offset: 0,
+            key_ids_data: unsafe { (aligned.as_mut_ptr() as *mut u8).add(1) } as *mut KeyIdRaw,
+            key_ids_size: 1,
+        };
+        assert!(matches!(
+            request_raw.verify(&always_valid),
+            Err(ValidationError::InvalidPointer)
+        ));
+    }
 }
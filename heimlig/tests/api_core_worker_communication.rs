@@ -4,19 +4,46 @@ mod tests {
     use futures::future::join;
     use heimlig::client::api::Api;
     use heimlig::client::api::SymmetricAlgorithm::{AesCbc, AesGcm, ChaCha20Poly1305};
-    use heimlig::common::jobs::{Error, Request, RequestType, Response};
+    use heimlig::common::jobs::{ClientId, Error, Request, RequestType, Response, SessionId};
     use heimlig::common::limits::MAX_RANDOM_SIZE;
     use heimlig::crypto;
-    use heimlig::crypto::rng::{EntropySource, Rng};
+    use heimlig::crypto::attestation::{MAX_QUOTE_SIZE, REGISTER_SIZE};
+    use heimlig::crypto::dice::ALIAS_CERTIFICATE_SIZE;
+    use heimlig::crypto::drbg::ctr::CtrDrbg;
+    use heimlig::crypto::drbg::hmac::HmacDrbg;
+    use heimlig::crypto::health::{HealthTestConfig, HealthTestedSource};
+    use heimlig::crypto::jws;
+    use heimlig::crypto::manifest::Manifest;
+    use heimlig::crypto::rng;
+    use heimlig::crypto::rng::{EntropySource, Rng, TryEntropySource};
+    use heimlig::crypto::spdm;
     use heimlig::hsm::core::Builder;
-    use heimlig::hsm::keystore::{KeyId, KeyInfo, KeyPermissions, KeyStore, KeyType};
+    use heimlig::hsm::counter_store;
+    use heimlig::hsm::counter_store::{CounterId, CounterStore};
+    use heimlig::hsm::keystore;
+    use heimlig::hsm::keystore::{
+        ClientSet, KeyAccessControl, KeyAlgorithm, KeyId, KeyInfo, KeyPermissions, KeyStore,
+        KeyType, KeyUsageFlags,
+    };
     use heimlig::hsm::workers::aes_worker::AesWorker;
+    use heimlig::hsm::workers::attestation_worker::AttestationWorker;
+    use heimlig::hsm::workers::boot_worker::{BootWorker, ImageVerifications};
     use heimlig::hsm::workers::chachapoly_worker::ChaChaPolyWorker;
+    use heimlig::hsm::workers::counter_worker::CounterWorker;
+    use heimlig::hsm::workers::dice_worker::DiceWorker;
     use heimlig::hsm::workers::ecc_worker::EccWorker;
+    use heimlig::hsm::workers::ecies_worker::EciesWorker;
+    use heimlig::hsm::workers::hpke_worker::HpkeWorker;
+    use heimlig::hsm::workers::jws_worker::JwsWorker;
+    use heimlig::hsm::workers::noise_worker::{NoiseSessions, NoiseWorker};
     use heimlig::hsm::workers::rng_worker::RngWorker;
+    use heimlig::hsm::workers::secoc_worker::{DataIdConfig, DataIdTable, SecOcWorker};
+    use heimlig::hsm::workers::spdm_worker::SpdmWorker;
+    use heimlig::hsm::workers::tls_worker::TlsWorker;
     use heimlig::integration::embassy::{
         AsyncQueue, RequestQueueSink, RequestQueueSource, ResponseQueueSink, ResponseQueueSource,
     };
+    use heimlig::integration::memory_counter_store::MemoryCounterStore;
     use heimlig::integration::memory_key_store::MemoryKeyStore;
     use sha2::{Digest, Sha256};
 
@@ -29,6 +56,16 @@ mod tests {
     pub const NUM_KEYS: usize = 3;
     pub const TOTAL_KEY_SIZE: usize =
         SYM_128_KEY.ty.key_size() + SYM_256_KEY.ty.key_size() + ASYM_NIST_P256_KEY.ty.key_size();
+    const NO_USAGE: KeyUsageFlags = KeyUsageFlags {
+        encrypt: false,
+        decrypt: false,
+        sign: false,
+        verify: false,
+        derive: false,
+        wrap: false,
+        unwrap: false,
+        mac: false,
+    };
     const SYM_128_KEY: KeyInfo = KeyInfo {
         id: KeyId(0),
         ty: KeyType::Symmetric128Bits,
@@ -38,6 +75,18 @@ mod tests {
             overwrite: false,
             delete: false,
         },
+        usage: KeyUsageFlags {
+            encrypt: true,
+            decrypt: true,
+            sign: false,
+            verify: false,
+            derive: false,
+            wrap: false,
+            unwrap: false,
+            mac: true,
+        },
+        algorithm: None,
+        access: KeyAccessControl::ALL,
     };
     const SYM_256_KEY: KeyInfo = KeyInfo {
         id: KeyId(1),
@@ -48,6 +97,18 @@ mod tests {
             overwrite: false,
             delete: false,
         },
+        usage: KeyUsageFlags {
+            encrypt: true,
+            decrypt: true,
+            sign: false,
+            verify: false,
+            derive: true,
+            wrap: false,
+            unwrap: false,
+            mac: false,
+        },
+        algorithm: None,
+        access: KeyAccessControl::ALL,
     };
     const ASYM_NIST_P256_KEY: KeyInfo = KeyInfo {
         id: KeyId(2),
@@ -58,6 +119,63 @@ mod tests {
             overwrite: false,
             delete: false,
         },
+        usage: KeyUsageFlags {
+            encrypt: false,
+            decrypt: true,
+            sign: true,
+            verify: true,
+            derive: true,
+            wrap: false,
+            unwrap: false,
+            mac: false,
+        },
+        algorithm: None,
+        access: KeyAccessControl::ALL,
+    };
+    const ASYM_ED25519_KEY: KeyInfo = KeyInfo {
+        id: KeyId(3),
+        ty: KeyType::EccKeypairEd25519,
+        permissions: KeyPermissions {
+            import: true,
+            export_private: false,
+            overwrite: false,
+            delete: false,
+        },
+        usage: KeyUsageFlags {
+            encrypt: false,
+            decrypt: false,
+            sign: true,
+            verify: true,
+            derive: false,
+            wrap: false,
+            unwrap: false,
+            mac: false,
+        },
+        algorithm: None,
+        access: KeyAccessControl::ALL,
+    };
+
+    const ASYM_X25519_KEY: KeyInfo = KeyInfo {
+        id: KeyId(4),
+        ty: KeyType::EccKeypairX25519,
+        permissions: KeyPermissions {
+            import: true,
+            export_private: false,
+            overwrite: false,
+            delete: false,
+        },
+        usage: KeyUsageFlags {
+            encrypt: false,
+            decrypt: true,
+            sign: false,
+            verify: false,
+            derive: true,
+            wrap: false,
+            unwrap: false,
+            mac: false,
+        },
+        algorithm: None,
+        access: KeyAccessControl::ALL,
     };
 
     #[derive(Default)]
@@ -161,6 +279,7 @@ mod tests {
         let mut rng_worker = RngWorker {
             rng: &rng,
             key_store: &key_store,
+            reseed_client: None,
             requests: rng_requests_rx,
             responses: rng_responses_tx,
         };
@@ -224,6 +343,7 @@ mod tests {
         let mut rng_worker = RngWorker {
             rng: &rng,
             key_store: &key_store,
+            reseed_client: None,
             requests: rng_requests_rx,
             responses: rng_responses_tx,
         };
@@ -287,6 +407,7 @@ mod tests {
         let mut rng_worker = RngWorker {
             rng: &rng,
             key_store: &key_store,
+            reseed_client: None,
             requests: rng_requests_rx,
             responses: rng_responses_tx,
         };
@@ -1301,6 +1422,3318 @@ mod tests {
         assert!(verified);
     }
 
+    #[async_std::test]
+    async fn sign_verify_jws() {
+        const KEY_INFOS: [KeyInfo; 2] = [ASYM_NIST_P256_KEY, ASYM_ED25519_KEY];
+        const HEADER: &[u8] = br#"{"alg":"ES256","typ":"JWT"}"#;
+        const PAYLOAD: &[u8] = br#"{"sub":"vehicle-42","iat":1516239022}"#;
+        let mut token = [0u8; crypto::jws::token_size(
+            crypto::jws::Algorithm::Es256,
+            HEADER.len(),
+            PAYLOAD.len(),
+        )];
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, public_key) = crypto::ecdsa::nist_p256_generate_key_pair(&mut rng);
+        let (ed25519_private_key, ed25519_public_key) =
+            crypto::ed25519::ed25519_generate_key_pair(&mut rng);
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut jws_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut jws_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (jws_requests_rx, jws_requests_tx, jws_responses_rx, jws_responses_tx) =
+            split_queues(&mut jws_requests, &mut jws_responses);
+        let mut key_store = MemoryKeyStore::<
+            { ASYM_NIST_P256_KEY.ty.key_size() + ASYM_ED25519_KEY.ty.key_size() },
+            2,
+        >::try_new(&KEY_INFOS)
+        .expect("failed to create key store");
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut jws_worker = JwsWorker {
+            key_store: &key_store,
+            requests: jws_requests_rx,
+            responses: jws_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[
+                RequestType::SignJws,
+                RequestType::VerifyJws,
+                RequestType::VerifyJwsExternalKey,
+            ],
+            jws_requests_tx,
+            jws_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Import keys
+        api.import_key_pair(ASYM_NIST_P256_KEY.id, &public_key, &private_key, false)
+            .await
+            .expect("failed to send request");
+        api.import_key_pair(
+            ASYM_ED25519_KEY.id,
+            &ed25519_public_key,
+            &ed25519_private_key,
+            false,
+        )
+        .await
+        .expect("failed to send request");
+        for _ in 0..2 {
+            core.execute().await.expect("failed to process request");
+            let Some(Response::ImportKeyPair { .. }) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+        }
+
+        // Sign token
+        let org_request_id = api
+            .sign_jws(ASYM_NIST_P256_KEY.id, HEADER, PAYLOAD, &mut token)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        jws_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::SignJws {
+            client_id: _,
+            request_id,
+            token,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert!(token.starts_with(b"eyJhbGciOiJFUzI1NiIsInR5cCI6IkpXVCJ9."));
+        let token: &[u8] = token;
+
+        // Verify token with stored key, external key and a key of a different type
+        for (key, expected) in [
+            (Some(ASYM_NIST_P256_KEY.id), true),
+            (None, true),
+            (Some(ASYM_ED25519_KEY.id), false),
+        ] {
+            let org_request_id = match key {
+                Some(key_id) => api.verify_jws(key_id, token).await,
+                None => api.verify_jws_external_key(&public_key, token).await,
+            }
+            .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            jws_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(response) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            match response {
+                Response::VerifyJws {
+                    client_id: _,
+                    request_id,
+                    verified,
+                } => {
+                    assert_eq!(request_id, org_request_id);
+                    assert_eq!(verified, expected);
+                }
+                _ => panic!("Unexpected response type {:?}", response),
+            }
+        }
+    }
+
+    #[async_std::test]
+    async fn hpke_seal_open() {
+        const KEY_INFOS: [KeyInfo; 1] = [ASYM_X25519_KEY];
+        const PLAINTEXT: &[u8] = b"Attack at dawn!";
+        const INFO: &[u8] = b"heimlig hpke test";
+        const AAD: &[u8] = b"header";
+        let mut buffers = [[0u8; PLAINTEXT.len()]; 2];
+        let mut encapsulated_keys = [[0u8; crypto::hpke::MAX_ENCAPSULATED_KEY_SIZE]; 2];
+        let mut tags = [[0u8; crypto::hpke::TAG_SIZE]; 2];
+        // Protocol workers are not tied to the default DRBG
+        let mut rng = Rng::<_, HmacDrbg>::instantiate(TestEntropySource::default(), &[], None);
+        let (private_key, public_key) = crypto::x25519::x25519_generate_key_pair(&mut rng);
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut hpke_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut hpke_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (hpke_requests_rx, hpke_requests_tx, hpke_responses_rx, hpke_responses_tx) =
+            split_queues(&mut hpke_requests, &mut hpke_responses);
+        let rng = Mutex::new(rng);
+        let mut key_store =
+            MemoryKeyStore::<{ ASYM_X25519_KEY.ty.key_size() }, 1>::try_new(&KEY_INFOS)
+                .expect("failed to create key store");
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut hpke_worker = HpkeWorker {
+            rng: &rng,
+            key_store: &key_store,
+            requests: hpke_requests_rx,
+            responses: hpke_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::HpkeSeal, RequestType::HpkeOpen],
+            hpke_requests_tx,
+            hpke_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Import recipient key
+        api.import_key_pair(ASYM_X25519_KEY.id, &public_key, &private_key, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::ImportKeyPair { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Base mode and auth mode (the stored key authenticates the sender and receives)
+        for (((sender_key_id, buffer), encapsulated_key), tag) in [None, Some(ASYM_X25519_KEY.id)]
+            .into_iter()
+            .zip(buffers.iter_mut())
+            .zip(encapsulated_keys.iter_mut())
+            .zip(tags.iter_mut())
+        {
+            buffer.copy_from_slice(PLAINTEXT);
+            let org_request_id = api
+                .hpke_seal(
+                    sender_key_id,
+                    crypto::hpke::Aead::Aes256Gcm,
+                    &public_key,
+                    INFO,
+                    AAD,
+                    buffer,
+                    encapsulated_key,
+                    tag,
+                )
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            hpke_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(response) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            let Response::HpkeSeal {
+                client_id: _,
+                request_id,
+                encapsulated_key,
+                buffer,
+                tag,
+            } = response
+            else {
+                panic!("Unexpected response type {:?}", response)
+            };
+            assert_eq!(request_id, org_request_id);
+            assert_eq!(encapsulated_key.len(), crypto::x25519::KEY_SIZE);
+            assert_ne!(buffer, PLAINTEXT);
+
+            // Open with the stored recipient key
+            let sender_public_key = sender_key_id.map(|_| public_key.as_slice());
+            let org_request_id = api
+                .hpke_open(
+                    ASYM_X25519_KEY.id,
+                    sender_public_key,
+                    crypto::hpke::Aead::Aes256Gcm,
+                    encapsulated_key,
+                    INFO,
+                    AAD,
+                    buffer,
+                    tag,
+                )
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            hpke_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(response) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            let Response::HpkeOpen {
+                client_id: _,
+                request_id,
+                plaintext,
+            } = response
+            else {
+                panic!("Unexpected response type {:?}", response)
+            };
+            assert_eq!(request_id, org_request_id);
+            assert_eq!(plaintext, PLAINTEXT);
+        }
+    }
+
+    #[async_std::test]
+    async fn ecies_encrypt_decrypt() {
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, ASYM_NIST_P256_KEY];
+        const PLAINTEXT: &[u8] = b"Provisioned secret";
+        const SHARED_INFO: &[u8] = b"partner";
+        let mut buffer = [0u8; PLAINTEXT.len()];
+        buffer.copy_from_slice(PLAINTEXT);
+        let mut ephemeral_public_key = [0u8; crypto::ecies::MAX_EPHEMERAL_PUBLIC_KEY_SIZE];
+        let mut tag = [0u8; crypto::ecies::TAG_SIZE];
+        let mut copy = [0u8; PLAINTEXT.len()];
+        let mut rng = Rng::<_, CtrDrbg>::instantiate(TestEntropySource::default(), &[], None);
+        let (private_key, public_key) = crypto::ecdsa::nist_p256_generate_key_pair(&mut rng);
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut ecies_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut ecies_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (ecies_requests_rx, ecies_requests_tx, ecies_responses_rx, ecies_responses_tx) =
+            split_queues(&mut ecies_requests, &mut ecies_responses);
+        let rng = Mutex::new(rng);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut ecies_worker = EciesWorker {
+            rng: &rng,
+            key_store: &key_store,
+            requests: ecies_requests_rx,
+            responses: ecies_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::EciesEncrypt, RequestType::EciesDecrypt],
+            ecies_requests_tx,
+            ecies_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Import recipient key
+        api.import_key_pair(ASYM_NIST_P256_KEY.id, &public_key, &private_key, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::ImportKeyPair { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Encrypt to the public key
+        let org_request_id = api
+            .ecies_encrypt(
+                crypto::ecies::Kdf::AnsiX963Sha256,
+                crypto::ecies::Cipher::Aes128Gcm,
+                &public_key,
+                SHARED_INFO,
+                &[],
+                &mut buffer,
+                &mut ephemeral_public_key,
+                &mut tag,
+            )
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        ecies_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::EciesEncrypt {
+            client_id: _,
+            request_id,
+            ephemeral_public_key,
+            buffer,
+            tag,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(
+            ephemeral_public_key.len(),
+            crypto::ecies::Curve::NistP256.ephemeral_public_key_size()
+        );
+        assert_ne!(buffer, PLAINTEXT);
+        let ephemeral_public_key: &[u8] = ephemeral_public_key;
+        let tag: &[u8] = tag;
+
+        // Decrypting with a symmetric key fails
+        copy.copy_from_slice(buffer);
+        let org_request_id = api
+            .ecies_decrypt(
+                SYM_128_KEY.id,
+                crypto::ecies::Kdf::AnsiX963Sha256,
+                crypto::ecies::Cipher::Aes128Gcm,
+                ephemeral_public_key,
+                SHARED_INFO,
+                &[],
+                &mut copy,
+                tag,
+            )
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        ecies_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _client_id,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::InvalidKeyType));
+
+        // Decrypt with the stored key
+        let org_request_id = api
+            .ecies_decrypt(
+                ASYM_NIST_P256_KEY.id,
+                crypto::ecies::Kdf::AnsiX963Sha256,
+                crypto::ecies::Cipher::Aes128Gcm,
+                ephemeral_public_key,
+                SHARED_INFO,
+                &[],
+                buffer,
+                tag,
+            )
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        ecies_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::EciesDecrypt {
+            client_id: _,
+            request_id,
+            plaintext,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(plaintext, PLAINTEXT);
+    }
+
+    #[async_std::test]
+    async fn tls_key_schedule() {
+        const SECRET_PERMISSIONS: KeyPermissions = KeyPermissions {
+            import: true,
+            export_private: false,
+            overwrite: true,
+            delete: false,
+        };
+        const SECRET_USAGE: KeyUsageFlags = KeyUsageFlags {
+            derive: true,
+            ..NO_USAGE
+        };
+        const SECRET_A: KeyInfo = KeyInfo {
+            id: KeyId(10),
+            ty: KeyType::Symmetric256Bits,
+            permissions: SECRET_PERMISSIONS,
+            usage: SECRET_USAGE,
+            algorithm: None,
+            access: KeyAccessControl::ALL,
+        };
+        const SECRET_B: KeyInfo = KeyInfo {
+            id: KeyId(11),
+            ty: KeyType::Symmetric256Bits,
+            permissions: SECRET_PERMISSIONS,
+            usage: SECRET_USAGE,
+            algorithm: None,
+            access: KeyAccessControl::ALL,
+        };
+        const TRAFFIC_KEY: KeyInfo = KeyInfo {
+            id: KeyId(12),
+            ty: KeyType::Symmetric128Bits,
+            permissions: KeyPermissions {
+                import: true,
+                export_private: true,
+                overwrite: true,
+                delete: false,
+            },
+            usage: KeyUsageFlags {
+                encrypt: true,
+                decrypt: true,
+                ..NO_USAGE
+            },
+            algorithm: None,
+            access: KeyAccessControl::ALL,
+        };
+        const KEY_INFOS: [KeyInfo; 5] = [
+            ASYM_NIST_P256_KEY,
+            ASYM_X25519_KEY,
+            SECRET_A,
+            SECRET_B,
+            TRAFFIC_KEY,
+        ];
+        const TOTAL_KEY_SIZE: usize = ASYM_NIST_P256_KEY.ty.key_size()
+            + ASYM_X25519_KEY.ty.key_size()
+            + SECRET_A.ty.key_size()
+            + SECRET_B.ty.key_size()
+            + TRAFFIC_KEY.ty.key_size();
+        let transcript_hash: [u8; crypto::tls::HASH_SIZE] = Sha256::digest(b"ClientHello").into();
+        let empty_hash: [u8; crypto::tls::HASH_SIZE] = Sha256::digest([]).into();
+        let mut key = [0u8; TRAFFIC_KEY.ty.key_size()];
+        let mut secret = [0u8; SECRET_B.ty.key_size()];
+        let mut iv = [0u8; crypto::tls::IV_SIZE];
+        let mut verify_data = [0u8; crypto::tls::HASH_SIZE];
+        let mut signature = [0u8; crypto::tls::MAX_DER_SIGNATURE_SIZE];
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, public_key) = crypto::x25519::x25519_generate_key_pair(&mut rng);
+        let (peer_private_key, peer_public_key) =
+            crypto::x25519::x25519_generate_key_pair(&mut rng);
+        let (signing_private_key, signing_public_key) =
+            crypto::ecdsa::nist_p256_generate_key_pair(&mut rng);
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut tls_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut tls_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (tls_requests_rx, tls_requests_tx, tls_responses_rx, tls_responses_tx) =
+            split_queues(&mut tls_requests, &mut tls_responses);
+        let mut key_store =
+            MemoryKeyStore::<{ TOTAL_KEY_SIZE }, { KEY_INFOS.len() }>::try_new(&KEY_INFOS)
+                .expect("failed to create key store");
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut tls_worker = TlsWorker {
+            key_store: &key_store,
+            requests: tls_requests_rx,
+            responses: tls_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[
+                RequestType::TlsHkdfExtract,
+                RequestType::TlsHkdfExpandLabel,
+                RequestType::TlsDeriveSecret,
+                RequestType::TlsDeriveIv,
+                RequestType::TlsFinished,
+                RequestType::TlsSignCertificateVerify,
+            ],
+            tls_requests_tx,
+            tls_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Compute the expected key schedule outside of the HSM
+        let mut shared_secret = [0u8; crypto::tls::HASH_SIZE];
+        crypto::x25519::x25519_calculate_shared_secret(
+            &peer_private_key,
+            &public_key,
+            &mut shared_secret,
+        )
+        .expect("failed to compute shared secret");
+        let mut early_secret = [0u8; crypto::tls::HASH_SIZE];
+        crypto::tls::hkdf_extract(None, None, &mut early_secret).expect("failed to extract");
+        let mut derived = [0u8; crypto::tls::HASH_SIZE];
+        crypto::tls::derive_secret(&early_secret, b"derived", &empty_hash, &mut derived)
+            .expect("failed to derive secret");
+        let mut handshake_secret = [0u8; crypto::tls::HASH_SIZE];
+        crypto::tls::hkdf_extract(Some(&derived), Some(&shared_secret), &mut handshake_secret)
+            .expect("failed to extract");
+        let mut traffic_secret = [0u8; crypto::tls::HASH_SIZE];
+        crypto::tls::derive_secret(
+            &handshake_secret,
+            b"c hs traffic",
+            &transcript_hash,
+            &mut traffic_secret,
+        )
+        .expect("failed to derive secret");
+        let mut expected_key = [0u8; TRAFFIC_KEY.ty.key_size()];
+        crypto::tls::hkdf_expand_label(&traffic_secret, b"key", &[], &mut expected_key)
+            .expect("failed to expand label");
+        let mut expected_iv = [0u8; crypto::tls::IV_SIZE];
+        crypto::tls::hkdf_expand_label(&traffic_secret, b"iv", &[], &mut expected_iv)
+            .expect("failed to expand label");
+        let mut expected_verify_data = [0u8; crypto::tls::HASH_SIZE];
+        crypto::tls::finished_verify_data(
+            &traffic_secret,
+            &transcript_hash,
+            &mut expected_verify_data,
+        )
+        .expect("failed to compute verify data");
+
+        // Import (EC)DHE and signing keys
+        api.import_key_pair(ASYM_X25519_KEY.id, &public_key, &private_key, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::ImportKeyPair { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        api.import_key_pair(
+            ASYM_NIST_P256_KEY.id,
+            &signing_public_key,
+            &signing_private_key,
+            false,
+        )
+        .await
+        .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::ImportKeyPair { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Early secret
+        api.tls_hkdf_extract(None, None, None, SECRET_A.id, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        tls_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::TlsHkdfExtract { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Handshake secret
+        api.tls_derive_secret(SECRET_A.id, b"derived", &empty_hash, SECRET_B.id, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        tls_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::TlsDeriveSecret { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        api.tls_hkdf_extract(
+            Some(SECRET_B.id),
+            Some(ASYM_X25519_KEY.id),
+            Some(&peer_public_key),
+            SECRET_A.id,
+            true,
+        )
+        .await
+        .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        tls_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::TlsHkdfExtract { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Client handshake traffic secret
+        api.tls_derive_secret(
+            SECRET_A.id,
+            b"c hs traffic",
+            &transcript_hash,
+            SECRET_B.id,
+            true,
+        )
+        .await
+        .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        tls_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::TlsDeriveSecret { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Secrets cannot be exported
+        let org_request_id = api
+            .export_symmetric_key(SECRET_B.id, &mut secret)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        }) = api.recv_response().await
+        else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::NotAllowed));
+
+        // Only traffic keys may be expanded into exportable slots
+        let org_request_id = api
+            .tls_hkdf_expand_label(SECRET_B.id, b"iv", &[], TRAFFIC_KEY.id, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        tls_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        }) = api.recv_response().await
+        else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::NotAllowed));
+
+        // Traffic key
+        api.tls_hkdf_expand_label(SECRET_B.id, b"key", &[], TRAFFIC_KEY.id, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        tls_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::TlsHkdfExpandLabel { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        api.export_symmetric_key(TRAFFIC_KEY.id, &mut key)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::ExportSymmetricKey { key, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(key, expected_key);
+
+        // Traffic IV
+        api.tls_derive_iv(SECRET_B.id, &mut iv)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        tls_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::TlsDeriveIv { iv, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(iv, expected_iv);
+
+        // Finished
+        api.tls_finished(SECRET_B.id, &transcript_hash, &mut verify_data)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        tls_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::TlsFinished { verify_data, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(verify_data, expected_verify_data);
+
+        // CertificateVerify
+        api.tls_sign_certificate_verify(
+            ASYM_NIST_P256_KEY.id,
+            false,
+            &transcript_hash,
+            &mut signature,
+        )
+        .await
+        .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        tls_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::TlsSignCertificateVerify { signature, .. }) = api.recv_response().await
+        else {
+            panic!("Failed to receive expected response")
+        };
+        let mut content = [0u8; crypto::tls::CERTIFICATE_VERIFY_CONTENT_SIZE];
+        crypto::tls::certificate_verify_content(false, &transcript_hash, &mut content)
+            .expect("failed to assemble content");
+        let signature = p256::ecdsa::Signature::from_der(signature).expect("invalid signature");
+        crypto::ecdsa::nist_p256_verify(&signing_public_key, &content, &signature.to_bytes())
+            .expect("failed to verify signature");
+    }
+
+    #[async_std::test]
+    async fn noise_xx_handshake() {
+        const RESPONDER_KEY: KeyInfo = KeyInfo {
+            id: KeyId(5),
+            ..ASYM_X25519_KEY
+        };
+        const KEY_INFOS: [KeyInfo; 2] = [ASYM_X25519_KEY, RESPONDER_KEY];
+        const PROLOGUE: &[u8] = b"heimlig noise test";
+        const PAYLOADS: [&[u8]; 3] = [b"", b"responder payload", b"initiator payload"];
+        const PLAINTEXT: &[u8] = b"transport message";
+        let mut messages = [[0u8; 128]; 3];
+        let mut payloads = [[0u8; 32]; 3];
+        let mut remote_static_public_key = [0u8; crypto::noise::DH_SIZE];
+        let mut handshake_hash = [0u8; crypto::noise::HASH_SIZE];
+        let mut buffer = [0u8; PLAINTEXT.len()];
+        buffer.copy_from_slice(PLAINTEXT);
+        let mut tag = [0u8; crypto::noise::TAG_SIZE];
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (initiator_private_key, initiator_public_key) =
+            crypto::x25519::x25519_generate_key_pair(&mut rng);
+        let (responder_private_key, responder_public_key) =
+            crypto::x25519::x25519_generate_key_pair(&mut rng);
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut noise_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut noise_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (noise_requests_rx, noise_requests_tx, noise_responses_rx, noise_responses_tx) =
+            split_queues(&mut noise_requests, &mut noise_responses);
+        let rng = Mutex::new(rng);
+        let mut key_store = MemoryKeyStore::<
+            { ASYM_X25519_KEY.ty.key_size() + RESPONDER_KEY.ty.key_size() },
+            { KEY_INFOS.len() },
+        >::try_new(&KEY_INFOS)
+        .expect("failed to create key store");
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut noise_worker = NoiseWorker::<_, _, _, _, _, 2> {
+            rng: &rng,
+            key_store: &key_store,
+            requests: noise_requests_rx,
+            responses: noise_responses_tx,
+            sessions: NoiseSessions::default(),
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[
+                RequestType::NoiseStartHandshake,
+                RequestType::NoiseWriteMessage,
+                RequestType::NoiseReadMessage,
+                RequestType::NoiseSessionInfo,
+                RequestType::NoiseEncrypt,
+                RequestType::NoiseDecrypt,
+                RequestType::NoiseCloseSession,
+            ],
+            noise_requests_tx,
+            noise_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Import static keys
+        for (key_info, public_key, private_key) in [
+            (
+                ASYM_X25519_KEY,
+                &initiator_public_key,
+                &initiator_private_key,
+            ),
+            (RESPONDER_KEY, &responder_public_key, &responder_private_key),
+        ] {
+            api.import_key_pair(key_info.id, public_key, private_key, false)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to process request");
+            let Some(Response::ImportKeyPair { .. }) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+        }
+
+        // Start both sides of the handshake
+        let mut sessions = [SessionId::default(); 2];
+        for (session, (key_id, initiator)) in sessions
+            .iter_mut()
+            .zip([(ASYM_X25519_KEY.id, true), (RESPONDER_KEY.id, false)])
+        {
+            api.noise_start_handshake(key_id, initiator, PROLOGUE)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            noise_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::NoiseStartHandshake {
+                session: new_session,
+                ..
+            }) = api.recv_response().await
+            else {
+                panic!("Failed to receive expected response")
+            };
+            *session = new_session;
+        }
+        assert_ne!(sessions[0], sessions[1]);
+
+        // The session table is full
+        api.noise_start_handshake(ASYM_X25519_KEY.id, true, PROLOGUE)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        noise_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(error, Error::TooManySessions);
+
+        // Exchange the handshake messages
+        for (index, ((message, payload), expected_payload)) in messages
+            .iter_mut()
+            .zip(payloads.iter_mut())
+            .zip(PAYLOADS)
+            .enumerate()
+        {
+            let (writer, reader) = (sessions[index % 2], sessions[(index + 1) % 2]);
+            let last = index == crypto::noise::HANDSHAKE_MESSAGES - 1;
+            api.noise_write_message(writer, expected_payload, message)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            noise_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::NoiseWriteMessage {
+                message,
+                handshake_finished,
+                ..
+            }) = api.recv_response().await
+            else {
+                panic!("Failed to receive expected response")
+            };
+            assert_eq!(
+                message.len(),
+                crypto::noise::handshake_overhead(index) + expected_payload.len()
+            );
+            assert_eq!(handshake_finished, last);
+            api.noise_read_message(reader, message, payload)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            noise_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::NoiseReadMessage {
+                payload,
+                handshake_finished,
+                ..
+            }) = api.recv_response().await
+            else {
+                panic!("Failed to receive expected response")
+            };
+            assert_eq!(payload, expected_payload);
+            assert_eq!(handshake_finished, last);
+        }
+
+        // The initiator authenticated the responder's static key
+        api.noise_session_info(
+            sessions[0],
+            &mut remote_static_public_key,
+            &mut handshake_hash,
+        )
+        .await
+        .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        noise_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::NoiseSessionInfo {
+            remote_static_public_key,
+            ..
+        }) = api.recv_response().await
+        else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(remote_static_public_key, responder_public_key);
+
+        // Transport messages
+        api.noise_encrypt(sessions[0], &mut buffer, &mut tag)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        noise_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::NoiseEncrypt { buffer, tag, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_ne!(buffer, PLAINTEXT);
+        let tag: &[u8] = tag;
+        api.noise_decrypt(sessions[1], buffer, tag)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        noise_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::NoiseDecrypt { plaintext, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(plaintext, PLAINTEXT);
+
+        // Closed sessions cannot be used anymore
+        api.noise_close_session(sessions[1])
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        noise_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::NoiseCloseSession { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        api.noise_decrypt(sessions[1], plaintext, tag)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        noise_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(error, Error::InvalidSession);
+    }
+
+    #[async_std::test]
+    async fn secoc_freshness() {
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, ASYM_NIST_P256_KEY];
+        const KEY: &[u8; 16] = b"SecOC test key!!";
+        const TX_DATA_ID: u16 = 0x0100;
+        const RX_DATA_ID: u16 = 0x0200;
+        const PROFILE: crypto::secoc::Profile = crypto::secoc::Profile {
+            freshness_size: 1,
+            mac_size: 4,
+        };
+        const TX_COUNTER: CounterId = CounterId(1);
+        const RX_COUNTER: CounterId = CounterId(2);
+        const PAYLOAD: &[u8] = b"wheel speed";
+        const PDU_SIZE: usize = PAYLOAD.len() + PROFILE.overhead();
+        let configs = [
+            DataIdConfig {
+                data_id: TX_DATA_ID,
+                key_id: SYM_128_KEY.id,
+                profile: PROFILE,
+                freshness_counter: TX_COUNTER,
+            },
+            DataIdConfig {
+                data_id: RX_DATA_ID,
+                key_id: SYM_128_KEY.id,
+                profile: PROFILE,
+                freshness_counter: RX_COUNTER,
+            },
+        ];
+        let mut secured_pdus = [[0u8; PDU_SIZE]; 2];
+        let mut expected_pdu = [0u8; PDU_SIZE];
+        // Received PDUs with freshness values 0x1ff, 0x1ff (replay), 0x200 (tampered) and 0x200
+        let mut received_pdus = [[0u8; PDU_SIZE]; 4];
+        for (pdu, freshness) in received_pdus.iter_mut().zip([0x1ff, 0x1ff, 0x200, 0x200]) {
+            crypto::secoc::authenticate(KEY, RX_DATA_ID, freshness, &PROFILE, PAYLOAD, pdu)
+                .expect("failed to authenticate PDU");
+        }
+        received_pdus[2][0] ^= 1;
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut secoc_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut secoc_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (secoc_requests_rx, secoc_requests_tx, secoc_responses_rx, secoc_responses_tx) =
+            split_queues(&mut secoc_requests, &mut secoc_responses);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut counter_store = MemoryCounterStore::<2>::new();
+        for counter in [TX_COUNTER, RX_COUNTER] {
+            counter_store
+                .create(counter, u64::MAX)
+                .expect("failed to create counter");
+        }
+        // Latest accepted freshness value before a reset
+        counter_store
+            .increment(RX_COUNTER, 0x1fe)
+            .expect("failed to increment counter");
+        let counter_store: Mutex<NoopRawMutex, &mut (dyn CounterStore + Send)> =
+            Mutex::new(&mut counter_store);
+        let mut secoc_worker = SecOcWorker::<_, _, _, 2> {
+            key_store: &key_store,
+            counter_store: &counter_store,
+            requests: secoc_requests_rx,
+            responses: secoc_responses_tx,
+            data_ids: DataIdTable::try_new(&configs).expect("failed to create table"),
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::SecOcAuthenticate, RequestType::SecOcVerify],
+            secoc_requests_tx,
+            secoc_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Import MAC key
+        api.import_symmetric_key(SYM_128_KEY.id, KEY, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::ImportSymmetricKey { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Transmitted PDUs use consecutive freshness values
+        for (freshness, secured_pdu) in (1..).zip(secured_pdus.iter_mut()) {
+            api.secoc_authenticate(TX_DATA_ID, PAYLOAD, secured_pdu)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            secoc_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::SecOcAuthenticate { secured_pdu, .. }) = api.recv_response().await
+            else {
+                panic!("Failed to receive expected response")
+            };
+            crypto::secoc::authenticate(
+                KEY,
+                TX_DATA_ID,
+                freshness,
+                &PROFILE,
+                PAYLOAD,
+                &mut expected_pdu,
+            )
+            .expect("failed to authenticate PDU");
+            assert_eq!(secured_pdu, expected_pdu);
+        }
+        let counter_value = |counter| {
+            counter_store
+                .try_lock()
+                .expect("counter store locked")
+                .get_counter_info(counter)
+                .expect("failed to get counter info")
+                .value
+        };
+        assert_eq!(counter_value(TX_COUNTER), 2);
+
+        // Received PDUs with replayed freshness values or invalid MACs are rejected
+        for (secured_pdu, expected) in received_pdus.iter().zip([true, false, false, true]) {
+            api.secoc_verify(RX_DATA_ID, secured_pdu)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            secoc_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::SecOcVerify { verified, .. }) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            assert_eq!(verified, expected);
+        }
+        assert_eq!(counter_value(RX_COUNTER), 0x200);
+
+        // Unknown data ID
+        api.secoc_verify(0x0300, &received_pdus[3])
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        secoc_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(error, Error::InvalidDataId);
+    }
+
+    #[async_std::test]
+    async fn monotonic_counters() {
+        const COUNTER: CounterId = CounterId(3);
+        const MAX_VALUE: u64 = 10;
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut counter_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut counter_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (counter_requests_rx, counter_requests_tx, counter_responses_rx, counter_responses_tx) =
+            split_queues(&mut counter_requests, &mut counter_responses);
+        let mut counter_store = MemoryCounterStore::<1>::new();
+        let counter_store: Mutex<NoopRawMutex, &mut (dyn CounterStore + Send)> =
+            Mutex::new(&mut counter_store);
+        let mut counter_worker = CounterWorker {
+            counter_store: &counter_store,
+            requests: counter_requests_rx,
+            responses: counter_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[
+                RequestType::CreateCounter,
+                RequestType::ReadCounter,
+                RequestType::IncrementCounter,
+            ],
+            counter_requests_tx,
+            counter_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Create counter
+        api.create_counter(COUNTER, MAX_VALUE)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        counter_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::CreateCounter { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Counters cannot be created twice
+        api.create_counter(COUNTER, MAX_VALUE)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        counter_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(
+            error,
+            Error::CounterStore(counter_store::Error::CounterExists)
+        );
+
+        // Increment counter up to its maximum value
+        for (amount, expected_value) in [(1, 1), (4, 5), (5, MAX_VALUE)] {
+            api.increment_counter(COUNTER, amount)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            counter_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::IncrementCounter { value, .. }) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            assert_eq!(value, expected_value);
+        }
+        api.increment_counter(COUNTER, 1)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        counter_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(
+            error,
+            Error::CounterStore(counter_store::Error::MaxValueExceeded)
+        );
+
+        // Read counter
+        api.read_counter(COUNTER)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        counter_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::ReadCounter {
+            value, max_value, ..
+        }) = api.recv_response().await
+        else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(value, MAX_VALUE);
+        assert_eq!(max_value, MAX_VALUE);
+
+        // Unknown counter
+        api.read_counter(CounterId(4))
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        counter_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(
+            error,
+            Error::CounterStore(counter_store::Error::CounterNotFound)
+        );
+    }
+
+    #[async_std::test]
+    async fn verify_image() {
+        const UNTRUSTED_KEY: KeyInfo = KeyInfo {
+            id: KeyId(3),
+            ..ASYM_NIST_P256_KEY
+        };
+        const KEY_INFOS: [KeyInfo; 2] = [ASYM_NIST_P256_KEY, UNTRUSTED_KEY];
+        const TRUSTED_KEYS: [KeyId; 1] = [ASYM_NIST_P256_KEY.id];
+        const SECURITY_VERSION_COUNTER: CounterId = CounterId(0);
+        const MIN_SECURITY_VERSION: u64 = 2;
+        const IMAGE_SIZE: usize = 1000;
+        const CHUNK_SIZE: usize = 300;
+        const MANIFEST_SIZE: usize = Manifest::size(jws::Algorithm::Es256);
+        let mut image = [0u8; IMAGE_SIZE];
+        for (i, byte) in image.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let mut tampered_image = image;
+        tampered_image[IMAGE_SIZE - 1] ^= 1;
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, public_key) = crypto::ecdsa::nist_p256_generate_key_pair(&mut rng);
+        let (other_private_key, other_public_key) =
+            crypto::ecdsa::nist_p256_generate_key_pair(&mut rng);
+        let manifest = Manifest {
+            security_version: MIN_SECURITY_VERSION as u32,
+            image_size: IMAGE_SIZE as u32,
+            image_hash: Sha256::digest(image).into(),
+        };
+        // Valid, rolled back and foreign manifests
+        let mut signed_manifests = [[0u8; MANIFEST_SIZE]; 3];
+        for (signed_manifest, (private_key, security_version)) in signed_manifests.iter_mut().zip([
+            (&private_key, MIN_SECURITY_VERSION as u32),
+            (&private_key, MIN_SECURITY_VERSION as u32 - 1),
+            (&other_private_key, MIN_SECURITY_VERSION as u32),
+        ]) {
+            let manifest = Manifest {
+                security_version,
+                ..manifest
+            };
+            crypto::manifest::sign(
+                jws::Algorithm::Es256,
+                private_key,
+                &manifest,
+                signed_manifest,
+            )
+            .expect("failed to sign manifest");
+        }
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut boot_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut boot_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (boot_requests_rx, boot_requests_tx, boot_responses_rx, boot_responses_tx) =
+            split_queues(&mut boot_requests, &mut boot_responses);
+        let mut key_store = MemoryKeyStore::<
+            { ASYM_NIST_P256_KEY.ty.key_size() + UNTRUSTED_KEY.ty.key_size() },
+            { KEY_INFOS.len() },
+        >::try_new(&KEY_INFOS)
+        .expect("failed to create key store");
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut counter_store = MemoryCounterStore::<1>::new();
+        counter_store
+            .create(SECURITY_VERSION_COUNTER, u64::MAX)
+            .expect("failed to create counter");
+        counter_store
+            .increment(SECURITY_VERSION_COUNTER, MIN_SECURITY_VERSION)
+            .expect("failed to increment counter");
+        let counter_store: Mutex<NoopRawMutex, &mut (dyn CounterStore + Send)> =
+            Mutex::new(&mut counter_store);
+        let mut boot_worker = BootWorker::<_, _, _, 1> {
+            key_store: &key_store,
+            counter_store: &counter_store,
+            security_version_counter: SECURITY_VERSION_COUNTER,
+            trusted_keys: &TRUSTED_KEYS,
+            requests: boot_requests_rx,
+            responses: boot_responses_tx,
+            verifications: ImageVerifications::default(),
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[
+                RequestType::VerifyImageStart,
+                RequestType::VerifyImageUpdate,
+                RequestType::VerifyImageFinish,
+            ],
+            boot_requests_tx,
+            boot_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Import manifest signing key and a key that is not trusted for manifests
+        for (key_id, public_key, private_key) in [
+            (ASYM_NIST_P256_KEY.id, &public_key, &private_key),
+            (UNTRUSTED_KEY.id, &other_public_key, &other_private_key),
+        ] {
+            api.import_key_pair(key_id, public_key, private_key, false)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to process request");
+            let Some(Response::ImportKeyPair { .. }) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+        }
+
+        // Manifests cannot be verified against untrusted keys, even if correctly signed
+        api.verify_image_start(UNTRUSTED_KEY.id, &signed_manifests[2])
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        boot_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(error, Error::KeyStore(keystore::Error::NotAllowed));
+
+        // Rolled back and foreign manifests are rejected
+        for (signed_manifest, expected_error) in signed_manifests[1..].iter().zip([
+            Error::SecurityVersionTooLow,
+            Error::Crypto(crypto::Error::InvalidSignature),
+        ]) {
+            api.verify_image_start(ASYM_NIST_P256_KEY.id, signed_manifest)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            boot_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::Error { error, .. }) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            assert_eq!(error, expected_error);
+        }
+
+        // Only the original image is verified
+        for (image, expected_verdict) in [(&image, true), (&tampered_image, false)] {
+            api.verify_image_start(ASYM_NIST_P256_KEY.id, &signed_manifests[0])
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            boot_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::VerifyImageStart { session, .. }) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            for chunk in image.chunks(CHUNK_SIZE) {
+                api.verify_image_update(session, chunk)
+                    .await
+                    .expect("failed to send request");
+                core.execute().await.expect("failed to forward request");
+                boot_worker
+                    .execute()
+                    .await
+                    .expect("failed to process request");
+                core.execute().await.expect("failed to forward response");
+                let Some(Response::VerifyImageUpdate { .. }) = api.recv_response().await else {
+                    panic!("Failed to receive expected response")
+                };
+            }
+            api.verify_image_finish(session)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            boot_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::VerifyImageFinish { verified, .. }) = api.recv_response().await
+            else {
+                panic!("Failed to receive expected response")
+            };
+            assert_eq!(verified, expected_verdict);
+
+            // Finished sessions are closed
+            api.verify_image_finish(session)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            boot_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::Error { error, .. }) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            assert_eq!(error, Error::InvalidSession);
+        }
+    }
+
+    #[async_std::test]
+    async fn measured_boot_quote() {
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, ASYM_NIST_P256_KEY];
+        const NUM_REGISTERS: usize = 4;
+        const NONCE: &[u8] = b"verifier nonce";
+        let bootloader_digest = Sha256::digest(b"bootloader");
+        let application_digest = Sha256::digest(b"application");
+        let mut value = [0u8; REGISTER_SIZE];
+        let mut quote = [0u8; MAX_QUOTE_SIZE];
+        let mut invalid_quote = [0u8; MAX_QUOTE_SIZE];
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, public_key) = crypto::ecdsa::nist_p256_generate_key_pair(&mut rng);
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut attestation_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut attestation_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (
+            attestation_requests_rx,
+            attestation_requests_tx,
+            attestation_responses_rx,
+            attestation_responses_tx,
+        ) = split_queues(&mut attestation_requests, &mut attestation_responses);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut attestation_worker = AttestationWorker {
+            key_store: &key_store,
+            attestation_key_id: ASYM_NIST_P256_KEY.id,
+            requests: attestation_requests_rx,
+            responses: attestation_responses_tx,
+            registers: [[0u8; REGISTER_SIZE]; NUM_REGISTERS],
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[
+                RequestType::ExtendMeasurement,
+                RequestType::ReadMeasurement,
+                RequestType::QuoteMeasurements,
+            ],
+            attestation_requests_tx,
+            attestation_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Import attestation key
+        api.import_key_pair(ASYM_NIST_P256_KEY.id, &public_key, &private_key, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::ImportKeyPair { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Extend registers 0 and 2
+        for (register, digest) in [(0, &bootloader_digest), (2, &application_digest)] {
+            api.extend_measurement(register, digest)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            attestation_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::ExtendMeasurement { .. }) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+        }
+        let mut expected_registers = [[0u8; REGISTER_SIZE]; NUM_REGISTERS];
+        crypto::attestation::extend(&mut expected_registers[0], &bootloader_digest)
+            .expect("failed to extend register");
+        crypto::attestation::extend(&mut expected_registers[2], &application_digest)
+            .expect("failed to extend register");
+
+        // Registers outside the bank cannot be extended
+        api.extend_measurement(NUM_REGISTERS as u32, &bootloader_digest)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        attestation_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(error, Error::InvalidRegister);
+
+        // Read register 2
+        api.read_measurement(2, &mut value)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        attestation_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::ReadMeasurement { value, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(value, expected_registers[2]);
+
+        // Quote registers 0 and 2
+        api.quote_measurements(0b101, NONCE, &mut quote)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        attestation_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::QuoteMeasurements { quote, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let (selection, digest, nonce) =
+            crypto::attestation::verify_quote(jws::Algorithm::Es256, &public_key, quote)
+                .expect("failed to verify quote");
+        assert_eq!(selection, 0b101);
+        assert_eq!(
+            digest,
+            crypto::attestation::composite_digest(0b101, &expected_registers)
+                .expect("failed to compute composite digest")
+        );
+        assert_eq!(nonce, NONCE);
+
+        // Registers outside the bank cannot be quoted
+        api.quote_measurements(1 << NUM_REGISTERS, NONCE, &mut invalid_quote)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        attestation_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(error, Error::InvalidRegister);
+    }
+
+    #[async_std::test]
+    async fn dice_alias_derivation() {
+        const DICE_ALIAS_KEY: KeyInfo = KeyInfo {
+            permissions: KeyPermissions {
+                overwrite: true,
+                ..ASYM_NIST_P256_KEY.permissions
+            },
+            ..ASYM_NIST_P256_KEY
+        };
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, DICE_ALIAS_KEY];
+        // Reproducible stand-in for the Unique Device Secret
+        let uds = TestEntropySource::default().random_seed();
+        let measurements = [Sha256::digest(b"layer 1"), Sha256::digest(b"layer 1'")];
+        let mut certificates = [[0u8; ALIAS_CERTIFICATE_SIZE]; 3];
+        let mut invalid_certificate = [0u8; ALIAS_CERTIFICATE_SIZE];
+        let mut rejected_certificate = [0u8; ALIAS_CERTIFICATE_SIZE];
+        let mut alias_public_keys = [[0u8; KeyType::EccKeypairNistP256.public_key_size()]; 3];
+        let mut exported_public_keys = [[0u8; KeyType::EccKeypairNistP256.public_key_size()]; 3];
+        let device_id = crypto::dice::derive_device_id(&uds).expect("failed to derive DeviceID");
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut dice_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut dice_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (dice_requests_rx, dice_requests_tx, dice_responses_rx, dice_responses_tx) =
+            split_queues(&mut dice_requests, &mut dice_responses);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut dice_worker = DiceWorker {
+            key_store: &key_store,
+            uds_key_id: SYM_256_KEY.id,
+            alias_key_id: DICE_ALIAS_KEY.id,
+            boot_client: Some(ClientId(0)),
+            requests: dice_requests_rx,
+            responses: dice_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::DeriveDiceAlias],
+            dice_requests_tx,
+            dice_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Provision UDS
+        api.import_symmetric_key(SYM_256_KEY.id, &uds, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::ImportSymmetricKey { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        // Derive the alias key twice for the same and once for a different measurement
+        for (((measurement, certificate), alias_public_key), exported_public_key) in
+            [&measurements[0], &measurements[0], &measurements[1]]
+                .into_iter()
+                .zip(certificates.iter_mut())
+                .zip(alias_public_keys.iter_mut())
+                .zip(exported_public_keys.iter_mut())
+        {
+            api.derive_dice_alias(measurement, certificate)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            dice_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::DeriveDiceAlias { certificate, .. }) = api.recv_response().await
+            else {
+                panic!("Failed to receive expected response")
+            };
+            let (certified_measurement, certified_key) =
+                crypto::dice::verify_alias_certificate(&device_id.public_key, certificate)
+                    .expect("failed to verify certificate");
+            assert_eq!(certified_measurement, measurement.as_slice());
+
+            // The certified alias key is stored in the alias key slot
+            api.export_public_key(DICE_ALIAS_KEY.id, alias_public_key)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to process request");
+            let Some(Response::ExportPublicKey { public_key, .. }) = api.recv_response().await
+            else {
+                panic!("Failed to receive expected response")
+            };
+            assert_eq!(public_key, certified_key);
+            exported_public_key.copy_from_slice(public_key);
+        }
+        assert_eq!(exported_public_keys[0], exported_public_keys[1]);
+        assert_ne!(exported_public_keys[0], exported_public_keys[2]);
+
+        // Measurements have to be SHA-256 digests
+        api.derive_dice_alias(&measurements[0][1..], &mut invalid_certificate)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        dice_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(error, Error::Crypto(crypto::Error::InvalidDigestSize));
+
+        // Measurements reported by clients other than the boot client are rejected
+        dice_worker.boot_client = Some(ClientId(1));
+        api.derive_dice_alias(&measurements[0], &mut rejected_certificate)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        dice_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(Response::Error { error, .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        assert_eq!(error, Error::AccessDenied);
+    }
+
+    /// Minimal SPDM requester keeping the transcripts needed to verify signed responses.
+    struct SpdmRequester {
+        public_key: [u8; KeyType::EccKeypairNistP256.public_key_size()],
+        vca: Sha256,
+        m1: Sha256,
+        l1: Sha256,
+        certificate_chain: heapless::Vec<u8, 256>,
+        measurement_record: heapless::Vec<u8, 256>,
+    }
+
+    impl SpdmRequester {
+        fn new(public_key: [u8; KeyType::EccKeypairNistP256.public_key_size()]) -> Self {
+            SpdmRequester {
+                public_key,
+                vca: Sha256::new(),
+                m1: Sha256::new(),
+                l1: Sha256::new(),
+                certificate_chain: heapless::Vec::new(),
+                measurement_record: heapless::Vec::new(),
+            }
+        }
+
+        fn get_version() -> heapless::Vec<u8, 64> {
+            heapless::Vec::from_slice(&[spdm::VERSION_1_0, spdm::GET_VERSION, 0, 0]).unwrap()
+        }
+
+        fn get_capabilities() -> heapless::Vec<u8, 64> {
+            let mut request = [0u8; 20];
+            request[..2].copy_from_slice(&[spdm::VERSION_1_2, spdm::GET_CAPABILITIES]);
+            request[12..16].copy_from_slice(&spdm::DATA_TRANSFER_SIZE.to_le_bytes());
+            request[16..20].copy_from_slice(&spdm::DATA_TRANSFER_SIZE.to_le_bytes());
+            heapless::Vec::from_slice(&request).unwrap()
+        }
+
+        fn negotiate_algorithms() -> heapless::Vec<u8, 64> {
+            let mut request = [0u8; 32];
+            request[..2].copy_from_slice(&[spdm::VERSION_1_2, spdm::NEGOTIATE_ALGORITHMS]);
+            request[4..6].copy_from_slice(&32u16.to_le_bytes());
+            request[6] = spdm::MEASUREMENT_SPECIFICATION_DMTF;
+            request[8..12].copy_from_slice(&spdm::BASE_ASYM_ALGO_ECDSA_P256.to_le_bytes());
+            request[12..16].copy_from_slice(&spdm::BASE_HASH_ALGO_SHA_256.to_le_bytes());
+            heapless::Vec::from_slice(&request).unwrap()
+        }
+
+        fn get_digests() -> heapless::Vec<u8, 64> {
+            heapless::Vec::from_slice(&[spdm::VERSION_1_2, spdm::GET_DIGESTS, 0, 0]).unwrap()
+        }
+
+        fn get_certificate(offset: u16, length: u16) -> heapless::Vec<u8, 64> {
+            let mut request = [spdm::VERSION_1_2, spdm::GET_CERTIFICATE, 0, 0, 0, 0, 0, 0];
+            request[4..6].copy_from_slice(&offset.to_le_bytes());
+            request[6..8].copy_from_slice(&length.to_le_bytes());
+            heapless::Vec::from_slice(&request).unwrap()
+        }
+
+        fn challenge(nonce: &[u8; spdm::NONCE_SIZE]) -> heapless::Vec<u8, 64> {
+            // Request the measurement summary hash of all measurements
+            let mut request =
+                heapless::Vec::from_slice(&[spdm::VERSION_1_2, spdm::CHALLENGE, 0, 0xFF]).unwrap();
+            request.extend_from_slice(nonce).unwrap();
+            request
+        }
+
+        fn get_measurements(
+            operation: u8,
+            nonce: Option<&[u8; spdm::NONCE_SIZE]>,
+        ) -> heapless::Vec<u8, 64> {
+            let mut request = heapless::Vec::from_slice(&[
+                spdm::VERSION_1_2,
+                spdm::GET_MEASUREMENTS,
+                nonce.is_some() as u8,
+                operation,
+            ])
+            .unwrap();
+            if let Some(nonce) = nonce {
+                request.extend_from_slice(nonce).unwrap();
+                request.push(0).unwrap();
+            }
+            request
+        }
+
+        /// Process a response and verify its signature, if any.
+        fn handle(&mut self, request: &[u8], response: &[u8]) {
+            let expected_code = match request[1] {
+                spdm::GET_VERSION => spdm::VERSION,
+                spdm::GET_CAPABILITIES => spdm::CAPABILITIES,
+                spdm::NEGOTIATE_ALGORITHMS => spdm::ALGORITHMS,
+                spdm::GET_DIGESTS => spdm::DIGESTS,
+                spdm::GET_CERTIFICATE => spdm::CERTIFICATE,
+                spdm::CHALLENGE => spdm::CHALLENGE_AUTH,
+                _ => spdm::MEASUREMENTS,
+            };
+            assert_eq!(
+                response[1], expected_code,
+                "unexpected response {response:02x?}"
+            );
+            if request[1] != spdm::GET_MEASUREMENTS {
+                self.l1 = self.vca.clone();
+            }
+            match request[1] {
+                spdm::GET_VERSION => {
+                    assert_eq!(response[5..], [1, 0x00, spdm::VERSION_1_2]);
+                    self.vca = Sha256::new();
+                    self.vca.update(request);
+                    self.vca.update(response);
+                }
+                spdm::GET_CAPABILITIES => {
+                    assert_eq!(
+                        u32::from_le_bytes(response[8..12].try_into().unwrap()),
+                        spdm::CAPABILITY_FLAGS
+                    );
+                    self.vca.update(request);
+                    self.vca.update(response);
+                }
+                spdm::NEGOTIATE_ALGORITHMS => {
+                    assert_eq!(
+                        u32::from_le_bytes(response[12..16].try_into().unwrap()),
+                        spdm::BASE_ASYM_ALGO_ECDSA_P256
+                    );
+                    assert_eq!(
+                        u32::from_le_bytes(response[16..20].try_into().unwrap()),
+                        spdm::BASE_HASH_ALGO_SHA_256
+                    );
+                    self.vca.update(request);
+                    self.vca.update(response);
+                    self.m1 = self.vca.clone();
+                    self.l1 = self.vca.clone();
+                }
+                spdm::GET_DIGESTS | spdm::GET_CERTIFICATE => {
+                    if request[1] == spdm::GET_CERTIFICATE {
+                        let portion = u16::from_le_bytes([response[4], response[5]]) as usize;
+                        self.certificate_chain
+                            .extend_from_slice(&response[8..8 + portion])
+                            .unwrap();
+                    }
+                    self.m1.update(request);
+                    self.m1.update(response);
+                }
+                spdm::CHALLENGE => {
+                    let (signed, signature) =
+                        response.split_at(response.len() - spdm::SIGNATURE_SIZE);
+                    assert_eq!(signed[4..36], Sha256::digest(&self.certificate_chain)[..]);
+                    self.m1.update(request);
+                    self.m1.update(signed);
+                    let transcript_hash = core::mem::replace(&mut self.m1, self.vca.clone());
+                    self.verify(spdm::CHALLENGE_AUTH_CONTEXT, transcript_hash, signature);
+                }
+                _ => {
+                    let signature_size = if request[2] & 0x01 != 0 {
+                        spdm::SIGNATURE_SIZE
+                    } else {
+                        0
+                    };
+                    let (signed, signature) = response.split_at(response.len() - signature_size);
+                    let record_size =
+                        u32::from_le_bytes([signed[5], signed[6], signed[7], 0]) as usize;
+                    self.measurement_record.clear();
+                    self.measurement_record
+                        .extend_from_slice(&signed[8..8 + record_size])
+                        .unwrap();
+                    self.l1.update(request);
+                    self.l1.update(signed);
+                    if signature_size != 0 {
+                        let transcript_hash = core::mem::replace(&mut self.l1, self.vca.clone());
+                        self.verify(spdm::MEASUREMENTS_CONTEXT, transcript_hash, signature);
+                    }
+                }
+            }
+        }
+
+        fn verify(&self, context: &[u8], transcript: Sha256, signature: &[u8]) {
+            let message = spdm::signing_message(context, &transcript.finalize().into());
+            crypto::ecdsa::nist_p256_verify(&self.public_key, &message, signature)
+                .expect("failed to verify signature");
+        }
+    }
+
+    #[async_std::test]
+    async fn spdm_attestation() {
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, ASYM_NIST_P256_KEY];
+        const MEASUREMENTS: [spdm::Measurement; 2] = [
+            spdm::Measurement {
+                value_type: 0,
+                digest: [0x11; spdm::DIGEST_SIZE],
+            },
+            spdm::Measurement {
+                value_type: 1,
+                digest: [0x22; spdm::DIGEST_SIZE],
+            },
+        ];
+        const CHAIN_SIZE: usize = 4 + spdm::DIGEST_SIZE + 64;
+        const CHALLENGE_NONCE: [u8; spdm::NONCE_SIZE] = [0xA5; spdm::NONCE_SIZE];
+        const MEASUREMENTS_NONCE: [u8; spdm::NONCE_SIZE] = [0x5A; spdm::NONCE_SIZE];
+        // Certificate chain with a stand-in for the DER encoded certificates
+        let certificates = [0xCE; 64];
+        let mut certificate_chain = [0u8; CHAIN_SIZE];
+        certificate_chain[..2].copy_from_slice(&(CHAIN_SIZE as u16).to_le_bytes());
+        certificate_chain[4..36].copy_from_slice(&Sha256::digest(&certificates[..32]));
+        certificate_chain[36..].copy_from_slice(&certificates);
+        let spdm_requests = [
+            SpdmRequester::get_version(),
+            SpdmRequester::get_capabilities(),
+            SpdmRequester::negotiate_algorithms(),
+            SpdmRequester::get_digests(),
+            SpdmRequester::get_certificate(0, 64),
+            SpdmRequester::get_certificate(64, 64),
+            SpdmRequester::challenge(&CHALLENGE_NONCE),
+            SpdmRequester::get_measurements(0x00, None),
+            SpdmRequester::get_measurements(0xFF, Some(&MEASUREMENTS_NONCE)),
+        ];
+        let mut spdm_responses = [[0u8; 256]; 9];
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (private_key, public_key) = crypto::ecdsa::nist_p256_generate_key_pair(&mut rng);
+        let rng = Mutex::new(rng);
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut spdm_worker_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut spdm_worker_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (spdm_requests_rx, spdm_requests_tx, spdm_responses_rx, spdm_responses_tx) =
+            split_queues(&mut spdm_worker_requests, &mut spdm_worker_responses);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut spdm_worker = SpdmWorker {
+            rng: &rng,
+            key_store: &key_store,
+            key_id: ASYM_NIST_P256_KEY.id,
+            certificate_chain: &certificate_chain,
+            measurements: MEASUREMENTS,
+            responder: spdm::Responder::default(),
+            requests: spdm_requests_rx,
+            responses: spdm_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::ProcessSpdmMessage],
+            spdm_requests_tx,
+            spdm_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Import device key
+        api.import_key_pair(ASYM_NIST_P256_KEY.id, &public_key, &private_key, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(Response::ImportKeyPair { .. }) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+
+        let mut requester = SpdmRequester::new(public_key);
+        for (message, response) in spdm_requests.iter().zip(spdm_responses.iter_mut()) {
+            api.process_spdm_message(message, response)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to forward request");
+            spdm_worker
+                .execute()
+                .await
+                .expect("failed to process request");
+            core.execute().await.expect("failed to forward response");
+            let Some(Response::ProcessSpdmMessage { response, .. }) = api.recv_response().await
+            else {
+                panic!("Failed to receive expected response")
+            };
+            requester.handle(message, response);
+        }
+        assert_eq!(requester.certificate_chain, certificate_chain);
+        let mut expected_record = heapless::Vec::<u8, 256>::new();
+        for (index, measurement) in MEASUREMENTS.iter().enumerate() {
+            expected_record
+                .extend_from_slice(&measurement.block(index as u8 + 1))
+                .unwrap();
+        }
+        assert_eq!(requester.measurement_record, expected_record);
+    }
+
+    #[async_std::test]
+    async fn get_random_health_test_failure() {
+        struct StuckEntropySource;
+
+        impl EntropySource for StuckEntropySource {
+            fn random_seed(&mut self) -> [u8; 32] {
+                [0x42; 32]
+            }
+        }
+
+        const REQUEST_SIZE: usize = 16;
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, ASYM_NIST_P256_KEY];
+        let mut random_output = [0u8; REQUEST_SIZE];
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut rng_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut rng_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (rng_requests_rx, rng_requests_tx, rng_responses_rx, rng_responses_tx) =
+            split_queues(&mut rng_requests, &mut rng_responses);
+        let config = HealthTestConfig::from_min_entropy(8).expect("invalid min-entropy");
+        let rng = Mutex::new(Rng::new(
+            HealthTestedSource::new(StuckEntropySource, config),
+            None,
+        ));
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut rng_worker = RngWorker {
+            rng: &rng,
+            key_store: &key_store,
+            reseed_client: None,
+            requests: rng_requests_rx,
+            responses: rng_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::GetRandom, RequestType::GenerateSymmetricKey],
+            rng_requests_tx,
+            rng_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        let org_request_id = api
+            .get_random(&mut random_output)
+            .await
+            .expect("failed to send request");
+        let (core_res, worker_res) = join(core.execute(), rng_worker.execute()).await;
+        core_res.expect("failed to forward request");
+        worker_res.expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _client_id,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::HealthTestFailure);
+    }
+
+    #[async_std::test]
+    async fn get_random_entropy_source_failure() {
+        struct FailingEntropySource;
+
+        impl TryEntropySource for FailingEntropySource {
+            fn try_random_seed(&mut self) -> Result<[u8; 32], rng::Error> {
+                Err(rng::Error::SourceFailure)
+            }
+        }
+
+        const REQUEST_SIZE: usize = 16;
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, ASYM_NIST_P256_KEY];
+        let mut random_output = [0u8; REQUEST_SIZE];
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut rng_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut rng_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (rng_requests_rx, rng_requests_tx, rng_responses_rx, rng_responses_tx) =
+            split_queues(&mut rng_requests, &mut rng_responses);
+        let rng = Mutex::new(Rng::new(FailingEntropySource, None));
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut rng_worker = RngWorker {
+            rng: &rng,
+            key_store: &key_store,
+            reseed_client: None,
+            requests: rng_requests_rx,
+            responses: rng_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::GetRandom, RequestType::GenerateSymmetricKey],
+            rng_requests_tx,
+            rng_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        let org_request_id = api
+            .get_random(&mut random_output)
+            .await
+            .expect("failed to send request");
+        let (core_res, worker_res) = join(core.execute(), rng_worker.execute()).await;
+        core_res.expect("failed to forward request");
+        worker_res.expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _client_id,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::EntropySourceFailure);
+    }
+
+    #[async_std::test]
+    async fn ecies_encrypt_entropy_source_failure() {
+        struct FailingEntropySource;
+
+        impl TryEntropySource for FailingEntropySource {
+            fn try_random_seed(&mut self) -> Result<[u8; 32], rng::Error> {
+                Err(rng::Error::SourceFailure)
+            }
+        }
+
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, ASYM_NIST_P256_KEY];
+        const PLAINTEXT: &[u8] = b"Provisioned secret";
+        let mut buffer = [0u8; PLAINTEXT.len()];
+        buffer.copy_from_slice(PLAINTEXT);
+        let mut ephemeral_public_key = [0u8; crypto::ecies::MAX_EPHEMERAL_PUBLIC_KEY_SIZE];
+        let mut tag = [0u8; crypto::ecies::TAG_SIZE];
+        let mut rng = Rng::new(TestEntropySource::default(), None);
+        let (_, public_key) = crypto::ecdsa::nist_p256_generate_key_pair(&mut rng);
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut ecies_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut ecies_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (ecies_requests_rx, ecies_requests_tx, ecies_responses_rx, ecies_responses_tx) =
+            split_queues(&mut ecies_requests, &mut ecies_responses);
+        let rng = Mutex::new(Rng::new(FailingEntropySource, None));
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut ecies_worker = EciesWorker {
+            rng: &rng,
+            key_store: &key_store,
+            requests: ecies_requests_rx,
+            responses: ecies_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::EciesEncrypt],
+            ecies_requests_tx,
+            ecies_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // No ciphertext is produced with an ephemeral key from a failed entropy source
+        let org_request_id = api
+            .ecies_encrypt(
+                crypto::ecies::Kdf::AnsiX963Sha256,
+                crypto::ecies::Cipher::Aes128Gcm,
+                &public_key,
+                &[],
+                &[],
+                &mut buffer,
+                &mut ephemeral_public_key,
+                &mut tag,
+            )
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        ecies_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::EntropySourceFailure);
+    }
+
+    #[async_std::test]
+    async fn get_random_with_prediction_resistance_and_reseed() {
+        const REQUEST_SIZE: usize = 16;
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, ASYM_NIST_P256_KEY];
+        let mut random_output = [0u8; REQUEST_SIZE];
+        let additional_input = *b"additional input";
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut rng_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut rng_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (rng_requests_rx, rng_requests_tx, rng_responses_rx, rng_responses_tx) =
+            split_queues(&mut rng_requests, &mut rng_responses);
+        let rng = Mutex::new(Rng::new(TestEntropySource::default(), None));
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut rng_worker = RngWorker {
+            rng: &rng,
+            key_store: &key_store,
+            reseed_client: Some(ClientId(0)),
+            requests: rng_requests_rx,
+            responses: rng_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[
+                RequestType::GetRandom,
+                RequestType::GenerateSymmetricKey,
+                RequestType::ReseedRng,
+            ],
+            rng_requests_tx,
+            rng_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        let org_request_id = api
+            .get_random_with_input(&mut random_output, true, &additional_input)
+            .await
+            .expect("failed to send request");
+        let (core_res, worker_res) = join(core.execute(), rng_worker.execute()).await;
+        core_res.expect("failed to forward request");
+        worker_res.expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::GetRandom {
+            client_id: _client_id,
+            request_id,
+            data,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(data.len(), REQUEST_SIZE);
+
+        // Reseed by the configured client
+        let org_request_id = api.reseed_rng().await.expect("failed to send request");
+        let (core_res, worker_res) = join(core.execute(), rng_worker.execute()).await;
+        core_res.expect("failed to forward request");
+        worker_res.expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::ReseedRng {
+            client_id: _client_id,
+            request_id,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+
+        // Reseed by any other client
+        rng_worker.reseed_client = Some(ClientId(1));
+        let org_request_id = api.reseed_rng().await.expect("failed to send request");
+        let (core_res, worker_res) = join(core.execute(), rng_worker.execute()).await;
+        core_res.expect("failed to forward request");
+        worker_res.expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _client_id,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::AccessDenied);
+    }
+
+    #[async_std::test]
+    async fn delete_key() {
+        const DELETABLE_KEY: KeyInfo = KeyInfo {
+            permissions: KeyPermissions {
+                delete: true,
+                ..SYM_128_KEY.permissions
+            },
+            ..SYM_128_KEY
+        };
+        const KEY_INFOS: [KeyInfo; 2] = [DELETABLE_KEY, SYM_256_KEY];
+        let deletable_key = [1u8; DELETABLE_KEY.ty.key_size()];
+        let undeletable_key = [2u8; SYM_256_KEY.ty.key_size()];
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Import keys
+        for (key_id, key) in [
+            (DELETABLE_KEY.id, deletable_key.as_slice()),
+            (SYM_256_KEY.id, undeletable_key.as_slice()),
+        ] {
+            api.import_symmetric_key(key_id, key, false)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to process request");
+            let Some(response) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            let Response::ImportSymmetricKey { .. } = response else {
+                panic!("Unexpected response type {:?}", response)
+            };
+        }
+
+        // Delete key
+        let org_request_id = api
+            .delete_key(DELETABLE_KEY.id)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::DeleteKey {
+            client_id: _,
+            request_id,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert!(!key_store.lock().await.is_key_available(DELETABLE_KEY.id));
+
+        // Delete absent key
+        let org_request_id = api
+            .delete_key(DELETABLE_KEY.id)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::KeyNotFound));
+
+        // Delete key without permission
+        let org_request_id = api
+            .delete_key(SYM_256_KEY.id)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::NotAllowed));
+        assert!(key_store.lock().await.is_key_available(SYM_256_KEY.id));
+    }
+
+    #[async_std::test]
+    async fn get_key_info_and_list_keys() {
+        const KEY_INFOS: [KeyInfo; 3] = [SYM_128_KEY, SYM_256_KEY, ASYM_NIST_P256_KEY];
+        const UNKNOWN_KEY_ID: KeyId = KeyId(7);
+        let key = [1u8; SYM_256_KEY.ty.key_size()];
+        let mut first_page = [KeyId::default(); 2];
+        let mut second_page = [KeyId::default(); 2];
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        key_store
+            .import_symmetric_key(SYM_256_KEY.id, &key, false)
+            .expect("failed to import key");
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Get info of stored and empty key slots
+        for (key_info, expected_availability) in [(SYM_256_KEY, true), (SYM_128_KEY, false)] {
+            let org_request_id = api
+                .get_key_info(key_info.id)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to process request");
+            let Some(response) = api.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            let Response::GetKeyInfo {
+                client_id: _,
+                request_id,
+                key_info: received_key_info,
+                is_available,
+            } = response
+            else {
+                panic!("Unexpected response type {:?}", response)
+            };
+            assert_eq!(request_id, org_request_id);
+            assert_eq!(received_key_info.id, key_info.id);
+            assert_eq!(received_key_info.ty, key_info.ty);
+            assert_eq!(
+                received_key_info.permissions.bits(),
+                key_info.permissions.bits()
+            );
+            assert_eq!(received_key_info.usage.bits(), key_info.usage.bits());
+            assert_eq!(is_available, expected_availability);
+        }
+
+        // Get info of unknown key slot
+        let org_request_id = api
+            .get_key_info(UNKNOWN_KEY_ID)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::InvalidKeyId));
+
+        // List keys in two pages
+        let org_request_id = api
+            .list_keys(0, &mut first_page)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::ListKeys {
+            client_id: _,
+            request_id,
+            key_ids,
+            num_keys,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(key_ids, [SYM_128_KEY.id, SYM_256_KEY.id]);
+        assert_eq!(num_keys, KEY_INFOS.len());
+        let org_request_id = api
+            .list_keys(key_ids.len(), &mut second_page)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::ListKeys {
+            client_id: _,
+            request_id,
+            key_ids,
+            num_keys,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(key_ids, [ASYM_NIST_P256_KEY.id]);
+        assert_eq!(num_keys, KEY_INFOS.len());
+    }
+
+    #[async_std::test]
+    async fn key_usage_not_allowed() {
+        const ENCRYPT_ONLY_KEY: KeyInfo = KeyInfo {
+            usage: KeyUsageFlags {
+                encrypt: true,
+                ..NO_USAGE
+            },
+            ..SYM_256_KEY
+        };
+        const KEY_INFOS: [KeyInfo; 1] = [ENCRYPT_ONLY_KEY];
+        let (key, nonce, mut plaintext, aad, mut tag) = alloc_aes_gcm_vars();
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut aes_gcm_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut aes_gcm_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (aes_gcm_requests_rx, aes_gcm_requests_tx, aes_gcm_responses_rx, aes_gcm_responses_tx) =
+            split_queues(&mut aes_gcm_requests, &mut aes_gcm_responses);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        key_store
+            .import_symmetric_key(ENCRYPT_ONLY_KEY.id, &key, false)
+            .expect("failed to import key");
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut aes_gcm_worker = AesWorker {
+            key_store: &key_store,
+            requests: aes_gcm_requests_rx,
+            responses: aes_gcm_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::EncryptAesGcm, RequestType::DecryptAesGcm],
+            aes_gcm_requests_tx,
+            aes_gcm_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Encrypt data
+        let org_request_id = api
+            .encrypt_in_place(
+                AesGcm,
+                ENCRYPT_ONLY_KEY.id,
+                &nonce,
+                plaintext.len(),
+                &mut plaintext,
+                &aad,
+                &mut tag,
+            )
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        aes_gcm_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Response::EncryptAesGcm {
+            client_id: _,
+            request_id,
+            buffer,
+            tag,
+        } = api
+            .recv_response()
+            .await
+            .expect("Failed to receive expected response")
+        else {
+            panic!("Unexpected response type")
+        };
+        assert_eq!(request_id, org_request_id);
+
+        // Decrypting is not allowed
+        let org_request_id = api
+            .decrypt_in_place(AesGcm, ENCRYPT_ONLY_KEY.id, &nonce, buffer, &aad, tag)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        aes_gcm_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::NotAllowed));
+    }
+
+    #[async_std::test]
+    async fn key_bound_to_algorithm() {
+        const AES_GCM_KEY: KeyInfo = KeyInfo {
+            algorithm: Some(KeyAlgorithm::AesGcm),
+            ..SYM_256_KEY
+        };
+        const KEY_INFOS: [KeyInfo; 1] = [AES_GCM_KEY];
+        let (key, nonce, mut plaintext, aad, mut tag) = alloc_aes_gcm_vars();
+        let (_, iv, plaintext_size, mut buffer) = alloc_aes_cbc_vars();
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut aes_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut aes_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (aes_requests_rx, aes_requests_tx, aes_responses_rx, aes_responses_tx) =
+            split_queues(&mut aes_requests, &mut aes_responses);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        key_store
+            .import_symmetric_key(AES_GCM_KEY.id, &key, false)
+            .expect("failed to import key");
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut aes_worker = AesWorker {
+            key_store: &key_store,
+            requests: aes_requests_rx,
+            responses: aes_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(
+            &[RequestType::EncryptAesGcm, RequestType::EncryptAesCbc],
+            aes_requests_tx,
+            aes_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Encrypt with the bound algorithm
+        let org_request_id = api
+            .encrypt_in_place(
+                AesGcm,
+                AES_GCM_KEY.id,
+                &nonce,
+                plaintext.len(),
+                &mut plaintext,
+                &aad,
+                &mut tag,
+            )
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        aes_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::EncryptAesGcm {
+            client_id: _,
+            request_id,
+            ..
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+
+        // Encrypt with another algorithm
+        let org_request_id = api
+            .encrypt_in_place(
+                AesCbc,
+                AES_GCM_KEY.id,
+                &iv,
+                plaintext_size,
+                &mut buffer,
+                &[],
+                &mut [],
+            )
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        aes_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::NotAllowed));
+    }
+
+    #[async_std::test]
+    async fn attestation_key_rejected_by_sign() {
+        const ATTESTATION_KEY: KeyInfo = KeyInfo {
+            algorithm: Some(KeyAlgorithm::AttestationQuote),
+            ..ASYM_NIST_P256_KEY
+        };
+        const KEY_INFOS: [KeyInfo; 1] = [ATTESTATION_KEY];
+        let message: &[u8] = b"Forged attestation quote";
+        let mut signature = [0u8; ATTESTATION_KEY.ty.signature_size()];
+        let mut client_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut ecc_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut ecc_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client_rx, req_client_tx, resp_client_rx, resp_client_tx) =
+            split_queues(&mut client_requests, &mut client_responses);
+        let (ecc_requests_rx, ecc_requests_tx, ecc_responses_rx, ecc_responses_tx) =
+            split_queues(&mut ecc_requests, &mut ecc_responses);
+        let rng = Mutex::new(Rng::new(TestEntropySource::default(), None));
+        let mut key_store = init_key_store(&KEY_INFOS);
+        key_store
+            .import_key_pair(
+                ATTESTATION_KEY.id,
+                &[1u8; ATTESTATION_KEY.ty.public_key_size()],
+                &[2u8; ATTESTATION_KEY.ty.private_key_size()],
+                false,
+            )
+            .expect("failed to import key pair");
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut ecc_worker = EccWorker {
+            rng: &rng,
+            key_store: &key_store,
+            requests: ecc_requests_rx,
+            responses: ecc_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client_rx, resp_client_tx)
+        .expect("failed to add client")
+        .with_worker(&[RequestType::Sign], ecc_requests_tx, ecc_responses_rx)
+        .expect("failed to add worker")
+        .build();
+        let mut api = Api::new(req_client_tx, resp_client_rx);
+
+        // Attestation keys must not sign arbitrary messages that could be passed off as quotes
+        let org_request_id = api
+            .sign(ATTESTATION_KEY.id, message, false, &mut signature)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        ecc_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::NotAllowed));
+    }
+
+    #[async_std::test]
+    async fn key_access_control() {
+        const OWNED_KEY: KeyInfo = KeyInfo {
+            permissions: KeyPermissions {
+                import: true,
+                export_private: true,
+                overwrite: true,
+                delete: true,
+            },
+            access: KeyAccessControl::only(ClientSet::of(&[ClientId(0)])),
+            ..SYM_256_KEY
+        };
+        const KEY_INFOS: [KeyInfo; 2] = [SYM_128_KEY, OWNED_KEY];
+        const UNCONFIGURED_KEY_ID: KeyId = KeyId(7);
+        let (key, nonce, mut plaintext, aad, mut tag) = alloc_aes_gcm_vars();
+        let other_key = [2u8; OWNED_KEY.ty.key_size()];
+        let mut exported_key = [0u8; OWNED_KEY.ty.key_size()];
+        let mut owner_exported_key = [0u8; OWNED_KEY.ty.key_size()];
+        let mut listed_key_ids = [KeyId(0); KEY_INFOS.len()];
+        let mut owner_listed_key_ids = [KeyId(0); KEY_INFOS.len()];
+        let mut client1_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client1_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut client2_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut client2_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let mut aes_requests = AsyncQueue::<Request, QUEUE_SIZE>::new();
+        let mut aes_responses = AsyncQueue::<Response, QUEUE_SIZE>::new();
+        let (req_client1_rx, req_client1_tx, resp_client1_rx, resp_client1_tx) =
+            split_queues(&mut client1_requests, &mut client1_responses);
+        let (req_client2_rx, req_client2_tx, resp_client2_rx, resp_client2_tx) =
+            split_queues(&mut client2_requests, &mut client2_responses);
+        let (aes_requests_rx, aes_requests_tx, aes_responses_rx, aes_responses_tx) =
+            split_queues(&mut aes_requests, &mut aes_responses);
+        let mut key_store = init_key_store(&KEY_INFOS);
+        let key_store: Mutex<NoopRawMutex, &mut (dyn KeyStore + Send)> = Mutex::new(&mut key_store);
+        let mut aes_worker = AesWorker {
+            key_store: &key_store,
+            requests: aes_requests_rx,
+            responses: aes_responses_tx,
+        };
+        let mut core = Builder::<
+            NoopRawMutex,
+            RequestQueueSource<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSink<'_, '_, QUEUE_SIZE>,
+            RequestQueueSink<'_, '_, QUEUE_SIZE>,
+            ResponseQueueSource<'_, '_, QUEUE_SIZE>,
+        >::default()
+        .with_keystore(&key_store)
+        .with_client(req_client1_rx, resp_client1_tx)
+        .expect("failed to add client 1")
+        .with_client(req_client2_rx, resp_client2_tx)
+        .expect("failed to add client 2")
+        .with_worker(
+            &[RequestType::EncryptAesGcm],
+            aes_requests_tx,
+            aes_responses_rx,
+        )
+        .expect("failed to add worker")
+        .build();
+        let mut api1 = Api::new(req_client1_tx, resp_client1_rx);
+        let mut api2 = Api::new(req_client2_tx, resp_client2_rx);
+
+        // Import key by owning client
+        let org_request_id = api1
+            .import_symmetric_key(OWNED_KEY.id, &key, false)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api1.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::ImportSymmetricKey {
+            client_id: _,
+            request_id,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+
+        // Overwrite key by other client
+        let org_request_id = api2
+            .import_symmetric_key(OWNED_KEY.id, &other_key, true)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api2.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::AccessDenied));
+
+        // Export key by other client
+        let org_request_id = api2
+            .export_symmetric_key(OWNED_KEY.id, &mut exported_key)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api2.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::AccessDenied));
+
+        // Use key by other client
+        let org_request_id = api2
+            .encrypt_in_place(
+                AesGcm,
+                OWNED_KEY.id,
+                &nonce,
+                plaintext.len(),
+                &mut plaintext,
+                &aad,
+                &mut tag,
+            )
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to forward request");
+        aes_worker
+            .execute()
+            .await
+            .expect("failed to process request");
+        core.execute().await.expect("failed to forward response");
+        let Some(response) = api2.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::AccessDenied));
+
+        // Delete key by other client
+        let org_request_id = api2
+            .delete_key(OWNED_KEY.id)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api2.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::Error {
+            client_id: _,
+            request_id,
+            error,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(error, Error::KeyStore(keystore::Error::AccessDenied));
+
+        // Keys without access look like unconfigured keys to other clients
+        for key_id in [OWNED_KEY.id, UNCONFIGURED_KEY_ID] {
+            let org_request_id = api2
+                .is_key_available(key_id)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to process request");
+            let Some(response) = api2.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            let Response::IsKeyAvailable {
+                client_id: _,
+                request_id,
+                is_available,
+            } = response
+            else {
+                panic!("Unexpected response type {:?}", response)
+            };
+            assert_eq!(request_id, org_request_id);
+            assert!(!is_available);
+
+            let org_request_id = api2
+                .get_key_info(key_id)
+                .await
+                .expect("failed to send request");
+            core.execute().await.expect("failed to process request");
+            let Some(response) = api2.recv_response().await else {
+                panic!("Failed to receive expected response")
+            };
+            let Response::Error {
+                client_id: _,
+                request_id,
+                error,
+            } = response
+            else {
+                panic!("Unexpected response type {:?}", response)
+            };
+            assert_eq!(request_id, org_request_id);
+            assert_eq!(error, Error::KeyStore(keystore::Error::InvalidKeyId));
+        }
+
+        // List keys by other client
+        let org_request_id = api2
+            .list_keys(0, &mut listed_key_ids)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api2.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::ListKeys {
+            client_id: _,
+            request_id,
+            key_ids,
+            num_keys,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(key_ids, [SYM_128_KEY.id]);
+        assert_eq!(num_keys, 1);
+
+        // List keys by owning client
+        let org_request_id = api1
+            .list_keys(0, &mut owner_listed_key_ids)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api1.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::ListKeys {
+            client_id: _,
+            request_id,
+            key_ids,
+            num_keys,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(key_ids, [SYM_128_KEY.id, OWNED_KEY.id]);
+        assert_eq!(num_keys, 2);
+
+        // Key is unchanged and still exportable by owning client
+        let org_request_id = api1
+            .export_symmetric_key(OWNED_KEY.id, &mut owner_exported_key)
+            .await
+            .expect("failed to send request");
+        core.execute().await.expect("failed to process request");
+        let Some(response) = api1.recv_response().await else {
+            panic!("Failed to receive expected response")
+        };
+        let Response::ExportSymmetricKey {
+            client_id: _,
+            request_id,
+            key: exported_key,
+        } = response
+        else {
+            panic!("Unexpected response type {:?}", response)
+        };
+        assert_eq!(request_id, org_request_id);
+        assert_eq!(exported_key, key);
+    }
+
     #[async_std::test]
     async fn multiple_clients() {
         const REQUEST1_SIZE: usize = 16;
@@ -1326,6 +4759,7 @@ mod tests {
         let mut rng_worker = RngWorker {
             rng: &rng,
             key_store: &key_store,
+            reseed_client: None,
             requests: rng_requests_rx,
             responses: rng_responses_tx,
         };